@@ -0,0 +1,8 @@
+#!/bin/bash
+set -euxo pipefail
+IFS=$'\n\t'
+cd "$(dirname "$0")"/../crossbeam-channel
+
+export RUSTFLAGS="${RUSTFLAGS:-} --cfg crossbeam_loom"
+
+env LOOM_MAX_PREEMPTIONS=2 cargo test --test loom --release --features loom -- --nocapture
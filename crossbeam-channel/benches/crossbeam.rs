@@ -2,7 +2,7 @@
 
 extern crate test;
 
-use crossbeam_channel::{bounded, unbounded};
+use crossbeam_channel::{bounded, select, unbounded};
 use crossbeam_utils::thread::scope;
 use test::Bencher;
 
@@ -216,6 +216,15 @@ mod unbounded {
 mod bounded_n {
     use super::*;
 
+    #[bench]
+    fn inout(b: &mut Bencher) {
+        let (s, r) = bounded::<i32>(TOTAL_STEPS);
+        b.iter(|| {
+            s.send(0).unwrap();
+            r.recv().unwrap();
+        });
+    }
+
     #[bench]
     fn spsc(b: &mut Bencher) {
         let steps = TOTAL_STEPS;
@@ -413,6 +422,15 @@ mod bounded_1 {
         });
     }
 
+    #[bench]
+    fn inout(b: &mut Bencher) {
+        let (s, r) = bounded::<i32>(1);
+        b.iter(|| {
+            s.send(0).unwrap();
+            r.recv().unwrap();
+        });
+    }
+
     #[bench]
     fn spsc(b: &mut Bencher) {
         let steps = TOTAL_STEPS;
@@ -710,3 +728,121 @@ mod bounded_0 {
         .unwrap();
     }
 }
+
+mod select {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[bench]
+    fn inout(b: &mut Bencher) {
+        let (s1, r1) = unbounded::<i32>();
+        let (_s2, r2) = unbounded::<i32>();
+        b.iter(|| {
+            s1.send(0).unwrap();
+            select! {
+                recv(r1) -> msg => msg.unwrap(),
+                recv(r2) -> msg => msg.unwrap(),
+            };
+        });
+    }
+
+    #[bench]
+    fn inout_timeout(b: &mut Bencher) {
+        let (s1, r1) = unbounded::<i32>();
+        let (_s2, r2) = unbounded::<i32>();
+        b.iter(|| {
+            s1.send(0).unwrap();
+            select! {
+                recv(r1) -> msg => msg.unwrap(),
+                recv(r2) -> msg => msg.unwrap(),
+                default(Duration::from_secs(1)) => panic!(),
+            };
+        });
+    }
+
+    #[bench]
+    fn spsc(b: &mut Bencher) {
+        let steps = TOTAL_STEPS;
+        let (s1, r1) = bounded::<i32>(1);
+        let (s2, r2) = bounded::<i32>(1);
+
+        let (s3, r3) = bounded(0);
+        let (s4, r4) = bounded(0);
+        scope(|scope| {
+            scope.spawn(|_| {
+                while r3.recv().is_ok() {
+                    for i in 0..steps / 2 {
+                        s1.send(i as i32).unwrap();
+                        s2.send(i as i32).unwrap();
+                    }
+                    s4.send(()).unwrap();
+                }
+            });
+
+            b.iter(|| {
+                s3.send(()).unwrap();
+                for _ in 0..steps {
+                    select! {
+                        recv(r1) -> msg => msg.unwrap(),
+                        recv(r2) -> msg => msg.unwrap(),
+                    };
+                }
+                r4.recv().unwrap();
+            });
+            drop(s3);
+        })
+        .unwrap();
+    }
+
+    #[bench]
+    fn mpmc(b: &mut Bencher) {
+        let threads = num_cpus::get();
+        assert_eq!(threads % 2, 0);
+        let steps = TOTAL_STEPS / threads;
+        let (s1, r1) = bounded::<i32>(threads);
+        let (s2, r2) = bounded::<i32>(threads);
+
+        let (s3, r3) = bounded(0);
+        let (s4, r4) = bounded(0);
+        scope(|scope| {
+            for _ in 0..threads / 2 {
+                scope.spawn(|_| {
+                    while r3.recv().is_ok() {
+                        for i in 0..steps {
+                            select! {
+                                send(s1, i as i32) -> res => res.unwrap(),
+                                send(s2, i as i32) -> res => res.unwrap(),
+                            }
+                        }
+                        s4.send(()).unwrap();
+                    }
+                });
+            }
+            for _ in 0..threads / 2 {
+                scope.spawn(|_| {
+                    while r3.recv().is_ok() {
+                        for _ in 0..steps {
+                            select! {
+                                recv(r1) -> msg => msg.unwrap(),
+                                recv(r2) -> msg => msg.unwrap(),
+                            };
+                        }
+                        s4.send(()).unwrap();
+                    }
+                });
+            }
+
+            b.iter(|| {
+                for _ in 0..threads {
+                    s3.send(()).unwrap();
+                }
+                for _ in 0..threads {
+                    r4.recv().unwrap();
+                }
+            });
+            drop(s3);
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+//! Runs a sequence of non-blocking operations on one thread and checks each result against a
+//! simple model of the channel.
+
+#![no_main]
+
+use std::collections::VecDeque;
+
+use crossbeam_channel::{bounded, unbounded, TryRecvError, TrySendError};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let (&first, ops) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    // The first byte picks the capacity, with the highest bit standing for unbounded.
+    let cap = if first & 0x80 != 0 {
+        None
+    } else {
+        Some(usize::from(first % 8))
+    };
+    let (s, r) = match cap {
+        None => unbounded(),
+        Some(cap) => bounded(cap),
+    };
+    let mut senders = vec![s];
+    let mut receivers = vec![r];
+    let mut queue = VecDeque::new();
+
+    for &op in ops {
+        let arg = op / 8;
+        match op % 8 {
+            0 | 1 => {
+                if let Some(s) = senders.get(usize::from(arg) % senders.len().max(1)) {
+                    let res = s.try_send(arg);
+                    if receivers.is_empty() {
+                        assert_eq!(res, Err(TrySendError::Disconnected(arg)));
+                    } else if Some(queue.len()) == cap {
+                        assert_eq!(res, Err(TrySendError::Full(arg)));
+                    } else {
+                        assert_eq!(res, Ok(()));
+                        queue.push_back(arg);
+                    }
+                }
+            }
+            2 | 3 => {
+                if let Some(r) = receivers.get(usize::from(arg) % receivers.len().max(1)) {
+                    let res = r.try_recv();
+                    match queue.pop_front() {
+                        Some(msg) => assert_eq!(res, Ok(msg)),
+                        None if senders.is_empty() => {
+                            assert_eq!(res, Err(TryRecvError::Disconnected))
+                        }
+                        None => assert_eq!(res, Err(TryRecvError::Empty)),
+                    }
+                }
+            }
+            4 => {
+                if let Some(s) = senders.first().cloned() {
+                    senders.push(s);
+                }
+            }
+            5 => {
+                senders.pop();
+            }
+            6 => {
+                if let Some(r) = receivers.first().cloned() {
+                    receivers.push(r);
+                }
+            }
+            _ => {
+                // Messages can't be received anymore once the last receiver is gone.
+                receivers.pop();
+                if receivers.is_empty() {
+                    queue.clear();
+                }
+            }
+        }
+
+        if senders.is_empty() && receivers.is_empty() {
+            return;
+        }
+        if let Some(r) = receivers.first() {
+            assert_eq!(r.len(), queue.len());
+            assert_eq!(r.capacity(), cap);
+        }
+    }
+});
@@ -0,0 +1,129 @@
+//! Sends and receives on several threads and checks that every message that was sent is received
+//! exactly once or still in the channel when it gets dropped.
+
+#![no_main]
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossbeam_channel::{
+    bounded_with_metrics, select, unbounded_with_metrics, ChannelMetrics, TryRecvError,
+    TrySendError,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Remembers how many messages were left in the channel when it got dropped.
+#[derive(Default)]
+struct Leftover(Mutex<Option<usize>>);
+
+impl ChannelMetrics for Leftover {
+    fn on_drop(&self, _name: &str, len: usize) {
+        *self.0.lock().unwrap() = Some(len);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 3 {
+        return;
+    }
+
+    // The first three bytes pick the capacity and the numbers of senders and receivers. The rest
+    // are split among the threads, one byte per operation.
+    let leftover = Arc::new(Leftover::default());
+    let (s, r) = if data[0] & 0x80 != 0 {
+        unbounded_with_metrics("fuzz", leftover.clone())
+    } else {
+        bounded_with_metrics(usize::from(data[0] % 8) + 1, "fuzz", leftover.clone())
+    };
+    let senders = usize::from(data[1] % 4) + 1;
+    let receivers = usize::from(data[2] % 4) + 1;
+    let ops: Arc<[u8]> = data[3..].into();
+    let threads = senders + receivers;
+
+    let sent = (0..senders)
+        .map(|id| {
+            let s = s.clone();
+            let ops = ops.clone();
+            thread::spawn(move || {
+                let mut sent = Vec::new();
+                for &op in ops.iter().skip(id).step_by(threads) {
+                    let msg = (id, sent.len());
+                    let ok = match op % 4 {
+                        // A sender gives up early by dropping its handle.
+                        0 => break,
+                        1 => s.send(msg).is_ok(),
+                        2 => loop {
+                            match s.try_send(msg) {
+                                Ok(()) => break true,
+                                Err(TrySendError::Full(_)) => thread::yield_now(),
+                                Err(TrySendError::Disconnected(_)) => break false,
+                            }
+                        },
+                        _ => select! {
+                            send(s, msg) -> res => res.is_ok(),
+                        },
+                    };
+                    if !ok {
+                        break;
+                    }
+                    sent.push(msg);
+                }
+                sent
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let received = (0..receivers)
+        .map(|i| {
+            let r = r.clone();
+            let ops = ops.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                let mut ops = ops.iter().skip(senders + i).step_by(threads);
+                loop {
+                    // A receiver gives up early by dropping its handle. After running out of
+                    // operations, it keeps receiving until the channel is disconnected.
+                    let res = match ops.next().map(|op| op % 4) {
+                        Some(0) => break,
+                        Some(1) | None => r.recv().ok(),
+                        Some(2) => loop {
+                            match r.try_recv() {
+                                Ok(msg) => break Some(msg),
+                                Err(TryRecvError::Empty) => thread::yield_now(),
+                                Err(TryRecvError::Disconnected) => break None,
+                            }
+                        },
+                        Some(_) => select! {
+                            recv(r) -> msg => msg.ok(),
+                        },
+                    };
+                    match res {
+                        Some(msg) => received.push(msg),
+                        None => break,
+                    }
+                }
+                received
+            })
+        })
+        .collect::<Vec<_>>();
+
+    drop(s);
+    drop(r);
+    let sent = sent
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect::<HashSet<_>>();
+    let received = received
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect::<Vec<_>>();
+    let leftover = leftover.0.lock().unwrap().expect("channel wasn't dropped");
+
+    let mut seen = HashSet::new();
+    for msg in &received {
+        assert!(sent.contains(msg), "{:?} was never sent", msg);
+        assert!(seen.insert(msg), "{:?} was received twice", msg);
+    }
+    assert_eq!(seen.len() + leftover, sent.len());
+});
@@ -0,0 +1,319 @@
+//! Receivers whose messages must be acknowledged.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::channel::Receiver;
+use crate::err::{RecvError, RecvTimeoutError, TryRecvError};
+use crate::select::{Notifier, Select, Selectable};
+use crate::utils;
+
+/// A receiver that hands out messages as [`Delivery`] guards, which must be acknowledged.
+///
+/// Created with [`Receiver::acked`]. A delivery that is [acknowledged] is done with. A delivery
+/// that is [given back], or dropped without being acknowledged, for example because the consumer
+/// panicked, is put back and delivered again, possibly to another clone of the receiver. Messages
+/// that were given back are delivered before new messages from the channel.
+///
+/// This gives at-least-once delivery within the process: a message is only lost if its delivery
+/// is acknowledged, or leaked with [`mem::forget`].
+///
+/// Once the channel is empty and disconnected, receiving only fails after every outstanding
+/// delivery has been acknowledged, since any of them could still be given back.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::unbounded;
+///
+/// let (s, r) = unbounded();
+/// let r = r.acked();
+/// s.send(1).unwrap();
+/// drop(s);
+///
+/// // The first consumer panics, so its delivery is given back.
+/// let r2 = r.clone();
+/// let consumer = thread::spawn(move || {
+///     let _msg = r2.recv().unwrap();
+///     panic!("consumer failed");
+/// });
+/// assert!(consumer.join().is_err());
+///
+/// // The second consumer gets the message again.
+/// let msg = r.recv().unwrap();
+/// assert_eq!(msg.ack(), 1);
+/// assert!(r.recv().is_err());
+/// ```
+///
+/// [acknowledged]: Delivery::ack
+/// [given back]: Delivery::nack
+/// [`mem::forget`]: std::mem::forget
+pub struct AckReceiver<T> {
+    /// State shared with clones and outstanding deliveries.
+    shared: Arc<Shared<T>>,
+}
+
+/// State shared by an acknowledging receiver, its clones, and its outstanding deliveries.
+struct Shared<T> {
+    /// The receiving side of the channel.
+    receiver: Receiver<T>,
+
+    /// Messages that were given back, waiting to be delivered again.
+    requeued: Mutex<VecDeque<T>>,
+
+    /// The number of deliveries that haven't been acknowledged or given back.
+    pending: AtomicUsize,
+
+    /// Wakes up receivers when a message is given back or the last delivery is acknowledged.
+    notifier: Notifier,
+}
+
+impl<T> Shared<T> {
+    /// Takes the oldest message that was given back.
+    fn pop(&self) -> Option<T> {
+        self.requeued.lock().unwrap().pop_front()
+    }
+
+    /// Returns `true` if no delivery is outstanding, so no message can be given back anymore.
+    fn is_settled(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) == 0
+    }
+
+    /// Settles an outstanding delivery, giving its message back if there is one.
+    fn settle(&self, msg: Option<T>) {
+        if let Some(msg) = msg {
+            self.requeued.lock().unwrap().push_back(msg);
+        }
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+        self.notifier.notify_all();
+    }
+}
+
+/// Waits for a message to be given back, or also for the last delivery to be settled.
+struct Requeued<'a, T> {
+    /// The shared state.
+    shared: &'a Shared<T>,
+
+    /// Whether settling the last delivery makes this ready.
+    or_settled: bool,
+}
+
+impl<T> Selectable for Requeued<'_, T> {
+    fn is_ready(&self) -> bool {
+        !self.shared.requeued.lock().unwrap().is_empty()
+            || (self.or_settled && self.shared.is_settled())
+    }
+
+    fn notifier(&self) -> &Notifier {
+        &self.shared.notifier
+    }
+}
+
+impl<T> AckReceiver<T> {
+    /// Wraps a receiver.
+    pub(crate) fn new(receiver: Receiver<T>) -> AckReceiver<T> {
+        AckReceiver {
+            shared: Arc::new(Shared {
+                receiver,
+                requeued: Mutex::new(VecDeque::new()),
+                pending: AtomicUsize::new(0),
+                notifier: Notifier::new(),
+            }),
+        }
+    }
+
+    /// Hands out a message as an outstanding delivery.
+    fn deliver(&self, msg: T) -> Delivery<T> {
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+        Delivery {
+            msg: Some(msg),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Attempts to receive a message without blocking.
+    ///
+    /// Messages that were given back come first. If the channel is empty and disconnected but
+    /// some deliveries are still outstanding, this returns [`TryRecvError::Empty`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, TryRecvError};
+    ///
+    /// let (s, r) = unbounded();
+    /// let r = r.acked();
+    /// s.send(1).unwrap();
+    /// drop(s);
+    ///
+    /// let msg = r.try_recv().unwrap();
+    /// assert_eq!(r.try_recv().err(), Some(TryRecvError::Empty));
+    /// msg.nack();
+    ///
+    /// let msg = r.try_recv().unwrap();
+    /// msg.ack();
+    /// assert_eq!(r.try_recv().err(), Some(TryRecvError::Disconnected));
+    /// ```
+    pub fn try_recv(&self) -> Result<Delivery<T>, TryRecvError> {
+        if let Some(msg) = self.shared.pop() {
+            return Ok(self.deliver(msg));
+        }
+        match self.shared.receiver.try_recv() {
+            Ok(msg) => Ok(self.deliver(msg)),
+            Err(TryRecvError::Empty) => Err(TryRecvError::Empty),
+            Err(TryRecvError::Disconnected) => {
+                if self.shared.is_settled() && self.shared.requeued.lock().unwrap().is_empty() {
+                    Err(TryRecvError::Disconnected)
+                } else {
+                    Err(TryRecvError::Empty)
+                }
+            }
+        }
+    }
+
+    /// Blocks the current thread until a message is received.
+    ///
+    /// Messages that were given back come first. Fails once the channel is empty and
+    /// disconnected and every delivery has been acknowledged.
+    pub fn recv(&self) -> Result<Delivery<T>, RecvError> {
+        self.recv_until(None).map_err(|_| RecvError)
+    }
+
+    /// Waits for a message to be received, but only for a limited time.
+    ///
+    /// See [`recv`] for details.
+    ///
+    /// [`recv`]: AckReceiver::recv
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Delivery<T>, RecvTimeoutError> {
+        self.recv_until(Some(utils::convert_timeout_to_deadline(timeout)))
+    }
+
+    /// Receives a message, waiting until the deadline if there is one.
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<Delivery<T>, RecvTimeoutError> {
+        let mut disconnected = false;
+        loop {
+            if let Some(msg) = self.shared.pop() {
+                return Ok(self.deliver(msg));
+            }
+
+            // Once the channel is disconnected, wait for deliveries to be given back or settled.
+            let requeued = Requeued {
+                shared: &self.shared,
+                or_settled: disconnected,
+            };
+            let mut sel = Select::new_biased();
+            let index = sel.custom(&requeued);
+            if !disconnected {
+                sel.recv(&self.shared.receiver);
+            }
+            let oper = match deadline {
+                None => sel.select(),
+                Some(deadline) => sel
+                    .select_deadline(deadline)
+                    .map_err(|_| RecvTimeoutError::Timeout)?,
+            };
+
+            if oper.index() == index {
+                oper.custom(&requeued);
+                if disconnected && self.shared.is_settled() {
+                    if let Some(msg) = self.shared.pop() {
+                        return Ok(self.deliver(msg));
+                    }
+                    return Err(RecvTimeoutError::Disconnected);
+                }
+            } else {
+                match oper.recv(&self.shared.receiver) {
+                    Ok(msg) => return Ok(self.deliver(msg)),
+                    Err(_) => disconnected = true,
+                }
+            }
+        }
+    }
+
+    /// Returns the number of deliveries that haven't been acknowledged or given back.
+    pub fn pending(&self) -> usize {
+        self.shared.pending.load(Ordering::SeqCst)
+    }
+
+    /// Returns the underlying receiver.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.shared.receiver
+    }
+}
+
+impl<T> Clone for AckReceiver<T> {
+    fn clone(&self) -> AckReceiver<T> {
+        AckReceiver {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for AckReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("AckReceiver { .. }")
+    }
+}
+
+/// A message handed out by an [`AckReceiver`], which must be acknowledged.
+///
+/// The message can be used through [`Deref`] and [`DerefMut`]. Calling [`ack`] marks it as
+/// processed and returns it. Calling [`nack`] or dropping the delivery gives it back to the
+/// receiver, which delivers it again.
+///
+/// [`ack`]: Delivery::ack
+/// [`nack`]: Delivery::nack
+pub struct Delivery<T> {
+    /// The message, until the delivery is settled.
+    msg: Option<T>,
+
+    /// The state of the receiver that handed out the message.
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Delivery<T> {
+    /// Acknowledges the message and returns it.
+    pub fn ack(mut self) -> T {
+        let msg = self.msg.take().unwrap();
+        self.shared.settle(None);
+        msg
+    }
+
+    /// Gives the message back, so that it is delivered again.
+    pub fn nack(self) {
+        drop(self);
+    }
+}
+
+impl<T> Deref for Delivery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.msg.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for Delivery<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.msg.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for Delivery<T> {
+    fn drop(&mut self) {
+        if let Some(msg) = self.msg.take() {
+            self.shared.settle(Some(msg));
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Delivery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Delivery").field("msg", &**self).finish()
+    }
+}
@@ -0,0 +1,205 @@
+//! Senders that buffer messages and send them in batches.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::channel::Sender;
+use crate::err::SendError;
+
+/// A sender that buffers messages and sends them into the channel in batches.
+///
+/// Created with [`Sender::buffered`]. Messages are collected in a buffer owned by the
+/// `BufferedSender` and sent once the buffer is full, once the oldest buffered message has waited
+/// longer than the delay set with [`set_max_delay`], or when [`flush`] is called. The delay is
+/// only checked when a message is sent, so a producer that goes idle should call [`flush`].
+///
+/// A bounded channel reserves as many free slots as possible for a batch at once, so producers
+/// that send many messages contend on the channel much less often. Other channels receive the
+/// batch one message at a time.
+///
+/// Sending a batch blocks while the channel is full. Dropping a `BufferedSender` flushes the
+/// remaining messages, which may block too.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::bounded;
+///
+/// let (s, r) = bounded(1000);
+///
+/// thread::spawn(move || {
+///     let mut s = s.buffered(64);
+///     for i in 0..1000 {
+///         s.send(i).unwrap();
+///     }
+///     // The remaining messages are flushed when `s` is dropped.
+/// });
+///
+/// assert_eq!(r.iter().count(), 1000);
+/// ```
+///
+/// [`set_max_delay`]: BufferedSender::set_max_delay
+/// [`flush`]: BufferedSender::flush
+pub struct BufferedSender<T> {
+    /// The sending side of the channel.
+    sender: Sender<T>,
+
+    /// Messages waiting to be sent.
+    buf: Vec<T>,
+
+    /// The number of messages that triggers a flush.
+    capacity: usize,
+
+    /// How long the oldest message may wait in the buffer.
+    max_delay: Option<Duration>,
+
+    /// The instant at which the oldest message was buffered, if there is a maximum delay.
+    oldest: Option<Instant>,
+}
+
+impl<T> BufferedSender<T> {
+    /// Creates a buffered sender holding at most `capacity` messages.
+    pub(crate) fn new(sender: Sender<T>, capacity: usize) -> BufferedSender<T> {
+        assert!(capacity > 0, "buffer capacity must be non-zero");
+        BufferedSender {
+            sender,
+            buf: Vec::with_capacity(capacity),
+            capacity,
+            max_delay: None,
+            oldest: None,
+        }
+    }
+
+    /// Buffers a message, sending the whole buffer if it is full or the maximum delay has passed.
+    ///
+    /// If the channel is disconnected, the error contains all messages that weren't sent,
+    /// including this one, and the buffer is left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, SendError};
+    ///
+    /// let (s, r) = unbounded();
+    /// let mut buffered = s.buffered(2);
+    ///
+    /// buffered.send(1).unwrap();
+    /// assert!(r.is_empty());
+    ///
+    /// drop(r);
+    /// assert_eq!(buffered.send(2), Err(SendError(vec![1, 2])));
+    /// ```
+    pub fn send(&mut self, msg: T) -> Result<(), SendError<Vec<T>>> {
+        if self.buf.is_empty() && self.max_delay.is_some() {
+            self.oldest = Some(Instant::now());
+        }
+        self.buf.push(msg);
+
+        let expired = match (self.oldest, self.max_delay) {
+            (Some(oldest), Some(delay)) => oldest.elapsed() >= delay,
+            _ => false,
+        };
+
+        if self.buf.len() >= self.capacity || expired {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sends all buffered messages into the channel.
+    ///
+    /// This call blocks while the channel is full. If the channel is disconnected, the error
+    /// contains the messages that weren't sent, and the buffer is left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// let mut buffered = s.buffered(10);
+    ///
+    /// buffered.send("a").unwrap();
+    /// buffered.send("b").unwrap();
+    /// assert!(r.is_empty());
+    ///
+    /// buffered.flush().unwrap();
+    /// assert_eq!(r.try_iter().collect::<Vec<_>>(), ["a", "b"]);
+    /// ```
+    pub fn flush(&mut self) -> Result<(), SendError<Vec<T>>> {
+        self.oldest = None;
+
+        let mut msgs = self.buf.drain(..);
+        match self.sender.send_all(&mut msgs) {
+            Ok(()) => Ok(()),
+            Err(SendError(msg)) => {
+                let mut unsent = vec![msg];
+                unsent.extend(msgs);
+                Err(SendError(unsent))
+            }
+        }
+    }
+
+    /// Sets how long a message may wait in the buffer before the buffer is sent.
+    ///
+    /// The delay is checked whenever a message is buffered. When it has passed for the oldest
+    /// buffered message, the whole buffer is sent even if it isn't full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// let mut buffered = s.buffered(100);
+    /// buffered.set_max_delay(Duration::from_millis(50));
+    ///
+    /// buffered.send(1).unwrap();
+    /// thread::sleep(Duration::from_millis(100));
+    /// buffered.send(2).unwrap();
+    ///
+    /// assert_eq!(r.len(), 2);
+    /// ```
+    pub fn set_max_delay(&mut self, delay: Duration) {
+        self.max_delay = Some(delay);
+        if self.oldest.is_none() && !self.buf.is_empty() {
+            self.oldest = Some(Instant::now());
+        }
+    }
+
+    /// Returns the number of buffered messages.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if no messages are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the maximum number of buffered messages.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the sender used to send the buffered messages.
+    pub fn sender(&self) -> &Sender<T> {
+        &self.sender
+    }
+}
+
+impl<T> Drop for BufferedSender<T> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<T> fmt::Debug for BufferedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("BufferedSender { .. }")
+    }
+}
@@ -0,0 +1,164 @@
+//! Cooperative cancellation of blocking operations.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::select::{Notifier, Selectable};
+
+/// Creates a cancellation token and a signal observing it.
+///
+/// Calling [`CancelToken::cancel`] wakes up every thread blocked in [`Sender::send_cancellable`],
+/// [`Receiver::recv_cancellable`], or a [`Select`] that the signal was added to. Both halves can
+/// be cloned, so a single token can abort any number of operations across threads.
+///
+/// Once cancelled, a token stays cancelled forever.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{cancel_token, unbounded, RecvCancelError};
+///
+/// let (token, signal) = cancel_token();
+/// let (_s, r) = unbounded::<i32>();
+///
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     token.cancel();
+/// });
+///
+/// // Nothing is ever sent, so the receive operation is aborted by the token.
+/// assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+/// assert!(signal.is_cancelled());
+/// ```
+///
+/// [`Sender::send_cancellable`]: super::Sender::send_cancellable
+/// [`Receiver::recv_cancellable`]: super::Receiver::recv_cancellable
+/// [`Select`]: super::Select
+pub fn cancel_token() -> (CancelToken, CancelSignal) {
+    let inner = Arc::new(Inner {
+        cancelled: AtomicBool::new(false),
+        notifier: Notifier::new(),
+    });
+    let token = CancelToken {
+        inner: inner.clone(),
+    };
+    let signal = CancelSignal { inner };
+    (token, signal)
+}
+
+/// State shared by a token and its signals.
+struct Inner {
+    /// Set to `true` when the token is cancelled.
+    cancelled: AtomicBool,
+
+    /// Threads waiting for cancellation.
+    notifier: Notifier,
+}
+
+/// The triggering half of a cancellation token.
+///
+/// Created with [`cancel_token`].
+#[derive(Clone)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+impl CancelToken {
+    /// Cancels all operations observing this token.
+    ///
+    /// Threads blocked on the corresponding [`CancelSignal`] are woken up. Operations started after
+    /// this call are cancelled immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::cancel_token;
+    ///
+    /// let (token, signal) = cancel_token();
+    /// assert!(!signal.is_cancelled());
+    ///
+    /// token.cancel();
+    /// assert!(signal.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            self.inner.notifier.notify_all();
+        }
+    }
+
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("CancelToken { .. }")
+    }
+}
+
+/// The observing half of a cancellation token.
+///
+/// A signal can be passed to [`Sender::send_cancellable`] and [`Receiver::recv_cancellable`], or
+/// added to a [`Select`] with [`Select::custom`], in which case the operation becomes ready once
+/// the token is cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{cancel_token, unbounded, Select};
+///
+/// let (token, signal) = cancel_token();
+/// let (_s, r) = unbounded::<i32>();
+///
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     token.cancel();
+/// });
+///
+/// let mut sel = Select::new();
+/// let oper1 = sel.recv(&r);
+/// let oper2 = sel.custom(&signal);
+///
+/// let oper = sel.select();
+/// assert_eq!(oper.index(), oper2);
+/// oper.custom(&signal);
+/// ```
+///
+/// [`Sender::send_cancellable`]: super::Sender::send_cancellable
+/// [`Receiver::recv_cancellable`]: super::Receiver::recv_cancellable
+/// [`Select`]: super::Select
+/// [`Select::custom`]: super::Select::custom
+#[derive(Clone)]
+pub struct CancelSignal {
+    inner: Arc<Inner>,
+}
+
+impl CancelSignal {
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Selectable for CancelSignal {
+    fn is_ready(&self) -> bool {
+        self.is_cancelled()
+    }
+
+    fn notifier(&self) -> &Notifier {
+        &self.inner.notifier
+    }
+}
+
+impl fmt::Debug for CancelSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("CancelSignal { .. }")
+    }
+}
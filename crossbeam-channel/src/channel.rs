@@ -1,20 +1,51 @@
 //! The channel interface.
 
+use std::error::Error;
 use std::fmt;
+#[cfg(all(unix, feature = "fd"))]
+use std::io;
 use std::iter::FusedIterator;
 use std::mem;
 use std::panic::{RefUnwindSafe, UnwindSafe};
+#[cfg(feature = "async")]
+use std::pin::Pin;
 use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{self, Poll};
 use std::time::{Duration, Instant};
+use std::vec;
 
+use crate::ack::AckReceiver;
+use crate::buffered::BufferedSender;
+use crate::cancel::CancelSignal;
+use crate::clock::{Clock, TimerClock};
 use crate::context::Context;
-use crate::counter;
+use crate::counter::{self, CloseReason};
+#[cfg(feature = "deadlock-detection")]
+use crate::deadlock::{self, Side, WaitFor};
 use crate::err::{
-    RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+    RecvCancelError, RecvError, RecvPoisonError, RecvTimeoutError, SendCancelError, SendError,
+    SendTimeoutError, TryRecvError, TrySendError,
 };
+use crate::events::{ChannelEvent, EventKind};
+#[cfg(all(unix, feature = "fd"))]
+use crate::fd::ReadyFd;
 use crate::flavors;
-use crate::select::{Operation, SelectHandle, Token};
+use crate::flavors::tick::{MissedTicks, Ticker};
+#[cfg(feature = "async")]
+use crate::future::{self, ReadyChunks, RecvFut, SendFut};
+#[cfg(feature = "timestamps")]
+use crate::latency::LatencyHistogram;
+use crate::metrics::{ChannelMetrics, Metrics};
+use crate::put_back::PutBackReceiver;
+use crate::select::{Operation, Select, SelectHandle, Token};
+#[cfg(feature = "async")]
+use crate::sink::SendSink;
+use crate::spin::SpinPolicy;
+#[cfg(feature = "stats")]
+use crate::stats::{ChannelStats, Counters};
 use crate::utils;
+use crate::waker::Listener;
 
 /// Creates a channel of unbounded capacity.
 ///
@@ -44,7 +75,112 @@ use crate::utils;
 /// println!("{}", r.recv().unwrap());
 /// ```
 pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
-    let (s, r) = counter::new(flavors::list::Channel::new());
+    unbounded_with_spin(SpinPolicy::default())
+}
+
+/// Creates a channel of unbounded capacity whose blocked receivers spin according to `spin`.
+///
+/// This works like [`unbounded`], except that receivers waiting for a message follow `spin`
+/// before their thread is parked.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::{unbounded_with_spin, SpinPolicy};
+///
+/// // Receivers are rarely woken up, so don't spend CPU time spinning.
+/// let (s, r) = unbounded_with_spin(SpinPolicy::park());
+///
+/// thread::spawn(move || s.send("done").unwrap());
+/// assert_eq!(r.recv(), Ok("done"));
+/// ```
+pub fn unbounded_with_spin<T>(spin: SpinPolicy) -> (Sender<T>, Receiver<T>) {
+    new_unbounded(spin, None, false)
+}
+
+/// Creates a channel of unbounded capacity that reports its events to `metrics`.
+///
+/// This works like [`unbounded`], except that `metrics` is notified of every message sent and
+/// received, every blocked operation, and the destruction of the channel. Each callback receives
+/// `name`, which tells apart channels that share the same `metrics`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use crossbeam_channel::{unbounded_with_metrics, ChannelMetrics};
+///
+/// #[derive(Default)]
+/// struct Received(AtomicUsize);
+///
+/// impl ChannelMetrics for Received {
+///     fn on_recv(&self, _name: &str, _len: usize) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let received = Arc::new(Received::default());
+/// let (s, r) = unbounded_with_metrics("events", received.clone());
+///
+/// s.send(1).unwrap();
+/// s.send(2).unwrap();
+/// assert_eq!(r.try_iter().count(), 2);
+/// assert_eq!(received.0.load(Ordering::Relaxed), 2);
+/// ```
+pub fn unbounded_with_metrics<T>(
+    name: &str,
+    metrics: Arc<dyn ChannelMetrics>,
+) -> (Sender<T>, Receiver<T>) {
+    new_unbounded(
+        SpinPolicy::default(),
+        Some(Metrics::new(name, metrics)),
+        false,
+    )
+}
+
+/// Creates a channel of unbounded capacity that stays open when all senders are dropped.
+///
+/// This works like [`unbounded`], except that dropping the last sender doesn't disconnect the
+/// channel: receivers just find it empty, and can create new senders with
+/// [`Receiver::new_sender`]. This suits long-lived mailboxes that producers come and go from.
+///
+/// The channel is still disconnected when all receivers are dropped, or when it is [closed].
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{unbounded_keep_open, TryRecvError};
+///
+/// let (s, r) = unbounded_keep_open();
+/// s.send(1).unwrap();
+/// drop(s);
+///
+/// assert_eq!(r.try_recv(), Ok(1));
+/// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+///
+/// let s = r.new_sender().unwrap();
+/// s.send(2).unwrap();
+/// assert_eq!(r.try_recv(), Ok(2));
+/// ```
+///
+/// [closed]: Sender::close
+pub fn unbounded_keep_open<T>() -> (Sender<T>, Receiver<T>) {
+    new_unbounded(SpinPolicy::default(), None, true)
+}
+
+/// Creates an unbounded channel, which stays open without senders if `keep_open` is `true`.
+fn new_unbounded<T>(
+    spin: SpinPolicy,
+    metrics: Option<Metrics>,
+    keep_open: bool,
+) -> (Sender<T>, Receiver<T>) {
+    let instrumented = metrics.is_some();
+    let (s, r) = counter::new(flavors::list::Channel::new(spin, metrics), keep_open);
+    if instrumented {
+        s.instrument();
+    }
     let s = Sender {
         flavor: SenderFlavor::List(s),
     };
@@ -61,6 +197,10 @@ pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
 /// A special case is zero-capacity channel, which cannot hold any messages. Instead, send and
 /// receive operations must appear at the same time in order to pair up and pass the message over.
 ///
+/// Messages of a zero-sized type, like `()`, hold no data, so a channel of them doesn't allocate
+/// a buffer at all and only counts the messages it holds. This makes `bounded::<()>(n)` a cheap
+/// way to pass signals or permits between threads.
+///
 /// # Examples
 ///
 /// A channel of capacity 1:
@@ -105,8 +245,169 @@ pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
 /// assert_eq!(r.recv(), Ok(1));
 /// ```
 pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    bounded_with_spin(cap, SpinPolicy::default())
+}
+
+/// Creates a channel of bounded capacity whose blocked operations spin according to `spin`.
+///
+/// This works like [`bounded`], except that senders waiting for room and receivers waiting for a
+/// message follow `spin` before their thread is parked. Operations waiting in a [`Select`] are
+/// not affected and always use the default policy.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::{bounded_with_spin, SpinPolicy};
+///
+/// // Spin longer than usual, because the other side answers within microseconds.
+/// let (s, r) = bounded_with_spin(1, SpinPolicy::new(10, 10));
+///
+/// thread::spawn(move || {
+///     for i in 0..3 {
+///         s.send(i).unwrap();
+///     }
+/// });
+///
+/// assert_eq!(r.iter().collect::<Vec<_>>(), [0, 1, 2]);
+/// ```
+pub fn bounded_with_spin<T>(cap: usize, spin: SpinPolicy) -> (Sender<T>, Receiver<T>) {
+    new_bounded(cap, spin, false, None, false)
+}
+
+/// Creates a channel of bounded capacity whose buffer gives every message its own cache line.
+///
+/// This works like [`bounded`], except that the buffer is laid out so that no two messages share
+/// a cache line. When messages are small, senders and receivers working on neighboring messages
+/// otherwise keep invalidating each other's caches, which slows down channels that many threads
+/// use at once.
+///
+/// Padding makes the buffer take at least a cache line per message, and helps most when messages
+/// are only a few bytes long. Messages that already fill a cache line are stored as they are. A
+/// zero-capacity channel has no buffer, so it isn't affected.
+///
+/// # Panics
+///
+/// Panics if the padded buffer would be larger than the address space.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::bounded_padded;
+///
+/// let (s, r) = bounded_padded(64);
+///
+/// for _ in 0..4 {
+///     let s = s.clone();
+///     thread::spawn(move || {
+///         for i in 0..100 {
+///             s.send(i).unwrap();
+///         }
+///     });
+/// }
+/// drop(s);
+///
+/// assert_eq!(r.iter().count(), 400);
+/// ```
+pub fn bounded_padded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    new_bounded(cap, SpinPolicy::default(), true, None, false)
+}
+
+/// Creates a channel of bounded capacity that reports its events to `metrics`.
+///
+/// This works like [`bounded`], except that `metrics` is notified of every message sent and
+/// received, every blocked operation, and the destruction of the channel. Each callback receives
+/// `name`, which tells apart channels that share the same `metrics`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{bounded_with_metrics, ChannelMetrics};
+///
+/// #[derive(Default)]
+/// struct Blocked(AtomicUsize);
+///
+/// impl ChannelMetrics for Blocked {
+///     fn on_block(&self, name: &str, len: usize) {
+///         assert_eq!((name, len), ("requests", 1));
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let blocked = Arc::new(Blocked::default());
+/// let (s, r) = bounded_with_metrics(1, "requests", blocked.clone());
+/// s.send(1).unwrap();
+///
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     r.recv().unwrap();
+///     r.recv().unwrap();
+/// });
+///
+/// // The channel is full, so this blocks until the other thread receives.
+/// s.send(2).unwrap();
+/// assert_eq!(blocked.0.load(Ordering::Relaxed), 1);
+/// ```
+pub fn bounded_with_metrics<T>(
+    cap: usize,
+    name: &str,
+    metrics: Arc<dyn ChannelMetrics>,
+) -> (Sender<T>, Receiver<T>) {
+    new_bounded(
+        cap,
+        SpinPolicy::default(),
+        false,
+        Some(Metrics::new(name, metrics)),
+        false,
+    )
+}
+
+/// Creates a channel of bounded capacity that stays open when all senders are dropped.
+///
+/// This works like [`bounded`], except that dropping the last sender doesn't disconnect the
+/// channel. See [`unbounded_keep_open`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::bounded_keep_open;
+///
+/// let (s, r) = bounded_keep_open(4);
+/// drop(s);
+///
+/// // Producers come and go, each with a sender of its own.
+/// for i in 0..3 {
+///     let s = r.new_sender().unwrap();
+///     thread::spawn(move || s.send(i).unwrap()).join().unwrap();
+///     assert_eq!(r.recv(), Ok(i));
+/// }
+/// assert_eq!(r.sender_count(), 0);
+/// ```
+pub fn bounded_keep_open<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    new_bounded(cap, SpinPolicy::default(), false, None, true)
+}
+
+/// Creates a bounded channel, padding the slots of its buffer if `padded` is `true`, which stays
+/// open without senders if `keep_open` is `true`.
+fn new_bounded<T>(
+    cap: usize,
+    spin: SpinPolicy,
+    padded: bool,
+    metrics: Option<Metrics>,
+    keep_open: bool,
+) -> (Sender<T>, Receiver<T>) {
+    let instrumented = metrics.is_some();
     if cap == 0 {
-        let (s, r) = counter::new(flavors::zero::Channel::new());
+        let (s, r) = counter::new(flavors::zero::Channel::new(spin, metrics), keep_open);
+        if instrumented {
+            s.instrument();
+        }
         let s = Sender {
             flavor: SenderFlavor::Zero(s),
         };
@@ -115,7 +416,13 @@ pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
         };
         (s, r)
     } else {
-        let (s, r) = counter::new(flavors::array::Channel::with_capacity(cap));
+        let (s, r) = counter::new(
+            flavors::array::Channel::with_capacity(cap, spin, padded, metrics),
+            keep_open,
+        );
+        if instrumented {
+            s.instrument();
+        }
         let s = Sender {
             flavor: SenderFlavor::Array(s),
         };
@@ -173,7 +480,10 @@ pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
 /// ```
 pub fn after(duration: Duration) -> Receiver<Instant> {
     Receiver {
-        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_timeout(duration))),
+        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_timeout(
+            duration,
+            TimerClock::system(),
+        ))),
     }
 }
 
@@ -221,7 +531,10 @@ pub fn after(duration: Duration) -> Receiver<Instant> {
 /// ```
 pub fn at(when: Instant) -> Receiver<Instant> {
     Receiver {
-        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_deadline(when))),
+        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_deadline(
+            when,
+            TimerClock::system(),
+        ))),
     }
 }
 
@@ -266,12 +579,179 @@ pub fn never<T>() -> Receiver<T> {
     }
 }
 
+/// Creates a receiver that delivers a message after a certain duration of time measured by
+/// `clock`.
+///
+/// This is the same as [`after`], except that time is read from `clock`. On a manual clock like
+/// [`MockClock`], the message is delivered once the clock has been advanced by `duration`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{after_with_clock, MockClock};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let r = after_with_clock(Duration::from_secs(3600), clock.clone());
+///
+/// let c = clock.clone();
+/// thread::spawn(move || c.advance(Duration::from_secs(3600)));
+///
+/// // Returns as soon as the clock is advanced, without waiting for an hour.
+/// r.recv().unwrap();
+/// ```
+///
+/// [`MockClock`]: crate::MockClock
+pub fn after_with_clock<C: Clock + 'static>(
+    duration: Duration,
+    clock: Arc<C>,
+) -> Receiver<Instant> {
+    Receiver {
+        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_timeout(
+            duration,
+            TimerClock::new(clock),
+        ))),
+    }
+}
+
+/// Creates a receiver that delivers a message at a certain instant in time measured by `clock`.
+///
+/// This is the same as [`at`], except that time is read from `clock`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use crossbeam_channel::{at_with_clock, Clock, MockClock, TryRecvError};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let when = clock.now() + Duration::from_secs(10);
+/// let r = at_with_clock(when, clock.clone());
+///
+/// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+/// clock.advance(Duration::from_secs(10));
+/// assert_eq!(r.try_recv(), Ok(when));
+/// ```
+pub fn at_with_clock<C: Clock + 'static>(when: Instant, clock: Arc<C>) -> Receiver<Instant> {
+    Receiver {
+        flavor: ReceiverFlavor::At(Arc::new(flavors::at::Channel::new_deadline(
+            when,
+            TimerClock::new(clock),
+        ))),
+    }
+}
+
+/// Creates a receiver that delivers messages periodically, with time measured by `clock`.
+///
+/// This is the same as [`tick_with`], except that time is read from `clock`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use crossbeam_channel::{tick_with_clock, Clock, MissedTicks, MockClock, TryRecvError};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let start = clock.now();
+/// let r = tick_with_clock(Duration::from_secs(1), MissedTicks::Burst, clock.clone());
+///
+/// clock.advance(Duration::from_secs(3));
+/// assert_eq!(r.try_recv(), Ok(start + Duration::from_secs(1)));
+/// assert_eq!(r.try_recv(), Ok(start + Duration::from_secs(2)));
+/// assert_eq!(r.try_recv(), Ok(start + Duration::from_secs(3)));
+/// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+/// ```
+pub fn tick_with_clock<C: Clock + 'static>(
+    duration: Duration,
+    missed: MissedTicks,
+    clock: Arc<C>,
+) -> Receiver<Instant> {
+    Receiver {
+        flavor: ReceiverFlavor::Tick(Arc::new(flavors::tick::Channel::new(
+            duration,
+            missed,
+            TimerClock::new(clock),
+        ))),
+    }
+}
+
+/// Creates a receiver that delivers a message at each instant produced by `times`.
+///
+/// The channel is bounded with capacity of 1. A message is sent into the channel at every instant
+/// in `times`, in order, and the message is the instant itself. Instants that are already in the
+/// past are delivered right away. Once the last message is received, the channel becomes
+/// disconnected. The iterator may also be infinite, in which case the channel never gets
+/// disconnected.
+///
+/// Instants are taken from the iterator lazily, one at a time, so a schedule can be computed on
+/// the fly from a calendar or a job queue.
+///
+/// # Examples
+///
+/// Selecting on the next scheduled job alongside other work:
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use crossbeam_channel::{schedule, select, unbounded};
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let (_s, work) = unbounded::<i32>();
+/// let start = Instant::now();
+/// let jobs = schedule(vec![start + ms(50), start + ms(100), start + ms(200)]);
+///
+/// let mut runs = 0;
+/// loop {
+///     select! {
+///         recv(work) -> msg => println!("work: {:?}", msg),
+///         recv(jobs) -> when => match when {
+///             Ok(when) => {
+///                 assert!(Instant::now() >= when);
+///                 runs += 1;
+///             }
+///             Err(_) => break,
+///         },
+///     }
+/// }
+/// assert_eq!(runs, 3);
+/// ```
+///
+/// An infinite schedule firing at the top of every second:
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use crossbeam_channel::schedule;
+///
+/// let start = Instant::now();
+/// let r = schedule((1..).map(move |i| start + Duration::from_secs(i)));
+///
+/// assert_eq!(r.recv(), Ok(start + Duration::from_secs(1)));
+/// ```
+pub fn schedule<I>(times: I) -> Receiver<Instant>
+where
+    I: IntoIterator<Item = Instant>,
+    I::IntoIter: Send + 'static,
+{
+    Receiver {
+        flavor: ReceiverFlavor::Schedule(Arc::new(flavors::schedule::Channel::new(
+            times.into_iter(),
+        ))),
+    }
+}
+
 /// Creates a receiver that delivers messages periodically.
 ///
 /// The channel is bounded with capacity of 1 and never gets disconnected. Messages will be
 /// sent into the channel in intervals of `duration`. Each message is the instant at which it is
 /// sent.
 ///
+/// If a message is received late, the next one is sent a full interval later. Other behaviors can
+/// be chosen with [`tick_with`].
+///
 /// # Examples
 ///
 /// Using a `tick` channel to periodically print elapsed time:
@@ -320,11 +800,75 @@ pub fn never<T>() -> Receiver<T> {
 /// assert!(eq(Instant::now(), start + ms(700)));
 /// ```
 pub fn tick(duration: Duration) -> Receiver<Instant> {
+    tick_with(duration, MissedTicks::Delay)
+}
+
+/// Creates a receiver that delivers messages periodically, handling missed ticks according to
+/// the given policy.
+///
+/// This is the same as [`tick`], except that `missed` decides when messages get sent after a
+/// message is received late. See [`MissedTicks`] for the available policies.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{tick_with, MissedTicks};
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let r = tick_with(ms(100), MissedTicks::Skip);
+///
+/// thread::sleep(ms(350));
+///
+/// // The first tick is delivered late, and the two after it are skipped.
+/// let first = r.recv().unwrap();
+/// assert_eq!(r.recv().unwrap(), first + ms(300));
+/// ```
+pub fn tick_with(duration: Duration, missed: MissedTicks) -> Receiver<Instant> {
     Receiver {
-        flavor: ReceiverFlavor::Tick(Arc::new(flavors::tick::Channel::new(duration))),
+        flavor: ReceiverFlavor::Tick(Arc::new(flavors::tick::Channel::new(
+            duration,
+            missed,
+            TimerClock::system(),
+        ))),
     }
 }
 
+/// Creates a receiver that delivers messages periodically, along with a [`Ticker`] controlling it.
+///
+/// The receiver behaves like one created with [`tick`]. The ticker can pause, resume, and reset
+/// its schedule at any time, and receivers blocked on the channel follow the change right away.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use crossbeam_channel::ticker;
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let (ticker, r) = ticker(ms(1000));
+///
+/// // Speed up the ticker. The next message is now sent 50 ms after the reset.
+/// let start = Instant::now();
+/// ticker.reset(ms(50));
+/// r.recv().unwrap();
+/// assert!(start.elapsed() < ms(500));
+/// ```
+pub fn ticker(duration: Duration) -> (Ticker, Receiver<Instant>) {
+    let chan = Arc::new(flavors::tick::Channel::new(
+        duration,
+        MissedTicks::Delay,
+        TimerClock::system(),
+    ));
+    let r = Receiver {
+        flavor: ReceiverFlavor::Tick(chan.clone()),
+    };
+    (Ticker::new(chan), r)
+}
+
 /// The sending side of a channel.
 ///
 /// # Examples
@@ -389,11 +933,18 @@ impl<T> Sender<T> {
     /// assert_eq!(s.try_send(3), Err(TrySendError::Disconnected(3)));
     /// ```
     pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
-        match &self.flavor {
+        let res = match &self.flavor {
             SenderFlavor::Array(chan) => chan.try_send(msg),
             SenderFlavor::List(chan) => chan.try_send(msg),
             SenderFlavor::Zero(chan) => chan.try_send(msg),
+        };
+        match &res {
+            Ok(()) => self.record_send(1),
+            #[cfg(feature = "stats")]
+            Err(TrySendError::Full(_)) => self.counters().full(),
+            Err(_) => {}
         }
+        res
     }
 
     /// Blocks the current thread until a message is sent or the channel is disconnected.
@@ -425,12 +976,25 @@ impl<T> Sender<T> {
     /// assert_eq!(s.send(3), Err(SendError(3)));
     /// ```
     pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
-        match &self.flavor {
-            SenderFlavor::Array(chan) => chan.send(msg, None),
-            SenderFlavor::List(chan) => chan.send(msg, None),
-            SenderFlavor::Zero(chan) => chan.send(msg, None),
+        #[cfg(not(feature = "deadlock-detection"))]
+        let res = self.send_until(msg, None);
+        #[cfg(feature = "deadlock-detection")]
+        let res = {
+            let mut msg = Some(msg);
+            deadlock::watch(
+                |deadline| match self.send_until(msg.take().unwrap(), Some(deadline)) {
+                    Err(SendTimeoutError::Timeout(m)) => {
+                        msg = Some(m);
+                        Err(self.wait_for().into_iter().collect())
+                    }
+                    res => Ok(res),
+                },
+            )
+        };
+        if res.is_ok() {
+            self.record_send(1);
         }
-        .map_err(|err| match err {
+        res.map_err(|err| match err {
             SendTimeoutError::Disconnected(msg) => SendError(msg),
             SendTimeoutError::Timeout(_) => unreachable!(),
         })
@@ -517,10 +1081,71 @@ impl<T> Sender<T> {
     /// );
     /// ```
     pub fn send_deadline(&self, msg: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        let res = self.send_until(msg, Some(deadline));
+        if res.is_ok() {
+            self.record_send(1);
+        }
+        res
+    }
+
+    /// Blocks the current thread until a message is sent or the deadline is reached.
+    fn send_until(&self, msg: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
         match &self.flavor {
-            SenderFlavor::Array(chan) => chan.send(msg, Some(deadline)),
-            SenderFlavor::List(chan) => chan.send(msg, Some(deadline)),
-            SenderFlavor::Zero(chan) => chan.send(msg, Some(deadline)),
+            SenderFlavor::Array(chan) => chan.send(msg, deadline),
+            SenderFlavor::List(chan) => chan.send(msg, deadline),
+            SenderFlavor::Zero(chan) => chan.send(msg, deadline),
+        }
+    }
+
+    /// Blocks the current thread until a message is sent or the operation is cancelled.
+    ///
+    /// If the channel is full and not disconnected, this call will block until the send operation
+    /// can proceed or the token observed by `signal` is cancelled. If the channel becomes
+    /// disconnected, this call will wake up and return an error. The returned error contains the
+    /// original message.
+    ///
+    /// Cancellation takes priority: if the token is already cancelled, the message is not sent even
+    /// if the channel has room for it.
+    ///
+    /// If called on a zero-capacity channel, this method will wait for a receive operation to
+    /// appear on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{bounded, cancel_token, SendCancelError};
+    ///
+    /// let (s, r) = bounded(0);
+    /// let (token, signal) = cancel_token();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     token.cancel();
+    /// });
+    ///
+    /// assert_eq!(
+    ///     s.send_cancellable(1, &signal),
+    ///     Err(SendCancelError::Cancelled(1)),
+    /// );
+    /// drop(r);
+    /// ```
+    pub fn send_cancellable(
+        &self,
+        msg: T,
+        signal: &CancelSignal,
+    ) -> Result<(), SendCancelError<T>> {
+        let mut sel = Select::new_biased();
+        let cancel = sel.custom(signal);
+        sel.send(self);
+
+        let oper = sel.select();
+        if oper.index() == cancel {
+            oper.custom(signal);
+            Err(SendCancelError::Cancelled(msg))
+        } else {
+            oper.send(self, msg).map_err(SendCancelError::from)
         }
     }
 
@@ -616,326 +1241,1804 @@ impl<T> Sender<T> {
         }
     }
 
-    /// Returns `true` if senders belong to the same channel.
+    /// Starts keeping track of the highest number of messages the channel has held.
     ///
-    /// # Examples
+    /// From then on the channel checks its length after every sent message, so that
+    /// [`max_len_seen`] reports peaks that polling [`len`] would likely miss. Tracking starts from
+    /// the current length and costs a length check on every send, so channels don't do it unless
+    /// asked to.
     ///
-    /// ```rust
-    /// use crossbeam_channel::unbounded;
+    /// The channel has a single watermark, so this affects all senders and receivers.
     ///
-    /// let (s, _) = unbounded::<usize>();
+    /// [`max_len_seen`]: Sender::max_len_seen
+    /// [`len`]: Sender::len
+    pub fn track_max_len(&self) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => {
+                chan.track_max_len();
+                chan.instrument();
+            }
+            SenderFlavor::List(chan) => {
+                chan.track_max_len();
+                chan.instrument();
+            }
+            SenderFlavor::Zero(_) => {}
+        }
+    }
+
+    /// Returns the highest number of messages the channel has held since [`track_max_len`] was
+    /// called.
     ///
-    /// let s2 = s.clone();
+    /// Until then, this returns the current length of the channel. Zero-capacity channels never
+    /// hold messages and always return 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// s.track_max_len();
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    /// s.send(3).unwrap();
+    /// r.recv().unwrap();
+    /// r.recv().unwrap();
+    ///
+    /// assert_eq!(s.len(), 1);
+    /// assert_eq!(s.max_len_seen(), 3);
+    ///
+    /// s.reset_max_len_seen();
+    /// assert_eq!(s.max_len_seen(), 1);
+    /// ```
+    ///
+    /// [`track_max_len`]: Sender::track_max_len
+    pub fn max_len_seen(&self) -> usize {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.max_len_seen(),
+            SenderFlavor::List(chan) => chan.max_len_seen(),
+            SenderFlavor::Zero(_) => 0,
+        }
+    }
+
+    /// Resets the value returned by [`max_len_seen`] to the current length of the channel.
+    ///
+    /// The channel has a single watermark, so this affects all senders and receivers.
+    ///
+    /// [`max_len_seen`]: Sender::max_len_seen
+    pub fn reset_max_len_seen(&self) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.reset_max_len_seen(),
+            SenderFlavor::List(chan) => chan.reset_max_len_seen(),
+            SenderFlavor::Zero(_) => {}
+        }
+    }
+
+    /// Returns a histogram of how long received messages waited in the channel.
+    ///
+    /// Every message is timestamped when it is sent, and its latency is counted when it is
+    /// received. Zero-capacity channels hand messages over directly and return `None`. Messages of
+    /// zero-sized types aren't timestamped, so their histogram stays empty.
+    ///
+    /// This method is only available with the `timestamps` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(1);
+    /// s.send(1).unwrap();
+    /// thread::sleep(Duration::from_millis(100));
+    /// r.recv().unwrap();
+    ///
+    /// let latency = s.latency().unwrap();
+    /// assert_eq!(latency.count(), 1);
+    /// assert!(latency.min().unwrap() >= Duration::from_millis(95));
+    /// ```
+    #[cfg(feature = "timestamps")]
+    pub fn latency(&self) -> Option<LatencyHistogram> {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => Some(chan.latency()),
+            SenderFlavor::List(chan) => Some(chan.latency()),
+            SenderFlavor::Zero(_) => None,
+        }
+    }
+
+    /// Empties the histogram returned by [`latency`].
+    ///
+    /// The channel has a single histogram, so this affects all senders and receivers.
+    ///
+    /// This method is only available with the `timestamps` feature enabled.
+    ///
+    /// [`latency`]: Sender::latency
+    #[cfg(feature = "timestamps")]
+    pub fn reset_latency(&self) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.reset_latency(),
+            SenderFlavor::List(chan) => chan.reset_latency(),
+            SenderFlavor::Zero(_) => {}
+        }
+    }
+
+    /// Starts recording the channel's events, keeping the last `capacity` of them.
+    ///
+    /// Sent and received messages, threads parking in blocking operations, and disconnection of
+    /// either side are recorded along with the thread and the time, and can be dumped with
+    /// [`events`] when an intermittent ordering bug shows up. Events recorded so far are
+    /// forgotten. A `capacity` of zero stops recording.
+    ///
+    /// The channel has a single log, so this affects all senders and receivers. Recording takes a
+    /// lock on every operation, so it is meant for debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, EventKind};
+    ///
+    /// let (s, r) = unbounded();
+    /// s.record_events(2);
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    /// r.recv().unwrap();
+    ///
+    /// let kinds: Vec<_> = s.events().iter().map(|e| e.kind()).collect();
+    /// assert_eq!(kinds, [EventKind::Send, EventKind::Recv]);
+    /// ```
+    ///
+    /// [`events`]: Sender::events
+    pub fn record_events(&self, capacity: usize) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+            SenderFlavor::List(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+            SenderFlavor::Zero(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+        }
+    }
+
+    /// Returns the events recorded since [`record_events`] was called, oldest first.
+    ///
+    /// [`record_events`]: Sender::record_events
+    pub fn events(&self) -> Vec<ChannelEvent> {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.events().snapshot(),
+            SenderFlavor::List(chan) => chan.events().snapshot(),
+            SenderFlavor::Zero(chan) => chan.events().snapshot(),
+        }
+    }
+
+    /// Returns counts of the operations performed on the channel since it was created.
+    ///
+    /// The counts include messages sent and received by all senders and receivers, and the
+    /// number of times [`try_send`] and [`Receiver::try_recv`] failed because the channel was full
+    /// or empty.
+    ///
+    /// This method is only available with the `stats` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(2);
+    /// for i in 0..3 {
+    ///     let _ = s.try_send(i);
+    /// }
+    /// assert_eq!(r.try_iter().count(), 2);
+    ///
+    /// let stats = s.stats();
+    /// assert_eq!(stats.sent(), 2);
+    /// assert_eq!(stats.received(), 2);
+    /// assert_eq!(stats.rejected_full(), 1);
+    /// ```
+    ///
+    /// [`try_send`]: Sender::try_send
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> ChannelStats {
+        self.counters().snapshot()
+    }
+
+    /// Returns the number of threads blocked sending into the channel.
+    ///
+    /// This counts threads blocked in [`send`] and similar methods, as well as threads blocked in
+    /// a [`Select`] with a send operation on this channel. Unbounded channels never block senders.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(1);
+    /// s.send(1).unwrap();
+    /// assert_eq!(s.blocked_senders(), 0);
+    ///
+    /// let s2 = s.clone();
+    /// let t = thread::Builder::new()
+    ///     .name("producer".to_string())
+    ///     .spawn(move || s2.send(2).unwrap())
+    ///     .unwrap();
+    ///
+    /// thread::sleep(Duration::from_millis(100));
+    /// assert_eq!(s.blocked_senders(), 1);
+    /// assert_eq!(s.blocked_sender_names(), ["producer"]);
+    ///
+    /// r.recv().unwrap();
+    /// t.join().unwrap();
+    /// assert_eq!(s.blocked_senders(), 0);
+    /// ```
+    ///
+    /// [`send`]: Sender::send
+    /// [`Select`]: crate::Select
+    pub fn blocked_senders(&self) -> usize {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.blocked_senders(),
+            SenderFlavor::List(_) => 0,
+            SenderFlavor::Zero(chan) => chan.blocked_senders(),
+        }
+    }
+
+    /// Returns the number of threads blocked receiving from the channel.
+    ///
+    /// This counts threads blocked in [`Receiver::recv`] and similar methods, as well as threads
+    /// blocked in a [`Select`] with a receive operation on this channel.
+    ///
+    /// [`Select`]: crate::Select
+    pub fn blocked_receivers(&self) -> usize {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.blocked_receivers(),
+            SenderFlavor::List(chan) => chan.blocked_receivers(),
+            SenderFlavor::Zero(chan) => chan.blocked_receivers(),
+        }
+    }
+
+    /// Returns the names of threads blocked sending into the channel.
+    ///
+    /// Threads without a name are listed by their [`ThreadId`], like `ThreadId(3)`. A thread that
+    /// is blocked on several operations on this channel is listed once for each of them.
+    ///
+    /// [`ThreadId`]: std::thread::ThreadId
+    pub fn blocked_sender_names(&self) -> Vec<String> {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.blocked_sender_names(),
+            SenderFlavor::List(_) => Vec::new(),
+            SenderFlavor::Zero(chan) => chan.blocked_sender_names(),
+        }
+    }
+
+    /// Returns the names of threads blocked receiving from the channel.
+    ///
+    /// Threads without a name are listed by their [`ThreadId`], like `ThreadId(3)`. A thread that
+    /// is blocked on several operations on this channel is listed once for each of them.
+    ///
+    /// [`ThreadId`]: std::thread::ThreadId
+    pub fn blocked_receiver_names(&self) -> Vec<String> {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.blocked_receiver_names(),
+            SenderFlavor::List(chan) => chan.blocked_receiver_names(),
+            SenderFlavor::Zero(chan) => chan.blocked_receiver_names(),
+        }
+    }
+
+    /// Returns `true` if all receivers have been dropped.
+    #[cfg(any(all(unix, feature = "fd"), feature = "futures-compat"))]
+    pub(crate) fn is_disconnected(&self) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.is_disconnected(),
+            SenderFlavor::List(chan) => chan.is_disconnected(),
+            SenderFlavor::Zero(chan) => chan.is_disconnected(),
+        }
+    }
+
+    /// Signals that this producer is done, without closing the channel for the other senders.
+    ///
+    /// This consumes the sender, which is the same as dropping it, but states the intent. Receivers
+    /// can tell how many producers haven't finished yet with [`Receiver::sender_count`], and once
+    /// all of them have finished, receivers get the remaining messages and then see that the
+    /// channel is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, RecvError};
+    ///
+    /// let (s1, r) = unbounded();
+    /// let s2 = s1.clone();
+    /// assert_eq!(r.sender_count(), 2);
+    ///
+    /// s1.send(1).unwrap();
+    /// s1.finish();
+    /// assert_eq!(r.sender_count(), 1);
+    ///
+    /// s2.send(2).unwrap();
+    /// s2.finish();
+    /// assert_eq!(r.sender_count(), 0);
+    ///
+    /// assert_eq!(r.recv(), Ok(1));
+    /// assert_eq!(r.recv(), Ok(2));
+    /// assert_eq!(r.recv(), Err(RecvError));
+    /// ```
+    pub fn finish(self) {
+        drop(self);
+    }
+
+    /// Returns the number of receivers associated with the channel.
+    ///
+    /// The count may already be out of date by the time it is returned if other threads are
+    /// cloning or dropping receivers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    /// let r2 = r.clone();
+    /// assert_eq!(s.receiver_count(), 2);
+    ///
+    /// drop(r);
+    /// drop(r2);
+    /// assert_eq!(s.receiver_count(), 0);
+    /// ```
+    pub fn receiver_count(&self) -> usize {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.receivers(),
+            SenderFlavor::List(chan) => chan.receivers(),
+            SenderFlavor::Zero(chan) => chan.receivers(),
+        }
+    }
+
+    /// Closes the channel.
+    ///
+    /// Once the channel is closed, sending fails for every sender, and so do threads that are
+    /// blocked sending. Messages already in the channel stay there: receivers keep getting them
+    /// until none is left, and only then see that the channel is disconnected.
+    ///
+    /// Returns `true` if this call closed the channel, or `false` if it was already closed or
+    /// disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, RecvError, SendError};
+    ///
+    /// let (s, r) = unbounded();
+    /// s.send(1).unwrap();
+    ///
+    /// assert!(s.close());
+    /// assert!(!s.close());
+    /// assert_eq!(s.send(2), Err(SendError(2)));
+    ///
+    /// assert_eq!(r.recv(), Ok(1));
+    /// assert_eq!(r.recv(), Err(RecvError));
+    /// ```
+    pub fn close(&self) -> bool {
+        self.close_channel(None)
+    }
+
+    /// Closes the channel, recording why.
+    ///
+    /// This is like [`close`], but the reason is kept with the channel and every handle can get
+    /// it from [`close_reason`], which saves passing it along through a separate channel. The
+    /// reason is only recorded if this call closed the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, RecvError};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    /// assert!(s.close_with("config file is missing"));
+    /// assert!(!s.close_with("ignored"));
+    ///
+    /// assert_eq!(r.recv(), Err(RecvError));
+    /// let reason = r.close_reason().unwrap();
+    /// assert_eq!(reason.to_string(), "config file is missing");
+    /// ```
+    ///
+    /// [`close`]: Sender::close
+    /// [`close_reason`]: Receiver::close_reason
+    pub fn close_with<E>(&self, reason: E) -> bool
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.close_channel(Some(Arc::from(reason.into())))
+    }
+
+    /// Closes the channel, recording `reason` if this call closed it.
+    fn close_channel(&self, reason: Option<CloseReason>) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.close(reason, |c| c.disconnect()),
+            SenderFlavor::List(chan) => chan.close(reason, |c| c.disconnect_senders()),
+            SenderFlavor::Zero(chan) => chan.close(reason, |c| c.disconnect()),
+        }
+    }
+
+    /// Returns the reason the channel was closed with, or `None` if it is open, was closed
+    /// without a reason, or was disconnected by dropping handles.
+    ///
+    /// See [`close_with`] for details.
+    ///
+    /// [`close_with`]: Sender::close_with
+    pub fn close_reason(&self) -> Option<Arc<dyn Error + Send + Sync>> {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.close_reason(),
+            SenderFlavor::List(chan) => chan.close_reason(),
+            SenderFlavor::Zero(chan) => chan.close_reason(),
+        }
+    }
+
+    /// Reopens a closed channel, so that it can be used again.
+    ///
+    /// This undoes [`close`] and forgets the [reason] the channel was closed with, which lets a
+    /// pool hand out the same channel again instead of allocating a new one. Threads blocked on
+    /// the channel aren't affected, since they were woken up when it was closed.
+    ///
+    /// The channel is only reopened if it is empty and still has senders and receivers. Returns
+    /// `true` if this call reopened the channel, or `false` if it is open already, still holds
+    /// messages, or was disconnected by dropping all senders or all receivers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(1);
+    /// s.send(1).unwrap();
+    /// s.close_with("done");
+    ///
+    /// // The message has to be received before the channel can be reopened.
+    /// assert!(!s.reopen());
+    /// assert_eq!(r.recv(), Ok(1));
+    /// assert!(s.reopen());
+    ///
+    /// assert!(s.close_reason().is_none());
+    /// s.send(2).unwrap();
+    /// assert_eq!(r.recv(), Ok(2));
+    /// ```
+    ///
+    /// [`close`]: Sender::close
+    /// [reason]: Sender::close_with
+    pub fn reopen(&self) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.reopen(|c| c.reconnect(), |c| c.disconnect()),
+            SenderFlavor::List(chan) => chan.reopen(|c| c.reconnect(), |c| c.disconnect_senders()),
+            SenderFlavor::Zero(chan) => chan.reopen(|c| c.reconnect(), |c| c.disconnect()),
+        }
+    }
+
+    /// Enables poisoning for the channel.
+    ///
+    /// Once poisoning is enabled, a sender that is dropped while its thread is panicking poisons
+    /// the channel, much like a [`MutexGuard`] poisons its mutex. Receivers still get every
+    /// message that was sent, but once the channel is empty and disconnected,
+    /// [`Receiver::recv_checked`] reports that it was poisoned instead of a normal disconnection.
+    ///
+    /// Poisoning can be enabled from either side of the channel and can't be disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use crossbeam_channel::{unbounded, RecvPoisonError};
+    ///
+    /// let (s, r) = unbounded();
+    /// s.poison_on_panic();
+    ///
+    /// let producer = thread::spawn(move || {
+    ///     s.send(1).unwrap();
+    ///     panic!("out of input");
+    /// });
+    /// assert!(producer.join().is_err());
+    ///
+    /// assert_eq!(r.recv_checked(), Ok(1));
+    /// assert_eq!(r.recv_checked(), Err(RecvPoisonError::Poisoned));
+    /// ```
+    ///
+    /// [`MutexGuard`]: std::sync::MutexGuard
+    pub fn poison_on_panic(&self) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.enable_poisoning(),
+            SenderFlavor::List(chan) => chan.enable_poisoning(),
+            SenderFlavor::Zero(chan) => chan.enable_poisoning(),
+        }
+    }
+
+    /// Returns `true` if poisoning is enabled and a sender was dropped while its thread was
+    /// panicking.
+    ///
+    /// See [`poison_on_panic`] for details.
+    ///
+    /// [`poison_on_panic`]: Sender::poison_on_panic
+    pub fn is_poisoned(&self) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.is_poisoned(),
+            SenderFlavor::List(chan) => chan.is_poisoned(),
+            SenderFlavor::Zero(chan) => chan.is_poisoned(),
+        }
+    }
+
+    /// Returns `true` if senders belong to the same channel.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, _) = unbounded::<usize>();
+    ///
+    /// let s2 = s.clone();
     /// assert!(s.same_channel(&s2));
     ///
-    /// let (s3, _) = unbounded();
-    /// assert!(!s.same_channel(&s3));
+    /// let (s3, _) = unbounded();
+    /// assert!(!s.same_channel(&s3));
+    /// ```
+    pub fn same_channel(&self, other: &Sender<T>) -> bool {
+        match (&self.flavor, &other.flavor) {
+            (SenderFlavor::Array(ref a), SenderFlavor::Array(ref b)) => a == b,
+            (SenderFlavor::List(ref a), SenderFlavor::List(ref b)) => a == b,
+            (SenderFlavor::Zero(ref a), SenderFlavor::Zero(ref b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Creates a sender that buffers messages and sends them into the channel in batches.
+    ///
+    /// The buffered sender owns a clone of this sender and holds at most `capacity` messages. See
+    /// [`BufferedSender`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(100);
+    /// let mut buffered = s.buffered(10);
+    ///
+    /// for i in 0..15 {
+    ///     buffered.send(i).unwrap();
+    /// }
+    ///
+    /// // The first 10 messages have been sent as a batch, the rest are still buffered.
+    /// assert_eq!(r.len(), 10);
+    /// assert_eq!(buffered.len(), 5);
+    ///
+    /// buffered.flush().unwrap();
+    /// assert_eq!(r.len(), 15);
+    /// ```
+    pub fn buffered(&self, capacity: usize) -> BufferedSender<T> {
+        BufferedSender::new(self.clone(), capacity)
+    }
+
+    /// Sends all messages from `msgs`, blocking while the channel is full.
+    ///
+    /// If the channel is disconnected, the message that couldn't be sent is returned and the rest
+    /// stay in `msgs`.
+    pub(crate) fn send_all(&self, msgs: &mut vec::Drain<'_, T>) -> Result<(), SendError<T>> {
+        let len = msgs.len();
+        let res =
+            match &self.flavor {
+                SenderFlavor::Array(chan) => chan.send_all(msgs),
+                SenderFlavor::List(chan) => msgs
+                    .try_for_each(|msg| chan.send(msg, None).map_err(SendTimeoutError::into_inner)),
+                SenderFlavor::Zero(chan) => msgs
+                    .try_for_each(|msg| chan.send(msg, None).map_err(SendTimeoutError::into_inner)),
+            };
+
+        // The message in the error was taken out of `msgs` but not sent.
+        let unsent = msgs.len() + res.is_err() as usize;
+        self.record_send(len - unsent);
+        res.map_err(SendError)
+    }
+
+    /// Returns the counters of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> &Counters {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.counters(),
+            SenderFlavor::List(chan) => chan.counters(),
+            SenderFlavor::Zero(chan) => chan.counters(),
+        }
+    }
+
+    /// Records `count` sent messages.
+    fn record_send(&self, count: usize) {
+        let instrumented = match &self.flavor {
+            SenderFlavor::Array(chan) => chan.is_instrumented(),
+            SenderFlavor::List(chan) => chan.is_instrumented(),
+            SenderFlavor::Zero(chan) => chan.is_instrumented(),
+        };
+        if instrumented && count > 0 {
+            self.observe_send(count);
+        }
+
+        #[cfg(feature = "stats")]
+        {
+            self.counters().sent(count);
+        }
+
+        #[cfg(feature = "deadlock-detection")]
+        {
+            if let (true, Some(op)) = (count > 0, self.wait_for()) {
+                op.used();
+            }
+        }
+
+        #[cfg(feature = "sim")]
+        crate::sim::step();
+    }
+
+    /// Reports `count` sent messages to the metrics, event log, watermark, and registry entry of
+    /// the channel, whichever it has.
+    fn observe_send(&self, count: usize) {
+        let (metrics, events, registration) = match &self.flavor {
+            SenderFlavor::Array(chan) => {
+                chan.observe_len();
+                (chan.metrics(), chan.events(), chan.registration())
+            }
+            SenderFlavor::List(chan) => {
+                chan.observe_len();
+                (chan.metrics(), chan.events(), chan.registration())
+            }
+            SenderFlavor::Zero(chan) => (chan.metrics(), chan.events(), chan.registration()),
+        };
+        if let Some(metrics) = metrics {
+            if count == 1 {
+                metrics.send(self.len());
+            } else {
+                metrics.send_batch(count, self.len());
+            }
+        }
+        for _ in 0..count {
+            events.record(EventKind::Send);
+        }
+        if let Some(registration) = registration {
+            registration.sent();
+        }
+    }
+
+    /// Creates a sink for sending messages from async code.
+    ///
+    /// The sink owns a clone of this sender. See [`SendSink`] for details.
+    ///
+    /// This method is only available with the `async` feature enabled.
+    #[cfg(feature = "async")]
+    pub fn sink(&self) -> SendSink<T> {
+        SendSink::new(self.clone())
+    }
+
+    /// Sends a message into the channel from async code.
+    ///
+    /// The returned future waits until there is room in the channel and then sends the message.
+    /// Unlike [`send`], it doesn't block the thread, so a task awaiting it lets other tasks run in
+    /// the meantime. If the channel becomes disconnected, the future resolves to an error
+    /// containing the message.
+    ///
+    /// Dropping the future before it completes drops the message without sending it. To take the
+    /// message back instead, call [`SendFut::into_inner`].
+    ///
+    /// A zero-capacity channel only accepts a message while a thread is blocked receiving from it,
+    /// so this future can't hand a message over to a task awaiting [`Receiver::recv_async`].
+    ///
+    /// This method is only available with the `async` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{SendError, Sender};
+    ///
+    /// async fn produce(s: Sender<i32>) -> Result<(), SendError<i32>> {
+    ///     for i in 0..10 {
+    ///         s.send_async(i).await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`send`]: Sender::send
+    #[cfg(feature = "async")]
+    pub fn send_async(&self, msg: T) -> SendFut<'_, T> {
+        SendFut::new(self, msg)
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.poison_if_panicking(),
+            SenderFlavor::List(chan) => chan.poison_if_panicking(),
+            SenderFlavor::Zero(chan) => chan.poison_if_panicking(),
+        }
+
+        unsafe {
+            match &self.flavor {
+                SenderFlavor::Array(chan) => chan.release(|c| {
+                    c.events().record(EventKind::SendersDisconnected);
+                    c.disconnect()
+                }),
+                SenderFlavor::List(chan) => chan.release(|c| {
+                    c.events().record(EventKind::SendersDisconnected);
+                    c.disconnect_senders()
+                }),
+                SenderFlavor::Zero(chan) => chan.release(|c| {
+                    c.events().record(EventKind::SendersDisconnected);
+                    c.disconnect()
+                }),
+            }
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        let flavor = match &self.flavor {
+            SenderFlavor::Array(chan) => SenderFlavor::Array(chan.acquire()),
+            SenderFlavor::List(chan) => SenderFlavor::List(chan.acquire()),
+            SenderFlavor::Zero(chan) => SenderFlavor::Zero(chan.acquire()),
+        };
+
+        Sender { flavor }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Sender");
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.debug_fields(&mut d),
+            SenderFlavor::List(chan) => chan.debug_fields(&mut d),
+            SenderFlavor::Zero(chan) => chan.debug_fields(&mut d),
+        }
+        d.finish()
+    }
+}
+
+/// The receiving side of a channel.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::unbounded;
+///
+/// let (s, r) = unbounded();
+///
+/// thread::spawn(move || {
+///     let _ = s.send(1);
+///     thread::sleep(Duration::from_secs(1));
+///     let _ = s.send(2);
+/// });
+///
+/// assert_eq!(r.recv(), Ok(1)); // Received immediately.
+/// assert_eq!(r.recv(), Ok(2)); // Received after 1 second.
+/// ```
+pub struct Receiver<T> {
+    flavor: ReceiverFlavor<T>,
+}
+
+/// Receiver flavors.
+enum ReceiverFlavor<T> {
+    /// Bounded channel based on a preallocated array.
+    Array(counter::Receiver<flavors::array::Channel<T>>),
+
+    /// Unbounded channel implemented as a linked list.
+    List(counter::Receiver<flavors::list::Channel<T>>),
+
+    /// Zero-capacity channel.
+    Zero(counter::Receiver<flavors::zero::Channel<T>>),
+
+    /// The after flavor.
+    At(Arc<flavors::at::Channel>),
+
+    /// The tick flavor.
+    Tick(Arc<flavors::tick::Channel>),
+
+    /// The schedule flavor.
+    Schedule(Arc<flavors::schedule::Channel>),
+
+    /// The never flavor.
+    Never(flavors::never::Channel<T>),
+}
+
+unsafe impl<T: Send> Send for Receiver<T> {}
+unsafe impl<T: Send> Sync for Receiver<T> {}
+
+impl<T> UnwindSafe for Receiver<T> {}
+impl<T> RefUnwindSafe for Receiver<T> {}
+
+// Messages live on the heap, so moving a receiver never moves them.
+impl<T> Unpin for Receiver<T> {}
+
+impl<T> Receiver<T> {
+    /// Attempts to receive a message from the channel without blocking.
+    ///
+    /// This method will either receive a message from the channel immediately or return an error
+    /// if the channel is empty.
+    ///
+    /// If called on a zero-capacity channel, this method will receive a message only if there
+    /// happens to be a send operation on the other side of the channel at the same time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, TryRecvError};
+    ///
+    /// let (s, r) = unbounded();
+    /// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    ///
+    /// s.send(5).unwrap();
+    /// drop(s);
+    ///
+    /// assert_eq!(r.try_recv(), Ok(5));
+    /// assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    /// ```
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let res = match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.try_recv(),
+            ReceiverFlavor::List(chan) => chan.try_recv(),
+            ReceiverFlavor::Zero(chan) => chan.try_recv(),
+            ReceiverFlavor::At(chan) => {
+                let msg = chan.try_recv();
+                unsafe {
+                    mem::transmute_copy::<Result<Instant, TryRecvError>, Result<T, TryRecvError>>(
+                        &msg,
+                    )
+                }
+            }
+            ReceiverFlavor::Tick(chan) => {
+                let msg = chan.try_recv();
+                unsafe {
+                    mem::transmute_copy::<Result<Instant, TryRecvError>, Result<T, TryRecvError>>(
+                        &msg,
+                    )
+                }
+            }
+            ReceiverFlavor::Schedule(chan) => {
+                let msg = chan.try_recv();
+                unsafe {
+                    mem::transmute_copy::<Result<Instant, TryRecvError>, Result<T, TryRecvError>>(
+                        &msg,
+                    )
+                }
+            }
+            ReceiverFlavor::Never(chan) => chan.try_recv(),
+        };
+        match &res {
+            Ok(_) => self.record_recv(),
+            #[cfg(feature = "stats")]
+            Err(TryRecvError::Empty) => {
+                if let Some(counters) = self.counters() {
+                    counters.empty();
+                }
+            }
+            Err(_) => {}
+        }
+        res
+    }
+
+    /// Blocks the current thread until a message is received or the channel is empty and
+    /// disconnected.
+    ///
+    /// If the channel is empty and not disconnected, this call will block until the receive
+    /// operation can proceed. If the channel is empty and becomes disconnected, this call will
+    /// wake up and return an error.
+    ///
+    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
+    /// on the other side of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, RecvError};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(1));
+    ///     s.send(5).unwrap();
+    ///     drop(s);
+    /// });
+    ///
+    /// assert_eq!(r.recv(), Ok(5));
+    /// assert_eq!(r.recv(), Err(RecvError));
+    /// ```
+    pub fn recv(&self) -> Result<T, RecvError> {
+        #[cfg(not(feature = "deadlock-detection"))]
+        let res = self.recv_until(None);
+        #[cfg(feature = "deadlock-detection")]
+        let res = deadlock::watch(|deadline| match self.recv_until(Some(deadline)) {
+            Err(RecvTimeoutError::Timeout) => Err(self.wait_for().into_iter().collect()),
+            res => Ok(res),
+        });
+        if res.is_ok() {
+            self.record_recv();
+        }
+        res.map_err(|_| RecvError)
+    }
+
+    /// Receives a message from the channel in async code.
+    ///
+    /// The returned future resolves to the next message, or to an error once the channel is empty
+    /// and disconnected. Unlike [`recv`], it doesn't block the thread, so a task awaiting it lets
+    /// other tasks run in the meantime. Dropping the future never loses a message.
+    ///
+    /// This method is only available with the `async` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::Receiver;
+    ///
+    /// async fn sum(r: Receiver<i32>) -> i32 {
+    ///     let mut sum = 0;
+    ///     while let Ok(n) = r.recv_async().await {
+    ///         sum += n;
+    ///     }
+    ///     sum
+    /// }
+    /// ```
+    ///
+    /// [`recv`]: Receiver::recv
+    #[cfg(feature = "async")]
+    pub fn recv_async(&self) -> RecvFut<'_, T> {
+        RecvFut::new(self)
+    }
+
+    /// Turns the receiver into a stream that yields the available messages in batches.
+    ///
+    /// Each batch contains every message that was in the channel when the stream was polled, up to
+    /// `max` of them, so an async consumer can process messages in bulk whenever they pile up. See
+    /// [`ReadyChunks`] for details.
+    ///
+    /// This method is only available with the `async` feature enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// for i in 0..5 {
+    ///     s.send(i).unwrap();
+    /// }
+    ///
+    /// // When polled, this stream yields `[0, 1, 2]` and then `[3, 4]`.
+    /// let chunks = r.ready_chunks(3);
+    /// assert_eq!(chunks.receiver().len(), 5);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn ready_chunks(self, max: usize) -> ReadyChunks<T> {
+        ReadyChunks::new(self, max)
+    }
+
+    /// Waits for a message to be received from the channel, but only for a limited time.
+    ///
+    /// If the channel is empty and not disconnected, this call will block until the receive
+    /// operation can proceed or the operation times out. If the channel is empty and becomes
+    /// disconnected, this call will wake up and return an error.
+    ///
+    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
+    /// on the other side of the channel.
+    ///
+    /// A timeout never loses a message: a message is only taken out of the channel once it is
+    /// certain to be returned, so when this call times out, every message is still in the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, RecvTimeoutError};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(1));
+    ///     s.send(5).unwrap();
+    ///     drop(s);
+    /// });
+    ///
+    /// assert_eq!(
+    ///     r.recv_timeout(Duration::from_millis(500)),
+    ///     Err(RecvTimeoutError::Timeout),
+    /// );
+    /// assert_eq!(
+    ///     r.recv_timeout(Duration::from_secs(1)),
+    ///     Ok(5),
+    /// );
+    /// assert_eq!(
+    ///     r.recv_timeout(Duration::from_secs(1)),
+    ///     Err(RecvTimeoutError::Disconnected),
+    /// );
+    /// ```
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline(utils::convert_timeout_to_deadline(timeout))
+    }
+
+    /// Waits for a message to be received from the channel, but only before a given deadline.
+    ///
+    /// If the channel is empty and not disconnected, this call will block until the receive
+    /// operation can proceed or the operation times out. If the channel is empty and becomes
+    /// disconnected, this call will wake up and return an error.
+    ///
+    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
+    /// on the other side of the channel.
+    ///
+    /// A timeout never loses a message: a message is only taken out of the channel once it is
+    /// certain to be returned, so when this call times out, every message is still in the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::{Instant, Duration};
+    /// use crossbeam_channel::{unbounded, RecvTimeoutError};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(1));
+    ///     s.send(5).unwrap();
+    ///     drop(s);
+    /// });
+    ///
+    /// let now = Instant::now();
+    ///
+    /// assert_eq!(
+    ///     r.recv_deadline(now + Duration::from_millis(500)),
+    ///     Err(RecvTimeoutError::Timeout),
+    /// );
+    /// assert_eq!(
+    ///     r.recv_deadline(now + Duration::from_millis(1500)),
+    ///     Ok(5),
+    /// );
+    /// assert_eq!(
+    ///     r.recv_deadline(now + Duration::from_secs(5)),
+    ///     Err(RecvTimeoutError::Disconnected),
+    /// );
+    /// ```
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        let res = self.recv_until(Some(deadline));
+        if res.is_ok() {
+            self.record_recv();
+        }
+        res
+    }
+
+    /// Blocks the current thread until a message is received or the deadline is reached.
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.recv(deadline),
+            ReceiverFlavor::List(chan) => chan.recv(deadline),
+            ReceiverFlavor::Zero(chan) => chan.recv(deadline),
+            ReceiverFlavor::At(chan) => {
+                let msg = chan.recv(deadline);
+                unsafe {
+                    mem::transmute_copy::<
+                        Result<Instant, RecvTimeoutError>,
+                        Result<T, RecvTimeoutError>,
+                    >(&msg)
+                }
+            }
+            ReceiverFlavor::Tick(chan) => {
+                let msg = chan.recv(deadline);
+                unsafe {
+                    mem::transmute_copy::<
+                        Result<Instant, RecvTimeoutError>,
+                        Result<T, RecvTimeoutError>,
+                    >(&msg)
+                }
+            }
+            ReceiverFlavor::Schedule(chan) => {
+                let msg = chan.recv(deadline);
+                unsafe {
+                    mem::transmute_copy::<
+                        Result<Instant, RecvTimeoutError>,
+                        Result<T, RecvTimeoutError>,
+                    >(&msg)
+                }
+            }
+            ReceiverFlavor::Never(chan) => chan.recv(deadline),
+        }
+    }
+
+    /// Blocks the current thread until a message is received or the operation is cancelled.
+    ///
+    /// If the channel is empty and not disconnected, this call will block until the receive
+    /// operation can proceed or the token observed by `signal` is cancelled. If the channel is
+    /// empty and becomes disconnected, this call will wake up and return an error.
+    ///
+    /// Cancellation takes priority: if the token is already cancelled, no message is received even
+    /// if one is available.
+    ///
+    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
+    /// on the other side of the channel.
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn same_channel(&self, other: &Sender<T>) -> bool {
-        match (&self.flavor, &other.flavor) {
-            (SenderFlavor::Array(ref a), SenderFlavor::Array(ref b)) => a == b,
-            (SenderFlavor::List(ref a), SenderFlavor::List(ref b)) => a == b,
-            (SenderFlavor::Zero(ref a), SenderFlavor::Zero(ref b)) => a == b,
-            _ => false,
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{cancel_token, unbounded, RecvCancelError};
+    ///
+    /// let (s, r) = unbounded();
+    /// let (token, signal) = cancel_token();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     token.cancel();
+    /// });
+    ///
+    /// s.send(5).unwrap();
+    /// assert_eq!(r.recv_cancellable(&signal), Ok(5));
+    /// assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+    /// ```
+    pub fn recv_cancellable(&self, signal: &CancelSignal) -> Result<T, RecvCancelError> {
+        let mut sel = Select::new_biased();
+        let cancel = sel.custom(signal);
+        sel.recv(self);
+
+        let oper = sel.select();
+        if oper.index() == cancel {
+            oper.custom(signal);
+            Err(RecvCancelError::Cancelled)
+        } else {
+            oper.recv(self).map_err(RecvCancelError::from)
         }
     }
-}
 
-impl<T> Drop for Sender<T> {
-    fn drop(&mut self) {
-        unsafe {
-            match &self.flavor {
-                SenderFlavor::Array(chan) => chan.release(|c| c.disconnect()),
-                SenderFlavor::List(chan) => chan.release(|c| c.disconnect_senders()),
-                SenderFlavor::Zero(chan) => chan.release(|c| c.disconnect()),
+    /// Blocks the current thread until a message is received or the channel is empty and
+    /// disconnected, telling apart a normal disconnection from a poisoned channel.
+    ///
+    /// This behaves like [`recv`], except that if the channel was poisoned by a panicking sender,
+    /// the error is [`RecvPoisonError::Poisoned`]. Poisoning must be enabled with
+    /// [`poison_on_panic`] first. Messages sent before the panic are still received first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, RecvPoisonError};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    /// r.poison_on_panic();
+    /// drop(s);
+    ///
+    /// // The sender was dropped normally.
+    /// assert_eq!(r.recv_checked(), Err(RecvPoisonError::Disconnected));
+    /// ```
+    ///
+    /// [`recv`]: Receiver::recv
+    /// [`poison_on_panic`]: Receiver::poison_on_panic
+    pub fn recv_checked(&self) -> Result<T, RecvPoisonError> {
+        self.recv().map_err(|_| {
+            if self.is_poisoned() {
+                RecvPoisonError::Poisoned
+            } else {
+                RecvPoisonError::Disconnected
             }
+        })
+    }
+
+    /// Returns the counters of operations performed on the channel, or `None` for timer channels.
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> Option<&Counters> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => Some(chan.counters()),
+            ReceiverFlavor::List(chan) => Some(chan.counters()),
+            ReceiverFlavor::Zero(chan) => Some(chan.counters()),
+            _ => None,
         }
     }
-}
 
-impl<T> Clone for Sender<T> {
-    fn clone(&self) -> Self {
-        let flavor = match &self.flavor {
-            SenderFlavor::Array(chan) => SenderFlavor::Array(chan.acquire()),
-            SenderFlavor::List(chan) => SenderFlavor::List(chan.acquire()),
-            SenderFlavor::Zero(chan) => SenderFlavor::Zero(chan.acquire()),
+    /// Records a received message.
+    fn record_recv(&self) {
+        let instrumented = match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_instrumented(),
+            ReceiverFlavor::List(chan) => chan.is_instrumented(),
+            ReceiverFlavor::Zero(chan) => chan.is_instrumented(),
+            _ => return,
         };
+        if instrumented {
+            self.observe_recv();
+        }
 
-        Sender { flavor }
+        #[cfg(feature = "stats")]
+        {
+            if let Some(counters) = self.counters() {
+                counters.received();
+            }
+        }
+
+        #[cfg(feature = "deadlock-detection")]
+        {
+            if let Some(op) = self.wait_for() {
+                op.used();
+            }
+        }
+
+        #[cfg(feature = "sim")]
+        crate::sim::step();
     }
-}
 
-impl<T> fmt::Debug for Sender<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.pad("Sender { .. }")
+    /// Reports a received message to the metrics, event log, and registry entry of the channel,
+    /// whichever it has.
+    fn observe_recv(&self) {
+        let (metrics, events, registration) = match &self.flavor {
+            ReceiverFlavor::Array(chan) => (chan.metrics(), chan.events(), chan.registration()),
+            ReceiverFlavor::List(chan) => (chan.metrics(), chan.events(), chan.registration()),
+            ReceiverFlavor::Zero(chan) => (chan.metrics(), chan.events(), chan.registration()),
+            _ => return,
+        };
+        if let Some(metrics) = metrics {
+            metrics.recv(self.len());
+        }
+        events.record(EventKind::Recv);
+        if let Some(registration) = registration {
+            registration.received();
+        }
     }
-}
 
-/// The receiving side of a channel.
-///
-/// # Examples
-///
-/// ```
-/// use std::thread;
-/// use std::time::Duration;
-/// use crossbeam_channel::unbounded;
-///
-/// let (s, r) = unbounded();
-///
-/// thread::spawn(move || {
-///     let _ = s.send(1);
-///     thread::sleep(Duration::from_secs(1));
-///     let _ = s.send(2);
-/// });
-///
-/// assert_eq!(r.recv(), Ok(1)); // Received immediately.
-/// assert_eq!(r.recv(), Ok(2)); // Received after 1 second.
-/// ```
-pub struct Receiver<T> {
-    flavor: ReceiverFlavor<T>,
-}
+    /// Returns `true` if the channel is empty.
+    ///
+    /// Note: Zero-capacity channels are always empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// assert!(r.is_empty());
+    /// s.send(0).unwrap();
+    /// assert!(!r.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_empty(),
+            ReceiverFlavor::List(chan) => chan.is_empty(),
+            ReceiverFlavor::Zero(chan) => chan.is_empty(),
+            ReceiverFlavor::At(chan) => chan.is_empty(),
+            ReceiverFlavor::Tick(chan) => chan.is_empty(),
+            ReceiverFlavor::Schedule(chan) => chan.is_empty(),
+            ReceiverFlavor::Never(chan) => chan.is_empty(),
+        }
+    }
 
-/// Receiver flavors.
-enum ReceiverFlavor<T> {
-    /// Bounded channel based on a preallocated array.
-    Array(counter::Receiver<flavors::array::Channel<T>>),
+    /// Returns `true` if the channel is full.
+    ///
+    /// Note: Zero-capacity channels are always full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(1);
+    ///
+    /// assert!(!r.is_full());
+    /// s.send(0).unwrap();
+    /// assert!(r.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_full(),
+            ReceiverFlavor::List(chan) => chan.is_full(),
+            ReceiverFlavor::Zero(chan) => chan.is_full(),
+            ReceiverFlavor::At(chan) => chan.is_full(),
+            ReceiverFlavor::Tick(chan) => chan.is_full(),
+            ReceiverFlavor::Schedule(chan) => chan.is_full(),
+            ReceiverFlavor::Never(chan) => chan.is_full(),
+        }
+    }
 
-    /// Unbounded channel implemented as a linked list.
-    List(counter::Receiver<flavors::list::Channel<T>>),
+    /// Returns the number of messages in the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// assert_eq!(r.len(), 0);
+    ///
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    /// assert_eq!(r.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.len(),
+            ReceiverFlavor::List(chan) => chan.len(),
+            ReceiverFlavor::Zero(chan) => chan.len(),
+            ReceiverFlavor::At(chan) => chan.len(),
+            ReceiverFlavor::Tick(chan) => chan.len(),
+            ReceiverFlavor::Schedule(chan) => chan.len(),
+            ReceiverFlavor::Never(chan) => chan.len(),
+        }
+    }
 
-    /// Zero-capacity channel.
-    Zero(counter::Receiver<flavors::zero::Channel<T>>),
+    /// If the channel is bounded, returns its capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{bounded, unbounded};
+    ///
+    /// let (_, r) = unbounded::<i32>();
+    /// assert_eq!(r.capacity(), None);
+    ///
+    /// let (_, r) = bounded::<i32>(5);
+    /// assert_eq!(r.capacity(), Some(5));
+    ///
+    /// let (_, r) = bounded::<i32>(0);
+    /// assert_eq!(r.capacity(), Some(0));
+    /// ```
+    pub fn capacity(&self) -> Option<usize> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.capacity(),
+            ReceiverFlavor::List(chan) => chan.capacity(),
+            ReceiverFlavor::Zero(chan) => chan.capacity(),
+            ReceiverFlavor::At(chan) => chan.capacity(),
+            ReceiverFlavor::Tick(chan) => chan.capacity(),
+            ReceiverFlavor::Schedule(chan) => chan.capacity(),
+            ReceiverFlavor::Never(chan) => chan.capacity(),
+        }
+    }
 
-    /// The after flavor.
-    At(Arc<flavors::at::Channel>),
+    /// Starts keeping track of the highest number of messages the channel has held.
+    ///
+    /// From then on the channel checks its length after every sent message, so that
+    /// [`max_len_seen`] reports peaks that polling [`len`] would likely miss. Tracking starts from
+    /// the current length and costs a length check on every send, so channels don't do it unless
+    /// asked to. Zero-capacity and timer channels ignore this.
+    ///
+    /// The channel has a single watermark, so this affects all senders and receivers.
+    ///
+    /// [`max_len_seen`]: Receiver::max_len_seen
+    /// [`len`]: Receiver::len
+    pub fn track_max_len(&self) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => {
+                chan.track_max_len();
+                chan.instrument();
+            }
+            ReceiverFlavor::List(chan) => {
+                chan.track_max_len();
+                chan.instrument();
+            }
+            _ => {}
+        }
+    }
 
-    /// The tick flavor.
-    Tick(Arc<flavors::tick::Channel>),
+    /// Returns the highest number of messages the channel has held since [`track_max_len`] was
+    /// called.
+    ///
+    /// Until then, this returns the current length of the channel. Zero-capacity channels never
+    /// hold messages and always return 0. Timer channels don't keep track of it and return their
+    /// current length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(10);
+    /// r.track_max_len();
+    /// for i in 0..4 {
+    ///     s.send(i).unwrap();
+    /// }
+    /// assert_eq!(r.try_iter().count(), 4);
+    ///
+    /// assert!(r.is_empty());
+    /// assert_eq!(r.max_len_seen(), 4);
+    /// ```
+    ///
+    /// [`track_max_len`]: Receiver::track_max_len
+    pub fn max_len_seen(&self) -> usize {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.max_len_seen(),
+            ReceiverFlavor::List(chan) => chan.max_len_seen(),
+            ReceiverFlavor::Zero(_) => 0,
+            _ => self.len(),
+        }
+    }
+
+    /// Resets the value returned by [`max_len_seen`] to the current length of the channel.
+    ///
+    /// The channel has a single watermark, so this affects all senders and receivers.
+    ///
+    /// [`max_len_seen`]: Receiver::max_len_seen
+    pub fn reset_max_len_seen(&self) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.reset_max_len_seen(),
+            ReceiverFlavor::List(chan) => chan.reset_max_len_seen(),
+            _ => {}
+        }
+    }
+
+    /// Returns a histogram of how long received messages waited in the channel.
+    ///
+    /// Every message is timestamped when it is sent, and its latency is counted when it is
+    /// received. Zero-capacity channels hand messages over directly and timer channels produce
+    /// messages on demand, so both return `None`. Messages of zero-sized types aren't timestamped,
+    /// so their histogram stays empty.
+    ///
+    /// This method is only available with the `timestamps` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// for i in 0..10 {
+    ///     s.send(i).unwrap();
+    /// }
+    /// assert_eq!(r.try_iter().count(), 10);
+    ///
+    /// let latency = r.latency().unwrap();
+    /// assert_eq!(latency.count(), 10);
+    /// assert!(latency.percentile(50.0) <= latency.percentile(90.0));
+    ///
+    /// r.reset_latency();
+    /// assert!(r.latency().unwrap().is_empty());
+    /// ```
+    #[cfg(feature = "timestamps")]
+    pub fn latency(&self) -> Option<LatencyHistogram> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => Some(chan.latency()),
+            ReceiverFlavor::List(chan) => Some(chan.latency()),
+            _ => None,
+        }
+    }
 
-    /// The never flavor.
-    Never(flavors::never::Channel<T>),
-}
+    /// Empties the histogram returned by [`latency`].
+    ///
+    /// The channel has a single histogram, so this affects all senders and receivers.
+    ///
+    /// This method is only available with the `timestamps` feature enabled.
+    ///
+    /// [`latency`]: Receiver::latency
+    #[cfg(feature = "timestamps")]
+    pub fn reset_latency(&self) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.reset_latency(),
+            ReceiverFlavor::List(chan) => chan.reset_latency(),
+            _ => {}
+        }
+    }
 
-unsafe impl<T: Send> Send for Receiver<T> {}
-unsafe impl<T: Send> Sync for Receiver<T> {}
+    /// Starts recording the channel's events, keeping the last `capacity` of them.
+    ///
+    /// Sent and received messages, threads parking in blocking operations, and disconnection of
+    /// either side are recorded along with the thread and the time, and can be dumped with
+    /// [`events`]. Events recorded so far are forgotten. A `capacity` of zero stops recording.
+    /// Timer channels don't record events.
+    ///
+    /// The channel has a single log, so this affects all senders and receivers. Recording takes a
+    /// lock on every operation, so it is meant for debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use crossbeam_channel::{bounded, EventKind};
+    ///
+    /// let (s, r) = bounded(0);
+    /// r.record_events(16);
+    ///
+    /// thread::Builder::new()
+    ///     .name("producer".to_string())
+    ///     .spawn(move || s.send(1).unwrap())
+    ///     .unwrap();
+    /// assert_eq!(r.recv(), Ok(1));
+    /// assert!(r.recv().is_err());
+    ///
+    /// for event in r.events() {
+    ///     println!("{:?} on {:?}", event.kind(), event.thread_name());
+    /// }
+    /// assert!(r
+    ///     .events()
+    ///     .iter()
+    ///     .any(|e| e.kind() == EventKind::Send && e.thread_name() == Some("producer")));
+    /// ```
+    ///
+    /// [`events`]: Receiver::events
+    pub fn record_events(&self, capacity: usize) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+            ReceiverFlavor::List(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+            ReceiverFlavor::Zero(chan) => {
+                chan.events().start(capacity);
+                chan.instrument();
+            }
+            _ => {}
+        }
+    }
 
-impl<T> UnwindSafe for Receiver<T> {}
-impl<T> RefUnwindSafe for Receiver<T> {}
+    /// Returns the events recorded since [`record_events`] was called, oldest first.
+    ///
+    /// [`record_events`]: Receiver::record_events
+    pub fn events(&self) -> Vec<ChannelEvent> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.events().snapshot(),
+            ReceiverFlavor::List(chan) => chan.events().snapshot(),
+            ReceiverFlavor::Zero(chan) => chan.events().snapshot(),
+            _ => Vec::new(),
+        }
+    }
 
-impl<T> Receiver<T> {
-    /// Attempts to receive a message from the channel without blocking.
+    /// Returns counts of the operations performed on the channel since it was created.
     ///
-    /// This method will either receive a message from the channel immediately or return an error
-    /// if the channel is empty.
+    /// The counts include messages sent and received by all senders and receivers, and the
+    /// number of times [`Sender::try_send`] and [`try_recv`] failed because the channel was full
+    /// or empty. Timer channels don't count operations and return all zeros.
     ///
-    /// If called on a zero-capacity channel, this method will receive a message only if there
-    /// happens to be a send operation on the other side of the channel at the same time.
+    /// This method is only available with the `stats` feature enabled.
     ///
     /// # Examples
     ///
     /// ```
-    /// use crossbeam_channel::{unbounded, TryRecvError};
+    /// use crossbeam_channel::unbounded;
     ///
     /// let (s, r) = unbounded();
-    /// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
-    ///
-    /// s.send(5).unwrap();
-    /// drop(s);
+    /// s.send(1).unwrap();
+    /// assert_eq!(r.try_recv(), Ok(1));
+    /// assert!(r.try_recv().is_err());
     ///
-    /// assert_eq!(r.try_recv(), Ok(5));
-    /// assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    /// let stats = r.stats();
+    /// assert_eq!(stats.sent(), 1);
+    /// assert_eq!(stats.received(), 1);
+    /// assert_eq!(stats.rejected_empty(), 1);
     /// ```
-    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+    ///
+    /// [`try_recv`]: Receiver::try_recv
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> ChannelStats {
+        self.counters().map(Counters::snapshot).unwrap_or_default()
+    }
+
+    /// Returns the number of threads blocked sending into the channel.
+    ///
+    /// This counts threads blocked in [`Sender::send`] and similar methods, as well as threads
+    /// blocked in a [`Select`] with a send operation on this channel. Unbounded channels never
+    /// block senders.
+    ///
+    /// [`Select`]: crate::Select
+    pub fn blocked_senders(&self) -> usize {
         match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.try_recv(),
-            ReceiverFlavor::List(chan) => chan.try_recv(),
-            ReceiverFlavor::Zero(chan) => chan.try_recv(),
-            ReceiverFlavor::At(chan) => {
-                let msg = chan.try_recv();
-                unsafe {
-                    mem::transmute_copy::<Result<Instant, TryRecvError>, Result<T, TryRecvError>>(
-                        &msg,
-                    )
-                }
-            }
-            ReceiverFlavor::Tick(chan) => {
-                let msg = chan.try_recv();
-                unsafe {
-                    mem::transmute_copy::<Result<Instant, TryRecvError>, Result<T, TryRecvError>>(
-                        &msg,
-                    )
-                }
-            }
-            ReceiverFlavor::Never(chan) => chan.try_recv(),
+            ReceiverFlavor::Array(chan) => chan.blocked_senders(),
+            ReceiverFlavor::Zero(chan) => chan.blocked_senders(),
+            _ => 0,
         }
     }
 
-    /// Blocks the current thread until a message is received or the channel is empty and
-    /// disconnected.
-    ///
-    /// If the channel is empty and not disconnected, this call will block until the receive
-    /// operation can proceed. If the channel is empty and becomes disconnected, this call will
-    /// wake up and return an error.
+    /// Returns the number of threads blocked receiving from the channel.
     ///
-    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
-    /// on the other side of the channel.
+    /// This counts threads blocked in [`recv`] and similar methods, as well as threads blocked in
+    /// a [`Select`] with a receive operation on this channel. Timer channels don't keep track of
+    /// their receivers and always return 0.
     ///
     /// # Examples
     ///
     /// ```
     /// use std::thread;
     /// use std::time::Duration;
-    /// use crossbeam_channel::{unbounded, RecvError};
+    /// use crossbeam_channel::unbounded;
     ///
-    /// let (s, r) = unbounded();
+    /// let (s, r) = unbounded::<i32>();
     ///
-    /// thread::spawn(move || {
-    ///     thread::sleep(Duration::from_secs(1));
-    ///     s.send(5).unwrap();
-    ///     drop(s);
-    /// });
+    /// let r2 = r.clone();
+    /// let t = thread::spawn(move || r2.recv().unwrap());
     ///
-    /// assert_eq!(r.recv(), Ok(5));
-    /// assert_eq!(r.recv(), Err(RecvError));
+    /// thread::sleep(Duration::from_millis(100));
+    /// assert_eq!(r.blocked_receivers(), 1);
+    ///
+    /// s.send(1).unwrap();
+    /// t.join().unwrap();
+    /// assert_eq!(r.blocked_receivers(), 0);
     /// ```
-    pub fn recv(&self) -> Result<T, RecvError> {
+    ///
+    /// [`recv`]: Receiver::recv
+    /// [`Select`]: crate::Select
+    pub fn blocked_receivers(&self) -> usize {
         match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.recv(None),
-            ReceiverFlavor::List(chan) => chan.recv(None),
-            ReceiverFlavor::Zero(chan) => chan.recv(None),
-            ReceiverFlavor::At(chan) => {
-                let msg = chan.recv(None);
-                unsafe {
-                    mem::transmute_copy::<
-                        Result<Instant, RecvTimeoutError>,
-                        Result<T, RecvTimeoutError>,
-                    >(&msg)
-                }
-            }
-            ReceiverFlavor::Tick(chan) => {
-                let msg = chan.recv(None);
-                unsafe {
-                    mem::transmute_copy::<
-                        Result<Instant, RecvTimeoutError>,
-                        Result<T, RecvTimeoutError>,
-                    >(&msg)
-                }
-            }
-            ReceiverFlavor::Never(chan) => chan.recv(None),
+            ReceiverFlavor::Array(chan) => chan.blocked_receivers(),
+            ReceiverFlavor::List(chan) => chan.blocked_receivers(),
+            ReceiverFlavor::Zero(chan) => chan.blocked_receivers(),
+            _ => 0,
         }
-        .map_err(|_| RecvError)
     }
 
-    /// Waits for a message to be received from the channel, but only for a limited time.
+    /// Returns the names of threads blocked sending into the channel.
     ///
-    /// If the channel is empty and not disconnected, this call will block until the receive
-    /// operation can proceed or the operation times out. If the channel is empty and becomes
-    /// disconnected, this call will wake up and return an error.
+    /// Threads without a name are listed by their [`ThreadId`], like `ThreadId(3)`. A thread that
+    /// is blocked on several operations on this channel is listed once for each of them.
     ///
-    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
-    /// on the other side of the channel.
+    /// [`ThreadId`]: std::thread::ThreadId
+    pub fn blocked_sender_names(&self) -> Vec<String> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.blocked_sender_names(),
+            ReceiverFlavor::Zero(chan) => chan.blocked_sender_names(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the names of threads blocked receiving from the channel.
+    ///
+    /// Threads without a name are listed by their [`ThreadId`], like `ThreadId(3)`. A thread that
+    /// is blocked on several operations on this channel is listed once for each of them.
+    ///
+    /// [`ThreadId`]: std::thread::ThreadId
+    pub fn blocked_receiver_names(&self) -> Vec<String> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.blocked_receiver_names(),
+            ReceiverFlavor::List(chan) => chan.blocked_receiver_names(),
+            ReceiverFlavor::Zero(chan) => chan.blocked_receiver_names(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// A blocking iterator over messages in the channel.
+    ///
+    /// Each call to [`next`] blocks waiting for the next message and then returns it. However, if
+    /// the channel becomes empty and disconnected, it returns [`None`] without blocking.
+    ///
+    /// [`next`]: Iterator::next
     ///
     /// # Examples
     ///
     /// ```
     /// use std::thread;
-    /// use std::time::Duration;
-    /// use crossbeam_channel::{unbounded, RecvTimeoutError};
+    /// use crossbeam_channel::unbounded;
     ///
     /// let (s, r) = unbounded();
     ///
     /// thread::spawn(move || {
-    ///     thread::sleep(Duration::from_secs(1));
-    ///     s.send(5).unwrap();
-    ///     drop(s);
+    ///     s.send(1).unwrap();
+    ///     s.send(2).unwrap();
+    ///     s.send(3).unwrap();
+    ///     drop(s); // Disconnect the channel.
     /// });
     ///
-    /// assert_eq!(
-    ///     r.recv_timeout(Duration::from_millis(500)),
-    ///     Err(RecvTimeoutError::Timeout),
-    /// );
-    /// assert_eq!(
-    ///     r.recv_timeout(Duration::from_secs(1)),
-    ///     Ok(5),
-    /// );
-    /// assert_eq!(
-    ///     r.recv_timeout(Duration::from_secs(1)),
-    ///     Err(RecvTimeoutError::Disconnected),
-    /// );
+    /// // Collect all messages from the channel.
+    /// // Note that the call to `collect` blocks until the sender is dropped.
+    /// let v: Vec<_> = r.iter().collect();
+    ///
+    /// assert_eq!(v, [1, 2, 3]);
     /// ```
-    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
-        self.recv_deadline(utils::convert_timeout_to_deadline(timeout))
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
     }
 
-    /// Waits for a message to be received from the channel, but only before a given deadline.
+    /// A non-blocking iterator over messages in the channel.
     ///
-    /// If the channel is empty and not disconnected, this call will block until the receive
-    /// operation can proceed or the operation times out. If the channel is empty and becomes
-    /// disconnected, this call will wake up and return an error.
+    /// Each call to [`next`] returns a message if there is one ready to be received. The iterator
+    /// never blocks waiting for the next message.
     ///
-    /// If called on a zero-capacity channel, this method will wait for a send operation to appear
-    /// on the other side of the channel.
+    /// [`next`]: Iterator::next
     ///
     /// # Examples
     ///
     /// ```
     /// use std::thread;
-    /// use std::time::{Instant, Duration};
-    /// use crossbeam_channel::{unbounded, RecvTimeoutError};
+    /// use std::time::Duration;
+    /// use crossbeam_channel::unbounded;
     ///
-    /// let (s, r) = unbounded();
+    /// let (s, r) = unbounded::<i32>();
     ///
     /// thread::spawn(move || {
+    ///     s.send(1).unwrap();
     ///     thread::sleep(Duration::from_secs(1));
-    ///     s.send(5).unwrap();
-    ///     drop(s);
+    ///     s.send(2).unwrap();
+    ///     thread::sleep(Duration::from_secs(2));
+    ///     s.send(3).unwrap();
     /// });
     ///
-    /// let now = Instant::now();
+    /// thread::sleep(Duration::from_secs(2));
     ///
-    /// assert_eq!(
-    ///     r.recv_deadline(now + Duration::from_millis(500)),
-    ///     Err(RecvTimeoutError::Timeout),
-    /// );
-    /// assert_eq!(
-    ///     r.recv_deadline(now + Duration::from_millis(1500)),
-    ///     Ok(5),
-    /// );
-    /// assert_eq!(
-    ///     r.recv_deadline(now + Duration::from_secs(5)),
-    ///     Err(RecvTimeoutError::Disconnected),
-    /// );
+    /// // Collect all messages from the channel without blocking.
+    /// // The third message hasn't been sent yet so we'll collect only the first two.
+    /// let v: Vec<_> = r.try_iter().collect();
+    ///
+    /// assert_eq!(v, [1, 2]);
     /// ```
-    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
-        match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.recv(Some(deadline)),
-            ReceiverFlavor::List(chan) => chan.recv(Some(deadline)),
-            ReceiverFlavor::Zero(chan) => chan.recv(Some(deadline)),
-            ReceiverFlavor::At(chan) => {
-                let msg = chan.recv(Some(deadline));
-                unsafe {
-                    mem::transmute_copy::<
-                        Result<Instant, RecvTimeoutError>,
-                        Result<T, RecvTimeoutError>,
-                    >(&msg)
-                }
-            }
-            ReceiverFlavor::Tick(chan) => {
-                let msg = chan.recv(Some(deadline));
-                unsafe {
-                    mem::transmute_copy::<
-                        Result<Instant, RecvTimeoutError>,
-                        Result<T, RecvTimeoutError>,
-                    >(&msg)
-                }
-            }
-            ReceiverFlavor::Never(chan) => chan.recv(Some(deadline)),
-        }
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { receiver: self }
+    }
+
+    /// Drops the messages currently in the channel and returns how many were dropped.
+    ///
+    /// Messages sent while the channel is being cleared may or may not be dropped. A
+    /// zero-capacity channel holds no messages, so this never receives from blocked senders.
+    ///
+    /// If the destructor of a message panics, the remaining messages are still dropped before
+    /// the panic is propagated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(3);
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    ///
+    /// assert_eq!(r.clear(), 2);
+    /// assert!(r.is_empty());
+    /// ```
+    pub fn clear(&self) -> usize {
+        let mut clear = Clear {
+            receiver: self,
+            remaining: self.len(),
+            dropped: 0,
+        };
+        while clear.step() {}
+        clear.dropped
+    }
+
+    /// Turns the receiver into one whose messages must be acknowledged.
+    ///
+    /// Messages received through the returned [`AckReceiver`] come wrapped in a [`Delivery`].
+    /// A delivery that is given back or dropped without being acknowledged is delivered again,
+    /// possibly to a clone of the receiver. See [`AckReceiver`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (s, r) = unbounded();
+    /// let r = r.acked();
+    /// s.send("job").unwrap();
+    ///
+    /// let job = r.recv().unwrap();
+    /// job.nack();
+    ///
+    /// let job = r.recv().unwrap();
+    /// assert_eq!(*job, "job");
+    /// job.ack();
+    /// ```
+    ///
+    /// [`Delivery`]: crate::Delivery
+    pub fn acked(self) -> AckReceiver<T> {
+        AckReceiver::new(self)
     }
 
-    /// Returns `true` if the channel is empty.
+    /// Turns the receiver into one that can put back a message it has received.
     ///
-    /// Note: Zero-capacity channels are always empty.
+    /// The returned [`PutBackReceiver`] holds at most one message that was put back, and
+    /// receives it again before any message from the channel. See [`PutBackReceiver`] for
+    /// details.
     ///
     /// # Examples
     ///
@@ -943,106 +3046,149 @@ impl<T> Receiver<T> {
     /// use crossbeam_channel::unbounded;
     ///
     /// let (s, r) = unbounded();
+    /// let mut r = r.with_put_back();
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
     ///
-    /// assert!(r.is_empty());
-    /// s.send(0).unwrap();
-    /// assert!(!r.is_empty());
+    /// let msg = r.recv().unwrap();
+    /// r.put_back(msg).unwrap();
+    /// assert_eq!(r.recv(), Ok(1));
+    /// assert_eq!(r.recv(), Ok(2));
     /// ```
-    pub fn is_empty(&self) -> bool {
-        match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.is_empty(),
-            ReceiverFlavor::List(chan) => chan.is_empty(),
-            ReceiverFlavor::Zero(chan) => chan.is_empty(),
-            ReceiverFlavor::At(chan) => chan.is_empty(),
-            ReceiverFlavor::Tick(chan) => chan.is_empty(),
-            ReceiverFlavor::Never(chan) => chan.is_empty(),
-        }
+    pub fn with_put_back(self) -> PutBackReceiver<T> {
+        PutBackReceiver::new(self)
     }
 
-    /// Returns `true` if the channel is full.
+    /// Returns the number of senders associated with the channel, which is the number of
+    /// producers that haven't finished yet.
     ///
-    /// Note: Zero-capacity channels are always full.
+    /// Senders go away when they are dropped or [finished]. Timer channels have no senders and
+    /// always return 0. The count may already be out of date by the time it is returned if other
+    /// threads are cloning or dropping senders, but unless the channel [stays open], once it
+    /// reaches 0 it stays there.
     ///
     /// # Examples
     ///
     /// ```
-    /// use crossbeam_channel::bounded;
-    ///
-    /// let (s, r) = bounded(1);
+    /// use std::thread;
+    /// use crossbeam_channel::unbounded;
     ///
-    /// assert!(!r.is_full());
-    /// s.send(0).unwrap();
-    /// assert!(r.is_full());
+    /// let (s, r) = unbounded();
+    /// let producers = (0..3)
+    ///     .map(|i| {
+    ///         let s = s.clone();
+    ///         thread::spawn(move || {
+    ///             s.send(i).unwrap();
+    ///             s.finish();
+    ///         })
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// s.finish();
+    ///
+    /// for p in producers {
+    ///     p.join().unwrap();
+    /// }
+    /// assert_eq!(r.sender_count(), 0);
+    /// assert_eq!(r.iter().count(), 3);
     /// ```
-    pub fn is_full(&self) -> bool {
+    ///
+    /// [finished]: Sender::finish
+    /// [stays open]: unbounded_keep_open
+    pub fn sender_count(&self) -> usize {
         match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.is_full(),
-            ReceiverFlavor::List(chan) => chan.is_full(),
-            ReceiverFlavor::Zero(chan) => chan.is_full(),
-            ReceiverFlavor::At(chan) => chan.is_full(),
-            ReceiverFlavor::Tick(chan) => chan.is_full(),
-            ReceiverFlavor::Never(chan) => chan.is_full(),
+            ReceiverFlavor::Array(chan) => chan.senders(),
+            ReceiverFlavor::List(chan) => chan.senders(),
+            ReceiverFlavor::Zero(chan) => chan.senders(),
+            _ => 0,
         }
     }
 
-    /// Returns the number of messages in the channel.
+    /// Creates a new sender for the channel.
+    ///
+    /// This works like cloning a sender, without needing one at hand. On a channel created with
+    /// [`bounded_keep_open`] or [`unbounded_keep_open`], a new sender can be created at any time.
+    /// On other channels, this returns `None` once all senders are gone, since the channel is
+    /// disconnected by then. Timer channels have no senders and always return `None`.
     ///
     /// # Examples
     ///
     /// ```
-    /// use crossbeam_channel::unbounded;
+    /// use crossbeam_channel::{unbounded, unbounded_keep_open};
     ///
-    /// let (s, r) = unbounded();
-    /// assert_eq!(r.len(), 0);
+    /// let (s, r) = unbounded::<i32>();
+    /// assert!(r.new_sender().is_some());
+    /// drop(s);
+    /// assert!(r.new_sender().is_none());
     ///
-    /// s.send(1).unwrap();
-    /// s.send(2).unwrap();
-    /// assert_eq!(r.len(), 2);
+    /// let (s, r) = unbounded_keep_open::<i32>();
+    /// drop(s);
+    /// assert!(r.new_sender().is_some());
     /// ```
-    pub fn len(&self) -> usize {
-        match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.len(),
-            ReceiverFlavor::List(chan) => chan.len(),
-            ReceiverFlavor::Zero(chan) => chan.len(),
-            ReceiverFlavor::At(chan) => chan.len(),
-            ReceiverFlavor::Tick(chan) => chan.len(),
-            ReceiverFlavor::Never(chan) => chan.len(),
-        }
+    pub fn new_sender(&self) -> Option<Sender<T>> {
+        let flavor = match &self.flavor {
+            ReceiverFlavor::Array(chan) => SenderFlavor::Array(chan.new_sender()?),
+            ReceiverFlavor::List(chan) => SenderFlavor::List(chan.new_sender()?),
+            ReceiverFlavor::Zero(chan) => SenderFlavor::Zero(chan.new_sender()?),
+            _ => return None,
+        };
+        Some(Sender { flavor })
     }
 
-    /// If the channel is bounded, returns its capacity.
+    /// Closes the channel.
+    ///
+    /// Once the channel is closed, sending fails for every sender, and so do threads that are
+    /// blocked sending. Messages already in the channel stay there: receivers keep getting them
+    /// until none is left, and only then see that the channel is disconnected.
+    ///
+    /// Returns `true` if this call closed the channel, or `false` if it was already closed or
+    /// disconnected. Timer channels can't be closed and always return `false`.
     ///
     /// # Examples
     ///
     /// ```
-    /// use crossbeam_channel::{bounded, unbounded};
+    /// use crossbeam_channel::{bounded, TryRecvError};
     ///
-    /// let (_, r) = unbounded::<i32>();
-    /// assert_eq!(r.capacity(), None);
+    /// let (s, r) = bounded(2);
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
     ///
-    /// let (_, r) = bounded::<i32>(5);
-    /// assert_eq!(r.capacity(), Some(5));
+    /// assert!(r.close());
+    /// assert!(s.send(3).is_err());
     ///
-    /// let (_, r) = bounded::<i32>(0);
-    /// assert_eq!(r.capacity(), Some(0));
+    /// assert_eq!(r.try_recv(), Ok(1));
+    /// assert_eq!(r.try_recv(), Ok(2));
+    /// assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
     /// ```
-    pub fn capacity(&self) -> Option<usize> {
+    pub fn close(&self) -> bool {
+        self.close_channel(None)
+    }
+
+    /// Closes the channel, recording why.
+    ///
+    /// See [`Sender::close_with`] for details. Timer channels can't be closed and always return
+    /// `false`.
+    pub fn close_with<E>(&self, reason: E) -> bool
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.close_channel(Some(Arc::from(reason.into())))
+    }
+
+    /// Closes the channel, recording `reason` if this call closed it.
+    fn close_channel(&self, reason: Option<CloseReason>) -> bool {
         match &self.flavor {
-            ReceiverFlavor::Array(chan) => chan.capacity(),
-            ReceiverFlavor::List(chan) => chan.capacity(),
-            ReceiverFlavor::Zero(chan) => chan.capacity(),
-            ReceiverFlavor::At(chan) => chan.capacity(),
-            ReceiverFlavor::Tick(chan) => chan.capacity(),
-            ReceiverFlavor::Never(chan) => chan.capacity(),
+            ReceiverFlavor::Array(chan) => chan.close(reason, |c| c.disconnect()),
+            ReceiverFlavor::List(chan) => chan.close(reason, |c| c.disconnect_senders()),
+            ReceiverFlavor::Zero(chan) => chan.close(reason, |c| c.disconnect()),
+            _ => false,
         }
     }
 
-    /// A blocking iterator over messages in the channel.
-    ///
-    /// Each call to [`next`] blocks waiting for the next message and then returns it. However, if
-    /// the channel becomes empty and disconnected, it returns [`None`] without blocking.
+    /// Returns the reason the channel was closed with, or `None` if it is open, was closed
+    /// without a reason, or was disconnected by dropping handles.
     ///
-    /// [`next`]: Iterator::next
+    /// The reason is available as soon as an operation reports that the channel is disconnected.
+    /// See [`Sender::close_with`] for details.
     ///
     /// # Examples
     ///
@@ -1051,58 +3197,159 @@ impl<T> Receiver<T> {
     /// use crossbeam_channel::unbounded;
     ///
     /// let (s, r) = unbounded();
-    ///
-    /// thread::spawn(move || {
+    /// let producer = thread::spawn(move || {
     ///     s.send(1).unwrap();
-    ///     s.send(2).unwrap();
-    ///     s.send(3).unwrap();
-    ///     drop(s); // Disconnect the channel.
+    ///     s.close_with(format!("stopped after {} message", 1));
     /// });
     ///
-    /// // Collect all messages from the channel.
-    /// // Note that the call to `collect` blocks until the sender is dropped.
-    /// let v: Vec<_> = r.iter().collect();
+    /// for msg in r.iter() {
+    ///     assert_eq!(msg, 1);
+    /// }
+    /// let reason = r.close_reason().unwrap();
+    /// assert_eq!(reason.to_string(), "stopped after 1 message");
+    /// # producer.join().unwrap();
+    /// ```
+    pub fn close_reason(&self) -> Option<Arc<dyn Error + Send + Sync>> {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.close_reason(),
+            ReceiverFlavor::List(chan) => chan.close_reason(),
+            ReceiverFlavor::Zero(chan) => chan.close_reason(),
+            _ => None,
+        }
+    }
+
+    /// Reopens a closed channel, so that it can be used again.
+    ///
+    /// The channel is only reopened if it is empty and still has senders and receivers. Timer
+    /// channels can't be closed and always return `false`. See [`Sender::reopen`] for details.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(v, [1, 2, 3]);
     /// ```
-    pub fn iter(&self) -> Iter<'_, T> {
-        Iter { receiver: self }
+    /// use crossbeam_channel::{unbounded, TryRecvError};
+    ///
+    /// let (s, r) = unbounded();
+    /// r.close();
+    /// assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    ///
+    /// assert!(r.reopen());
+    /// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    /// s.send(1).unwrap();
+    /// assert_eq!(r.try_recv(), Ok(1));
+    /// ```
+    pub fn reopen(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.reopen(|c| c.reconnect(), |c| c.disconnect()),
+            ReceiverFlavor::List(chan) => {
+                chan.reopen(|c| c.reconnect(), |c| c.disconnect_senders())
+            }
+            ReceiverFlavor::Zero(chan) => chan.reopen(|c| c.reconnect(), |c| c.disconnect()),
+            _ => false,
+        }
     }
 
-    /// A non-blocking iterator over messages in the channel.
+    /// Returns `true` if the channel has been closed or all senders have been dropped.
+    fn is_closed(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_disconnected(),
+            ReceiverFlavor::List(chan) => chan.is_disconnected(),
+            ReceiverFlavor::Zero(chan) => chan.is_disconnected(),
+            _ => false,
+        }
+    }
+
+    /// Returns the number of messages left to receive from a closed channel, or `None` if the
+    /// channel is still open.
     ///
-    /// Each call to [`next`] returns a message if there is one ready to be received. The iterator
-    /// never blocks waiting for the next message.
+    /// A channel is closed by [`Sender::close`], by [`Receiver::close`], or by dropping all
+    /// senders. No more messages can arrive after that, so the count only goes down as messages
+    /// are received. [`recv`] returns every one of them before reporting that the channel is
+    /// disconnected.
     ///
-    /// [`next`]: Iterator::next
+    /// Timer channels are never closed and always return `None`.
     ///
     /// # Examples
     ///
     /// ```
-    /// use std::thread;
-    /// use std::time::Duration;
     /// use crossbeam_channel::unbounded;
     ///
-    /// let (s, r) = unbounded::<i32>();
+    /// let (s, r) = unbounded();
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    /// assert_eq!(r.remaining(), None);
     ///
-    /// thread::spawn(move || {
-    ///     s.send(1).unwrap();
-    ///     thread::sleep(Duration::from_secs(1));
-    ///     s.send(2).unwrap();
-    ///     thread::sleep(Duration::from_secs(2));
-    ///     s.send(3).unwrap();
-    /// });
+    /// drop(s);
+    /// assert_eq!(r.remaining(), Some(2));
+    /// r.recv().unwrap();
+    /// assert_eq!(r.remaining(), Some(1));
+    /// ```
     ///
-    /// thread::sleep(Duration::from_secs(2));
+    /// [`recv`]: Receiver::recv
+    pub fn remaining(&self) -> Option<usize> {
+        if self.is_closed() {
+            Some(self.len())
+        } else {
+            None
+        }
+    }
+
+    /// Receives the messages left in a closed channel, or returns `None` if the channel is still
+    /// open.
     ///
-    /// // Collect all messages from the channel without blocking.
-    /// // The third message hasn't been sent yet so we'll collect only the first two.
-    /// let v: Vec<_> = r.try_iter().collect();
+    /// This never blocks. The returned messages are exactly the ones that were in the channel
+    /// when it was closed, minus those that have been received since, possibly by other
+    /// receivers at the same time.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(v, [1, 2]);
     /// ```
-    pub fn try_iter(&self) -> TryIter<'_, T> {
-        TryIter { receiver: self }
+    /// use crossbeam_channel::bounded;
+    ///
+    /// let (s, r) = bounded(3);
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    /// assert_eq!(r.recv_remaining(), None);
+    ///
+    /// s.close();
+    /// assert_eq!(r.recv_remaining(), Some(vec![1, 2]));
+    /// assert_eq!(r.recv_remaining(), Some(vec![]));
+    /// ```
+    pub fn recv_remaining(&self) -> Option<Vec<T>> {
+        if !self.is_closed() {
+            return None;
+        }
+
+        let mut msgs = Vec::with_capacity(self.len());
+        while let Ok(msg) = self.try_recv() {
+            msgs.push(msg);
+        }
+        Some(msgs)
+    }
+
+    /// Enables poisoning for the channel.
+    ///
+    /// See [`Sender::poison_on_panic`] for details. Timer channels can't be poisoned, so this
+    /// does nothing for them.
+    pub fn poison_on_panic(&self) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.enable_poisoning(),
+            ReceiverFlavor::List(chan) => chan.enable_poisoning(),
+            ReceiverFlavor::Zero(chan) => chan.enable_poisoning(),
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if poisoning is enabled and a sender was dropped while its thread was
+    /// panicking.
+    ///
+    /// See [`Sender::poison_on_panic`] for details.
+    pub fn is_poisoned(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_poisoned(),
+            ReceiverFlavor::List(chan) => chan.is_poisoned(),
+            ReceiverFlavor::Zero(chan) => chan.is_poisoned(),
+            _ => false,
+        }
     }
 
     /// Returns `true` if receivers belong to the same channel.
@@ -1127,21 +3374,68 @@ impl<T> Receiver<T> {
             (ReceiverFlavor::Zero(a), ReceiverFlavor::Zero(b)) => a == b,
             (ReceiverFlavor::At(a), ReceiverFlavor::At(b)) => Arc::ptr_eq(a, b),
             (ReceiverFlavor::Tick(a), ReceiverFlavor::Tick(b)) => Arc::ptr_eq(a, b),
+            (ReceiverFlavor::Schedule(a), ReceiverFlavor::Schedule(b)) => Arc::ptr_eq(a, b),
             (ReceiverFlavor::Never(_), ReceiverFlavor::Never(_)) => true,
             _ => false,
         }
     }
+
+    /// Creates a file descriptor that becomes readable when the channel has messages.
+    ///
+    /// This lets the channel be registered with an event loop based on `epoll`, `kqueue`, or
+    /// `mio`, without a thread bridging the two. The returned [`ReadyFd`] owns a clone of this
+    /// receiver and a pipe, so the descriptor stays valid for as long as it is alive. See
+    /// [`ReadyFd`] for how to consume readiness without missing messages.
+    ///
+    /// This method is only available on Unix with the `fd` feature enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pipe could not be created, e.g. because the process ran out of
+    /// file descriptors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{readable, unbounded};
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (s, r) = unbounded();
+    /// let ready = r.ready_fd().unwrap();
+    ///
+    /// // Any code polling the descriptor sees it become readable.
+    /// let polled = readable(ready.as_raw_fd());
+    /// s.send("hello").unwrap();
+    /// assert_eq!(polled.recv(), Ok(()));
+    ///
+    /// ready.clear();
+    /// assert_eq!(r.try_recv(), Ok("hello"));
+    /// ```
+    #[cfg(all(unix, feature = "fd"))]
+    pub fn ready_fd(&self) -> io::Result<ReadyFd<T>> {
+        ReadyFd::new(self.clone())
+    }
 }
 
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
         unsafe {
             match &self.flavor {
-                ReceiverFlavor::Array(chan) => chan.release(|c| c.disconnect()),
-                ReceiverFlavor::List(chan) => chan.release(|c| c.disconnect_receivers()),
-                ReceiverFlavor::Zero(chan) => chan.release(|c| c.disconnect()),
+                ReceiverFlavor::Array(chan) => chan.release(|c| {
+                    c.events().record(EventKind::ReceiversDisconnected);
+                    c.disconnect()
+                }),
+                ReceiverFlavor::List(chan) => chan.release(|c| {
+                    c.events().record(EventKind::ReceiversDisconnected);
+                    c.disconnect_receivers()
+                }),
+                ReceiverFlavor::Zero(chan) => chan.release(|c| {
+                    c.events().record(EventKind::ReceiversDisconnected);
+                    c.disconnect()
+                }),
                 ReceiverFlavor::At(_) => {}
                 ReceiverFlavor::Tick(_) => {}
+                ReceiverFlavor::Schedule(_) => {}
                 ReceiverFlavor::Never(_) => {}
             }
         }
@@ -1156,6 +3450,7 @@ impl<T> Clone for Receiver<T> {
             ReceiverFlavor::Zero(chan) => ReceiverFlavor::Zero(chan.acquire()),
             ReceiverFlavor::At(chan) => ReceiverFlavor::At(chan.clone()),
             ReceiverFlavor::Tick(chan) => ReceiverFlavor::Tick(chan.clone()),
+            ReceiverFlavor::Schedule(chan) => ReceiverFlavor::Schedule(chan.clone()),
             ReceiverFlavor::Never(_) => ReceiverFlavor::Never(flavors::never::Channel::new()),
         };
 
@@ -1165,7 +3460,17 @@ impl<T> Clone for Receiver<T> {
 
 impl<T> fmt::Debug for Receiver<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.pad("Receiver { .. }")
+        let mut d = f.debug_struct("Receiver");
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::List(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::Zero(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::At(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::Tick(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::Schedule(chan) => chan.debug_fields(&mut d),
+            ReceiverFlavor::Never(chan) => chan.debug_fields(&mut d),
+        }
+        d.finish()
     }
 }
 
@@ -1187,6 +3492,15 @@ impl<T> IntoIterator for Receiver<T> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<T> futures_core::Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<T>> {
+        future::poll_recv(&*self, cx).map(Result::ok)
+    }
+}
+
 /// A blocking iterator over messages in a channel.
 ///
 /// Each call to [`next`] blocks waiting for the next message and then returns it. However, if the
@@ -1285,6 +3599,51 @@ impl<T> fmt::Debug for TryIter<'_, T> {
     }
 }
 
+/// Drops the messages in a channel being cleared by [`Receiver::clear`].
+///
+/// If the destructor of a message panics, dropping the `Clear` while unwinding drops the
+/// remaining messages.
+struct Clear<'a, T> {
+    /// The receiver the messages are received from.
+    receiver: &'a Receiver<T>,
+
+    /// The number of messages left to drop.
+    remaining: usize,
+
+    /// The number of messages dropped so far.
+    dropped: usize,
+}
+
+impl<T> Clear<'_, T> {
+    /// Drops the next message.
+    ///
+    /// Returns `false` if there are no messages left.
+    fn step(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+
+        match self.receiver.try_recv() {
+            Ok(msg) => {
+                self.dropped += 1;
+                drop(msg);
+                true
+            }
+            Err(_) => {
+                self.remaining = 0;
+                false
+            }
+        }
+    }
+}
+
+impl<T> Drop for Clear<'_, T> {
+    fn drop(&mut self) {
+        while self.step() {}
+    }
+}
+
 /// A blocking iterator over messages in a channel.
 ///
 /// Each call to [`next`] blocks waiting for the next message and then returns it. However, if the
@@ -1393,6 +3752,41 @@ impl<T> SelectHandle for Sender<T> {
             SenderFlavor::Zero(chan) => chan.sender().unwatch(oper),
         }
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.sender().listen(listener, index),
+            SenderFlavor::List(chan) => chan.sender().listen(listener, index),
+            SenderFlavor::Zero(chan) => chan.sender().listen(listener, index),
+        }
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.sender().unlisten(listener, index),
+            SenderFlavor::List(chan) => chan.sender().unlisten(listener, index),
+            SenderFlavor::Zero(chan) => chan.sender().unlisten(listener, index),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.sender().watch_task(waker),
+            SenderFlavor::List(chan) => chan.sender().watch_task(waker),
+            SenderFlavor::Zero(chan) => chan.sender().watch_task(waker),
+        }
+    }
+
+    #[cfg(feature = "deadlock-detection")]
+    fn wait_for(&self) -> Option<WaitFor> {
+        let tracker = match &self.flavor {
+            SenderFlavor::Array(chan) => chan.tracker(),
+            SenderFlavor::List(chan) => chan.tracker(),
+            SenderFlavor::Zero(chan) => chan.tracker(),
+        };
+        Some(WaitFor::new(tracker, Side::Send))
+    }
 }
 
 impl<T> SelectHandle for Receiver<T> {
@@ -1403,6 +3797,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().try_select(token),
             ReceiverFlavor::At(chan) => chan.try_select(token),
             ReceiverFlavor::Tick(chan) => chan.try_select(token),
+            ReceiverFlavor::Schedule(chan) => chan.try_select(token),
             ReceiverFlavor::Never(chan) => chan.try_select(token),
         }
     }
@@ -1414,6 +3809,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(_) => None,
             ReceiverFlavor::At(chan) => chan.deadline(),
             ReceiverFlavor::Tick(chan) => chan.deadline(),
+            ReceiverFlavor::Schedule(chan) => chan.deadline(),
             ReceiverFlavor::Never(chan) => chan.deadline(),
         }
     }
@@ -1425,6 +3821,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().register(oper, cx),
             ReceiverFlavor::At(chan) => chan.register(oper, cx),
             ReceiverFlavor::Tick(chan) => chan.register(oper, cx),
+            ReceiverFlavor::Schedule(chan) => chan.register(oper, cx),
             ReceiverFlavor::Never(chan) => chan.register(oper, cx),
         }
     }
@@ -1436,6 +3833,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().unregister(oper),
             ReceiverFlavor::At(chan) => chan.unregister(oper),
             ReceiverFlavor::Tick(chan) => chan.unregister(oper),
+            ReceiverFlavor::Schedule(chan) => chan.unregister(oper),
             ReceiverFlavor::Never(chan) => chan.unregister(oper),
         }
     }
@@ -1447,6 +3845,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().accept(token, cx),
             ReceiverFlavor::At(chan) => chan.accept(token, cx),
             ReceiverFlavor::Tick(chan) => chan.accept(token, cx),
+            ReceiverFlavor::Schedule(chan) => chan.accept(token, cx),
             ReceiverFlavor::Never(chan) => chan.accept(token, cx),
         }
     }
@@ -1458,6 +3857,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().is_ready(),
             ReceiverFlavor::At(chan) => chan.is_ready(),
             ReceiverFlavor::Tick(chan) => chan.is_ready(),
+            ReceiverFlavor::Schedule(chan) => chan.is_ready(),
             ReceiverFlavor::Never(chan) => chan.is_ready(),
         }
     }
@@ -1469,6 +3869,7 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().watch(oper, cx),
             ReceiverFlavor::At(chan) => chan.watch(oper, cx),
             ReceiverFlavor::Tick(chan) => chan.watch(oper, cx),
+            ReceiverFlavor::Schedule(chan) => chan.watch(oper, cx),
             ReceiverFlavor::Never(chan) => chan.watch(oper, cx),
         }
     }
@@ -1480,23 +3881,76 @@ impl<T> SelectHandle for Receiver<T> {
             ReceiverFlavor::Zero(chan) => chan.receiver().unwatch(oper),
             ReceiverFlavor::At(chan) => chan.unwatch(oper),
             ReceiverFlavor::Tick(chan) => chan.unwatch(oper),
+            ReceiverFlavor::Schedule(chan) => chan.unwatch(oper),
             ReceiverFlavor::Never(chan) => chan.unwatch(oper),
         }
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.receiver().listen(listener, index),
+            ReceiverFlavor::List(chan) => chan.receiver().listen(listener, index),
+            ReceiverFlavor::Zero(chan) => chan.receiver().listen(listener, index),
+            ReceiverFlavor::At(chan) => chan.listen(listener, index),
+            ReceiverFlavor::Tick(chan) => chan.listen(listener, index),
+            ReceiverFlavor::Schedule(chan) => chan.listen(listener, index),
+            ReceiverFlavor::Never(chan) => chan.listen(listener, index),
+        }
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.receiver().unlisten(listener, index),
+            ReceiverFlavor::List(chan) => chan.receiver().unlisten(listener, index),
+            ReceiverFlavor::Zero(chan) => chan.receiver().unlisten(listener, index),
+            ReceiverFlavor::At(chan) => chan.unlisten(listener, index),
+            ReceiverFlavor::Tick(chan) => chan.unlisten(listener, index),
+            ReceiverFlavor::Schedule(chan) => chan.unlisten(listener, index),
+            ReceiverFlavor::Never(chan) => chan.unlisten(listener, index),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.receiver().watch_task(waker),
+            ReceiverFlavor::List(chan) => chan.receiver().watch_task(waker),
+            ReceiverFlavor::Zero(chan) => chan.receiver().watch_task(waker),
+            ReceiverFlavor::At(chan) => chan.watch_task(waker),
+            ReceiverFlavor::Tick(chan) => chan.watch_task(waker),
+            ReceiverFlavor::Schedule(chan) => chan.watch_task(waker),
+            ReceiverFlavor::Never(chan) => chan.watch_task(waker),
+        }
+    }
+
+    #[cfg(feature = "deadlock-detection")]
+    fn wait_for(&self) -> Option<WaitFor> {
+        let tracker = match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.tracker(),
+            ReceiverFlavor::List(chan) => chan.tracker(),
+            ReceiverFlavor::Zero(chan) => chan.tracker(),
+            _ => return None,
+        };
+        Some(WaitFor::new(tracker, Side::Recv))
+    }
 }
 
 /// Writes a message into the channel.
 pub(crate) unsafe fn write<T>(s: &Sender<T>, token: &mut Token, msg: T) -> Result<(), T> {
-    match &s.flavor {
+    let res = match &s.flavor {
         SenderFlavor::Array(chan) => chan.write(token, msg),
         SenderFlavor::List(chan) => chan.write(token, msg),
         SenderFlavor::Zero(chan) => chan.write(token, msg),
+    };
+    if res.is_ok() {
+        s.record_send(1);
     }
+    res
 }
 
 /// Reads a message from the channel.
 pub(crate) unsafe fn read<T>(r: &Receiver<T>, token: &mut Token) -> Result<T, ()> {
-    match &r.flavor {
+    let res = match &r.flavor {
         ReceiverFlavor::Array(chan) => chan.read(token),
         ReceiverFlavor::List(chan) => chan.read(token),
         ReceiverFlavor::Zero(chan) => chan.read(token),
@@ -1506,6 +3960,13 @@ pub(crate) unsafe fn read<T>(r: &Receiver<T>, token: &mut Token) -> Result<T, ()
         ReceiverFlavor::Tick(chan) => {
             mem::transmute_copy::<Result<Instant, ()>, Result<T, ()>>(&chan.read(token))
         }
+        ReceiverFlavor::Schedule(chan) => {
+            mem::transmute_copy::<Result<Instant, ()>, Result<T, ()>>(&chan.read(token))
+        }
         ReceiverFlavor::Never(chan) => chan.read(token),
+    };
+    if res.is_ok() {
+        r.record_recv();
     }
+    res
 }
@@ -0,0 +1,184 @@
+//! Clocks driving timer channels.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::select::Notifier;
+use crate::utils;
+use crate::waker::SyncWaker;
+
+/// A source of time for timer channels.
+///
+/// Timer channels created with [`after_with_clock`], [`at_with_clock`], and [`tick_with_clock`]
+/// read the current time from a clock instead of [`Instant::now`].
+///
+/// A clock either advances on its own, in step with [`Instant::now`], or is advanced manually. A
+/// manual clock must return a [`Notifier`] from [`notifier`] and wake it up whenever its time
+/// changes. Timer channels on a manual clock only fire in response to those notifications, which
+/// makes them fully deterministic. [`MockClock`] is such a clock.
+///
+/// Timeouts passed to methods like [`Receiver::recv_timeout`] and [`Select::select_timeout`] are
+/// always measured by the system clock, or by the virtual clock on threads simulated by the `sim`
+/// module. To wait for a message with a timeout on a manual clock, select over the channel and a
+/// timer created with [`after_with_clock`] instead.
+///
+/// [`after_with_clock`]: crate::after_with_clock
+/// [`at_with_clock`]: crate::at_with_clock
+/// [`tick_with_clock`]: crate::tick_with_clock
+/// [`notifier`]: Clock::notifier
+/// [`Receiver::recv_timeout`]: crate::Receiver::recv_timeout
+/// [`Select::select_timeout`]: crate::Select::select_timeout
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+
+    /// Returns the notifier used to wake up threads when the time is changed manually.
+    ///
+    /// Clocks that advance on their own return `None`, which is the default.
+    fn notifier(&self) -> Option<&Notifier> {
+        None
+    }
+}
+
+/// The system's monotonic clock.
+///
+/// This is the clock used by timer channels unless another one is given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when it is advanced.
+///
+/// The clock starts at the moment it is created. Every call to [`advance`] wakes up the threads
+/// blocked on timer channels driven by it, and the timers that became due deliver their messages.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use crossbeam_channel::{after_with_clock, Clock, MockClock, TryRecvError};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let start = clock.now();
+/// let r = after_with_clock(Duration::from_secs(60), clock.clone());
+///
+/// clock.advance(Duration::from_secs(59));
+/// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+///
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(r.recv(), Ok(start + Duration::from_secs(60)));
+/// ```
+///
+/// [`advance`]: MockClock::advance
+pub struct MockClock {
+    /// The current time.
+    now: Mutex<Instant>,
+
+    /// Threads waiting for the time to change.
+    notifier: Notifier,
+}
+
+impl MockClock {
+    /// Creates a clock that starts at the current time.
+    pub fn new() -> MockClock {
+        MockClock {
+            now: Mutex::new(Instant::now()),
+            notifier: Notifier::new(),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+        self.notifier.notify_all();
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn notifier(&self) -> Option<&Notifier> {
+        Some(&self.notifier)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> MockClock {
+        MockClock::new()
+    }
+}
+
+impl fmt::Debug for MockClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("MockClock { .. }")
+    }
+}
+
+/// The clock of a timer channel.
+///
+/// `None` stands for the system clock, which avoids dynamic dispatch in the common case.
+#[derive(Clone)]
+pub(crate) struct TimerClock(Option<Arc<dyn Clock>>);
+
+impl TimerClock {
+    /// Returns the system clock.
+    #[inline]
+    pub(crate) fn system() -> TimerClock {
+        TimerClock(None)
+    }
+
+    /// Wraps a user-provided clock.
+    #[inline]
+    pub(crate) fn new(clock: Arc<dyn Clock>) -> TimerClock {
+        TimerClock(Some(clock))
+    }
+
+    /// Returns the current time.
+    #[inline]
+    pub(crate) fn now(&self) -> Instant {
+        match &self.0 {
+            None => utils::now(),
+            Some(clock) => clock.now(),
+        }
+    }
+
+    /// Returns the time `timeout` from now, or a very distant time if that overflows.
+    #[inline]
+    pub(crate) fn after(&self, timeout: Duration) -> Instant {
+        let now = self.now();
+        match now.checked_add(timeout) {
+            Some(deadline) => deadline,
+            None => now + Duration::from_secs(86400 * 365 * 30),
+        }
+    }
+
+    /// Returns the waker notified when a manual clock changes, or `None` for a clock that
+    /// advances on its own.
+    #[inline]
+    pub(crate) fn waker(&self) -> Option<&SyncWaker> {
+        self.0
+            .as_ref()
+            .and_then(|clock| clock.notifier())
+            .map(|notifier| &notifier.waker)
+    }
+
+    /// Converts an instant on this clock into a deadline for blocking threads.
+    ///
+    /// A manual clock has no deadlines: blocked threads are woken up by its notifier instead.
+    #[inline]
+    pub(crate) fn deadline(&self, when: Instant) -> Option<Instant> {
+        match self.waker() {
+            None => Some(when),
+            Some(_) => None,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Drop-in replacements for channels from other libraries.
+//!
+//! Each module mirrors the API of the channels it replaces, but is backed by crossbeam channels,
+//! so existing code can switch over by changing an import.
+
+pub mod mpsc;
@@ -0,0 +1,297 @@
+//! A drop-in replacement for [`std::sync::mpsc`].
+//!
+//! The functions and types in this module have the same signatures as their counterparts in
+//! [`std::sync::mpsc`], and the error types are the standard library's own. Code written against
+//! the standard library therefore keeps compiling after changing
+//! `use std::sync::mpsc` to `use crossbeam_channel::compat::mpsc`, while running on crossbeam
+//! channels.
+//!
+//! Every sender and receiver converts into its crossbeam counterpart with [`From`], and back. This
+//! makes it possible to adopt the rest of the crossbeam API one part of a program at a time, for
+//! example by turning a receiver into a [`crossbeam_channel::Receiver`] to use it in [`select!`].
+//! Unlike in the standard library, receivers can be shared between threads.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::thread;
+//! use crossbeam_channel::compat::mpsc::{channel, RecvError};
+//!
+//! let (tx, rx) = channel();
+//! thread::spawn(move || {
+//!     for i in 0..3 {
+//!         tx.send(i).unwrap();
+//!     }
+//! });
+//!
+//! assert_eq!(rx.iter().sum::<i32>(), 3);
+//! assert_eq!(rx.recv(), Err(RecvError));
+//!
+//! // Switch to the crossbeam API where it is needed.
+//! let rx = crossbeam_channel::Receiver::from(rx);
+//! assert!(rx.is_empty());
+//! ```
+//!
+//! [`crossbeam_channel::Receiver`]: crate::Receiver
+//! [`select!`]: crate::select!
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::channel;
+use crate::err;
+
+pub use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+
+/// Creates a new asynchronous channel, returning the sender/receiver halves.
+///
+/// The channel is unbounded, so sending never blocks. Messages are received in the order they
+/// were sent. This is backed by [`unbounded`].
+///
+/// [`unbounded`]: crate::unbounded
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (s, r) = channel::unbounded();
+    (Sender { inner: s }, Receiver { inner: r })
+}
+
+/// Creates a new synchronous, bounded channel.
+///
+/// Sending blocks while the channel holds `bound` messages. If `bound` is zero, every send waits
+/// until a receiver takes the message. This is backed by [`bounded`].
+///
+/// [`bounded`]: crate::bounded
+pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
+    let (s, r) = channel::bounded(bound);
+    (SyncSender { inner: s }, Receiver { inner: r })
+}
+
+/// The sending half of a channel created by [`channel`].
+pub struct Sender<T> {
+    inner: channel::Sender<T>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a message on this channel.
+    ///
+    /// This never blocks. If the receiver has been dropped, the message is returned in the error.
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        self.inner.send(t).map_err(|err::SendError(t)| SendError(t))
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        Sender {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Sender { .. }")
+    }
+}
+
+impl<T> From<Sender<T>> for channel::Sender<T> {
+    fn from(s: Sender<T>) -> channel::Sender<T> {
+        s.inner
+    }
+}
+
+impl<T> From<channel::Sender<T>> for Sender<T> {
+    fn from(inner: channel::Sender<T>) -> Sender<T> {
+        Sender { inner }
+    }
+}
+
+/// The sending half of a channel created by [`sync_channel`].
+pub struct SyncSender<T> {
+    inner: channel::Sender<T>,
+}
+
+impl<T> SyncSender<T> {
+    /// Sends a message on this channel, blocking while the channel is full.
+    ///
+    /// If the receiver has been dropped, the message is returned in the error.
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        self.inner.send(t).map_err(|err::SendError(t)| SendError(t))
+    }
+
+    /// Attempts to send a message on this channel without blocking.
+    pub fn try_send(&self, t: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(t).map_err(|err| match err {
+            err::TrySendError::Full(t) => TrySendError::Full(t),
+            err::TrySendError::Disconnected(t) => TrySendError::Disconnected(t),
+        })
+    }
+}
+
+impl<T> Clone for SyncSender<T> {
+    fn clone(&self) -> SyncSender<T> {
+        SyncSender {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("SyncSender { .. }")
+    }
+}
+
+impl<T> From<SyncSender<T>> for channel::Sender<T> {
+    fn from(s: SyncSender<T>) -> channel::Sender<T> {
+        s.inner
+    }
+}
+
+impl<T> From<channel::Sender<T>> for SyncSender<T> {
+    fn from(inner: channel::Sender<T>) -> SyncSender<T> {
+        SyncSender { inner }
+    }
+}
+
+/// The receiving half of a channel created by [`channel`] or [`sync_channel`].
+pub struct Receiver<T> {
+    inner: channel::Receiver<T>,
+}
+
+impl<T> Receiver<T> {
+    /// Attempts to receive a message without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv().map_err(|err| match err {
+            err::TryRecvError::Empty => TryRecvError::Empty,
+            err::TryRecvError::Disconnected => TryRecvError::Disconnected,
+        })
+    }
+
+    /// Blocks the current thread until a message is received or all senders are dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv().map_err(|_| RecvError)
+    }
+
+    /// Waits for a message to be received, but only for a limited time.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.inner.recv_timeout(timeout).map_err(|err| match err {
+            err::RecvTimeoutError::Timeout => RecvTimeoutError::Timeout,
+            err::RecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected,
+        })
+    }
+
+    /// Returns an iterator that blocks waiting for messages until all senders are dropped.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.inner.iter(),
+        }
+    }
+
+    /// Returns an iterator over the messages that are already in the channel.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter {
+            inner: self.inner.try_iter(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Receiver { .. }")
+    }
+}
+
+impl<T> From<Receiver<T>> for channel::Receiver<T> {
+    fn from(r: Receiver<T>) -> channel::Receiver<T> {
+        r.inner
+    }
+}
+
+impl<T> From<channel::Receiver<T>> for Receiver<T> {
+    fn from(inner: channel::Receiver<T>) -> Receiver<T> {
+        Receiver { inner }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.inner.into_iter(),
+        }
+    }
+}
+
+/// A blocking iterator over messages in a channel.
+///
+/// Created with [`Receiver::iter`].
+pub struct Iter<'a, T> {
+    inner: channel::Iter<'a, T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+}
+
+impl<T> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Iter { .. }")
+    }
+}
+
+/// A non-blocking iterator over messages in a channel.
+///
+/// Created with [`Receiver::try_iter`].
+pub struct TryIter<'a, T> {
+    inner: channel::TryIter<'a, T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+}
+
+impl<T> fmt::Debug for TryIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("TryIter { .. }")
+    }
+}
+
+/// An owning, blocking iterator over messages in a channel.
+///
+/// Created by calling `into_iter` on a [`Receiver`].
+pub struct IntoIter<T> {
+    inner: channel::IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+}
+
+impl<T> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("IntoIter { .. }")
+    }
+}
@@ -2,14 +2,18 @@
 
 use std::cell::Cell;
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::thread::{self, Thread, ThreadId};
 use std::time::Instant;
 
 use crossbeam_utils::Backoff;
 
+use crate::primitive::sync::atomic::{AtomicPtr, AtomicUsize};
+use crate::primitive::thread::{self, Thread, ThreadId};
+use crate::primitive::thread_local;
 use crate::select::Selected;
+use crate::spin::{SpinPolicy, SpinWait};
+use crate::utils;
 
 /// Thread-local context used in select.
 // This is a private API that is used by the select macro.
@@ -32,6 +36,10 @@ struct Inner {
 
     /// Thread id.
     thread_id: ThreadId,
+
+    /// The simulated thread this context belongs to, if any.
+    #[cfg(feature = "sim")]
+    sim: Option<crate::sim::Handle>,
 }
 
 impl Context {
@@ -74,6 +82,8 @@ impl Context {
                 packet: AtomicPtr::new(ptr::null_mut()),
                 thread: thread::current(),
                 thread_id: thread::current().id(),
+                #[cfg(feature = "sim")]
+                sim: crate::sim::current(),
             }),
         }
     }
@@ -138,8 +148,17 @@ impl Context {
     /// If the deadline is reached, `Selected::Aborted` will be selected.
     #[inline]
     pub fn wait_until(&self, deadline: Option<Instant>) -> Selected {
+        self.wait_until_with(deadline, SpinPolicy::default())
+    }
+
+    /// Waits until an operation is selected and returns it, spinning according to `spin` before
+    /// parking the thread.
+    ///
+    /// If the deadline is reached, `Selected::Aborted` will be selected.
+    #[inline]
+    pub fn wait_until_with(&self, deadline: Option<Instant>, spin: SpinPolicy) -> Selected {
         // Spin for a short time, waiting until an operation is selected.
-        let backoff = Backoff::new();
+        let backoff = SpinWait::new(spin);
         loop {
             let sel = Selected::from(self.inner.select.load(Ordering::Acquire));
             if sel != Selected::Waiting {
@@ -162,9 +181,16 @@ impl Context {
 
             // If there's a deadline, park the current thread until the deadline is reached.
             if let Some(end) = deadline {
-                let now = Instant::now();
+                let now = utils::now();
 
                 if now < end {
+                    #[cfg(feature = "sim")]
+                    {
+                        if let Some(sim) = &self.inner.sim {
+                            sim.park(Some(end));
+                            continue;
+                        }
+                    }
                     thread::park_timeout(end - now);
                 } else {
                     // The deadline has been reached. Try aborting select.
@@ -174,6 +200,13 @@ impl Context {
                     };
                 }
             } else {
+                #[cfg(feature = "sim")]
+                {
+                    if let Some(sim) = &self.inner.sim {
+                        sim.park(None);
+                        continue;
+                    }
+                }
                 thread::park();
             }
         }
@@ -182,6 +215,12 @@ impl Context {
     /// Unparks the thread this context belongs to.
     #[inline]
     pub fn unpark(&self) {
+        #[cfg(feature = "sim")]
+        {
+            if let Some(sim) = &self.inner.sim {
+                return sim.unpark();
+            }
+        }
         self.inner.thread.unpark();
     }
 
@@ -190,4 +229,12 @@ impl Context {
     pub fn thread_id(&self) -> ThreadId {
         self.inner.thread_id
     }
+
+    /// Returns the name of the thread this context belongs to, or its id if it has no name.
+    pub(crate) fn thread_name(&self) -> String {
+        match self.inner.thread.name() {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", self.inner.thread_id),
+        }
+    }
 }
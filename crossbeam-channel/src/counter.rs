@@ -1,9 +1,21 @@
 //! Reference counter for channels.
 
+use std::error::Error;
 use std::isize;
 use std::ops;
 use std::process;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(feature = "deadlock-detection")]
+use crate::deadlock::Tracker;
+use crate::primitive::sync::atomic::{AtomicBool, AtomicUsize};
+use crate::primitive::sync::Mutex;
+use crate::registry::{self, ChannelInfo, Inspect, Registration};
+
+/// The reason a channel was closed with.
+pub(crate) type CloseReason = Arc<dyn Error + Send + Sync>;
 
 /// Reference counter internals.
 struct Counter<C> {
@@ -16,23 +28,124 @@ struct Counter<C> {
     /// Set to `true` if the last sender or the last receiver reference deallocates the channel.
     destroy: AtomicBool,
 
+    /// Set once poisoning has been enabled for the channel.
+    poisoning: AtomicBool,
+
+    /// Set if a sender was dropped during a panic while poisoning was enabled.
+    poisoned: AtomicBool,
+
+    /// The reason given when the channel was closed, if any.
+    reason: Mutex<Option<CloseReason>>,
+
+    /// Set if dropping the last sender doesn't disconnect the channel.
+    ///
+    /// The receivers then jointly hold one extra sender reference, released by the last receiver,
+    /// so that `senders` can't drop to zero while any receiver is alive.
+    keep_open: bool,
+
+    /// Set once anything observing the operations on the channel is attached, i.e. metrics hooks,
+    /// the registry, an event log, or a watermark.
+    ///
+    /// Sending and receiving only have to check this flag as long as nothing is attached.
+    instrumented: AtomicBool,
+
+    /// The channel's entry in the registry, if it is registered.
+    ///
+    /// This is declared before `chan` so that the entry is removed before the channel is dropped.
+    registration: Option<Box<Registration>>,
+
+    /// The threads that have used the channel, for detecting deadlocks.
+    #[cfg(feature = "deadlock-detection")]
+    tracker: Arc<Tracker>,
+
     /// The internal channel.
     chan: C,
 }
 
+impl<C> Counter<C> {
+    /// Reopens the channel by calling `reopen`, unless all senders or all receivers are gone.
+    ///
+    /// The last sender or receiver may be released concurrently and find the channel closed
+    /// already, in which case `disconnect` closes it again right after it is reopened.
+    fn reopen<R, D>(&self, reopen: R, disconnect: D) -> bool
+    where
+        R: FnOnce(&C) -> bool,
+        D: FnOnce(&C) -> bool,
+    {
+        let mut slot = self.reason.lock().unwrap();
+        let connected =
+            || self.senders.load(Ordering::SeqCst) > 0 && self.receivers.load(Ordering::SeqCst) > 0;
+        if !connected() || !reopen(&self.chan) {
+            return false;
+        }
+        if !connected() {
+            disconnect(&self.chan);
+            return false;
+        }
+        *slot = None;
+        true
+    }
+
+    /// Returns the number of senders, leaving out the one held by the receivers.
+    fn senders(&self) -> usize {
+        let senders = self.senders.load(Ordering::SeqCst);
+        if self.keep_open {
+            senders - 1
+        } else {
+            senders
+        }
+    }
+}
+
 /// Wraps a channel into the reference counter.
-pub(crate) fn new<C>(chan: C) -> (Sender<C>, Receiver<C>) {
+///
+/// If `keep_open` is `true`, dropping the last sender doesn't disconnect the channel.
+pub(crate) fn new<C: Inspect>(chan: C, keep_open: bool) -> (Sender<C>, Receiver<C>) {
     let counter = Box::into_raw(Box::new(Counter {
-        senders: AtomicUsize::new(1),
+        senders: AtomicUsize::new(if keep_open { 2 } else { 1 }),
         receivers: AtomicUsize::new(1),
         destroy: AtomicBool::new(false),
+        poisoning: AtomicBool::new(false),
+        poisoned: AtomicBool::new(false),
+        reason: Mutex::new(None),
+        keep_open,
+        instrumented: AtomicBool::new(false),
+        registration: None,
+        #[cfg(feature = "deadlock-detection")]
+        tracker: Tracker::new(),
         chan,
     }));
+    unsafe {
+        if let Some(registration) = registry::register(counter as *const (), info::<C>) {
+            (*counter).registration = Some(Box::new(registration));
+            (*counter).instrumented.store(true, Ordering::Relaxed);
+        }
+    }
     let s = Sender { counter };
     let r = Receiver { counter };
     (s, r)
 }
 
+/// Reads the statistics of the channel behind `counter` for the registry.
+unsafe fn info<C: Inspect>(counter: *const ()) -> ChannelInfo {
+    let counter = &*(counter as *const Counter<C>);
+    let info = ChannelInfo::new(
+        counter.chan.name(),
+        counter.chan.capacity(),
+        counter.chan.len(),
+        counter.senders(),
+        counter.receivers.load(Ordering::SeqCst),
+    );
+    match counter.registration.as_ref().map(|r| &**r) {
+        Some(registration) => info.with_threads(
+            registration,
+            counter.chan.blocked_sender_names(),
+            counter.chan.blocked_receiver_names(),
+        ),
+        None => info,
+    }
+}
+
 /// The sending side.
 pub(crate) struct Sender<C> {
     counter: *mut Counter<C>,
@@ -60,16 +173,94 @@ impl<C> Sender<C> {
         }
     }
 
+    /// Returns the threads that have used the channel.
+    #[cfg(feature = "deadlock-detection")]
+    pub(crate) fn tracker(&self) -> &Arc<Tracker> {
+        &self.counter().tracker
+    }
+
+    /// Returns the channel's entry in the registry, if it is registered.
+    pub(crate) fn registration(&self) -> Option<&Registration> {
+        self.counter().registration.as_ref().map(|r| &**r)
+    }
+
+    /// Returns `true` if anything observing the operations on the channel is attached.
+    #[inline]
+    pub(crate) fn is_instrumented(&self) -> bool {
+        self.counter().instrumented.load(Ordering::Relaxed)
+    }
+
+    /// Notes that something observing the operations on the channel has been attached.
+    pub(crate) fn instrument(&self) {
+        self.counter().instrumented.store(true, Ordering::Relaxed);
+    }
+
+    /// Enables poisoning for the channel.
+    pub(crate) fn enable_poisoning(&self) {
+        self.counter().poisoning.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the channel has been poisoned.
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.counter().poisoned.load(Ordering::Acquire)
+    }
+
+    /// Closes the channel by calling `disconnect`, and remembers `reason` if this call closed it.
+    ///
+    /// The reason is stored under the lock held while disconnecting, so anyone who sees the
+    /// channel disconnected and then asks for the reason gets it.
+    pub(crate) fn close<F: FnOnce(&C) -> bool>(
+        &self,
+        reason: Option<CloseReason>,
+        disconnect: F,
+    ) -> bool {
+        let mut slot = self.counter().reason.lock().unwrap();
+        let closed = disconnect(&self.counter().chan);
+        if closed {
+            *slot = reason;
+        }
+        closed
+    }
+
+    /// Returns the reason the channel was closed with, if any.
+    pub(crate) fn close_reason(&self) -> Option<CloseReason> {
+        self.counter().reason.lock().unwrap().clone()
+    }
+
+    /// Reopens the channel by calling `reopen`, and forgets the reason it was closed with.
+    ///
+    /// See [`Counter::reopen`] for details.
+    pub(crate) fn reopen<R, D>(&self, reopen: R, disconnect: D) -> bool
+    where
+        R: FnOnce(&C) -> bool,
+        D: FnOnce(&C) -> bool,
+    {
+        self.counter().reopen(reopen, disconnect)
+    }
+
+    /// Returns the number of receivers associated with the channel.
+    pub(crate) fn receivers(&self) -> usize {
+        self.counter().receivers.load(Ordering::SeqCst)
+    }
+
+    /// Poisons the channel if poisoning is enabled and the current thread is panicking.
+    pub(crate) fn poison_if_panicking(&self) {
+        let counter = self.counter();
+        if counter.poisoning.load(Ordering::Relaxed) && thread::panicking() {
+            counter.poisoned.store(true, Ordering::Release);
+        }
+    }
+
     /// Releases the sender reference.
     ///
     /// Function `disconnect` will be called if this is the last sender reference.
     pub(crate) unsafe fn release<F: FnOnce(&C) -> bool>(&self, disconnect: F) {
         if self.counter().senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let _finish = Finish {
+                counter: self.counter,
+                receivers: false,
+            };
             disconnect(&self.counter().chan);
-
-            if self.counter().destroy.swap(true, Ordering::AcqRel) {
-                drop(Box::from_raw(self.counter));
-            }
         }
     }
 }
@@ -99,6 +290,41 @@ impl<C> Receiver<C> {
         unsafe { &*self.counter }
     }
 
+    /// Returns the number of senders associated with the channel.
+    pub(crate) fn senders(&self) -> usize {
+        self.counter().senders()
+    }
+
+    /// Acquires a new sender reference, unless every sender has been released already.
+    pub(crate) fn new_sender(&self) -> Option<Sender<C>> {
+        let senders = &self.counter().senders;
+        let mut count = senders.load(Ordering::Relaxed);
+        loop {
+            if count == 0 {
+                return None;
+            }
+
+            // See `Sender::acquire` for why overflowing the counter aborts.
+            if count > isize::MAX as usize {
+                process::abort();
+            }
+
+            match senders.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return Some(Sender {
+                        counter: self.counter,
+                    })
+                }
+                Err(current) => count = current,
+            }
+        }
+    }
+
     /// Acquires another receiver reference.
     pub(crate) fn acquire(&self) -> Receiver<C> {
         let count = self.counter().receivers.fetch_add(1, Ordering::Relaxed);
@@ -115,14 +341,114 @@ impl<C> Receiver<C> {
         }
     }
 
+    /// Returns the threads that have used the channel.
+    #[cfg(feature = "deadlock-detection")]
+    pub(crate) fn tracker(&self) -> &Arc<Tracker> {
+        &self.counter().tracker
+    }
+
+    /// Returns the channel's entry in the registry, if it is registered.
+    pub(crate) fn registration(&self) -> Option<&Registration> {
+        self.counter().registration.as_ref().map(|r| &**r)
+    }
+
+    /// Returns `true` if anything observing the operations on the channel is attached.
+    #[inline]
+    pub(crate) fn is_instrumented(&self) -> bool {
+        self.counter().instrumented.load(Ordering::Relaxed)
+    }
+
+    /// Notes that something observing the operations on the channel has been attached.
+    pub(crate) fn instrument(&self) {
+        self.counter().instrumented.store(true, Ordering::Relaxed);
+    }
+
+    /// Enables poisoning for the channel.
+    pub(crate) fn enable_poisoning(&self) {
+        self.counter().poisoning.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the channel has been poisoned.
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.counter().poisoned.load(Ordering::Acquire)
+    }
+
+    /// Closes the channel by calling `disconnect`, and remembers `reason` if this call closed it.
+    ///
+    /// The reason is stored under the lock held while disconnecting, so anyone who sees the
+    /// channel disconnected and then asks for the reason gets it.
+    pub(crate) fn close<F: FnOnce(&C) -> bool>(
+        &self,
+        reason: Option<CloseReason>,
+        disconnect: F,
+    ) -> bool {
+        let mut slot = self.counter().reason.lock().unwrap();
+        let closed = disconnect(&self.counter().chan);
+        if closed {
+            *slot = reason;
+        }
+        closed
+    }
+
+    /// Returns the reason the channel was closed with, if any.
+    pub(crate) fn close_reason(&self) -> Option<CloseReason> {
+        self.counter().reason.lock().unwrap().clone()
+    }
+
+    /// Reopens the channel by calling `reopen`, and forgets the reason it was closed with.
+    ///
+    /// See [`Counter::reopen`] for details.
+    pub(crate) fn reopen<R, D>(&self, reopen: R, disconnect: D) -> bool
+    where
+        R: FnOnce(&C) -> bool,
+        D: FnOnce(&C) -> bool,
+    {
+        self.counter().reopen(reopen, disconnect)
+    }
+
     /// Releases the receiver reference.
     ///
     /// Function `disconnect` will be called if this is the last receiver reference.
     pub(crate) unsafe fn release<F: FnOnce(&C) -> bool>(&self, disconnect: F) {
         if self.counter().receivers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let _finish = Finish {
+                counter: self.counter,
+                receivers: true,
+            };
             disconnect(&self.counter().chan);
+        }
+    }
+}
+
+/// Finishes releasing the last reference of one side of the channel when dropped.
+///
+/// Disconnecting may drop messages whose destructors panic. Doing the rest of the work in a
+/// destructor makes sure the channel is still destroyed once both sides are gone.
+struct Finish<C> {
+    /// The counter being released.
+    counter: *mut Counter<C>,
+
+    /// Whether the last receiver reference is being released.
+    receivers: bool,
+}
+
+impl<C> Drop for Finish<C> {
+    fn drop(&mut self) {
+        unsafe {
+            let counter = &*self.counter;
+            if counter.destroy.swap(true, Ordering::AcqRel) {
+                drop(Box::from_raw(self.counter));
+                return;
+            }
 
-            if self.counter().destroy.swap(true, Ordering::AcqRel) {
+            // Release the sender reference held by the receivers, which kept the senders side
+            // from finishing. The channel is already disconnected, so there is nothing more to do
+            // than destroying it if this was the last sender reference.
+            if self.receivers
+                && counter.keep_open
+                && counter.senders.fetch_sub(1, Ordering::AcqRel) == 1
+                && counter.destroy.swap(true, Ordering::AcqRel)
+            {
                 drop(Box::from_raw(self.counter));
             }
         }
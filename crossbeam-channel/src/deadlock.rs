@@ -0,0 +1,223 @@
+//! Detection of deadlocks between threads blocked on channels.
+//!
+//! Every channel remembers which threads have sent into it and received from it. A thread blocked
+//! sending into a channel waits for the threads that have received from it, and the other way
+//! around. If a blocked thread only waits for threads that are themselves blocked, and so on,
+//! none of them can ever make progress.
+//!
+//! Blocking operations wait in slices of [`CHECK_INTERVAL`]. After the first slice, a thread
+//! registers what it is waiting for, and after every slice it looks for a deadlock. To avoid
+//! reporting threads that are just about to be woken up, all threads involved must have been
+//! blocked for at least two slices.
+//!
+//! A channel side that hasn't been used yet could still be used by anyone, so waiting for it is
+//! never reported as a deadlock. Threads that used a channel and then exited are still counted on,
+//! so some deadlocks go undetected.
+
+use std::collections::{HashMap, HashSet};
+use std::ptr;
+use std::sync::{Arc, Mutex, Once};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use crate::utils;
+
+/// How long a blocking operation waits between checks for a deadlock.
+pub(crate) const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A side of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    /// The sending side.
+    Send,
+
+    /// The receiving side.
+    Recv,
+}
+
+/// The threads that have used each side of a channel.
+#[derive(Debug, Default)]
+pub(crate) struct Tracker {
+    /// Threads that have sent a message into the channel.
+    senders: Mutex<Vec<ThreadId>>,
+
+    /// Threads that have received a message from the channel.
+    receivers: Mutex<Vec<ThreadId>>,
+}
+
+impl Tracker {
+    /// Creates a tracker for a new channel.
+    pub(crate) fn new() -> Arc<Tracker> {
+        Arc::new(Tracker::default())
+    }
+
+    /// Returns the threads that have used `side` of the channel.
+    fn users(&self, side: Side) -> &Mutex<Vec<ThreadId>> {
+        match side {
+            Side::Send => &self.senders,
+            Side::Recv => &self.receivers,
+        }
+    }
+
+    /// Records that the current thread has completed an operation on `side` of the channel.
+    fn used(&self, side: Side) {
+        let id = thread::current().id();
+        let mut users = self.users(side).lock().unwrap();
+        if !users.contains(&id) {
+            users.push(id);
+        }
+    }
+}
+
+/// An operation a blocked thread is waiting on.
+// This is a private API that is used by the select macro.
+#[derive(Debug, Clone)]
+pub struct WaitFor {
+    /// The channel.
+    tracker: Arc<Tracker>,
+
+    /// The side of the channel the operation is on.
+    side: Side,
+}
+
+impl WaitFor {
+    /// Creates an operation on `side` of the channel tracked by `tracker`.
+    pub(crate) fn new(tracker: &Arc<Tracker>, side: Side) -> WaitFor {
+        WaitFor {
+            tracker: tracker.clone(),
+            side,
+        }
+    }
+
+    /// Records that the current thread has completed this operation.
+    pub(crate) fn used(&self) {
+        self.tracker.used(self.side);
+    }
+
+    /// Returns the threads that can complete this operation.
+    fn counterparts(&self) -> Vec<ThreadId> {
+        let side = match self.side {
+            Side::Send => Side::Recv,
+            Side::Recv => Side::Send,
+        };
+        self.tracker.users(side).lock().unwrap().clone()
+    }
+}
+
+/// A thread that has been blocked for at least one slice.
+struct Blocked {
+    /// The thread's name, for reporting.
+    name: String,
+
+    /// The operations the thread is waiting on.
+    ops: Vec<WaitFor>,
+
+    /// The number of slices the thread has been blocked for since it registered.
+    slices: u64,
+}
+
+/// Returns the blocked threads.
+fn blocked() -> &'static Mutex<HashMap<ThreadId, Blocked>> {
+    static INIT: Once = Once::new();
+    static mut BLOCKED: *const Mutex<HashMap<ThreadId, Blocked>> = ptr::null();
+
+    INIT.call_once(|| {
+        let blocked: &'static Mutex<HashMap<ThreadId, Blocked>> =
+            Box::leak(Box::new(Mutex::new(HashMap::new())));
+        unsafe { BLOCKED = blocked }
+    });
+
+    unsafe { &*BLOCKED }
+}
+
+/// Keeps the current thread registered as blocked until it is dropped.
+pub(crate) struct Waiting {
+    /// The current thread.
+    id: ThreadId,
+}
+
+impl Waiting {
+    /// Registers the current thread as blocked on `ops`.
+    pub(crate) fn new(ops: Vec<WaitFor>) -> Waiting {
+        let current = thread::current();
+        let id = current.id();
+        let name = match current.name() {
+            Some(name) => name.to_string(),
+            None => format!("{:?}", id),
+        };
+
+        blocked().lock().unwrap().insert(
+            id,
+            Blocked {
+                name,
+                ops,
+                slices: 0,
+            },
+        );
+        Waiting { id }
+    }
+
+    /// Records another slice of blocking and panics if the current thread is deadlocked.
+    pub(crate) fn check(&self) {
+        let mut blocked = blocked().lock().unwrap();
+        if let Some(me) = blocked.get_mut(&self.id) {
+            me.slices += 1;
+        }
+
+        // Collect every thread the current thread transitively waits for, giving up as soon as
+        // one of them might still make progress.
+        let mut stack = vec![self.id];
+        let mut seen = HashSet::new();
+        seen.insert(self.id);
+        while let Some(id) = stack.pop() {
+            let b = match blocked.get(&id) {
+                Some(b) if b.slices >= 2 => b,
+                _ => return,
+            };
+
+            let mut waits_for_someone = false;
+            for op in &b.ops {
+                for other in op.counterparts() {
+                    waits_for_someone = true;
+                    if seen.insert(other) {
+                        stack.push(other);
+                    }
+                }
+            }
+            if !waits_for_someone {
+                return;
+            }
+        }
+
+        let mut names = seen
+            .iter()
+            .map(|id| format!("`{}`", blocked[id].name))
+            .collect::<Vec<_>>();
+        names.sort();
+        drop(blocked);
+        panic!(
+            "deadlock detected: threads {} are blocked on channels only they can unblock",
+            names.join(", ")
+        );
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        blocked().lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Runs a blocking operation in slices, looking for a deadlock after each one.
+///
+/// `attempt` runs the operation until the given deadline. If it times out, it returns the
+/// operations the thread is waiting on.
+pub(crate) fn watch<R>(mut attempt: impl FnMut(Instant) -> Result<R, Vec<WaitFor>>) -> R {
+    let mut waiting = None;
+    loop {
+        match attempt(utils::now() + CHECK_INTERVAL) {
+            Ok(res) => return res,
+            Err(ops) => waiting.get_or_insert_with(|| Waiting::new(ops)).check(),
+        }
+    }
+}
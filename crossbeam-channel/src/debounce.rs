@@ -0,0 +1,67 @@
+//! Debouncing of bursty channels.
+
+use std::thread;
+use std::time::Duration;
+
+use crate::channel::{bounded, Receiver};
+use crate::err::RecvTimeoutError;
+
+/// Creates a receiver that delivers the latest message from `r` once no new messages have arrived
+/// for `quiet`.
+///
+/// Every message received from `r` restarts the quiet period and replaces the previous one, so a
+/// burst of messages results in a single message: the last one of the burst. When `r` becomes
+/// disconnected, a pending message is delivered right away, after which the returned channel
+/// becomes disconnected too.
+///
+/// Messages are forwarded by a background thread. It stops when `r` becomes disconnected, or when
+/// the returned receiver has been dropped and there is a message to deliver.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{debounce, unbounded};
+///
+/// let (s, r) = unbounded();
+/// let r = debounce(r, Duration::from_millis(100));
+///
+/// for i in 0..5 {
+///     s.send(i).unwrap();
+///     thread::sleep(Duration::from_millis(10));
+/// }
+///
+/// // Only the last message of the burst is delivered.
+/// assert_eq!(r.recv(), Ok(4));
+///
+/// drop(s);
+/// assert!(r.recv().is_err());
+/// ```
+pub fn debounce<T: Send + 'static>(r: Receiver<T>, quiet: Duration) -> Receiver<T> {
+    let (s, out) = bounded(1);
+
+    thread::Builder::new()
+        .name("crossbeam-channel-debounce".to_string())
+        .spawn(move || {
+            while let Ok(mut latest) = r.recv() {
+                loop {
+                    match r.recv_timeout(quiet) {
+                        Ok(msg) => latest = msg,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            let _ = s.send(latest);
+                            return;
+                        }
+                    }
+                }
+
+                if s.send(latest).is_err() {
+                    return;
+                }
+            }
+        })
+        .expect("failed to spawn a thread for debouncing a channel");
+
+    out
+}
@@ -83,6 +83,56 @@ pub enum RecvTimeoutError {
     Disconnected,
 }
 
+/// An error returned from the [`send_cancellable`] method.
+///
+/// The error contains the message being sent so it can be recovered.
+///
+/// [`send_cancellable`]: super::Sender::send_cancellable
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendCancelError<T> {
+    /// The message could not be sent because the operation was cancelled.
+    Cancelled(T),
+
+    /// The message could not be sent because the channel is disconnected.
+    Disconnected(T),
+}
+
+/// An error returned from the [`recv_cancellable`] method.
+///
+/// [`recv_cancellable`]: super::Receiver::recv_cancellable
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvCancelError {
+    /// A message could not be received because the operation was cancelled.
+    Cancelled,
+
+    /// The message could not be received because the channel is empty and disconnected.
+    Disconnected,
+}
+
+/// An error returned from the [`recv_checked`] method.
+///
+/// [`recv_checked`]: super::Receiver::recv_checked
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvPoisonError {
+    /// The message could not be received because the channel is empty and disconnected.
+    Disconnected,
+
+    /// The message could not be received because the channel is empty and disconnected, and a
+    /// sender was dropped while its thread was panicking.
+    Poisoned,
+}
+
+/// An error returned from the [`put_back`] method.
+///
+/// The message could not be put back because another message is already waiting to be received
+/// again.
+///
+/// The error contains the message so it can be recovered.
+///
+/// [`put_back`]: super::PutBackReceiver::put_back
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct PutBackError<T>(pub T);
+
 /// An error returned from the [`try_select`] method.
 ///
 /// Failed because none of the channel operations were ready.
@@ -177,6 +227,16 @@ impl<T> From<SendError<T>> for TrySendError<T> {
     }
 }
 
+impl<T> From<SendTimeoutError<T>> for TrySendError<T> {
+    /// Converts a timeout into [`TrySendError::Full`], since the channel stayed full.
+    fn from(err: SendTimeoutError<T>) -> TrySendError<T> {
+        match err {
+            SendTimeoutError::Timeout(t) => TrySendError::Full(t),
+            SendTimeoutError::Disconnected(t) => TrySendError::Disconnected(t),
+        }
+    }
+}
+
 impl<T> TrySendError<T> {
     /// Unwraps the message.
     ///
@@ -240,6 +300,16 @@ impl<T> From<SendError<T>> for SendTimeoutError<T> {
     }
 }
 
+impl<T> From<TrySendError<T>> for SendTimeoutError<T> {
+    /// Converts [`TrySendError::Full`] into a timeout, as if the send had a zero timeout.
+    fn from(err: TrySendError<T>) -> SendTimeoutError<T> {
+        match err {
+            TrySendError::Full(t) => SendTimeoutError::Timeout(t),
+            TrySendError::Disconnected(t) => SendTimeoutError::Disconnected(t),
+        }
+    }
+}
+
 impl<T> SendTimeoutError<T> {
     /// Unwraps the message.
     ///
@@ -306,6 +376,16 @@ impl From<RecvError> for TryRecvError {
     }
 }
 
+impl From<RecvTimeoutError> for TryRecvError {
+    /// Converts a timeout into [`TryRecvError::Empty`], since the channel stayed empty.
+    fn from(err: RecvTimeoutError) -> TryRecvError {
+        match err {
+            RecvTimeoutError::Timeout => TryRecvError::Empty,
+            RecvTimeoutError::Disconnected => TryRecvError::Disconnected,
+        }
+    }
+}
+
 impl TryRecvError {
     /// Returns `true` if the receive operation failed because the channel is empty.
     pub fn is_empty(&self) -> bool {
@@ -343,6 +423,16 @@ impl From<RecvError> for RecvTimeoutError {
     }
 }
 
+impl From<TryRecvError> for RecvTimeoutError {
+    /// Converts [`TryRecvError::Empty`] into a timeout, as if the receive had a zero timeout.
+    fn from(err: TryRecvError) -> RecvTimeoutError {
+        match err {
+            TryRecvError::Empty => RecvTimeoutError::Timeout,
+            TryRecvError::Disconnected => RecvTimeoutError::Disconnected,
+        }
+    }
+}
+
 impl RecvTimeoutError {
     /// Returns `true` if the receive operation timed out.
     pub fn is_timeout(&self) -> bool {
@@ -361,6 +451,180 @@ impl RecvTimeoutError {
     }
 }
 
+impl<T> fmt::Debug for SendCancelError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "SendCancelError(..)".fmt(f)
+    }
+}
+
+impl<T> fmt::Display for SendCancelError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendCancelError::Cancelled(..) => "send operation was cancelled".fmt(f),
+            SendCancelError::Disconnected(..) => "sending on a disconnected channel".fmt(f),
+        }
+    }
+}
+
+impl<T: Send> error::Error for SendCancelError<T> {}
+
+impl<T> From<SendError<T>> for SendCancelError<T> {
+    fn from(err: SendError<T>) -> SendCancelError<T> {
+        match err {
+            SendError(e) => SendCancelError::Disconnected(e),
+        }
+    }
+}
+
+impl<T> SendCancelError<T> {
+    /// Unwraps the message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{bounded, cancel_token};
+    ///
+    /// let (s, r) = bounded(0);
+    /// let (token, signal) = cancel_token();
+    /// token.cancel();
+    ///
+    /// if let Err(err) = s.send_cancellable("foo", &signal) {
+    ///     assert_eq!(err.into_inner(), "foo");
+    /// }
+    /// ```
+    pub fn into_inner(self) -> T {
+        match self {
+            SendCancelError::Cancelled(v) => v,
+            SendCancelError::Disconnected(v) => v,
+        }
+    }
+
+    /// Returns `true` if the send operation was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            SendCancelError::Cancelled(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the send operation failed because the channel is disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        match self {
+            SendCancelError::Disconnected(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RecvCancelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecvCancelError::Cancelled => "receive operation was cancelled".fmt(f),
+            RecvCancelError::Disconnected => "channel is empty and disconnected".fmt(f),
+        }
+    }
+}
+
+impl error::Error for RecvCancelError {}
+
+impl From<RecvError> for RecvCancelError {
+    fn from(err: RecvError) -> RecvCancelError {
+        match err {
+            RecvError => RecvCancelError::Disconnected,
+        }
+    }
+}
+
+impl RecvCancelError {
+    /// Returns `true` if the receive operation was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            RecvCancelError::Cancelled => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the receive operation failed because the channel is disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        match self {
+            RecvCancelError::Disconnected => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RecvPoisonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecvPoisonError::Disconnected => "channel is empty and disconnected".fmt(f),
+            RecvPoisonError::Poisoned => "channel is empty and a sender panicked".fmt(f),
+        }
+    }
+}
+
+impl error::Error for RecvPoisonError {}
+
+impl From<RecvError> for RecvPoisonError {
+    fn from(err: RecvError) -> RecvPoisonError {
+        match err {
+            RecvError => RecvPoisonError::Disconnected,
+        }
+    }
+}
+
+impl RecvPoisonError {
+    /// Returns `true` if the receive operation failed because the channel is disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        match self {
+            RecvPoisonError::Disconnected => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the receive operation failed because a sender panicked.
+    pub fn is_poisoned(&self) -> bool {
+        match self {
+            RecvPoisonError::Poisoned => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T> fmt::Debug for PutBackError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "PutBackError(..)".fmt(f)
+    }
+}
+
+impl<T> fmt::Display for PutBackError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "a message was already put back".fmt(f)
+    }
+}
+
+impl<T: Send> error::Error for PutBackError<T> {}
+
+impl<T> PutBackError<T> {
+    /// Unwraps the message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::unbounded;
+    ///
+    /// let (_s, r) = unbounded();
+    /// let mut r = r.with_put_back();
+    /// r.put_back("foo").unwrap();
+    ///
+    /// if let Err(err) = r.put_back("bar") {
+    ///     assert_eq!(err.into_inner(), "bar");
+    /// }
+    /// ```
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
 impl fmt::Display for TrySelectError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         "all operations in select would block".fmt(f)
@@ -369,6 +633,12 @@ impl fmt::Display for TrySelectError {
 
 impl error::Error for TrySelectError {}
 
+impl From<SelectTimeoutError> for TrySelectError {
+    fn from(_: SelectTimeoutError) -> TrySelectError {
+        TrySelectError
+    }
+}
+
 impl fmt::Display for SelectTimeoutError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         "timed out waiting on select".fmt(f)
@@ -376,3 +646,37 @@ impl fmt::Display for SelectTimeoutError {
 }
 
 impl error::Error for SelectTimeoutError {}
+
+impl From<TrySelectError> for SelectTimeoutError {
+    fn from(_: TrySelectError) -> SelectTimeoutError {
+        SelectTimeoutError
+    }
+}
+
+impl fmt::Display for TryReadyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "all operations in select would block".fmt(f)
+    }
+}
+
+impl error::Error for TryReadyError {}
+
+impl From<ReadyTimeoutError> for TryReadyError {
+    fn from(_: ReadyTimeoutError) -> TryReadyError {
+        TryReadyError
+    }
+}
+
+impl fmt::Display for ReadyTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "timed out waiting on select".fmt(f)
+    }
+}
+
+impl error::Error for ReadyTimeoutError {}
+
+impl From<TryReadyError> for ReadyTimeoutError {
+    fn from(_: TryReadyError) -> ReadyTimeoutError {
+        ReadyTimeoutError
+    }
+}
@@ -0,0 +1,159 @@
+//! Logs of recent events in a channel.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, Thread, ThreadId};
+use std::time::Instant;
+
+use crate::utils;
+
+/// The kind of a [`ChannelEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// A message was sent into the channel.
+    Send,
+
+    /// A message was received from the channel.
+    Recv,
+
+    /// A thread parked until it could send a message.
+    ParkSend,
+
+    /// A thread parked until it could receive a message.
+    ParkRecv,
+
+    /// The last sender was dropped.
+    SendersDisconnected,
+
+    /// The last receiver was dropped.
+    ReceiversDisconnected,
+}
+
+/// An event recorded in a channel's event log.
+///
+/// Events are recorded once [`Sender::record_events`] or [`Receiver::record_events`] has been
+/// called on the channel, and are returned by [`Sender::events`] and [`Receiver::events`].
+///
+/// [`Sender::record_events`]: crate::Sender::record_events
+/// [`Receiver::record_events`]: crate::Receiver::record_events
+/// [`Sender::events`]: crate::Sender::events
+/// [`Receiver::events`]: crate::Receiver::events
+#[derive(Clone)]
+pub struct ChannelEvent {
+    /// What happened.
+    kind: EventKind,
+
+    /// The thread it happened on.
+    thread: Thread,
+
+    /// When it happened.
+    time: Instant,
+}
+
+impl ChannelEvent {
+    /// Returns what happened.
+    pub fn kind(&self) -> EventKind {
+        self.kind
+    }
+
+    /// Returns the ID of the thread the event happened on.
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread.id()
+    }
+
+    /// Returns the name of the thread the event happened on, or `None` if it is unnamed.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread.name()
+    }
+
+    /// Returns when the event happened.
+    pub fn time(&self) -> Instant {
+        self.time
+    }
+}
+
+impl fmt::Debug for ChannelEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelEvent")
+            .field("kind", &self.kind)
+            .field("thread_id", &self.thread.id())
+            .field("thread_name", &self.thread.name())
+            .field("time", &self.time)
+            .finish()
+    }
+}
+
+/// The most recent events in a channel, once recording has started.
+pub(crate) struct EventLog {
+    /// Set while events are being recorded, so that channels that don't record skip the lock.
+    enabled: AtomicBool,
+
+    /// The recorded events, oldest first.
+    ring: Mutex<Ring>,
+}
+
+/// A bounded queue of events that drops the oldest one when a new one doesn't fit.
+struct Ring {
+    /// The recorded events, oldest first.
+    events: VecDeque<ChannelEvent>,
+
+    /// The number of events that are kept.
+    capacity: usize,
+}
+
+impl EventLog {
+    /// Creates a log that doesn't record events yet.
+    pub(crate) fn new() -> EventLog {
+        EventLog {
+            enabled: AtomicBool::new(false),
+            ring: Mutex::new(Ring {
+                events: VecDeque::new(),
+                capacity: 0,
+            }),
+        }
+    }
+
+    /// Forgets the recorded events and starts recording the last `capacity` events, or stops
+    /// recording if `capacity` is zero.
+    pub(crate) fn start(&self, capacity: usize) {
+        let mut ring = self.ring.lock().unwrap();
+        ring.events = VecDeque::with_capacity(capacity);
+        ring.capacity = capacity;
+        self.enabled.store(capacity > 0, Ordering::Relaxed);
+    }
+
+    /// Records an event of the given kind on the current thread, if recording has started.
+    #[inline]
+    pub(crate) fn record(&self, kind: EventKind) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.push(kind);
+        }
+    }
+
+    /// Records an event of the given kind on the current thread.
+    #[cold]
+    fn push(&self, kind: EventKind) {
+        let thread = thread::current();
+        let mut ring = self.ring.lock().unwrap();
+        if ring.capacity == 0 {
+            return;
+        }
+        if ring.events.len() == ring.capacity {
+            ring.events.pop_front();
+        }
+
+        // The time is taken under the lock so that events are ordered by time.
+        ring.events.push_back(ChannelEvent {
+            kind,
+            thread,
+            time: utils::now(),
+        });
+    }
+
+    /// Returns a copy of the recorded events, oldest first.
+    pub(crate) fn snapshot(&self) -> Vec<ChannelEvent> {
+        self.ring.lock().unwrap().events.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,345 @@
+//! Bridges between channels and file descriptors.
+//!
+//! In one direction, a background thread polls a file descriptor and hands over a message through
+//! a zero-capacity channel whenever the descriptor is ready, so the receiver can participate in
+//! selection just like any other channel.
+//!
+//! In the other direction, a receiver can own a self-pipe that senders signal, so the channel can
+//! be registered with an event loop based on `epoll`, `kqueue`, or `mio`.
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::channel::{bounded, Receiver, Sender};
+use crate::select::SelectHandle;
+use crate::waker::Listener;
+
+/// Creates a receiver that delivers a message whenever a file descriptor is readable.
+///
+/// The file descriptor is watched by a background thread, which exits once the receiver is dropped.
+/// If the file descriptor is invalid or cannot be polled, the channel becomes
+/// disconnected.
+///
+/// Readiness is level-triggered and may be reported spuriously, e.g. when another thread has
+/// already consumed the data in the meantime. Use non-blocking I/O on the file descriptor after
+/// receiving a message.
+///
+/// The file descriptor must stay open for as long as the receiver is alive.
+///
+/// # Examples
+///
+/// Waiting for a socket to become readable or for a shutdown signal:
+///
+/// ```
+/// use std::io::Write;
+/// use std::os::unix::io::AsRawFd;
+/// use std::os::unix::net::UnixStream;
+/// use crossbeam_channel::{readable, select, unbounded};
+///
+/// let (mut a, b) = UnixStream::pair().unwrap();
+/// let (shutdown_s, shutdown_r) = unbounded::<()>();
+///
+/// let ready = readable(b.as_raw_fd());
+/// a.write_all(b"hello").unwrap();
+///
+/// select! {
+///     recv(ready) -> _ => println!("socket is readable"),
+///     recv(shutdown_r) -> _ => println!("shutting down"),
+/// }
+/// ```
+pub fn readable(fd: RawFd) -> Receiver<()> {
+    watch(fd, libc::POLLIN)
+}
+
+/// Creates a receiver that delivers a message whenever a file descriptor is writable.
+///
+/// The file descriptor is watched by a background thread, which exits once the receiver is dropped.
+/// If the file descriptor is invalid or cannot be polled, the channel becomes
+/// disconnected.
+///
+/// Readiness is level-triggered and may be reported spuriously. Use non-blocking I/O on the file
+/// descriptor after receiving a message.
+///
+/// The file descriptor must stay open for as long as the receiver is alive.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::io::AsRawFd;
+/// use std::os::unix::net::UnixStream;
+/// use crossbeam_channel::writable;
+///
+/// let (a, _b) = UnixStream::pair().unwrap();
+///
+/// // A fresh socket has room in its send buffer.
+/// let ready = writable(a.as_raw_fd());
+/// assert_eq!(ready.recv(), Ok(()));
+/// ```
+pub fn writable(fd: RawFd) -> Receiver<()> {
+    watch(fd, libc::POLLOUT)
+}
+
+/// Spawns a thread that polls `fd` for `events` and returns the receiving side.
+fn watch(fd: RawFd, events: libc::c_short) -> Receiver<()> {
+    let (s, r) = bounded(0);
+
+    thread::Builder::new()
+        .name("crossbeam-channel-fd".to_string())
+        .spawn(move || {
+            // The sending side is notified when the receiver gets dropped, which signals the pipe
+            // and wakes the thread up from `poll`.
+            let listener = match Pipe::new() {
+                Ok(pipe) => Arc::new(Listener::with_pipe(pipe)),
+                Err(_) => return,
+            };
+            s.listen(&listener, 0);
+            run(fd, events, &s, &listener);
+            s.unlisten(&listener, 0);
+        })
+        .expect("failed to spawn a thread for polling a file descriptor");
+
+    r
+}
+
+/// Polls `fd` until the receiver is dropped or the file descriptor fails.
+///
+/// The reading end of the pipe owned by `listener` is polled alongside `fd`, so that the call
+/// blocks without a timeout and still returns when the receiver is dropped.
+fn run(fd: RawFd, events: libc::c_short, s: &Sender<()>, listener: &Listener) {
+    let pipe = listener
+        .pipe()
+        .expect("listener of the polling thread has no pipe");
+
+    loop {
+        // Clear the pipe before checking for disconnection, so that a disconnection happening
+        // right after the check signals it again.
+        pipe.clear();
+        if s.is_disconnected() {
+            return;
+        }
+
+        let mut pollfds = [
+            libc::pollfd {
+                fd,
+                events,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: pipe.read,
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+
+        let res = unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, -1) };
+        if res < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return;
+        }
+
+        if pollfds[0].revents & libc::POLLNVAL != 0 {
+            return;
+        }
+
+        if pollfds[0].revents != 0 {
+            // Block until the readiness is observed so that it is reported only once.
+            if s.send(()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// A file descriptor that becomes readable when a channel has messages.
+///
+/// Created with [`Receiver::ready_fd`]. The descriptor is the reading end of a pipe owned by this
+/// value, and can be registered with `epoll`, `kqueue`, `mio`, or any other event loop that
+/// watches file descriptors.
+///
+/// The descriptor becomes readable when a message is sent into the channel or the channel becomes
+/// disconnected, and stays readable until [`clear`] is called. After the event loop reports it as
+/// readable, call [`clear`] first and then receive with [`Receiver::try_recv`] until the channel
+/// is empty. Messages sent after [`clear`] make the descriptor readable again, so none of them
+/// are missed.
+///
+/// The descriptor is signaled by senders, so it only works with channels created by [`bounded`]
+/// and [`unbounded`]. A receiver of a timer channel doesn't signal it when a message is due.
+///
+/// The descriptor is closed when this value is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::io::AsRawFd;
+/// use crossbeam_channel::unbounded;
+///
+/// let (s, r) = unbounded();
+/// let ready = r.ready_fd().unwrap();
+///
+/// // Register `ready.as_raw_fd()` with the event loop here.
+/// let _fd = ready.as_raw_fd();
+///
+/// s.send(1).unwrap();
+/// s.send(2).unwrap();
+///
+/// // Once the event loop reports the descriptor as readable:
+/// ready.clear();
+/// let msgs: Vec<_> = ready.receiver().try_iter().collect();
+/// assert_eq!(msgs, [1, 2]);
+/// ```
+///
+/// [`clear`]: ReadyFd::clear
+/// [`Receiver::ready_fd`]: crate::Receiver::ready_fd
+/// [`Receiver::try_recv`]: crate::Receiver::try_recv
+/// [`bounded`]: crate::bounded
+/// [`unbounded`]: crate::unbounded
+pub struct ReadyFd<T> {
+    /// The receiving side of the channel.
+    receiver: Receiver<T>,
+
+    /// The listener owning the pipe, registered with the channel.
+    listener: Arc<Listener>,
+}
+
+impl<T> ReadyFd<T> {
+    /// Creates a pipe signaled by the channel of `receiver`.
+    pub(crate) fn new(receiver: Receiver<T>) -> io::Result<ReadyFd<T>> {
+        let listener = Arc::new(Listener::with_pipe(Pipe::new()?));
+        receiver.listen(&listener, 0);
+
+        // Messages sent before the listener was registered didn't signal the pipe.
+        if receiver.is_ready() {
+            listener.notify(0);
+        }
+
+        Ok(ReadyFd { receiver, listener })
+    }
+
+    /// Returns the receiver whose channel signals the descriptor.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    /// Makes the descriptor non-readable until the next message is sent.
+    ///
+    /// Call this before receiving the messages currently in the channel.
+    pub fn clear(&self) {
+        self.pipe().clear();
+    }
+
+    /// Returns the pipe owned by the listener.
+    fn pipe(&self) -> &Pipe {
+        self.listener
+            .pipe()
+            .expect("listener of `ReadyFd` has no pipe")
+    }
+}
+
+impl<T> AsRawFd for ReadyFd<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.pipe().read
+    }
+}
+
+impl<T> Drop for ReadyFd<T> {
+    fn drop(&mut self) {
+        self.receiver.unlisten(&self.listener, 0);
+    }
+}
+
+impl<T> fmt::Debug for ReadyFd<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("ReadyFd { .. }")
+    }
+}
+
+/// A non-blocking pipe that becomes readable when signaled.
+#[derive(Debug)]
+pub(crate) struct Pipe {
+    /// The reading end.
+    read: RawFd,
+
+    /// The writing end.
+    write: RawFd,
+
+    /// `true` if a byte has been written since the pipe was last cleared.
+    signaled: AtomicBool,
+}
+
+impl Pipe {
+    /// Creates a new pipe.
+    pub(crate) fn new() -> io::Result<Pipe> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // From here on, the descriptors are closed on error by dropping the pipe.
+        let pipe = Pipe {
+            read: fds[0],
+            write: fds[1],
+            signaled: AtomicBool::new(false),
+        };
+        for &fd in &fds {
+            set_nonblocking_cloexec(fd)?;
+        }
+        Ok(pipe)
+    }
+
+    /// Makes the reading end readable.
+    pub(crate) fn signal(&self) {
+        // At most one byte is ever in the pipe, so the write can't block.
+        if !self.signaled.swap(true, Ordering::SeqCst) {
+            let byte = 1u8;
+            unsafe {
+                libc::write(self.write, &byte as *const u8 as *const libc::c_void, 1);
+            }
+        }
+    }
+
+    /// Makes the reading end non-readable.
+    pub(crate) fn clear(&self) {
+        let mut buf = [0u8; 8];
+        loop {
+            let n =
+                unsafe { libc::read(self.read, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+            if n > 0 || (n < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted) {
+                continue;
+            }
+            break;
+        }
+
+        // Reset the flag only after draining. A signal arriving in between skips the write, but
+        // its message is still found by the receive that follows.
+        self.signaled.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
+
+/// Puts `fd` into non-blocking mode and makes it close on `exec`.
+fn set_nonblocking_cloexec(fd: RawFd) -> io::Result<()> {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0
+            || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0
+            || libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) < 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
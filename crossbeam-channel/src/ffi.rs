@@ -0,0 +1,286 @@
+//! A C interface to channels of byte messages.
+//!
+//! The functions in this module are exported with unmangled names, so C and C++ code linked into
+//! the same process can create channels, send and receive messages, and hand channels over to and
+//! from Rust threads. A channel is represented by an opaque pointer to a [`Channel`], which holds
+//! both a sender and a receiver of `Vec<u8>` messages. The pointer may be shared between threads.
+//!
+//! The C declarations are:
+//!
+//! ```c
+//! typedef struct crossbeam_channel crossbeam_channel;
+//!
+//! #define CROSSBEAM_CHANNEL_OK 0
+//! #define CROSSBEAM_CHANNEL_TIMEOUT 1
+//! #define CROSSBEAM_CHANNEL_DISCONNECTED 2
+//! #define CROSSBEAM_CHANNEL_INVALID -1
+//!
+//! crossbeam_channel *crossbeam_channel_bounded(size_t cap);
+//! crossbeam_channel *crossbeam_channel_unbounded(void);
+//! int crossbeam_channel_send(const crossbeam_channel *chan, const uint8_t *data, size_t len);
+//! int crossbeam_channel_recv(const crossbeam_channel *chan, uint8_t **data, size_t *len);
+//! int crossbeam_channel_recv_timeout(const crossbeam_channel *chan, uint64_t timeout_ms,
+//!                                    uint8_t **data, size_t *len);
+//! void crossbeam_channel_free_message(uint8_t *data, size_t len);
+//! int crossbeam_channel_close(const crossbeam_channel *chan);
+//! void crossbeam_channel_destroy(crossbeam_channel *chan);
+//! ```
+//!
+//! Received messages are allocated by Rust and must be released with
+//! `crossbeam_channel_free_message`. Closing a channel makes sending fail everywhere, while the
+//! messages already in it can still be received. Destroying a handle only releases that handle:
+//! the channel itself lives on while Rust code still holds a sender or receiver of it.
+//!
+//! This module is only available with the `ffi` feature enabled.
+//!
+//! # Examples
+//!
+//! Handing a channel created in Rust to C code:
+//!
+//! ```
+//! use crossbeam_channel::{ffi, unbounded};
+//!
+//! let (s, r) = unbounded();
+//! let chan = ffi::Channel::new(s.clone(), r.clone()).into_raw();
+//!
+//! // On the C side:
+//! let msg = b"hello";
+//! let status = unsafe { ffi::crossbeam_channel_send(chan, msg.as_ptr(), msg.len()) };
+//! assert_eq!(status, ffi::CROSSBEAM_CHANNEL_OK);
+//! unsafe { ffi::crossbeam_channel_destroy(chan) };
+//!
+//! assert_eq!(r.recv(), Ok(b"hello".to_vec()));
+//! ```
+
+use std::fmt;
+use std::os::raw::c_int;
+use std::slice;
+use std::time::Duration;
+
+use crate::channel::{bounded, unbounded, Receiver, Sender};
+use crate::err::{RecvTimeoutError, SendError};
+
+/// The operation succeeded.
+pub const CROSSBEAM_CHANNEL_OK: c_int = 0;
+
+/// No message was received before the timeout.
+pub const CROSSBEAM_CHANNEL_TIMEOUT: c_int = 1;
+
+/// The channel is closed or disconnected.
+///
+/// Sending fails with this status as soon as the channel is closed, and receiving once the channel
+/// is also empty.
+pub const CROSSBEAM_CHANNEL_DISCONNECTED: c_int = 2;
+
+/// A required pointer argument was null.
+pub const CROSSBEAM_CHANNEL_INVALID: c_int = -1;
+
+/// A channel handle that can be passed to C code.
+///
+/// C code only ever sees a pointer to it, created with [`into_raw`] or by
+/// `crossbeam_channel_bounded` and `crossbeam_channel_unbounded`.
+///
+/// [`into_raw`]: Channel::into_raw
+pub struct Channel {
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl Channel {
+    /// Creates a handle from both sides of a channel.
+    pub fn new(sender: Sender<Vec<u8>>, receiver: Receiver<Vec<u8>>) -> Channel {
+        Channel { sender, receiver }
+    }
+
+    /// Returns the sending side of the channel.
+    pub fn sender(&self) -> &Sender<Vec<u8>> {
+        &self.sender
+    }
+
+    /// Returns the receiving side of the channel.
+    pub fn receiver(&self) -> &Receiver<Vec<u8>> {
+        &self.receiver
+    }
+
+    /// Moves the handle to the heap and returns a pointer to it for C code.
+    ///
+    /// The handle is released by `crossbeam_channel_destroy` or [`from_raw`].
+    ///
+    /// [`from_raw`]: Channel::from_raw
+    pub fn into_raw(self) -> *mut Channel {
+        Box::into_raw(Box::new(self))
+    }
+
+    /// Takes back ownership of a handle passed to C code.
+    ///
+    /// # Safety
+    ///
+    /// `chan` must come from [`into_raw`], `crossbeam_channel_bounded` or
+    /// `crossbeam_channel_unbounded`, and must not be used again afterwards.
+    ///
+    /// [`into_raw`]: Channel::into_raw
+    pub unsafe fn from_raw(chan: *mut Channel) -> Channel {
+        *Box::from_raw(chan)
+    }
+}
+
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Channel { .. }")
+    }
+}
+
+/// Creates a channel of bounded capacity and returns a handle to it.
+///
+/// A capacity of zero creates a channel where every send waits for a receive.
+#[no_mangle]
+pub extern "C" fn crossbeam_channel_bounded(cap: usize) -> *mut Channel {
+    let (s, r) = bounded(cap);
+    Channel::new(s, r).into_raw()
+}
+
+/// Creates a channel of unbounded capacity and returns a handle to it.
+#[no_mangle]
+pub extern "C" fn crossbeam_channel_unbounded() -> *mut Channel {
+    let (s, r) = unbounded();
+    Channel::new(s, r).into_raw()
+}
+
+/// Sends a copy of `len` bytes starting at `data`, blocking while the channel is full.
+///
+/// # Safety
+///
+/// `chan` must be a live handle, and `data` must point to `len` readable bytes. `data` may be null
+/// if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_send(
+    chan: *const Channel,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    if chan.is_null() || (data.is_null() && len > 0) {
+        return CROSSBEAM_CHANNEL_INVALID;
+    }
+
+    let msg = if len == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(data, len).to_vec()
+    };
+    match (*chan).sender.send(msg) {
+        Ok(()) => CROSSBEAM_CHANNEL_OK,
+        Err(SendError(_)) => CROSSBEAM_CHANNEL_DISCONNECTED,
+    }
+}
+
+/// Blocks until a message is received, storing it in `*data` and its length in `*len`.
+///
+/// The message must be released with [`crossbeam_channel_free_message`].
+///
+/// # Safety
+///
+/// `chan` must be a live handle, and `data` and `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_recv(
+    chan: *const Channel,
+    data: *mut *mut u8,
+    len: *mut usize,
+) -> c_int {
+    if chan.is_null() || data.is_null() || len.is_null() {
+        return CROSSBEAM_CHANNEL_INVALID;
+    }
+
+    match (*chan).receiver.recv() {
+        Ok(msg) => store(msg, data, len),
+        Err(_) => CROSSBEAM_CHANNEL_DISCONNECTED,
+    }
+}
+
+/// Waits up to `timeout_ms` milliseconds for a message, storing it in `*data` and its length in
+/// `*len`.
+///
+/// A timeout of zero doesn't block. The message must be released with
+/// [`crossbeam_channel_free_message`].
+///
+/// # Safety
+///
+/// `chan` must be a live handle, and `data` and `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_recv_timeout(
+    chan: *const Channel,
+    timeout_ms: u64,
+    data: *mut *mut u8,
+    len: *mut usize,
+) -> c_int {
+    if chan.is_null() || data.is_null() || len.is_null() {
+        return CROSSBEAM_CHANNEL_INVALID;
+    }
+
+    match (*chan)
+        .receiver
+        .recv_timeout(Duration::from_millis(timeout_ms))
+    {
+        Ok(msg) => store(msg, data, len),
+        Err(RecvTimeoutError::Timeout) => CROSSBEAM_CHANNEL_TIMEOUT,
+        Err(RecvTimeoutError::Disconnected) => CROSSBEAM_CHANNEL_DISCONNECTED,
+    }
+}
+
+/// Hands a received message over to C code.
+unsafe fn store(msg: Vec<u8>, data: *mut *mut u8, len: *mut usize) -> c_int {
+    let msg = msg.into_boxed_slice();
+    *len = msg.len();
+    *data = Box::into_raw(msg) as *mut u8;
+    CROSSBEAM_CHANNEL_OK
+}
+
+/// Releases a message returned by [`crossbeam_channel_recv`] or
+/// [`crossbeam_channel_recv_timeout`].
+///
+/// Passing a null pointer does nothing.
+///
+/// # Safety
+///
+/// `data` and `len` must be exactly as returned by a receive, and the message must not be used
+/// again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_free_message(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(slice::from_raw_parts_mut(data, len)));
+    }
+}
+
+/// Closes the channel, so that sending fails from now on.
+///
+/// Messages already in the channel can still be received. Returns [`CROSSBEAM_CHANNEL_OK`] if
+/// this call closed the channel, or [`CROSSBEAM_CHANNEL_DISCONNECTED`] if it was already closed.
+///
+/// # Safety
+///
+/// `chan` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_close(chan: *const Channel) -> c_int {
+    if chan.is_null() {
+        return CROSSBEAM_CHANNEL_INVALID;
+    }
+
+    if (*chan).sender.close() {
+        CROSSBEAM_CHANNEL_OK
+    } else {
+        CROSSBEAM_CHANNEL_DISCONNECTED
+    }
+}
+
+/// Releases a handle.
+///
+/// Passing a null pointer does nothing.
+///
+/// # Safety
+///
+/// `chan` must be a live handle, and must not be used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn crossbeam_channel_destroy(chan: *mut Channel) {
+    if !chan.is_null() {
+        drop(Channel::from_raw(chan));
+    }
+}
@@ -8,18 +8,34 @@
 //!   - <http://www.1024cores.net/home/lock-free-algorithms/queues/bounded-mpmc-queue>
 //!   - <https://docs.google.com/document/d/1yIAYmbvL3JxOKOjuCyon7JhW4cSv1wy5hC0ApeGMV9s/pub>
 
-use std::cell::UnsafeCell;
-use std::mem::MaybeUninit;
-use std::ptr;
-use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::fmt;
+use std::mem::{self, MaybeUninit};
+use std::ptr::{self, NonNull};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::time::Instant;
+use std::vec;
 
 use crossbeam_utils::{Backoff, CachePadded};
 
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, SendTimeoutError, TryRecvError, TrySendError};
+use crate::events::{EventKind, EventLog};
+#[cfg(feature = "timestamps")]
+use crate::latency::{Latency, LatencyHistogram};
+use crate::metrics::Metrics;
+use crate::primitive::cell::UnsafeCell;
+use crate::primitive::sync::atomic::{self, AtomicUsize};
+use crate::registry::Inspect;
 use crate::select::{Operation, SelectHandle, Selected, Token};
-use crate::waker::SyncWaker;
+use crate::spin::{SpinPolicy, SpinWait};
+#[cfg(feature = "stats")]
+use crate::stats::Counters;
+use crate::trace;
+use crate::utils::{self, Watermark};
+use crate::waker::{Listener, SyncWaker};
 
 /// A slot in a channel.
 struct Slot<T> {
@@ -28,6 +44,10 @@ struct Slot<T> {
 
     /// The message in this slot.
     msg: UnsafeCell<MaybeUninit<T>>,
+
+    /// When the message was sent.
+    #[cfg(feature = "timestamps")]
+    sent: UnsafeCell<MaybeUninit<Instant>>,
 }
 
 /// The token type for the array flavor.
@@ -71,8 +91,21 @@ pub(crate) struct Channel<T> {
     tail: CachePadded<AtomicUsize>,
 
     /// The buffer holding slots.
+    ///
+    /// The buffer is empty if messages are zero-sized. Such messages hold no data, so the head and
+    /// the tail are used as plain counters and `ready` counts the messages that can be received.
     buffer: Box<[Slot<T>]>,
 
+    /// The number of zero-sized messages that have been written but not received yet.
+    ///
+    /// This is only used if messages are zero-sized.
+    ready: AtomicUsize,
+
+    /// The distance between two used slots in the buffer.
+    ///
+    /// This is 1 unless slots are padded, in which case the slots in between are never used.
+    stride: usize,
+
     /// The channel capacity.
     cap: usize,
 
@@ -87,13 +120,52 @@ pub(crate) struct Channel<T> {
 
     /// Receivers waiting while the channel is empty and not disconnected.
     receivers: SyncWaker,
+
+    /// How long blocked senders and receivers spin before parking.
+    spin: SpinPolicy,
+
+    /// Hooks notified of events in the channel, if any.
+    metrics: Option<Metrics>,
+
+    /// The highest number of messages the channel has held, once tracking has started.
+    max_len: Watermark,
+
+    /// How long received messages waited in the channel.
+    #[cfg(feature = "timestamps")]
+    latency: Latency,
+
+    /// The most recent events in the channel, once recording has started.
+    events: EventLog,
+
+    /// Counts of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    counters: Counters,
 }
 
 impl<T> Channel<T> {
-    /// Creates a bounded channel of capacity `cap`.
-    pub(crate) fn with_capacity(cap: usize) -> Self {
+    /// Creates a bounded channel of capacity `cap` whose blocked operations spin according to
+    /// `spin`.
+    ///
+    /// If `padded` is `true`, used slots are spread out so that no two of them share a cache line.
+    pub(crate) fn with_capacity(
+        cap: usize,
+        spin: SpinPolicy,
+        padded: bool,
+        metrics: Option<Metrics>,
+    ) -> Self {
         assert!(cap > 0, "capacity must be positive");
 
+        // Two slots never share a cache line if the gap between them is at least a line minus one
+        // byte long, so each used slot is followed by enough unused ones to fill that gap. Slots
+        // that are a line long or longer are left as they are.
+        let line = mem::size_of::<CachePadded<u8>>();
+        let size = mem::size_of::<Slot<T>>();
+        let stride = if padded && size < line {
+            1 + (line - 1 + size - 1) / size
+        } else {
+            1
+        };
+
         // Compute constants `mark_bit` and `one_lap`.
         let mark_bit = (cap + 1).next_power_of_two();
         let one_lap = mark_bit * 2;
@@ -103,20 +175,29 @@ impl<T> Channel<T> {
         // Tail is initialized to `{ lap: 0, mark: 0, index: 0 }`.
         let tail = 0;
 
-        // Allocate a buffer of `cap` slots initialized
+        // Allocate a buffer of `cap` used slots initialized
         // with stamps.
-        let buffer: Box<[Slot<T>]> = (0..cap)
+        let len = if mem::size_of::<T>() == 0 {
+            0
+        } else {
+            cap.checked_mul(stride).expect("capacity overflow")
+        };
+        let buffer: Box<[Slot<T>]> = (0..len)
             .map(|i| {
-                // Set the stamp to `{ lap: 0, mark: 0, index: i }`.
+                // Set the stamp to `{ lap: 0, mark: 0, index: i / stride }`.
                 Slot {
-                    stamp: AtomicUsize::new(i),
+                    stamp: AtomicUsize::new(i / stride),
                     msg: UnsafeCell::new(MaybeUninit::uninit()),
+                    #[cfg(feature = "timestamps")]
+                    sent: UnsafeCell::new(MaybeUninit::uninit()),
                 }
             })
             .collect();
 
         Channel {
             buffer,
+            ready: AtomicUsize::new(0),
+            stride,
             cap,
             one_lap,
             mark_bit,
@@ -124,9 +205,40 @@ impl<T> Channel<T> {
             tail: CachePadded::new(AtomicUsize::new(tail)),
             senders: SyncWaker::new(),
             receivers: SyncWaker::new(),
+            spin,
+            metrics,
+            max_len: Watermark::new(),
+            #[cfg(feature = "timestamps")]
+            latency: Latency::new(),
+            events: EventLog::new(),
+            #[cfg(feature = "stats")]
+            counters: Counters::new(),
         }
     }
 
+    /// Returns the slot at `index`, which must be less than the capacity.
+    #[inline]
+    unsafe fn slot(&self, index: usize) -> &Slot<T> {
+        debug_assert!(index < self.cap);
+        self.buffer.get_unchecked(index * self.stride)
+    }
+
+    /// Returns the hooks notified of events in the channel, if any.
+    pub(crate) fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Returns the log of recent events in the channel.
+    pub(crate) fn events(&self) -> &EventLog {
+        &self.events
+    }
+
+    /// Returns the counters of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    pub(crate) fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
     /// Returns a receiver handle to the channel.
     pub(crate) fn receiver(&self) -> Receiver<'_, T> {
         Receiver(self)
@@ -139,6 +251,10 @@ impl<T> Channel<T> {
 
     /// Attempts to reserve a slot for sending a message.
     fn start_send(&self, token: &mut Token) -> bool {
+        if mem::size_of::<T>() == 0 {
+            return self.start_send_counted(token);
+        }
+
         let backoff = Backoff::new();
         let mut tail = self.tail.load(Ordering::Relaxed);
 
@@ -155,8 +271,7 @@ impl<T> Channel<T> {
             let lap = tail & !(self.one_lap - 1);
 
             // Inspect the corresponding slot.
-            debug_assert!(index < self.buffer.len());
-            let slot = unsafe { self.buffer.get_unchecked(index) };
+            let slot = unsafe { self.slot(index) };
             let stamp = slot.stamp.load(Ordering::Acquire);
 
             // If the tail and the stamp match, we may attempt to push.
@@ -209,6 +324,61 @@ impl<T> Channel<T> {
         }
     }
 
+    /// Attempts to reserve room for sending a zero-sized message.
+    ///
+    /// The tail moves forward as long as it's less than one lap ahead of the head. The message
+    /// only becomes visible to receivers once it has been written.
+    fn start_send_counted(&self, token: &mut Token) -> bool {
+        let backoff = Backoff::new();
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            // Check if the channel is disconnected.
+            if tail & self.mark_bit != 0 {
+                token.array.slot = ptr::null();
+                token.array.stamp = 0;
+                return true;
+            }
+
+            atomic::fence(Ordering::SeqCst);
+            let head = self.head.load(Ordering::Relaxed);
+
+            // If the head lags one lap behind the tail, the channel is full.
+            if head.wrapping_add(self.one_lap) == tail {
+                return false;
+            }
+
+            // Deconstruct the tail.
+            let index = tail & (self.mark_bit - 1);
+            let lap = tail & !(self.one_lap - 1);
+
+            let new_tail = if index + 1 < self.cap {
+                tail + 1
+            } else {
+                lap.wrapping_add(self.one_lap)
+            };
+
+            // Try moving the tail.
+            match self.tail.compare_exchange_weak(
+                tail,
+                new_tail,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    // There is no slot, but the token must not look like a disconnected channel.
+                    token.array.slot = NonNull::<Slot<T>>::dangling().as_ptr() as *const u8;
+                    token.array.stamp = 0;
+                    return true;
+                }
+                Err(t) => {
+                    tail = t;
+                    backoff.spin();
+                }
+            }
+        }
+    }
+
     /// Writes a message into the channel.
     pub(crate) unsafe fn write(&self, token: &mut Token, msg: T) -> Result<(), T> {
         // If there is no slot, the channel is disconnected.
@@ -216,10 +386,21 @@ impl<T> Channel<T> {
             return Err(msg);
         }
 
+        if mem::size_of::<T>() == 0 {
+            // The message holds no data, so it's enough to count it.
+            mem::forget(msg);
+            self.ready.fetch_add(1, Ordering::SeqCst);
+            self.receivers.notify();
+            return Ok(());
+        }
+
         let slot: &Slot<T> = &*token.array.slot.cast::<Slot<T>>();
 
         // Write the message into the slot and update the stamp.
-        slot.msg.get().write(MaybeUninit::new(msg));
+        slot.msg.with_mut(|p| p.write(MaybeUninit::new(msg)));
+        #[cfg(feature = "timestamps")]
+        slot.sent
+            .with_mut(|p| p.write(MaybeUninit::new(Instant::now())));
         slot.stamp.store(token.array.stamp, Ordering::Release);
 
         // Wake a sleeping receiver.
@@ -227,8 +408,125 @@ impl<T> Channel<T> {
         Ok(())
     }
 
+    /// Attempts to reserve up to `max` consecutive slots for sending messages with a single CAS.
+    ///
+    /// Returns the number of reserved slots. The run stops at the end of the buffer and at the
+    /// first slot that isn't free yet. Zero is returned if the slot at the tail isn't free, in
+    /// which case the channel may be full or disconnected.
+    fn start_send_run(&self, token: &mut Token, max: usize) -> usize {
+        if mem::size_of::<T>() == 0 {
+            // Zero-sized messages are sent one by one, which costs one CAS each anyway.
+            return 0;
+        }
+
+        let backoff = Backoff::new();
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            // Check if the channel is disconnected.
+            if tail & self.mark_bit != 0 {
+                return 0;
+            }
+
+            // Deconstruct the tail.
+            let index = tail & (self.mark_bit - 1);
+            let lap = tail & !(self.one_lap - 1);
+
+            // Count the free slots starting at the tail. A slot is free if its stamp equals the
+            // tail value that would point to it.
+            let mut n = 0;
+            while n < max && index + n < self.cap {
+                let slot = unsafe { self.slot(index + n) };
+                if slot.stamp.load(Ordering::Acquire) != tail + n {
+                    break;
+                }
+                n += 1;
+            }
+
+            if n == 0 {
+                return 0;
+            }
+
+            let new_tail = if index + n < self.cap {
+                // Same lap, incremented index.
+                tail + n
+            } else {
+                // One lap forward, index wraps around to zero.
+                lap.wrapping_add(self.one_lap)
+            };
+
+            // Try moving the tail past all reserved slots.
+            match self.tail.compare_exchange_weak(
+                tail,
+                new_tail,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    // Prepare the token for the follow-up call to `write_run`.
+                    let slot = unsafe { self.slot(index) };
+                    token.array.slot = slot as *const Slot<T> as *const u8;
+                    token.array.stamp = tail + 1;
+                    return n;
+                }
+                Err(t) => {
+                    tail = t;
+                    backoff.spin();
+                }
+            }
+        }
+    }
+
+    /// Writes `n` messages from `msgs` into the slots reserved by `start_send_run`.
+    ///
+    /// `msgs` must contain at least `n` messages.
+    unsafe fn write_run(&self, token: &mut Token, n: usize, msgs: &mut vec::Drain<'_, T>) {
+        let first = token.array.slot.cast::<Slot<T>>();
+        #[cfg(feature = "timestamps")]
+        let now = Instant::now();
+
+        for i in 0..n {
+            // Write the message into the slot and update the stamp.
+            let slot = &*first.add(i * self.stride);
+            let msg = msgs.next().unwrap();
+            slot.msg.with_mut(|p| p.write(MaybeUninit::new(msg)));
+            #[cfg(feature = "timestamps")]
+            slot.sent.with_mut(|p| p.write(MaybeUninit::new(now)));
+            slot.stamp.store(token.array.stamp + i, Ordering::Release);
+
+            // Wake a sleeping receiver.
+            self.receivers.notify();
+        }
+    }
+
+    /// Sends all messages from `msgs`, reserving runs of free slots with a single CAS each.
+    ///
+    /// Blocks while the channel is full. If the channel is disconnected, the message that couldn't
+    /// be sent is returned and the rest stay in `msgs`.
+    pub(crate) fn send_all(&self, msgs: &mut vec::Drain<'_, T>) -> Result<(), T> {
+        while msgs.len() > 0 {
+            let token = &mut Token::default();
+            let n = self.start_send_run(token, msgs.len());
+
+            if n > 0 {
+                unsafe { self.write_run(token, n, msgs) };
+            } else {
+                // The channel is full or disconnected, so fall back to sending one message, which
+                // blocks until there is room.
+                let msg = msgs.next().unwrap();
+                self.send(msg, None).map_err(SendTimeoutError::into_inner)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Attempts to reserve a slot for receiving a message.
     fn start_recv(&self, token: &mut Token) -> bool {
+        if mem::size_of::<T>() == 0 {
+            return self.start_recv_counted(token);
+        }
+
         let backoff = Backoff::new();
         let mut head = self.head.load(Ordering::Relaxed);
 
@@ -238,8 +536,7 @@ impl<T> Channel<T> {
             let lap = head & !(self.one_lap - 1);
 
             // Inspect the corresponding slot.
-            debug_assert!(index < self.buffer.len());
-            let slot = unsafe { self.buffer.get_unchecked(index) };
+            let slot = unsafe { self.slot(index) };
             let stamp = slot.stamp.load(Ordering::Acquire);
 
             // If the the stamp is ahead of the head by 1, we may attempt to pop.
@@ -300,6 +597,78 @@ impl<T> Channel<T> {
         }
     }
 
+    /// Attempts to take a written zero-sized message and move the head past it.
+    fn start_recv_counted(&self, token: &mut Token) -> bool {
+        let backoff = Backoff::new();
+        let mut ready = self.ready.load(Ordering::SeqCst);
+
+        loop {
+            if ready == 0 {
+                let tail = self.tail.load(Ordering::SeqCst);
+                if tail & self.mark_bit == 0 {
+                    return false;
+                }
+
+                // The tail doesn't move once the channel is disconnected, but senders that moved
+                // it before that may still be about to count their messages. The channel is only
+                // empty once the head has caught up with the tail.
+                ready = self.ready.load(Ordering::SeqCst);
+                if ready == 0 {
+                    if self.head.load(Ordering::SeqCst) == tail & !self.mark_bit {
+                        token.array.slot = ptr::null();
+                        token.array.stamp = 0;
+                        return true;
+                    }
+                    backoff.snooze();
+                }
+                continue;
+            }
+
+            match self.ready.compare_exchange_weak(
+                ready,
+                ready - 1,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(r) => {
+                    ready = r;
+                    backoff.spin();
+                }
+            }
+        }
+
+        // Move the head forward to make room for senders.
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // Deconstruct the head.
+            let index = head & (self.mark_bit - 1);
+            let lap = head & !(self.one_lap - 1);
+
+            let new = if index + 1 < self.cap {
+                head + 1
+            } else {
+                lap.wrapping_add(self.one_lap)
+            };
+
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(h) => {
+                    head = h;
+                    backoff.spin();
+                }
+            }
+        }
+
+        // There is no slot, but the token must not look like a disconnected channel.
+        token.array.slot = NonNull::<Slot<T>>::dangling().as_ptr() as *const u8;
+        token.array.stamp = 0;
+        true
+    }
+
     /// Reads a message from the channel.
     pub(crate) unsafe fn read(&self, token: &mut Token) -> Result<T, ()> {
         if token.array.slot.is_null() {
@@ -307,11 +676,21 @@ impl<T> Channel<T> {
             return Err(());
         }
 
+        if mem::size_of::<T>() == 0 {
+            // A sender has written this message, and reading a zero-sized value needs no memory.
+            self.senders.notify();
+            return Ok(NonNull::<T>::dangling().as_ptr().read());
+        }
+
         let slot: &Slot<T> = &*token.array.slot.cast::<Slot<T>>();
 
         // Read the message from the slot and update the stamp.
-        let msg = slot.msg.get().read().assume_init();
+        let msg = slot.msg.with_mut(|p| p.read().assume_init());
+        #[cfg(feature = "timestamps")]
+        let sent = slot.sent.with(|p| p.read().assume_init());
         slot.stamp.store(token.array.stamp, Ordering::Release);
+        #[cfg(feature = "timestamps")]
+        self.latency.record(sent.elapsed());
 
         // Wake a sleeping sender.
         self.senders.notify();
@@ -337,7 +716,7 @@ impl<T> Channel<T> {
         let token = &mut Token::default();
         loop {
             // Try sending a message several times.
-            let backoff = Backoff::new();
+            let backoff = SpinWait::new(self.spin);
             loop {
                 if self.start_send(token) {
                     let res = unsafe { self.write(token, msg) };
@@ -352,7 +731,7 @@ impl<T> Channel<T> {
             }
 
             if let Some(d) = deadline {
-                if Instant::now() >= d {
+                if utils::now() >= d {
                     return Err(SendTimeoutError::Timeout(msg));
                 }
             }
@@ -367,8 +746,17 @@ impl<T> Channel<T> {
                     let _ = cx.try_select(Selected::Aborted);
                 }
 
+                if let Some(metrics) = &self.metrics {
+                    if cx.selected() == Selected::Waiting {
+                        metrics.block(self.len());
+                    }
+                }
+                if cx.selected() == Selected::Waiting {
+                    self.events.record(EventKind::ParkSend);
+                }
+
                 // Block the current thread.
-                let sel = cx.wait_until(deadline);
+                let sel = trace::wait(cx, deadline, self.spin, "send", self.metrics.as_ref());
 
                 match sel {
                     Selected::Waiting => unreachable!(),
@@ -397,7 +785,7 @@ impl<T> Channel<T> {
         let token = &mut Token::default();
         loop {
             // Try receiving a message several times.
-            let backoff = Backoff::new();
+            let backoff = SpinWait::new(self.spin);
             loop {
                 if self.start_recv(token) {
                     let res = unsafe { self.read(token) };
@@ -412,7 +800,7 @@ impl<T> Channel<T> {
             }
 
             if let Some(d) = deadline {
-                if Instant::now() >= d {
+                if utils::now() >= d {
                     return Err(RecvTimeoutError::Timeout);
                 }
             }
@@ -427,8 +815,17 @@ impl<T> Channel<T> {
                     let _ = cx.try_select(Selected::Aborted);
                 }
 
+                if let Some(metrics) = &self.metrics {
+                    if cx.selected() == Selected::Waiting {
+                        metrics.block(self.len());
+                    }
+                }
+                if cx.selected() == Selected::Waiting {
+                    self.events.record(EventKind::ParkRecv);
+                }
+
                 // Block the current thread.
-                let sel = cx.wait_until(deadline);
+                let sel = trace::wait(cx, deadline, self.spin, "recv", self.metrics.as_ref());
 
                 match sel {
                     Selected::Waiting => unreachable!(),
@@ -445,26 +842,23 @@ impl<T> Channel<T> {
 
     /// Returns the current number of messages inside the channel.
     pub(crate) fn len(&self) -> usize {
-        loop {
-            // Load the tail, then load the head.
-            let tail = self.tail.load(Ordering::SeqCst);
-            let head = self.head.load(Ordering::SeqCst);
-
-            // If the tail didn't change, we've got consistent values to work with.
-            if self.tail.load(Ordering::SeqCst) == tail {
-                let hix = head & (self.mark_bit - 1);
-                let tix = tail & (self.mark_bit - 1);
-
-                return if hix < tix {
-                    tix - hix
-                } else if hix > tix {
-                    self.cap - hix + tix
-                } else if (tail & !self.mark_bit) == head {
-                    0
-                } else {
-                    self.cap
-                };
-            }
+        // Load the head, then load the tail.
+        //
+        // The tail can't be behind a head that was loaded before it. It may have moved more than
+        // a lap ahead in the meantime, though, so the result is capped at the capacity.
+        let head = self.head.load(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::SeqCst) & !self.mark_bit;
+
+        let hix = head & (self.mark_bit - 1);
+        let tix = tail & (self.mark_bit - 1);
+        let laps = (tail & !(self.one_lap - 1)).wrapping_sub(head & !(self.one_lap - 1));
+
+        if laps == 0 {
+            tix - hix
+        } else if laps == self.one_lap {
+            (self.cap - hix + tix).min(self.cap)
+        } else {
+            self.cap
         }
     }
 
@@ -473,6 +867,69 @@ impl<T> Channel<T> {
         Some(self.cap)
     }
 
+    /// Starts keeping track of the highest number of messages the channel has held.
+    pub(crate) fn track_max_len(&self) {
+        self.max_len.start(self.len());
+    }
+
+    /// Raises the highest number of messages seen to the current length, if it is tracked.
+    pub(crate) fn observe_len(&self) {
+        self.max_len.observe(|| self.len());
+    }
+
+    /// Returns the highest number of messages the channel has held, or its current length if that
+    /// isn't tracked.
+    pub(crate) fn max_len_seen(&self) -> usize {
+        self.max_len.get().unwrap_or_else(|| self.len())
+    }
+
+    /// Forgets the highest number of messages seen so far, starting over from the current length.
+    pub(crate) fn reset_max_len_seen(&self) {
+        self.max_len.reset(self.len());
+    }
+
+    /// Returns a histogram of how long received messages waited in the channel.
+    #[cfg(feature = "timestamps")]
+    pub(crate) fn latency(&self) -> LatencyHistogram {
+        self.latency.snapshot()
+    }
+
+    /// Forgets the latencies of all messages received so far.
+    #[cfg(feature = "timestamps")]
+    pub(crate) fn reset_latency(&self) {
+        self.latency.reset();
+    }
+
+    /// Returns the number of threads blocked sending into the channel.
+    pub(crate) fn blocked_senders(&self) -> usize {
+        self.senders.waiting()
+    }
+
+    /// Returns the number of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receivers(&self) -> usize {
+        self.receivers.waiting()
+    }
+
+    /// Returns the names of threads blocked sending into the channel.
+    pub(crate) fn blocked_sender_names(&self) -> Vec<String> {
+        self.senders.waiting_names()
+    }
+
+    /// Returns the names of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receiver_names(&self) -> Vec<String> {
+        self.receivers.waiting_names()
+    }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"array")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("disconnected", &self.is_disconnected())
+            .field("blocked_senders", &self.blocked_senders())
+            .field("blocked_receivers", &self.blocked_receivers());
+    }
+
     /// Disconnects the channel and wakes up all blocked senders and receivers.
     ///
     /// Returns `true` if this call disconnected the channel.
@@ -488,6 +945,27 @@ impl<T> Channel<T> {
         }
     }
 
+    /// Reconnects the channel if it is disconnected and empty.
+    ///
+    /// Returns `true` if this call reconnected the channel.
+    pub(crate) fn reconnect(&self) -> bool {
+        let tail = self.tail.load(Ordering::SeqCst);
+        if tail & self.mark_bit == 0 || tail & !self.mark_bit != self.head.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        // Neither senders nor receivers move the tail while the channel is disconnected, and
+        // receivers don't move the head while it is empty.
+        self.tail
+            .compare_exchange(
+                tail,
+                tail & !self.mark_bit,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+
     /// Returns `true` if the channel is disconnected.
     pub(crate) fn is_disconnected(&self) -> bool {
         self.tail.load(Ordering::SeqCst) & self.mark_bit != 0
@@ -518,41 +996,109 @@ impl<T> Channel<T> {
     }
 }
 
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
+}
+
 impl<T> Drop for Channel<T> {
     fn drop(&mut self) {
-        // Get the index of the head.
-        let head = *self.head.get_mut();
-        let tail = *self.tail.get_mut();
-
-        let hix = head & (self.mark_bit - 1);
-        let tix = tail & (self.mark_bit - 1);
+        if let Some(metrics) = &self.metrics {
+            metrics.destroy(self.len());
+        }
 
-        let len = if hix < tix {
-            tix - hix
-        } else if hix > tix {
-            self.cap - hix + tix
-        } else if (tail & !self.mark_bit) == head {
-            0
+        let len = if mem::size_of::<T>() == 0 {
+            // Drop the zero-sized messages that were written and never received.
+            self.ready.load(Ordering::Relaxed)
         } else {
-            self.cap
+            self.len()
         };
+        let head = self.head.load(Ordering::Relaxed);
 
         // Loop over all slots that hold a message and drop them.
-        for i in 0..len {
-            // Compute the index of the next slot holding a message.
-            let index = if hix + i < self.cap {
-                hix + i
-            } else {
-                hix + i - self.cap
-            };
+        let mut teardown = Teardown {
+            chan: self,
+            index: head & (self.mark_bit - 1),
+            remaining: len,
+        };
+        while teardown.step() {}
+    }
+}
 
-            unsafe {
-                debug_assert!(index < self.buffer.len());
-                let slot = self.buffer.get_unchecked_mut(index);
-                let msg = &mut *slot.msg.get();
-                msg.as_mut_ptr().drop_in_place();
-            }
+/// Drops the messages left in a channel that is being destroyed.
+///
+/// If the destructor of a message panics, dropping the `Teardown` while unwinding drops the
+/// remaining messages, so that they aren't leaked.
+struct Teardown<'a, T> {
+    /// The channel being destroyed.
+    chan: &'a Channel<T>,
+
+    /// The index of the next slot holding a message.
+    index: usize,
+
+    /// The number of messages left to drop.
+    remaining: usize,
+}
+
+impl<T> Teardown<'_, T> {
+    /// Drops the next message.
+    ///
+    /// Returns `false` if there are no messages left.
+    fn step(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+
+        if mem::size_of::<T>() == 0 {
+            unsafe { NonNull::<T>::dangling().as_ptr().drop_in_place() };
+            return true;
         }
+
+        // Move on to the next slot before dropping the message, in case its destructor panics.
+        let index = self.index;
+        self.index = if index + 1 < self.chan.cap {
+            index + 1
+        } else {
+            0
+        };
+
+        unsafe {
+            let slot = self.chan.slot(index);
+            slot.msg.with_mut(|p| (*p).as_mut_ptr().drop_in_place());
+        }
+        true
+    }
+}
+
+impl<T> Drop for Teardown<'_, T> {
+    fn drop(&mut self) {
+        while self.step() {}
+    }
+}
+
+impl<T> Inspect for Channel<T> {
+    fn name(&self) -> Option<&str> {
+        self.metrics.as_ref().map(Metrics::name)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.capacity()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn blocked_sender_names(&self) -> Vec<String> {
+        self.blocked_sender_names()
+    }
+
+    fn blocked_receiver_names(&self) -> Vec<String> {
+        self.blocked_receiver_names()
     }
 }
 
@@ -596,6 +1142,19 @@ impl<T> SelectHandle for Receiver<'_, T> {
     fn unwatch(&self, oper: Operation) {
         self.0.receivers.unwatch(oper);
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.receivers.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.receivers.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.0.receivers.watch_task(waker);
+    }
 }
 
 impl<T> SelectHandle for Sender<'_, T> {
@@ -632,4 +1191,17 @@ impl<T> SelectHandle for Sender<'_, T> {
     fn unwatch(&self, oper: Operation) {
         self.0.senders.unwatch(oper);
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.senders.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.senders.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.0.senders.watch_task(waker);
+    }
 }
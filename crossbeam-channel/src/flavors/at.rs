@@ -2,14 +2,20 @@
 //!
 //! Messages cannot be sent into this kind of channel; they are materialized on demand.
 
+use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::clock::TimerClock;
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, TryRecvError};
-use crate::select::{Operation, SelectHandle, Token};
+use crate::select::{Operation, SelectHandle, Selected, Token};
 use crate::utils;
+use crate::waker::{Listener, SyncWaker};
 
 /// Result of a receive operation.
 pub(crate) type AtToken = Option<Instant>;
@@ -21,21 +27,26 @@ pub(crate) struct Channel {
 
     /// `true` if the message has been received.
     received: AtomicBool,
+
+    /// The clock measuring the delivery time.
+    clock: TimerClock,
 }
 
 impl Channel {
     /// Creates a channel that delivers a message at a certain instant in time.
     #[inline]
-    pub(crate) fn new_deadline(when: Instant) -> Self {
+    pub(crate) fn new_deadline(when: Instant, clock: TimerClock) -> Self {
         Channel {
             delivery_time: when,
             received: AtomicBool::new(false),
+            clock,
         }
     }
     /// Creates a channel that delivers a message after a certain duration of time.
     #[inline]
-    pub(crate) fn new_timeout(dur: Duration) -> Self {
-        Self::new_deadline(utils::convert_timeout_to_deadline(dur))
+    pub(crate) fn new_timeout(dur: Duration, clock: TimerClock) -> Self {
+        let when = clock.after(dur);
+        Self::new_deadline(when, clock)
     }
 
     /// Attempts to receive a message without blocking.
@@ -47,7 +58,7 @@ impl Channel {
             return Err(TryRecvError::Empty);
         }
 
-        if Instant::now() < self.delivery_time {
+        if self.clock.now() < self.delivery_time {
             // The message was not delivered yet.
             return Err(TryRecvError::Empty);
         }
@@ -65,6 +76,10 @@ impl Channel {
     /// Receives a message from the channel.
     #[inline]
     pub(crate) fn recv(&self, deadline: Option<Instant>) -> Result<Instant, RecvTimeoutError> {
+        if let Some(waker) = self.clock.waker() {
+            return self.recv_manual(waker, deadline);
+        }
+
         // We use relaxed ordering because this is just an optional optimistic check.
         if self.received.load(Ordering::Relaxed) {
             // The message has already been received.
@@ -74,7 +89,7 @@ impl Channel {
 
         // Wait until the message is received or the deadline is reached.
         loop {
-            let now = Instant::now();
+            let now = utils::now();
 
             let deadline = match deadline {
                 // Check if we can receive the next message.
@@ -101,6 +116,48 @@ impl Channel {
         }
     }
 
+    /// Receives a message from a channel driven by a manual clock, which wakes up `waker` whenever
+    /// it changes.
+    fn recv_manual(
+        &self,
+        waker: &SyncWaker,
+        deadline: Option<Instant>,
+    ) -> Result<Instant, RecvTimeoutError> {
+        let token = &mut Token::default();
+        loop {
+            if let Ok(msg) = self.try_recv() {
+                return Ok(msg);
+            }
+
+            if let Some(d) = deadline {
+                if utils::now() >= d {
+                    return Err(RecvTimeoutError::Timeout);
+                }
+            }
+
+            Context::with(|cx| {
+                // Prepare for blocking until the clock changes.
+                let oper = Operation::hook(token);
+                waker.register(oper, cx);
+
+                // Has the message become due just now?
+                if self.is_ready() {
+                    let _ = cx.try_select(Selected::Aborted);
+                }
+
+                // Block the current thread.
+                let sel = cx.wait_until(deadline);
+
+                match sel {
+                    Selected::Waiting => unreachable!(),
+                    Selected::Aborted | Selected::Disconnected | Selected::Operation(_) => {
+                        waker.unregister(oper);
+                    }
+                }
+            });
+        }
+    }
+
     /// Reads a message from the channel.
     #[inline]
     pub(crate) unsafe fn read(&self, token: &mut Token) -> Result<Instant, ()> {
@@ -116,7 +173,7 @@ impl Channel {
         }
 
         // If the delivery time hasn't been reached yet, the channel is empty.
-        if Instant::now() < self.delivery_time {
+        if self.clock.now() < self.delivery_time {
             return true;
         }
 
@@ -146,6 +203,21 @@ impl Channel {
     pub(crate) fn capacity(&self) -> Option<usize> {
         Some(1)
     }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"at")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len());
+    }
+}
+
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
 }
 
 impl SelectHandle for Channel {
@@ -170,17 +242,24 @@ impl SelectHandle for Channel {
         if self.received.load(Ordering::Relaxed) {
             None
         } else {
-            Some(self.delivery_time)
+            self.clock.deadline(self.delivery_time)
         }
     }
 
     #[inline]
-    fn register(&self, _oper: Operation, _cx: &Context) -> bool {
+    fn register(&self, oper: Operation, cx: &Context) -> bool {
+        if let Some(waker) = self.clock.waker() {
+            waker.register(oper, cx);
+        }
         self.is_ready()
     }
 
     #[inline]
-    fn unregister(&self, _oper: Operation) {}
+    fn unregister(&self, oper: Operation) {
+        if let Some(waker) = self.clock.waker() {
+            waker.unregister(oper);
+        }
+    }
 
     #[inline]
     fn accept(&self, token: &mut Token, _cx: &Context) -> bool {
@@ -193,10 +272,39 @@ impl SelectHandle for Channel {
     }
 
     #[inline]
-    fn watch(&self, _oper: Operation, _cx: &Context) -> bool {
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        if let Some(waker) = self.clock.waker() {
+            waker.watch(oper, cx);
+        }
         self.is_ready()
     }
 
     #[inline]
-    fn unwatch(&self, _oper: Operation) {}
+    fn unwatch(&self, oper: Operation) {
+        if let Some(waker) = self.clock.waker() {
+            waker.unwatch(oper);
+        }
+    }
+
+    #[inline]
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        if let Some(waker) = self.clock.waker() {
+            waker.listen(listener, index);
+        }
+    }
+
+    #[inline]
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        if let Some(waker) = self.clock.waker() {
+            waker.unlisten(listener, index);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    fn watch_task(&self, waker: &task::Waker) {
+        if let Some(w) = self.clock.waker() {
+            w.watch_task(waker);
+        }
+    }
 }
@@ -1,18 +1,32 @@
 //! Unbounded channel implemented as a linked list.
 
 use std::cell::UnsafeCell;
+use std::fmt;
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::ptr;
 use std::sync::atomic::{self, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::time::Instant;
 
 use crossbeam_utils::{Backoff, CachePadded};
 
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, SendTimeoutError, TryRecvError, TrySendError};
+use crate::events::{EventKind, EventLog};
+#[cfg(feature = "timestamps")]
+use crate::latency::{Latency, LatencyHistogram};
+use crate::metrics::Metrics;
+use crate::registry::Inspect;
 use crate::select::{Operation, SelectHandle, Selected, Token};
-use crate::waker::SyncWaker;
+use crate::spin::{SpinPolicy, SpinWait};
+#[cfg(feature = "stats")]
+use crate::stats::Counters;
+use crate::trace;
+use crate::utils::{self, Watermark};
+use crate::waker::{Listener, SyncWaker};
 
 // TODO(stjepang): Once we bump the minimum required Rust version to 1.28 or newer, re-apply the
 // following changes by @kleimkuhler:
@@ -44,6 +58,10 @@ struct Slot<T> {
     /// The message.
     msg: UnsafeCell<MaybeUninit<T>>,
 
+    /// When the message was sent.
+    #[cfg(feature = "timestamps")]
+    sent: UnsafeCell<MaybeUninit<Instant>>,
+
     /// The state of the slot.
     state: AtomicUsize,
 }
@@ -51,6 +69,8 @@ struct Slot<T> {
 impl<T> Slot<T> {
     const UNINIT: Self = Self {
         msg: UnsafeCell::new(MaybeUninit::uninit()),
+        #[cfg(feature = "timestamps")]
+        sent: UnsafeCell::new(MaybeUninit::uninit()),
         state: AtomicUsize::new(0),
     };
 
@@ -83,6 +103,16 @@ impl<T> Block<T> {
         }
     }
 
+    /// Resets the block to the state of a freshly created one.
+    ///
+    /// Messages must have been read from all slots.
+    fn reset(&mut self) {
+        *self.next.get_mut() = ptr::null_mut();
+        for slot in self.slots.iter_mut() {
+            *slot.state.get_mut() = 0;
+        }
+    }
+
     /// Waits until the next pointer is set.
     fn wait_next(&self) -> *mut Block<T> {
         let backoff = Backoff::new();
@@ -96,7 +126,9 @@ impl<T> Block<T> {
     }
 
     /// Sets the `DESTROY` bit in slots starting from `start` and destroys the block.
-    unsafe fn destroy(this: *mut Block<T>, start: usize) {
+    ///
+    /// The block is handed back to `chan` so that its memory can be reused.
+    unsafe fn destroy(this: *mut Block<T>, start: usize, chan: &Channel<T>) {
         // It is not necessary to set the `DESTROY` bit in the last slot because that slot has
         // begun destruction of the block.
         for i in start..BLOCK_CAP - 1 {
@@ -112,7 +144,7 @@ impl<T> Block<T> {
         }
 
         // No thread is using the block, now it is safe to destroy it.
-        drop(Box::from_raw(this));
+        chan.recycle_block(Box::from_raw(this));
     }
 }
 
@@ -163,13 +195,42 @@ pub(crate) struct Channel<T> {
     /// Receivers waiting while the channel is empty and not disconnected.
     receivers: SyncWaker,
 
+    /// A destroyed block kept for reuse, or null.
+    ///
+    /// Keeping a single block is enough to avoid allocations while receivers keep up with
+    /// senders, and bounds the memory retained after a burst of messages.
+    spare: AtomicPtr<Block<T>>,
+
+    /// How long blocked receivers spin before parking.
+    spin: SpinPolicy,
+
+    /// Hooks notified of events in the channel, if any.
+    metrics: Option<Metrics>,
+
+    /// The highest number of messages the channel has held, once tracking has started.
+    max_len: Watermark,
+
+    /// The number of messages discarded when the receivers were dropped.
+    discarded: AtomicUsize,
+
+    /// How long received messages waited in the channel.
+    #[cfg(feature = "timestamps")]
+    latency: Latency,
+
+    /// The most recent events in the channel, once recording has started.
+    events: EventLog,
+
+    /// Counts of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    counters: Counters,
+
     /// Indicates that dropping a `Channel<T>` may drop messages of type `T`.
     _marker: PhantomData<T>,
 }
 
 impl<T> Channel<T> {
-    /// Creates a new unbounded channel.
-    pub(crate) fn new() -> Self {
+    /// Creates a new unbounded channel whose blocked receivers spin according to `spin`.
+    pub(crate) fn new(spin: SpinPolicy, metrics: Option<Metrics>) -> Self {
         Channel {
             head: CachePadded::new(Position {
                 block: AtomicPtr::new(ptr::null_mut()),
@@ -180,10 +241,36 @@ impl<T> Channel<T> {
                 index: AtomicUsize::new(0),
             }),
             receivers: SyncWaker::new(),
+            spare: AtomicPtr::new(ptr::null_mut()),
+            spin,
+            metrics,
+            max_len: Watermark::new(),
+            discarded: AtomicUsize::new(0),
+            #[cfg(feature = "timestamps")]
+            latency: Latency::new(),
+            events: EventLog::new(),
+            #[cfg(feature = "stats")]
+            counters: Counters::new(),
             _marker: PhantomData,
         }
     }
 
+    /// Returns the hooks notified of events in the channel, if any.
+    pub(crate) fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Returns the log of recent events in the channel.
+    pub(crate) fn events(&self) -> &EventLog {
+        &self.events
+    }
+
+    /// Returns the counters of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    pub(crate) fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
     /// Returns a receiver handle to the channel.
     pub(crate) fn receiver(&self) -> Receiver<'_, T> {
         Receiver(self)
@@ -194,6 +281,29 @@ impl<T> Channel<T> {
         Sender(self)
     }
 
+    /// Allocates an empty block, reusing the spare one if there is any.
+    fn new_block(&self) -> Box<Block<T>> {
+        let spare = self.spare.swap(ptr::null_mut(), Ordering::Acquire);
+        if spare.is_null() {
+            Box::new(Block::<T>::new())
+        } else {
+            unsafe { Box::from_raw(spare) }
+        }
+    }
+
+    /// Keeps a destroyed block as the spare one, or deallocates it if there already is one.
+    fn recycle_block(&self, mut block: Box<Block<T>>) {
+        block.reset();
+        let block = Box::into_raw(block);
+        if self
+            .spare
+            .compare_exchange(ptr::null_mut(), block, Ordering::Release, Ordering::Relaxed)
+            .is_err()
+        {
+            drop(unsafe { Box::from_raw(block) });
+        }
+    }
+
     /// Attempts to reserve a slot for sending a message.
     fn start_send(&self, token: &mut Token) -> bool {
         let backoff = Backoff::new();
@@ -222,13 +332,13 @@ impl<T> Channel<T> {
             // If we're going to have to install the next block, allocate it in advance in order to
             // make the wait for other threads as short as possible.
             if offset + 1 == BLOCK_CAP && next_block.is_none() {
-                next_block = Some(Box::new(Block::<T>::new()));
+                next_block = Some(self.new_block());
             }
 
             // If this is the first message to be sent into the channel, we need to allocate the
             // first block and install it.
             if block.is_null() {
-                let new = Box::into_raw(Box::new(Block::<T>::new()));
+                let new = Box::into_raw(self.new_block());
 
                 if self
                     .tail
@@ -289,6 +399,8 @@ impl<T> Channel<T> {
         let offset = token.list.offset;
         let slot = (*block).slots.get_unchecked(offset);
         slot.msg.get().write(MaybeUninit::new(msg));
+        #[cfg(feature = "timestamps")]
+        slot.sent.get().write(MaybeUninit::new(Instant::now()));
         slot.state.fetch_or(WRITE, Ordering::Release);
 
         // Wake a sleeping receiver.
@@ -394,15 +506,19 @@ impl<T> Channel<T> {
         let slot = (*block).slots.get_unchecked(offset);
         slot.wait_write();
         let msg = slot.msg.get().read().assume_init();
+        #[cfg(feature = "timestamps")]
+        let sent = slot.sent.get().read().assume_init();
 
         // Destroy the block if we've reached the end, or if another thread wanted to destroy but
         // couldn't because we were busy reading from the slot.
         if offset + 1 == BLOCK_CAP {
-            Block::destroy(block, 0);
+            Block::destroy(block, 0, self);
         } else if slot.state.fetch_or(READ, Ordering::AcqRel) & DESTROY != 0 {
-            Block::destroy(block, offset + 1);
+            Block::destroy(block, offset + 1, self);
         }
 
+        #[cfg(feature = "timestamps")]
+        self.latency.record(sent.elapsed());
         Ok(msg)
     }
 
@@ -444,7 +560,7 @@ impl<T> Channel<T> {
         let token = &mut Token::default();
         loop {
             // Try receiving a message several times.
-            let backoff = Backoff::new();
+            let backoff = SpinWait::new(self.spin);
             loop {
                 if self.start_recv(token) {
                     unsafe {
@@ -460,7 +576,7 @@ impl<T> Channel<T> {
             }
 
             if let Some(d) = deadline {
-                if Instant::now() >= d {
+                if utils::now() >= d {
                     return Err(RecvTimeoutError::Timeout);
                 }
             }
@@ -475,8 +591,17 @@ impl<T> Channel<T> {
                     let _ = cx.try_select(Selected::Aborted);
                 }
 
+                if let Some(metrics) = &self.metrics {
+                    if cx.selected() == Selected::Waiting {
+                        metrics.block(self.len());
+                    }
+                }
+                if cx.selected() == Selected::Waiting {
+                    self.events.record(EventKind::ParkRecv);
+                }
+
                 // Block the current thread.
-                let sel = cx.wait_until(deadline);
+                let sel = trace::wait(cx, deadline, self.spin, "recv", self.metrics.as_ref());
 
                 match sel {
                     Selected::Waiting => unreachable!(),
@@ -493,38 +618,34 @@ impl<T> Channel<T> {
 
     /// Returns the current number of messages inside the channel.
     pub(crate) fn len(&self) -> usize {
-        loop {
-            // Load the tail index, then load the head index.
-            let mut tail = self.tail.index.load(Ordering::SeqCst);
-            let mut head = self.head.index.load(Ordering::SeqCst);
-
-            // If the tail index didn't change, we've got consistent indices to work with.
-            if self.tail.index.load(Ordering::SeqCst) == tail {
-                // Erase the lower bits.
-                tail &= !((1 << SHIFT) - 1);
-                head &= !((1 << SHIFT) - 1);
-
-                // Fix up indices if they fall onto block ends.
-                if (tail >> SHIFT) & (LAP - 1) == LAP - 1 {
-                    tail = tail.wrapping_add(1 << SHIFT);
-                }
-                if (head >> SHIFT) & (LAP - 1) == LAP - 1 {
-                    head = head.wrapping_add(1 << SHIFT);
-                }
-
-                // Rotate indices so that head falls into the first block.
-                let lap = (head >> SHIFT) / LAP;
-                tail = tail.wrapping_sub((lap * LAP) << SHIFT);
-                head = head.wrapping_sub((lap * LAP) << SHIFT);
+        // Load the head index, then load the tail index. The tail can't be behind a head that was
+        // loaded before it.
+        let mut head = self.head.index.load(Ordering::SeqCst);
+        let mut tail = self.tail.index.load(Ordering::SeqCst);
 
-                // Remove the lower bits.
-                tail >>= SHIFT;
-                head >>= SHIFT;
+        // Erase the lower bits.
+        tail &= !((1 << SHIFT) - 1);
+        head &= !((1 << SHIFT) - 1);
 
-                // Return the difference minus the number of blocks between tail and head.
-                return tail - head - tail / LAP;
-            }
+        // Fix up indices if they fall onto block ends.
+        if (tail >> SHIFT) & (LAP - 1) == LAP - 1 {
+            tail = tail.wrapping_add(1 << SHIFT);
+        }
+        if (head >> SHIFT) & (LAP - 1) == LAP - 1 {
+            head = head.wrapping_add(1 << SHIFT);
         }
+
+        // Rotate indices so that head falls into the first block.
+        let lap = (head >> SHIFT) / LAP;
+        tail = tail.wrapping_sub((lap * LAP) << SHIFT);
+        head = head.wrapping_sub((lap * LAP) << SHIFT);
+
+        // Remove the lower bits.
+        tail >>= SHIFT;
+        head >>= SHIFT;
+
+        // Return the difference minus the number of blocks between tail and head.
+        tail - head - tail / LAP
     }
 
     /// Returns the capacity of the channel.
@@ -532,6 +653,58 @@ impl<T> Channel<T> {
         None
     }
 
+    /// Starts keeping track of the highest number of messages the channel has held.
+    pub(crate) fn track_max_len(&self) {
+        self.max_len.start(self.len());
+    }
+
+    /// Raises the highest number of messages seen to the current length, if it is tracked.
+    pub(crate) fn observe_len(&self) {
+        self.max_len.observe(|| self.len());
+    }
+
+    /// Returns the highest number of messages the channel has held, or its current length if that
+    /// isn't tracked.
+    pub(crate) fn max_len_seen(&self) -> usize {
+        self.max_len.get().unwrap_or_else(|| self.len())
+    }
+
+    /// Forgets the highest number of messages seen so far, starting over from the current length.
+    pub(crate) fn reset_max_len_seen(&self) {
+        self.max_len.reset(self.len());
+    }
+
+    /// Returns a histogram of how long received messages waited in the channel.
+    #[cfg(feature = "timestamps")]
+    pub(crate) fn latency(&self) -> LatencyHistogram {
+        self.latency.snapshot()
+    }
+
+    /// Forgets the latencies of all messages received so far.
+    #[cfg(feature = "timestamps")]
+    pub(crate) fn reset_latency(&self) {
+        self.latency.reset();
+    }
+
+    /// Returns the number of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receivers(&self) -> usize {
+        self.receivers.waiting()
+    }
+
+    /// Returns the names of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receiver_names(&self) -> Vec<String> {
+        self.receivers.waiting_names()
+    }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"list")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("disconnected", &self.is_disconnected())
+            .field("blocked_receivers", &self.blocked_receivers());
+    }
+
     /// Disconnects senders and wakes up all blocked receivers.
     ///
     /// Returns `true` if this call disconnected the channel.
@@ -581,39 +754,43 @@ impl<T> Channel<T> {
             tail = self.tail.index.load(Ordering::Acquire);
         }
 
-        let mut head = self.head.index.load(Ordering::Acquire);
-        let mut block = self.head.block.load(Ordering::Acquire);
+        let head = self.head.index.load(Ordering::Acquire);
+        let block = self.head.block.load(Ordering::Acquire);
 
-        unsafe {
-            // Drop all messages between head and tail and deallocate the heap-allocated blocks.
-            while head >> SHIFT != tail >> SHIFT {
-                let offset = (head >> SHIFT) % LAP;
-
-                if offset < BLOCK_CAP {
-                    // Drop the message in the slot.
-                    let slot = (*block).slots.get_unchecked(offset);
-                    slot.wait_write();
-                    let p = &mut *slot.msg.get();
-                    p.as_mut_ptr().drop_in_place();
-                } else {
-                    (*block).wait_next();
-                    // Deallocate the block and move to the next one.
-                    let next = (*block).next.load(Ordering::Acquire);
-                    drop(Box::from_raw(block));
-                    block = next;
-                }
-
-                head = head.wrapping_add(1 << SHIFT);
-            }
+        // Take the messages out of the channel before dropping them, so that it doesn't point to
+        // deallocated blocks if a destructor panics.
+        self.head.block.store(ptr::null_mut(), Ordering::Release);
+        self.head.index.store(tail & !MARK_BIT, Ordering::Release);
+
+        let mut teardown = Teardown {
+            head,
+            tail,
+            block,
+            wait: true,
+            dropped: 0,
+        };
+        unsafe { while teardown.step() {} }
+        self.discarded.store(teardown.dropped, Ordering::Relaxed);
+    }
 
-            // Deallocate the last remaining block.
-            if !block.is_null() {
-                drop(Box::from_raw(block));
-            }
+    /// Reconnects the channel if senders were disconnected and it is empty.
+    ///
+    /// This must not be called once receivers are disconnected, since that discards the blocks.
+    ///
+    /// Returns `true` if this call reconnected the channel.
+    pub(crate) fn reconnect(&self) -> bool {
+        let tail = self.tail.index.load(Ordering::SeqCst);
+        if tail & MARK_BIT == 0 || tail >> SHIFT != self.head.index.load(Ordering::SeqCst) >> SHIFT
+        {
+            return false;
         }
-        head &= !MARK_BIT;
-        self.head.block.store(ptr::null_mut(), Ordering::Release);
-        self.head.index.store(head, Ordering::Release);
+
+        // Senders don't move the tail while the channel is disconnected, and receivers don't
+        // move the head while it is empty.
+        self.tail
+            .index
+            .compare_exchange(tail, tail & !MARK_BIT, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
     }
 
     /// Returns `true` if the channel is disconnected.
@@ -634,44 +811,133 @@ impl<T> Channel<T> {
     }
 }
 
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
+}
+
 impl<T> Drop for Channel<T> {
     fn drop(&mut self) {
-        let mut head = *self.head.index.get_mut();
-        let mut tail = *self.tail.index.get_mut();
-        let mut block = *self.head.block.get_mut();
-
-        // Erase the lower bits.
-        head &= !((1 << SHIFT) - 1);
-        tail &= !((1 << SHIFT) - 1);
+        if let Some(metrics) = &self.metrics {
+            // Messages discarded when the receivers were dropped were never received either.
+            metrics.destroy(self.len() + *self.discarded.get_mut());
+        }
 
         unsafe {
+            // Deallocate the spare block.
+            let spare = *self.spare.get_mut();
+            if !spare.is_null() {
+                drop(Box::from_raw(spare));
+            }
+
             // Drop all messages between head and tail and deallocate the heap-allocated blocks.
-            while head != tail {
-                let offset = (head >> SHIFT) % LAP;
-
-                if offset < BLOCK_CAP {
-                    // Drop the message in the slot.
-                    let slot = (*block).slots.get_unchecked(offset);
-                    let p = &mut *slot.msg.get();
-                    p.as_mut_ptr().drop_in_place();
-                } else {
-                    // Deallocate the block and move to the next one.
-                    let next = *(*block).next.get_mut();
-                    drop(Box::from_raw(block));
-                    block = next;
-                }
+            let mut teardown = Teardown {
+                head: *self.head.index.get_mut(),
+                tail: *self.tail.index.get_mut(),
+                block: *self.head.block.get_mut(),
+                wait: false,
+                dropped: 0,
+            };
+            while teardown.step() {}
+        }
+    }
+}
+
+/// Drops the messages between two indices and deallocates the blocks holding them.
+///
+/// If the destructor of a message panics, dropping the `Teardown` while unwinding finishes the
+/// job, so that the remaining messages and blocks aren't leaked.
+struct Teardown<T> {
+    /// The index of the next slot to tear down.
+    head: usize,
+
+    /// The index past the last message.
+    tail: usize,
+
+    /// The block `head` points into, which may be null if there is no block.
+    block: *mut Block<T>,
+
+    /// Whether senders may still be finishing their writes, which then have to be waited for.
+    wait: bool,
+
+    /// The number of messages dropped so far.
+    dropped: usize,
+}
+
+impl<T> Teardown<T> {
+    /// Drops the next message, or deallocates a block once all of its messages are dropped.
+    ///
+    /// Returns `false` if nothing is left between the head and the tail.
+    unsafe fn step(&mut self) -> bool {
+        if self.head >> SHIFT == self.tail >> SHIFT {
+            return false;
+        }
+
+        // Move on to the next slot before dropping the message, in case its destructor panics.
+        let offset = (self.head >> SHIFT) % LAP;
+        self.head = self.head.wrapping_add(1 << SHIFT);
 
-                head = head.wrapping_add(1 << SHIFT);
+        if offset < BLOCK_CAP {
+            // Drop the message in the slot.
+            let slot = (*self.block).slots.get_unchecked(offset);
+            if self.wait {
+                slot.wait_write();
             }
+            self.dropped += 1;
+            let p = &mut *slot.msg.get();
+            p.as_mut_ptr().drop_in_place();
+        } else {
+            // Deallocate the block and move to the next one.
+            let next = if self.wait {
+                (*self.block).wait_next()
+            } else {
+                (*self.block).next.load(Ordering::Relaxed)
+            };
+            drop(Box::from_raw(self.block));
+            self.block = next;
+        }
+        true
+    }
+}
+
+impl<T> Drop for Teardown<T> {
+    fn drop(&mut self) {
+        unsafe {
+            while self.step() {}
 
             // Deallocate the last remaining block.
-            if !block.is_null() {
-                drop(Box::from_raw(block));
+            if !self.block.is_null() {
+                drop(Box::from_raw(self.block));
             }
         }
     }
 }
 
+impl<T> Inspect for Channel<T> {
+    fn name(&self) -> Option<&str> {
+        self.metrics.as_ref().map(Metrics::name)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.capacity()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn blocked_sender_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn blocked_receiver_names(&self) -> Vec<String> {
+        self.blocked_receiver_names()
+    }
+}
+
 /// Receiver handle to a channel.
 pub(crate) struct Receiver<'a, T>(&'a Channel<T>);
 
@@ -712,6 +978,19 @@ impl<T> SelectHandle for Receiver<'_, T> {
     fn unwatch(&self, oper: Operation) {
         self.0.receivers.unwatch(oper);
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.receivers.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.receivers.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.0.receivers.watch_task(waker);
+    }
 }
 
 impl<T> SelectHandle for Sender<'_, T> {
@@ -742,4 +1021,11 @@ impl<T> SelectHandle for Sender<'_, T> {
     }
 
     fn unwatch(&self, _oper: Operation) {}
+
+    fn listen(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    fn unlisten(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, _waker: &task::Waker) {}
 }
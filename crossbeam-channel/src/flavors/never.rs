@@ -2,13 +2,18 @@
 //!
 //! Messages cannot be sent into this kind of channel.
 
+use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::time::Instant;
 
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, TryRecvError};
 use crate::select::{Operation, SelectHandle, Token};
 use crate::utils;
+use crate::waker::Listener;
 
 /// This flavor doesn't need a token.
 pub(crate) type NeverToken = ();
@@ -69,6 +74,21 @@ impl<T> Channel<T> {
     pub(crate) fn capacity(&self) -> Option<usize> {
         Some(0)
     }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"never")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len());
+    }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
 }
 
 impl<T> SelectHandle for Channel<T> {
@@ -107,4 +127,14 @@ impl<T> SelectHandle for Channel<T> {
 
     #[inline]
     fn unwatch(&self, _oper: Operation) {}
+
+    #[inline]
+    fn listen(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[inline]
+    fn unlisten(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[cfg(feature = "async")]
+    #[inline]
+    fn watch_task(&self, _waker: &task::Waker) {}
 }
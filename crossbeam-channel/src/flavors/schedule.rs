@@ -0,0 +1,211 @@
+//! Channel that delivers messages at a sequence of moments in time.
+//!
+//! Messages cannot be sent into this kind of channel; they are materialized on demand.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "async")]
+use std::task;
+use std::time::Instant;
+
+use crate::context::Context;
+use crate::err::{RecvTimeoutError, TryRecvError};
+use crate::select::{Operation, SelectHandle, Token};
+use crate::utils;
+use crate::waker::Listener;
+
+/// Result of a receive operation.
+pub(crate) type ScheduleToken = Option<Instant>;
+
+/// The remaining occurrences of a schedule.
+struct Occurrences {
+    /// The instant at which the next message will be delivered, or `None` if there are no more.
+    next: Option<Instant>,
+
+    /// The occurrences after `next`.
+    rest: Box<dyn Iterator<Item = Instant> + Send>,
+}
+
+/// Channel that delivers messages at a sequence of moments in time.
+pub(crate) struct Channel {
+    /// The remaining occurrences.
+    occurrences: Mutex<Occurrences>,
+}
+
+impl Channel {
+    /// Creates a channel that delivers a message at each instant produced by `times`.
+    #[inline]
+    pub(crate) fn new<I>(times: I) -> Self
+    where
+        I: Iterator<Item = Instant> + Send + 'static,
+    {
+        let mut rest: Box<dyn Iterator<Item = Instant> + Send> = Box::new(times);
+        Channel {
+            occurrences: Mutex::new(Occurrences {
+                next: rest.next(),
+                rest,
+            }),
+        }
+    }
+
+    /// Returns the instant of the next occurrence, or `None` if there are no more.
+    #[inline]
+    fn next(&self) -> Option<Instant> {
+        self.occurrences.lock().unwrap().next
+    }
+
+    /// Attempts to receive a message without blocking.
+    #[inline]
+    pub(crate) fn try_recv(&self) -> Result<Instant, TryRecvError> {
+        let mut occurrences = self.occurrences.lock().unwrap();
+
+        match occurrences.next {
+            None => Err(TryRecvError::Disconnected),
+            Some(when) if utils::now() < when => Err(TryRecvError::Empty),
+            Some(when) => {
+                occurrences.next = occurrences.rest.next();
+                Ok(when)
+            }
+        }
+    }
+
+    /// Receives a message from the channel.
+    #[inline]
+    pub(crate) fn recv(&self, deadline: Option<Instant>) -> Result<Instant, RecvTimeoutError> {
+        loop {
+            let next = match self.try_recv() {
+                Ok(msg) => return Ok(msg),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => match self.next() {
+                    Some(when) => when,
+                    None => continue,
+                },
+            };
+
+            let now = utils::now();
+            let wake = match deadline {
+                // Check if the timeout deadline has been reached.
+                Some(d) if now >= d => return Err(RecvTimeoutError::Timeout),
+
+                // Sleep until the deadline or the next occurrence, whichever comes first.
+                Some(d) if d < next => d,
+                _ => next,
+            };
+
+            utils::sleep_until(Some(wake));
+        }
+    }
+
+    /// Reads a message from the channel.
+    #[inline]
+    pub(crate) unsafe fn read(&self, token: &mut Token) -> Result<Instant, ()> {
+        token.schedule.ok_or(())
+    }
+
+    /// Returns `true` if the channel is empty.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        match self.next() {
+            None => true,
+            Some(when) => utils::now() < when,
+        }
+    }
+
+    /// Returns `true` if the channel is full.
+    #[inline]
+    pub(crate) fn is_full(&self) -> bool {
+        !self.is_empty()
+    }
+
+    /// Returns the number of messages in the channel.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Returns the capacity of the channel.
+    #[inline]
+    pub(crate) fn capacity(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"schedule")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len());
+    }
+}
+
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
+}
+
+impl SelectHandle for Channel {
+    #[inline]
+    fn try_select(&self, token: &mut Token) -> bool {
+        match self.try_recv() {
+            Ok(msg) => {
+                token.schedule = Some(msg);
+                true
+            }
+            Err(TryRecvError::Disconnected) => {
+                token.schedule = None;
+                true
+            }
+            Err(TryRecvError::Empty) => false,
+        }
+    }
+
+    #[inline]
+    fn deadline(&self) -> Option<Instant> {
+        self.next()
+    }
+
+    #[inline]
+    fn register(&self, _oper: Operation, _cx: &Context) -> bool {
+        self.is_ready()
+    }
+
+    #[inline]
+    fn unregister(&self, _oper: Operation) {}
+
+    #[inline]
+    fn accept(&self, token: &mut Token, _cx: &Context) -> bool {
+        self.try_select(token)
+    }
+
+    #[inline]
+    fn is_ready(&self) -> bool {
+        match self.next() {
+            None => true,
+            Some(when) => utils::now() >= when,
+        }
+    }
+
+    #[inline]
+    fn watch(&self, _oper: Operation, _cx: &Context) -> bool {
+        self.is_ready()
+    }
+
+    #[inline]
+    fn unwatch(&self, _oper: Operation) {}
+
+    #[inline]
+    fn listen(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[inline]
+    fn unlisten(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[cfg(feature = "async")]
+    #[inline]
+    fn watch_task(&self, _waker: &task::Waker) {}
+}
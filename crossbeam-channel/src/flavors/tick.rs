@@ -2,35 +2,276 @@
 //!
 //! Messages cannot be sent into this kind of channel; they are materialized on demand.
 
-use std::thread;
+use std::fmt;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::time::{Duration, Instant};
 
 use crossbeam_utils::atomic::AtomicCell;
 
+use crate::clock::TimerClock;
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, TryRecvError};
-use crate::select::{Operation, SelectHandle, Token};
+use crate::select::{Operation, SelectHandle, Selected, Token};
 use crate::utils;
+use crate::waker::{Listener, SyncWaker};
 
 /// Result of a receive operation.
 pub(crate) type TickToken = Option<Instant>;
 
-/// Channel that delivers messages periodically.
-pub(crate) struct Channel {
+/// What a [`tick`] channel does when messages are received later than they are due.
+///
+/// A tick channel holds at most one message, so if the receiver falls behind by more than one
+/// interval, some ticks are missed. The policy decides when the messages after a late one get
+/// delivered.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use std::time::{Duration, Instant};
+/// use crossbeam_channel::{tick_with, MissedTicks};
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let start = Instant::now();
+/// let r = tick_with(ms(100), MissedTicks::Burst);
+///
+/// thread::sleep(ms(350));
+///
+/// // The ticks due about 100, 200, and 300 ms from the start are delivered right away.
+/// let first = r.recv().unwrap();
+/// assert_eq!(r.recv().unwrap(), first + ms(100));
+/// assert_eq!(r.recv().unwrap(), first + ms(200));
+/// assert!(start.elapsed() < ms(400));
+/// ```
+///
+/// [`tick`]: crate::tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MissedTicks {
+    /// Delivers every missed tick, one after another, until the channel catches up with its
+    /// original schedule.
+    ///
+    /// This suits rate limiting, where the number of ticks matters more than their timing.
+    Burst,
+
+    /// Delivers the next tick a full interval after the late one was received.
+    ///
+    /// Missed ticks are dropped and the schedule shifts by the delay. This is the default policy.
+    Delay,
+
+    /// Drops missed ticks and delivers the next one at the next instant on the original schedule.
+    ///
+    /// This suits cron-like tasks that should run at fixed times.
+    Skip,
+}
+
+impl Default for MissedTicks {
+    fn default() -> MissedTicks {
+        MissedTicks::Delay
+    }
+}
+
+/// A handle that controls the schedule of a tick channel.
+///
+/// Created with [`ticker`]. A ticker can pause, resume, and restart its channel while receivers
+/// are blocked on it or have it registered in a [`Select`]. Those receivers are woken up and pick
+/// up the new schedule.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::{ticker, TryRecvError};
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let (ticker, r) = ticker(ms(50));
+/// ticker.pause();
+///
+/// // No messages are delivered while the ticker is paused.
+/// assert_eq!(r.recv_timeout(ms(100)).map_err(|_| ()), Err(()));
+///
+/// // After resuming, the remaining 50 ms of the interval have to pass again.
+/// ticker.resume();
+/// assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+/// assert!(r.recv_timeout(ms(200)).is_ok());
+/// ```
+///
+/// [`ticker`]: crate::ticker
+/// [`Select`]: crate::Select
+#[derive(Clone)]
+pub struct Ticker {
+    chan: Arc<Channel>,
+}
+
+impl Ticker {
+    /// Creates a handle controlling the given channel.
+    pub(crate) fn new(chan: Arc<Channel>) -> Ticker {
+        Ticker { chan }
+    }
+
+    /// Stops delivering messages until [`resume`] is called.
+    ///
+    /// The time left until the next message is remembered and counted down again once the ticker
+    /// is resumed. A message that is already due stays in the channel and can still be received.
+    ///
+    /// Pausing a paused ticker does nothing.
+    ///
+    /// [`resume`]: Ticker::resume
+    pub fn pause(&self) {
+        self.chan.update(|sched, now| {
+            if sched.paused.is_none() {
+                sched.paused = Some(remaining(sched.delivery_time, now));
+            }
+        });
+    }
+
+    /// Resumes delivering messages after [`pause`].
+    ///
+    /// Resuming a running ticker does nothing.
+    ///
+    /// [`pause`]: Ticker::pause
+    pub fn resume(&self) {
+        self.chan.update(|sched, now| {
+            if let Some(left) = sched.paused.take() {
+                sched.delivery_time = now + left;
+            }
+        });
+    }
+
+    /// Restarts the ticker with a new interval.
+    ///
+    /// Any message waiting in the channel is discarded, and the next one is delivered `period`
+    /// after this call. If the ticker is paused, it stays paused and delivers the next message
+    /// `period` after it is resumed.
+    pub fn reset(&self, period: Duration) {
+        self.chan.update(|sched, now| {
+            sched.duration = period;
+            match sched.paused {
+                Some(_) => sched.paused = Some(period),
+                None => sched.delivery_time = now + period,
+            }
+        });
+    }
+
+    /// Returns `true` if the ticker is paused.
+    pub fn is_paused(&self) -> bool {
+        self.chan.schedule.load().paused.is_some()
+    }
+
+    /// Returns the interval in which messages get delivered.
+    pub fn period(&self) -> Duration {
+        self.chan.schedule.load().duration
+    }
+}
+
+impl fmt::Debug for Ticker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Ticker { .. }")
+    }
+}
+
+/// Returns the time left from `now` until `deadline`, or zero if it has passed.
+fn remaining(deadline: Instant, now: Instant) -> Duration {
+    if now < deadline {
+        deadline - now
+    } else {
+        Duration::from_secs(0)
+    }
+}
+
+/// The delivery schedule of a tick channel.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Schedule {
     /// The instant at which the next message will be delivered.
-    delivery_time: AtomicCell<Instant>,
+    delivery_time: Instant,
 
     /// The time interval in which messages get delivered.
     duration: Duration,
+
+    /// Time left until the next delivery if the channel is paused, or `None` if it is running.
+    paused: Option<Duration>,
 }
 
+/// Channel that delivers messages periodically.
+pub(crate) struct Channel {
+    /// The current delivery schedule.
+    schedule: AtomicCell<Schedule>,
+
+    /// What to do when messages are received late.
+    missed: MissedTicks,
+
+    /// Receivers waiting for a message or a change of the schedule.
+    receivers: SyncWaker,
+
+    /// The clock measuring delivery times.
+    clock: TimerClock,
+}
+
+unsafe impl Send for Channel {}
+unsafe impl Sync for Channel {}
+
 impl Channel {
     /// Creates a channel that delivers messages periodically.
     #[inline]
-    pub(crate) fn new(dur: Duration) -> Self {
+    pub(crate) fn new(dur: Duration, missed: MissedTicks, clock: TimerClock) -> Self {
         Channel {
-            delivery_time: AtomicCell::new(utils::convert_timeout_to_deadline(dur)),
-            duration: dur,
+            schedule: AtomicCell::new(Schedule {
+                delivery_time: clock.after(dur),
+                duration: dur,
+                paused: None,
+            }),
+            missed,
+            receivers: SyncWaker::new(),
+            clock,
+        }
+    }
+
+    /// Changes the schedule and wakes up all receivers so that they pick up the change.
+    fn update<F: Fn(&mut Schedule, Instant)>(&self, f: F) {
+        loop {
+            let current = self.schedule.load();
+            let mut new = current;
+            f(&mut new, self.clock.now());
+
+            if new == current {
+                return;
+            }
+            if self.schedule.compare_exchange(current, new).is_ok() {
+                break;
+            }
+        }
+
+        // Blocked receivers wait for the old delivery time, so make them check again.
+        self.receivers.disconnect();
+    }
+
+    /// Returns the delivery time of the message after the one due at `delivery_time`, which is
+    /// received at `now`.
+    fn next_delivery_time(
+        &self,
+        delivery_time: Instant,
+        duration: Duration,
+        now: Instant,
+    ) -> Instant {
+        match self.missed {
+            MissedTicks::Burst => delivery_time + duration,
+            MissedTicks::Delay => delivery_time.max(now) + duration,
+            MissedTicks::Skip => {
+                let next = delivery_time + duration;
+                if now < next {
+                    return next;
+                }
+
+                let period = duration.as_nanos();
+                if period == 0 {
+                    return now;
+                }
+                let missed = (now - delivery_time).as_nanos() / period;
+                delivery_time + Duration::from_nanos(((missed + 1) * period) as u64)
+            }
         }
     }
 
@@ -38,19 +279,19 @@ impl Channel {
     #[inline]
     pub(crate) fn try_recv(&self) -> Result<Instant, TryRecvError> {
         loop {
-            let now = Instant::now();
-            let delivery_time = self.delivery_time.load();
+            let now = self.clock.now();
+            let sched = self.schedule.load();
 
-            if now < delivery_time {
+            if sched.paused.is_some() || now < sched.delivery_time {
                 return Err(TryRecvError::Empty);
             }
 
-            if self
-                .delivery_time
-                .compare_exchange(delivery_time, now + self.duration)
-                .is_ok()
-            {
-                return Ok(delivery_time);
+            let next = Schedule {
+                delivery_time: self.next_delivery_time(sched.delivery_time, sched.duration, now),
+                ..sched
+            };
+            if self.schedule.compare_exchange(sched, next).is_ok() {
+                return Ok(sched.delivery_time);
             }
         }
     }
@@ -58,29 +299,43 @@ impl Channel {
     /// Receives a message from the channel.
     #[inline]
     pub(crate) fn recv(&self, deadline: Option<Instant>) -> Result<Instant, RecvTimeoutError> {
+        let token = &mut Token::default();
         loop {
-            let delivery_time = self.delivery_time.load();
-            let now = Instant::now();
+            if let Ok(msg) = self.try_recv() {
+                return Ok(msg);
+            }
 
             if let Some(d) = deadline {
-                if d < delivery_time {
-                    if now < d {
-                        thread::sleep(d - now);
-                    }
+                if utils::now() >= d {
                     return Err(RecvTimeoutError::Timeout);
                 }
             }
 
-            if self
-                .delivery_time
-                .compare_exchange(delivery_time, delivery_time.max(now) + self.duration)
-                .is_ok()
-            {
-                if now < delivery_time {
-                    thread::sleep(delivery_time - now);
+            Context::with(|cx| {
+                // Prepare for blocking until the next delivery or a change of the schedule.
+                let oper = Operation::hook(token);
+                self.register(oper, cx);
+
+                // Has the channel become ready just now?
+                if self.is_ready() {
+                    let _ = cx.try_select(Selected::Aborted);
                 }
-                return Ok(delivery_time);
-            }
+
+                // Block the current thread until the earlier of the deadline and the next
+                // delivery time.
+                let wake = match (deadline, SelectHandle::deadline(self)) {
+                    (Some(d), Some(t)) => Some(d.min(t)),
+                    (d, t) => d.or(t),
+                };
+                let sel = cx.wait_until(wake);
+
+                match sel {
+                    Selected::Waiting => unreachable!(),
+                    Selected::Aborted | Selected::Disconnected | Selected::Operation(_) => {
+                        self.unregister(oper);
+                    }
+                }
+            });
         }
     }
 
@@ -93,7 +348,8 @@ impl Channel {
     /// Returns `true` if the channel is empty.
     #[inline]
     pub(crate) fn is_empty(&self) -> bool {
-        Instant::now() < self.delivery_time.load()
+        let sched = self.schedule.load();
+        sched.paused.is_some() || self.clock.now() < sched.delivery_time
     }
 
     /// Returns `true` if the channel is full.
@@ -117,6 +373,21 @@ impl Channel {
     pub(crate) fn capacity(&self) -> Option<usize> {
         Some(1)
     }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        d.field("flavor", &"tick")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len());
+    }
+}
+
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
 }
 
 impl SelectHandle for Channel {
@@ -137,16 +408,29 @@ impl SelectHandle for Channel {
 
     #[inline]
     fn deadline(&self) -> Option<Instant> {
-        Some(self.delivery_time.load())
+        let sched = self.schedule.load();
+        match sched.paused {
+            Some(_) => None,
+            None => self.clock.deadline(sched.delivery_time),
+        }
     }
 
     #[inline]
-    fn register(&self, _oper: Operation, _cx: &Context) -> bool {
+    fn register(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.register(oper, cx);
+        if let Some(waker) = self.clock.waker() {
+            waker.register(oper, cx);
+        }
         self.is_ready()
     }
 
     #[inline]
-    fn unregister(&self, _oper: Operation) {}
+    fn unregister(&self, oper: Operation) {
+        self.receivers.unregister(oper);
+        if let Some(waker) = self.clock.waker() {
+            waker.unregister(oper);
+        }
+    }
 
     #[inline]
     fn accept(&self, token: &mut Token, _cx: &Context) -> bool {
@@ -159,10 +443,44 @@ impl SelectHandle for Channel {
     }
 
     #[inline]
-    fn watch(&self, _oper: Operation, _cx: &Context) -> bool {
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.watch(oper, cx);
+        if let Some(waker) = self.clock.waker() {
+            waker.watch(oper, cx);
+        }
         self.is_ready()
     }
 
     #[inline]
-    fn unwatch(&self, _oper: Operation) {}
+    fn unwatch(&self, oper: Operation) {
+        self.receivers.unwatch(oper);
+        if let Some(waker) = self.clock.waker() {
+            waker.unwatch(oper);
+        }
+    }
+
+    #[inline]
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.receivers.listen(listener, index);
+        if let Some(waker) = self.clock.waker() {
+            waker.listen(listener, index);
+        }
+    }
+
+    #[inline]
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.receivers.unlisten(listener, index);
+        if let Some(waker) = self.clock.waker() {
+            waker.unlisten(listener, index);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.receivers.watch_task(waker);
+        if let Some(w) = self.clock.waker() {
+            w.watch_task(waker);
+        }
+    }
 }
@@ -2,10 +2,11 @@
 //!
 //! This kind of channel is also known as *rendezvous* channel.
 
-use std::cell::UnsafeCell;
 use std::marker::PhantomData;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task;
 use std::time::Instant;
 use std::{fmt, ptr};
 
@@ -13,13 +14,24 @@ use crossbeam_utils::Backoff;
 
 use crate::context::Context;
 use crate::err::{RecvTimeoutError, SendTimeoutError, TryRecvError, TrySendError};
+use crate::events::{EventKind, EventLog};
+use crate::metrics::Metrics;
+use crate::primitive::cell::UnsafeCell;
+use crate::primitive::sync::atomic::AtomicBool;
+use crate::primitive::sync::Mutex;
+use crate::registry::Inspect;
 use crate::select::{Operation, SelectHandle, Selected, Token};
-use crate::waker::Waker;
+use crate::spin::SpinPolicy;
+#[cfg(feature = "stats")]
+use crate::stats::Counters;
+use crate::trace;
+use crate::waker::{Listener, Waker};
 
 /// A pointer to a packet.
 pub(crate) struct ZeroToken(*mut ());
 
 impl Default for ZeroToken {
+    #[inline]
     fn default() -> Self {
         Self(ptr::null_mut())
     }
@@ -97,23 +109,57 @@ pub(crate) struct Channel<T> {
     /// Inner representation of the channel.
     inner: Mutex<Inner>,
 
+    /// How long blocked senders and receivers spin before parking.
+    spin: SpinPolicy,
+
+    /// Hooks notified of events in the channel, if any.
+    metrics: Option<Metrics>,
+
+    /// The most recent events in the channel, once recording has started.
+    events: EventLog,
+
+    /// Counts of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    counters: Counters,
+
     /// Indicates that dropping a `Channel<T>` may drop values of type `T`.
     _marker: PhantomData<T>,
 }
 
 impl<T> Channel<T> {
-    /// Constructs a new zero-capacity channel.
-    pub(crate) fn new() -> Self {
+    /// Constructs a new zero-capacity channel whose blocked operations spin according to `spin`.
+    pub(crate) fn new(spin: SpinPolicy, metrics: Option<Metrics>) -> Self {
         Channel {
             inner: Mutex::new(Inner {
                 senders: Waker::new(),
                 receivers: Waker::new(),
                 is_disconnected: false,
             }),
+            spin,
+            metrics,
+            events: EventLog::new(),
+            #[cfg(feature = "stats")]
+            counters: Counters::new(),
             _marker: PhantomData,
         }
     }
 
+    /// Returns the hooks notified of events in the channel, if any.
+    pub(crate) fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Returns the log of recent events in the channel.
+    pub(crate) fn events(&self) -> &EventLog {
+        &self.events
+    }
+
+    /// Returns the counters of operations performed on the channel.
+    #[cfg(feature = "stats")]
+    pub(crate) fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
     /// Returns a receiver handle to the channel.
     pub(crate) fn receiver(&self) -> Receiver<'_, T> {
         Receiver(self)
@@ -148,7 +194,7 @@ impl<T> Channel<T> {
         }
 
         let packet = &*(token.zero.0 as *const Packet<T>);
-        packet.msg.get().write(Some(msg));
+        packet.msg.with_mut(|p| p.write(Some(msg)));
         packet.ready.store(true, Ordering::Release);
         Ok(())
     }
@@ -182,14 +228,14 @@ impl<T> Channel<T> {
             // The message has been in the packet from the beginning, so there is no need to wait
             // for it. However, after reading the message, we need to set `ready` to `true` in
             // order to signal that the packet can be destroyed.
-            let msg = packet.msg.get().replace(None).unwrap();
+            let msg = packet.msg.with_mut(|p| (*p).take().unwrap());
             packet.ready.store(true, Ordering::Release);
             Ok(msg)
         } else {
             // Wait until the message becomes available, then read it and destroy the
             // heap-allocated packet.
             packet.wait_ready();
-            let msg = packet.msg.get().replace(None).unwrap();
+            let msg = packet.msg.with_mut(|p| (*p).take().unwrap());
             drop(Box::from_raw(token.zero.0.cast::<Packet<T>>()));
             Ok(msg)
         }
@@ -248,19 +294,28 @@ impl<T> Channel<T> {
             inner.receivers.notify();
             drop(inner);
 
+            if let Some(metrics) = &self.metrics {
+                if cx.selected() == Selected::Waiting {
+                    metrics.block(0);
+                }
+            }
+            if cx.selected() == Selected::Waiting {
+                self.events.record(EventKind::ParkSend);
+            }
+
             // Block the current thread.
-            let sel = cx.wait_until(deadline);
+            let sel = trace::wait(cx, deadline, self.spin, "send", self.metrics.as_ref());
 
             match sel {
                 Selected::Waiting => unreachable!(),
                 Selected::Aborted => {
                     self.inner.lock().unwrap().senders.unregister(oper).unwrap();
-                    let msg = unsafe { packet.msg.get().replace(None).unwrap() };
+                    let msg = unsafe { packet.msg.with_mut(|p| (*p).take().unwrap()) };
                     Err(SendTimeoutError::Timeout(msg))
                 }
                 Selected::Disconnected => {
                     self.inner.lock().unwrap().senders.unregister(oper).unwrap();
-                    let msg = unsafe { packet.msg.get().replace(None).unwrap() };
+                    let msg = unsafe { packet.msg.with_mut(|p| (*p).take().unwrap()) };
                     Err(SendTimeoutError::Disconnected(msg))
                 }
                 Selected::Operation(_) => {
@@ -319,8 +374,17 @@ impl<T> Channel<T> {
             inner.senders.notify();
             drop(inner);
 
+            if let Some(metrics) = &self.metrics {
+                if cx.selected() == Selected::Waiting {
+                    metrics.block(0);
+                }
+            }
+            if cx.selected() == Selected::Waiting {
+                self.events.record(EventKind::ParkRecv);
+            }
+
             // Block the current thread.
-            let sel = cx.wait_until(deadline);
+            let sel = trace::wait(cx, deadline, self.spin, "recv", self.metrics.as_ref());
 
             match sel {
                 Selected::Waiting => unreachable!(),
@@ -345,7 +409,7 @@ impl<T> Channel<T> {
                 Selected::Operation(_) => {
                     // Wait until the message is provided, then read it.
                     packet.wait_ready();
-                    unsafe { Ok(packet.msg.get().replace(None).unwrap()) }
+                    unsafe { Ok(packet.msg.with_mut(|p| (*p).take().unwrap())) }
                 }
             }
         })
@@ -367,6 +431,21 @@ impl<T> Channel<T> {
         }
     }
 
+    /// Reconnects the channel if it is disconnected.
+    ///
+    /// Returns `true` if this call reconnected the channel.
+    pub(crate) fn reconnect(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let reconnected = inner.is_disconnected;
+        inner.is_disconnected = false;
+        reconnected
+    }
+
+    /// Returns `true` if the channel is disconnected.
+    pub(crate) fn is_disconnected(&self) -> bool {
+        self.inner.lock().unwrap().is_disconnected
+    }
+
     /// Returns the current number of messages inside the channel.
     pub(crate) fn len(&self) -> usize {
         0
@@ -377,6 +456,37 @@ impl<T> Channel<T> {
         Some(0)
     }
 
+    /// Returns the number of threads blocked sending into the channel.
+    pub(crate) fn blocked_senders(&self) -> usize {
+        self.inner.lock().unwrap().senders.waiting()
+    }
+
+    /// Returns the number of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receivers(&self) -> usize {
+        self.inner.lock().unwrap().receivers.waiting()
+    }
+
+    /// Returns the names of threads blocked sending into the channel.
+    pub(crate) fn blocked_sender_names(&self) -> Vec<String> {
+        self.inner.lock().unwrap().senders.waiting_names()
+    }
+
+    /// Returns the names of threads blocked receiving from the channel.
+    pub(crate) fn blocked_receiver_names(&self) -> Vec<String> {
+        self.inner.lock().unwrap().receivers.waiting_names()
+    }
+
+    /// Adds the state of the channel to a `Debug` representation.
+    pub(crate) fn debug_fields(&self, d: &mut fmt::DebugStruct<'_, '_>) {
+        let inner = self.inner.lock().unwrap();
+        d.field("flavor", &"zero")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("disconnected", &inner.is_disconnected)
+            .field("blocked_senders", &inner.senders.waiting())
+            .field("blocked_receivers", &inner.receivers.waiting());
+    }
+
     /// Returns `true` if the channel is empty.
     pub(crate) fn is_empty(&self) -> bool {
         true
@@ -388,6 +498,44 @@ impl<T> Channel<T> {
     }
 }
 
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Channel");
+        self.debug_fields(&mut d);
+        d.finish()
+    }
+}
+
+impl<T> Drop for Channel<T> {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.destroy(0);
+        }
+    }
+}
+
+impl<T> Inspect for Channel<T> {
+    fn name(&self) -> Option<&str> {
+        self.metrics.as_ref().map(Metrics::name)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.capacity()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn blocked_sender_names(&self) -> Vec<String> {
+        self.blocked_sender_names()
+    }
+
+    fn blocked_receiver_names(&self) -> Vec<String> {
+        self.blocked_receiver_names()
+    }
+}
+
 /// Receiver handle to a channel.
 pub(crate) struct Receiver<'a, T>(&'a Channel<T>);
 
@@ -442,6 +590,22 @@ impl<T> SelectHandle for Receiver<'_, T> {
         let mut inner = self.0.inner.lock().unwrap();
         inner.receivers.unwatch(oper);
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.receivers.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.receivers.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.receivers.watch_task(waker);
+    }
 }
 
 impl<T> SelectHandle for Sender<'_, T> {
@@ -492,4 +656,20 @@ impl<T> SelectHandle for Sender<'_, T> {
         let mut inner = self.0.inner.lock().unwrap();
         inner.senders.unwatch(oper);
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.senders.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.senders.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        let mut inner = self.0.inner.lock().unwrap();
+        inner.senders.watch_task(waker);
+    }
 }
@@ -0,0 +1,301 @@
+//! Futures and streams for sending and receiving messages from async code.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures_core::Stream;
+
+use crate::channel::{Receiver, Sender};
+use crate::err::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::select::{Select, SelectHandle, SelectedOperation};
+use crate::timer;
+
+/// Attempts to receive a message, registering the task to be woken up if the channel is empty.
+pub(crate) fn poll_recv<T>(r: &Receiver<T>, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+    match r.try_recv() {
+        Ok(msg) => return Poll::Ready(Ok(msg)),
+        Err(TryRecvError::Disconnected) => return Poll::Ready(Err(RecvError)),
+        Err(TryRecvError::Empty) => {}
+    }
+
+    // Ask to be woken up, then check again in case a message arrived in the meantime.
+    r.watch_task(cx.waker());
+    if let Some(deadline) = SelectHandle::deadline(r) {
+        timer::wake_at(deadline, cx.waker());
+    }
+
+    match r.try_recv() {
+        Ok(msg) => Poll::Ready(Ok(msg)),
+        Err(TryRecvError::Disconnected) => Poll::Ready(Err(RecvError)),
+        Err(TryRecvError::Empty) => Poll::Pending,
+    }
+}
+
+/// Attempts to send the message in `slot`, registering the task to be woken up if the channel is
+/// full.
+///
+/// The message stays in `slot` until it is sent. An empty slot is ready right away.
+pub(crate) fn poll_send<T>(
+    s: &Sender<T>,
+    slot: &mut Option<T>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<(), SendError<T>>> {
+    let mut registered = false;
+
+    while let Some(msg) = slot.take() {
+        match s.try_send(msg) {
+            Ok(()) => {}
+            Err(TrySendError::Disconnected(msg)) => return Poll::Ready(Err(SendError(msg))),
+            Err(TrySendError::Full(msg)) => {
+                *slot = Some(msg);
+                if registered {
+                    return Poll::Pending;
+                }
+
+                // Ask to be woken up, then try once more in case a slot was freed in the
+                // meantime.
+                s.watch_task(cx.waker());
+                registered = true;
+            }
+        }
+    }
+
+    Poll::Ready(Ok(()))
+}
+
+/// A future that receives a message from a channel.
+///
+/// Created with [`Receiver::recv_async`]. A message is only taken out of the channel when the
+/// future resolves to it, so dropping the future before that never loses a message.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecvFut<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<'a, T> RecvFut<'a, T> {
+    /// Creates a future receiving from `receiver`.
+    pub(crate) fn new(receiver: &'a Receiver<T>) -> RecvFut<'a, T> {
+        RecvFut { receiver }
+    }
+}
+
+impl<T> Future for RecvFut<'_, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        poll_recv(self.receiver, cx)
+    }
+}
+
+impl<T> fmt::Debug for RecvFut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("RecvFut { .. }")
+    }
+}
+
+/// A future that sends a message into a channel.
+///
+/// Created with [`Sender::send_async`]. Until the message is sent, it is kept in the future and
+/// can be taken back with [`into_inner`].
+///
+/// [`into_inner`]: SendFut::into_inner
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SendFut<'a, T> {
+    sender: &'a Sender<T>,
+    msg: Option<T>,
+}
+
+impl<'a, T> SendFut<'a, T> {
+    /// Creates a future sending `msg` into the channel of `sender`.
+    pub(crate) fn new(sender: &'a Sender<T>, msg: T) -> SendFut<'a, T> {
+        SendFut {
+            sender,
+            msg: Some(msg),
+        }
+    }
+
+    /// Consumes the future and returns the message if it hasn't been sent yet.
+    ///
+    /// This lets a task that gives up on sending, for example because another future won a race,
+    /// keep the message instead of dropping it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    /// use std::task::{Context, RawWaker, RawWakerVTable, Waker};
+    /// use crossbeam_channel::bounded;
+    ///
+    /// # fn noop(_: *const ()) {}
+    /// # fn clone(_: *const ()) -> RawWaker { RawWaker::new(std::ptr::null(), &VTABLE) }
+    /// # static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    /// # let waker = unsafe { Waker::from_raw(clone(std::ptr::null())) };
+    /// let (s, r) = bounded(1);
+    /// s.send(1).unwrap();
+    ///
+    /// // The channel is full, so the message can't be sent yet.
+    /// let mut fut = s.send_async(2);
+    /// let mut cx = Context::from_waker(&waker);
+    /// assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    ///
+    /// assert_eq!(fut.into_inner(), Some(2));
+    /// assert_eq!(r.try_recv(), Ok(1));
+    /// assert!(r.try_recv().is_err());
+    /// ```
+    pub fn into_inner(self) -> Option<T> {
+        self.msg
+    }
+}
+
+impl<T> Future for SendFut<'_, T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<T>>> {
+        let this = self.get_mut();
+        assert!(this.msg.is_some(), "`SendFut` polled after completion");
+        poll_send(this.sender, &mut this.msg, cx)
+    }
+}
+
+// The message is never pinned.
+impl<T> Unpin for SendFut<'_, T> {}
+
+impl<T> fmt::Debug for SendFut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("SendFut { .. }")
+    }
+}
+
+/// A future that selects one of the operations in a [`Select`].
+///
+/// Created with [`Select::select_async`]. An operation is only selected when the future
+/// resolves to it, so dropping the future before that never loses a message.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SelectFut<'s, 'a> {
+    sel: &'s mut Select<'a>,
+    start: Option<Instant>,
+}
+
+impl<'s, 'a> SelectFut<'s, 'a> {
+    /// Creates a future selecting one of the operations in `sel`.
+    pub(crate) fn new(sel: &'s mut Select<'a>) -> SelectFut<'s, 'a> {
+        let start = sel.stats().map(|_| Instant::now());
+        SelectFut { sel, start }
+    }
+}
+
+impl<'a> Future for SelectFut<'_, 'a> {
+    type Output = SelectedOperation<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<SelectedOperation<'a>> {
+        let this = self.get_mut();
+        this.sel.poll_select(cx, this.start)
+    }
+}
+
+impl fmt::Debug for SelectFut<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("SelectFut { .. }")
+    }
+}
+
+/// A future that waits until one of the operations in a [`Select`] becomes ready.
+///
+/// Created with [`Select::ready_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadyFut<'s, 'a> {
+    sel: &'s mut Select<'a>,
+    start: Option<Instant>,
+}
+
+impl<'s, 'a> ReadyFut<'s, 'a> {
+    /// Creates a future waiting for one of the operations in `sel`.
+    pub(crate) fn new(sel: &'s mut Select<'a>) -> ReadyFut<'s, 'a> {
+        let start = sel.stats().map(|_| Instant::now());
+        ReadyFut { sel, start }
+    }
+}
+
+impl Future for ReadyFut<'_, '_> {
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        let this = self.get_mut();
+        this.sel.poll_ready(cx, this.start)
+    }
+}
+
+impl fmt::Debug for ReadyFut<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("ReadyFut { .. }")
+    }
+}
+
+/// A stream that yields the messages available in a channel in batches.
+///
+/// Created with [`Receiver::ready_chunks`]. Each batch contains at least one message and at most
+/// the maximum size. The stream waits for the first message of a batch like the receiver itself
+/// does, and then only adds messages that are already in the channel, so it never waits to fill a
+/// batch and never takes more messages than it yields. Messages that don't fit stay in the channel,
+/// where they keep occupying capacity and slowing down senders of a bounded channel.
+///
+/// The stream ends once the channel is empty and disconnected.
+#[must_use = "streams do nothing unless polled"]
+pub struct ReadyChunks<T> {
+    receiver: Receiver<T>,
+    max: usize,
+}
+
+impl<T> ReadyChunks<T> {
+    /// Creates a stream of batches of at most `max` messages from `receiver`.
+    pub(crate) fn new(receiver: Receiver<T>, max: usize) -> ReadyChunks<T> {
+        assert!(max > 0, "chunk size must be non-zero");
+        ReadyChunks { receiver, max }
+    }
+
+    /// Returns the receiver the batches are taken from.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    /// Consumes the stream and returns the receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T> Stream for ReadyChunks<T> {
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<T>>> {
+        let first = match poll_recv(&self.receiver, cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Err(RecvError)) => return Poll::Ready(None),
+            Poll::Ready(Ok(msg)) => msg,
+        };
+
+        let mut chunk = Vec::with_capacity(self.max.min(self.receiver.len() + 1));
+        chunk.push(first);
+        while chunk.len() < self.max {
+            match self.receiver.try_recv() {
+                Ok(msg) => chunk.push(msg),
+                Err(_) => break,
+            }
+        }
+
+        Poll::Ready(Some(chunk))
+    }
+}
+
+// Messages live on the heap, so moving the stream never moves them.
+impl<T> Unpin for ReadyChunks<T> {}
+
+impl<T> fmt::Debug for ReadyChunks<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("ReadyChunks { .. }")
+    }
+}
@@ -0,0 +1,358 @@
+//! Adapters between crossbeam channels and [`futures`] channels.
+//!
+//! Each adapter spawns a background thread that forwards messages from one kind of channel to the
+//! other. Messages are handed over one at a time, so a full channel on the receiving side blocks
+//! the forwarding thread, which in turn stops taking messages from the sending side. Backpressure
+//! therefore works across the bridge the same way it works within a single channel.
+//!
+//! A receiver created by [`from_receiver`] is an ordinary crossbeam receiver, so it can
+//! participate in [`select!`] alongside other channels. This makes it possible to migrate one
+//! part of a program at a time.
+//!
+//! This module is only available with the `futures-compat` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use crossbeam_channel::{futures_compat, select, unbounded};
+//!
+//! let (mut tx, rx) = futures_channel::mpsc::channel(16);
+//! let r1 = futures_compat::from_receiver(rx);
+//! let (_s2, r2) = unbounded::<i32>();
+//!
+//! tx.try_send(1).unwrap();
+//!
+//! select! {
+//!     recv(r1) -> msg => assert_eq!(msg, Ok(1)),
+//!     recv(r2) -> _ => panic!(),
+//!     default(Duration::from_secs(1)) => panic!(),
+//! }
+//! ```
+//!
+//! [`futures`]: https://docs.rs/futures-channel
+//! [`select!`]: crate::select!
+
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use crate::channel::{bounded, Receiver, Sender};
+use crate::err::RecvTimeoutError;
+
+/// How long the forwarding thread may block on one side at a time.
+///
+/// The thread checks whether the other side has been disconnected between waits.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Creates a crossbeam receiver that delivers messages from a futures receiver.
+///
+/// The returned channel has zero capacity, so messages stay in the futures channel until they are
+/// received from the crossbeam side, and futures senders wait for room as usual. Once all futures
+/// senders are dropped and the remaining messages are delivered, the returned channel becomes
+/// disconnected. If the returned receiver is dropped, the futures channel is closed shortly
+/// afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::futures_compat;
+///
+/// let (mut tx, rx) = futures_channel::mpsc::channel(16);
+/// let r = futures_compat::from_receiver(rx);
+///
+/// tx.try_send(1).unwrap();
+/// tx.try_send(2).unwrap();
+/// drop(tx);
+///
+/// assert_eq!(r.iter().collect::<Vec<_>>(), [1, 2]);
+/// ```
+pub fn from_receiver<T: Send + 'static>(rx: mpsc::Receiver<T>) -> Receiver<T> {
+    from_stream(rx)
+}
+
+/// Creates a crossbeam receiver that delivers messages from an unbounded futures receiver.
+///
+/// This works like [`from_receiver`], except that futures senders never wait.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::futures_compat;
+///
+/// let (tx, rx) = futures_channel::mpsc::unbounded();
+/// let r = futures_compat::from_unbounded_receiver(rx);
+///
+/// tx.unbounded_send("hello").unwrap();
+/// assert_eq!(r.recv(), Ok("hello"));
+/// ```
+pub fn from_unbounded_receiver<T: Send + 'static>(rx: mpsc::UnboundedReceiver<T>) -> Receiver<T> {
+    from_stream(rx)
+}
+
+/// Creates a crossbeam sender that sends messages into a futures sender.
+///
+/// The returned channel has zero capacity, so a send blocks until the forwarding thread takes the
+/// message, and the thread only takes the next one once the previous one was accepted by the
+/// futures channel. Once the futures receiver is dropped or closed, the returned channel becomes
+/// disconnected shortly afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::futures_compat;
+///
+/// let (tx, mut rx) = futures_channel::mpsc::channel(16);
+/// let s = futures_compat::from_sender(tx);
+///
+/// s.send(5).unwrap();
+///
+/// // Wait for the forwarding thread to pass the message on.
+/// let msg = loop {
+///     if let Ok(msg) = rx.try_next() {
+///         break msg;
+///     }
+/// };
+/// assert_eq!(msg, Some(5));
+/// ```
+pub fn from_sender<T: Send + 'static>(mut tx: mpsc::Sender<T>) -> Sender<T> {
+    let (s, r) = bounded(0);
+
+    spawn(move || {
+        while let Some(msg) = recv(&r, || tx.is_closed()) {
+            if !send(&mut tx, msg) {
+                return;
+            }
+        }
+    });
+
+    s
+}
+
+/// Creates a crossbeam sender that sends messages into an unbounded futures sender.
+///
+/// This works like [`from_sender`], except that the futures channel never fills up.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::futures_compat;
+///
+/// let (tx, mut rx) = futures_channel::mpsc::unbounded();
+/// let s = futures_compat::from_unbounded_sender(tx);
+///
+/// s.send(5).unwrap();
+///
+/// // Wait for the forwarding thread to pass the message on.
+/// let msg = loop {
+///     if let Ok(msg) = rx.try_next() {
+///         break msg;
+///     }
+/// };
+/// assert_eq!(msg, Some(5));
+/// ```
+pub fn from_unbounded_sender<T: Send + 'static>(tx: mpsc::UnboundedSender<T>) -> Sender<T> {
+    let (s, r) = bounded(0);
+
+    spawn(move || {
+        while let Some(msg) = recv(&r, || tx.is_closed()) {
+            if tx.unbounded_send(msg).is_err() {
+                return;
+            }
+        }
+    });
+
+    s
+}
+
+/// Creates a futures receiver that delivers messages from a crossbeam receiver.
+///
+/// The futures channel has room for a single message, so at most two messages are taken from `r`
+/// before the futures receiver catches up: one in the futures channel and one held by the
+/// forwarding thread. Once `r` becomes disconnected and the remaining messages are delivered, the
+/// futures channel is closed.
+///
+/// If the futures receiver is dropped, the forwarding thread stops shortly afterwards and drops
+/// `r`, along with any message it was holding.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{futures_compat, unbounded};
+///
+/// let (s, r) = unbounded();
+/// let mut rx = futures_compat::into_receiver(r);
+///
+/// s.send(1).unwrap();
+/// drop(s);
+///
+/// // Wait for the forwarding thread to pass the message on.
+/// let msg = loop {
+///     if let Ok(msg) = rx.try_next() {
+///         break msg;
+///     }
+/// };
+/// assert_eq!(msg, Some(1));
+/// ```
+pub fn into_receiver<T: Send + 'static>(r: Receiver<T>) -> mpsc::Receiver<T> {
+    let (mut tx, rx) = mpsc::channel(0);
+
+    spawn(move || {
+        while let Some(msg) = recv(&r, || tx.is_closed()) {
+            if !send(&mut tx, msg) {
+                return;
+            }
+        }
+    });
+
+    rx
+}
+
+/// Creates a futures sender that sends messages into a crossbeam sender.
+///
+/// The futures channel has room for one message per futures sender, and the forwarding thread
+/// blocks until each message is accepted by the crossbeam channel, so futures senders wait
+/// whenever that channel is full. Once all futures senders are dropped and the remaining messages
+/// are delivered, the forwarding thread drops `s`. If all crossbeam receivers are dropped, the
+/// futures channel is closed shortly afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{bounded, futures_compat};
+///
+/// let (s, r) = bounded(1);
+/// let mut tx = futures_compat::into_sender(s);
+///
+/// tx.try_send(7).unwrap();
+/// assert_eq!(r.recv(), Ok(7));
+/// ```
+pub fn into_sender<T: Send + 'static>(s: Sender<T>) -> mpsc::Sender<T> {
+    let (tx, mut rx) = mpsc::channel(0);
+
+    spawn(move || {
+        while let Some(msg) = next(&mut rx, || s.is_disconnected()) {
+            if s.send(msg).is_err() {
+                break;
+            }
+        }
+        rx.close();
+    });
+
+    tx
+}
+
+/// Creates a crossbeam receiver that delivers messages from `stream`.
+fn from_stream<S>(mut stream: S) -> Receiver<S::Item>
+where
+    S: Stream + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+    let (s, r) = bounded(0);
+
+    spawn(move || {
+        while let Some(msg) = next(&mut stream, || s.is_disconnected()) {
+            if s.send(msg).is_err() {
+                return;
+            }
+        }
+    });
+
+    r
+}
+
+/// Receives a message from `r`, giving up once `r` is disconnected or `is_closed` returns `true`.
+fn recv<T, F: Fn() -> bool>(r: &Receiver<T>, is_closed: F) -> Option<T> {
+    loop {
+        match r.recv_timeout(POLL_INTERVAL) {
+            Ok(msg) => return Some(msg),
+            Err(RecvTimeoutError::Timeout) => {
+                if is_closed() {
+                    return None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+/// Receives the next message from `stream`, giving up once it ends or `is_closed` returns
+/// `true`.
+fn next<S, F>(stream: &mut S, is_closed: F) -> Option<S::Item>
+where
+    S: Stream + Unpin,
+    F: Fn() -> bool,
+{
+    match block_on(|cx| Pin::new(&mut *stream).poll_next(cx), is_closed) {
+        Some(Some(msg)) => Some(msg),
+        _ => None,
+    }
+}
+
+/// Sends `msg` into `tx`, waiting for room in the channel. Returns `false` if it is closed.
+fn send<T>(tx: &mut mpsc::Sender<T>, msg: T) -> bool {
+    match block_on(|cx| tx.poll_ready(cx), || false) {
+        Some(Ok(())) => tx.start_send(msg).is_ok(),
+        _ => false,
+    }
+}
+
+/// Polls `f` on the current thread until it is ready, giving up once `is_closed` returns `true`.
+fn block_on<R, F, C>(mut f: F, is_closed: C) -> Option<R>
+where
+    F: FnMut(&mut Context<'_>) -> Poll<R>,
+    C: Fn() -> bool,
+{
+    let waker = thread_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(res) = f(&mut cx) {
+            return Some(res);
+        }
+        if is_closed() {
+            return None;
+        }
+        thread::park_timeout(POLL_INTERVAL);
+    }
+}
+
+/// Returns a waker that unparks the current thread.
+fn thread_waker() -> Waker {
+    unsafe fn clone(p: *const ()) -> RawWaker {
+        let thread = mem::ManuallyDrop::new(Arc::from_raw(p as *const Thread));
+        RawWaker::new(Arc::into_raw(Arc::clone(&thread)) as *const (), &VTABLE)
+    }
+
+    unsafe fn wake(p: *const ()) {
+        wake_by_ref(p);
+        drop_waker(p);
+    }
+
+    unsafe fn wake_by_ref(p: *const ()) {
+        (*(p as *const Thread)).unpark();
+    }
+
+    unsafe fn drop_waker(p: *const ()) {
+        drop(Arc::from_raw(p as *const Thread));
+    }
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_waker);
+
+    let thread = Arc::new(thread::current());
+    unsafe { Waker::from_raw(RawWaker::new(Arc::into_raw(thread) as *const (), &VTABLE)) }
+}
+
+/// Spawns a forwarding thread.
+fn spawn<F: FnOnce() + Send + 'static>(f: F) {
+    thread::Builder::new()
+        .name("crossbeam-channel-futures".to_string())
+        .spawn(f)
+        .expect("failed to spawn a thread for forwarding messages");
+}
@@ -0,0 +1,49 @@
+//! Channels that report completion of threads.
+
+use std::thread::{self, JoinHandle};
+
+use crate::channel::{bounded, Receiver};
+
+/// Creates a receiver that delivers the result of a thread once it finishes.
+///
+/// The thread is joined by a background thread, and its result is sent as the only message, after
+/// which the channel becomes disconnected. If the thread panicked, the message is an `Err`
+/// containing the panic payload, just like the return value of [`JoinHandle::join`].
+///
+/// This makes it possible to wait for a thread and for messages on other channels at the same
+/// time. If the receiver is dropped, the result of the thread is dropped as well.
+///
+/// # Examples
+///
+/// Waiting for a worker to finish or for a message, whichever comes first:
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{joined, select, unbounded};
+///
+/// let (_s, r) = unbounded::<i32>();
+/// let worker = thread::spawn(|| {
+///     thread::sleep(Duration::from_millis(100));
+///     42
+/// });
+///
+/// let done = joined(worker);
+///
+/// select! {
+///     recv(r) -> msg => println!("received {:?}", msg),
+///     recv(done) -> res => assert_eq!(res.unwrap().unwrap(), 42),
+/// }
+/// ```
+pub fn joined<T: Send + 'static>(handle: JoinHandle<T>) -> Receiver<thread::Result<T>> {
+    let (s, r) = bounded(1);
+
+    thread::Builder::new()
+        .name("crossbeam-channel-join".to_string())
+        .spawn(move || {
+            let _ = s.send(handle.join());
+        })
+        .expect("failed to spawn a thread for joining a thread");
+
+    r
+}
@@ -0,0 +1,198 @@
+//! Histograms of how long messages wait in a channel.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Number of bits below the highest set bit that select a sub-bucket.
+const SUB_BITS: u32 = 5;
+
+/// Number of sub-buckets each power of two is split into.
+const SUB_BUCKETS: usize = 1 << SUB_BITS;
+
+/// The highest bit of the largest latency in nanoseconds that is told apart from longer ones.
+///
+/// This is about nine and a half hours. Longer latencies are counted in the last bucket.
+const MAX_BIT: u32 = 44;
+
+/// Total number of buckets.
+const BUCKETS: usize = (MAX_BIT - SUB_BITS + 2) as usize * SUB_BUCKETS;
+
+/// Returns the bucket counting latencies of `nanos` nanoseconds.
+fn bucket(nanos: u64) -> usize {
+    let nanos = nanos.min((1 << (MAX_BIT + 1)) - 1);
+    if nanos < SUB_BUCKETS as u64 {
+        return nanos as usize;
+    }
+
+    // Values with the same highest bit are split by the bits right below it.
+    let bit = 63 - nanos.leading_zeros();
+    let sub = (nanos >> (bit - SUB_BITS)) as usize & (SUB_BUCKETS - 1);
+    (bit - SUB_BITS + 1) as usize * SUB_BUCKETS + sub
+}
+
+/// Returns the lowest and highest latency in nanoseconds counted in bucket `index`.
+fn bounds(index: usize) -> (u64, u64) {
+    if index < SUB_BUCKETS {
+        return (index as u64, index as u64);
+    }
+
+    let shift = (index / SUB_BUCKETS) as u32 - 1;
+    let low = ((SUB_BUCKETS + index % SUB_BUCKETS) as u64) << shift;
+    (low, low + (1 << shift) - 1)
+}
+
+/// Counts of message latencies, updated as messages are received.
+pub(crate) struct Latency {
+    /// The number of latencies in each bucket.
+    counts: Box<[AtomicUsize]>,
+}
+
+impl Latency {
+    /// Creates an empty histogram.
+    pub(crate) fn new() -> Latency {
+        Latency {
+            counts: (0..BUCKETS).map(|_| AtomicUsize::new(0)).collect(),
+        }
+    }
+
+    /// Counts a message that waited for `latency`.
+    #[inline]
+    pub(crate) fn record(&self, latency: Duration) {
+        let nanos = latency.as_nanos().min(u128::from(u64::max_value())) as u64;
+        self.counts[bucket(nanos)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a copy of the counts.
+    pub(crate) fn snapshot(&self) -> LatencyHistogram {
+        LatencyHistogram {
+            counts: self
+                .counts
+                .iter()
+                .map(|c| c.load(Ordering::Relaxed) as u64)
+                .collect(),
+        }
+    }
+
+    /// Forgets all counted latencies.
+    pub(crate) fn reset(&self) {
+        for c in self.counts.iter() {
+            c.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A histogram of how long messages waited in a channel between being sent and received.
+///
+/// A histogram is returned by [`Sender::latency`] and [`Receiver::latency`] when the `timestamps`
+/// feature is enabled. Latencies are counted in buckets that grow with the latency, so every
+/// value reported by the histogram is within about 3% of the latencies it stands for.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::unbounded;
+///
+/// let (s, r) = unbounded();
+/// for i in 0..100 {
+///     s.send(i).unwrap();
+///     r.recv().unwrap();
+/// }
+///
+/// let latency = r.latency().unwrap();
+/// assert_eq!(latency.count(), 100);
+/// assert!(latency.percentile(99.0).unwrap() < Duration::from_secs(1));
+/// ```
+///
+/// [`Sender::latency`]: crate::Sender::latency
+/// [`Receiver::latency`]: crate::Receiver::latency
+#[derive(Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// The number of latencies in each bucket.
+    counts: Box<[u64]>,
+}
+
+impl LatencyHistogram {
+    /// Returns the number of received messages counted in the histogram.
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns `true` if no messages are counted in the histogram.
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Returns the shortest latency, or `None` if the histogram is empty.
+    pub fn min(&self) -> Option<Duration> {
+        let index = self.counts.iter().position(|&c| c > 0)?;
+        Some(Duration::from_nanos(bounds(index).0))
+    }
+
+    /// Returns the longest latency, or `None` if the histogram is empty.
+    pub fn max(&self) -> Option<Duration> {
+        let index = self.counts.iter().rposition(|&c| c > 0)?;
+        Some(Duration::from_nanos(bounds(index).1))
+    }
+
+    /// Returns the average latency, or `None` if the histogram is empty.
+    pub fn mean(&self) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        let total: u128 = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let (low, high) = bounds(i);
+                u128::from(c) * u128::from(low + (high - low) / 2)
+            })
+            .sum();
+        Some(Duration::from_nanos((total / u128::from(count)) as u64))
+    }
+
+    /// Returns the latency that `percentile` percent of messages didn't exceed, or `None` if the
+    /// histogram is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` is not between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        // The rank of the message whose latency is returned, counting from 1.
+        let rank = ((percentile / 100.0 * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, &c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= rank {
+                return Some(Duration::from_nanos(bounds(i).1));
+            }
+        }
+        unreachable!()
+    }
+}
+
+impl fmt::Debug for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyHistogram")
+            .field("count", &self.count())
+            .field("min", &self.min())
+            .field("p50", &self.percentile(50.0))
+            .field("p99", &self.percentile(99.0))
+            .field("max", &self.max())
+            .finish()
+    }
+}
@@ -78,6 +78,11 @@
 //! assert_eq!(r.recv(), Ok("Hi!"));
 //! ```
 //!
+//! Blocked operations spin for a short while before parking the thread. [`bounded_with_spin`]
+//! and [`unbounded_with_spin`] take a [`SpinPolicy`] that changes how long, or turns spinning off.
+//! Very busy producers can wrap their sender with [`Sender::buffered`], which sends messages in
+//! batches, and [`bounded_padded`] keeps small messages on separate cache lines.
+//!
 //! # Sharing channels
 //!
 //! Senders and receivers can be cloned and sent to other threads:
@@ -170,6 +175,28 @@
 //! assert_eq!(r.recv(), Err(RecvError));
 //! ```
 //!
+//! A channel can also be disconnected explicitly with [`Sender::close`] or [`Receiver::close`],
+//! while handles to it are still around. Receivers are guaranteed to get every message that was
+//! in the channel before it was closed, and [`Receiver::remaining`] tells how many are left.
+//! [`Sender::close_with`] also records why the channel was closed, which every handle can read
+//! with [`Receiver::close_reason`]. Once a closed channel has been emptied, [`Sender::reopen`]
+//! makes it usable again, so pooled channels don't need to be allocated anew.
+//!
+//! Channels created with [`bounded_keep_open`] or [`unbounded_keep_open`] aren't disconnected
+//! when their last sender is dropped. Receivers then find them empty, and can create new senders
+//! with [`Receiver::new_sender`], which suits long-lived mailboxes.
+//!
+//! If a producer panics, the channel is disconnected just like when it finishes normally. To tell
+//! the two apart, enable poisoning with [`Sender::poison_on_panic`]: a sender dropped during a
+//! panic then poisons the channel, and [`Receiver::recv_checked`] reports it once the remaining
+//! messages have been received.
+//!
+//! [`Receiver::acked`] turns a receiver into one whose messages must be acknowledged. A message
+//! that a consumer gives back, or drops while panicking, is delivered again to another consumer,
+//! which makes for at-least-once work queues without a second channel for bookkeeping.
+//! [`Receiver::with_put_back`] lets a consumer that read one message too far, like a parser
+//! looking ahead, put it back so that it is received again first.
+//!
 //! # Blocking operations
 //!
 //! Send and receive operations come in three flavors:
@@ -207,6 +234,9 @@
 //! assert_eq!(r.recv(), Err(RecvError));
 //! ```
 //!
+//! Blocking operations can also be aborted from another thread with a token created by
+//! [`cancel_token`], using [`send_cancellable`] and [`recv_cancellable`].
+//!
 //! # Iteration
 //!
 //! Receivers can be used as iterators. For example, method [`iter`] creates an iterator that
@@ -251,6 +281,14 @@
 //! assert_eq!(v, [1, 2, 3]);
 //! ```
 //!
+//! With the `async` feature enabled, [`Receiver`] also implements `Stream` from the
+//! `futures-core` crate, so async tasks can receive messages without blocking a thread. A task
+//! waiting on a timer channel is woken up by a shared background thread, and
+//! `Receiver::ready_chunks` turns a receiver into a stream of batches. In the other direction,
+//! `Sender::sink` returns a `SendSink` implementing `Sink` from the `futures-sink` crate. For
+//! one-off operations, `Sender::send_async` and `Receiver::recv_async` return futures that can be
+//! awaited directly, and `Select::select_async` awaits the first of several operations.
+//!
 //! # Selection
 //!
 //! The [`select!`] macro allows you to define a set of channel operations, wait until any one of
@@ -286,18 +324,38 @@
 //!
 //! If you need to select over a dynamically created list of channel operations, use [`Select`]
 //! instead. The [`select!`] macro is just a convenience wrapper around [`Select`].
+//! [`Selector`] offers the same with a closure per operation, like the arms of the macro.
+//! Event loops that keep receiving and sending until all of their channels are disconnected can
+//! be written with [`select_loop!`], which stops selecting each channel once it disconnects, and [`select_enum!`] receives from channels of different message types
+//! into a single enum.
+//!
+//! If ready operations should be picked in the order they are listed rather than randomly, use
+//! [`select_biased!`] or [`Select::new_biased`] instead. Other policies for choosing among ready
+//! operations are described by [`Fairness`].
+//!
+//! Primitives from other crates can be waited on together with channels by implementing
+//! [`Selectable`] and adding them to a [`Select`].
+//!
+//! Event loops that repeatedly wait on the same large set of channels can use [`SelectSet`],
+//! which keeps operations registered with their channels between calls.
 //!
 //! # Extra channels
 //!
-//! Three functions can create special kinds of channels, all of which return just a [`Receiver`]
-//! handle:
+//! Several functions can create special kinds of channels, which have just a [`Receiver`] handle:
 //!
 //! * [`after`] creates a channel that delivers a single message after a certain duration of time.
-//! * [`tick`] creates a channel that delivers messages periodically.
+//! * [`tick`] creates a channel that delivers messages periodically. [`tick_with`] also chooses
+//!   what happens to the ticks a slow receiver misses, and [`ticker`] returns a [`Ticker`] that
+//!   pauses, resumes, or resets the schedule.
+//! * [`schedule`] creates a channel that delivers a message at each instant of a sequence.
 //! * [`never`](never()) creates a channel that never delivers messages.
 //!
 //! These channels are very efficient because messages get lazily generated on receive operations.
 //!
+//! Timers normally follow the system clock. [`after_with_clock`], [`at_with_clock`], and
+//! [`tick_with_clock`] take a [`Clock`] instead, such as a [`MockClock`] that only moves when it is
+//! advanced, so that time-dependent code can be tested deterministically.
+//!
 //! An example that prints elapsed time every 50 milliseconds for the duration of 1 second:
 //!
 //! ```
@@ -316,8 +374,68 @@
 //! }
 //! ```
 //!
+//! On Unix with the `fd` feature enabled, `readable` and `writable` create channels that deliver a
+//! message whenever a file descriptor is ready for I/O, which makes it possible to wait for a
+//! socket and a channel at once. Going the other way, `Receiver::ready_fd` creates a file
+//! descriptor that becomes readable when the channel has messages, so the channel can be
+//! registered with an event loop.
+//! The [`joined`] function turns a thread's [`JoinHandle`] into a channel that delivers the
+//! thread's result, so waiting for a worker can be combined with other operations. The
+//! [`debounce`] function wraps a receiver so that a burst of messages collapses into the last one,
+//! and [`throttle`] wraps one so that messages are delivered at most once per interval.
+//!
+//! The [`registry`] module keeps a process-wide table of live channels with their lengths and
+//! sender and receiver counts, once it has been enabled.
+//!
+//! A channel can also keep a log of its most recent events. After [`Sender::record_events`] is
+//! called, sends, receives, parked threads, and disconnections are recorded with the thread and
+//! the time, and [`Sender::events`] returns them so that the order of operations leading to an
+//! intermittent bug can be reconstructed.
+//!
+//! With the `stats` feature enabled, every channel counts the messages sent and received over its
+//! lifetime, and the non-blocking operations that failed because it was full or empty.
+//! `Sender::stats` returns these counts, from which throughput and rejection rates can be computed
+//! without external instrumentation.
+//!
+//! With the `signals` feature enabled, the `signals` module provides channels that deliver OS
+//! signals, such as the one sent by Ctrl-C.
+//!
+//! The [`compat::mpsc`] module is a drop-in replacement for [`std::sync::mpsc`] backed by crossbeam
+//! channels, so a program can switch over by changing one import and then adopt the rest of the
+//! API gradually.
+//!
+//! With the `tokio-compat` feature enabled, the `tokio_compat` module converts between crossbeam
+//! channels and `tokio::sync::mpsc` channels, so one channel topology can span sync and async code.
+//! The `futures_compat` module, enabled by the `futures-compat` feature, does the same for
+//! `futures::channel::mpsc` channels.
+//!
+//! With the `ffi` feature enabled, the `ffi` module exports C functions that create channels of
+//! byte messages and send and receive on them, so C and C++ code in the same process can exchange
+//! messages with Rust threads.
+//!
+//! With the `tracing` feature enabled, every send or receive that has to block is recorded as a
+//! `channel_wait` span, followed by an event saying how long the thread was parked. Channels
+//! created with [`bounded_with_metrics`] or [`unbounded_with_metrics`] are identified in the span
+//! by their name.
+//!
+//! With the `deadlock-detection` feature enabled, threads that are blocked sending or receiving
+//! and can only be woken up by each other panic with a message naming them, instead of blocking
+//! forever. A thread only counts on the threads that have already used the other side of a
+//! channel, and has to be blocked for a couple of seconds before it is reported.
+//!
+//! With the `timestamps` feature enabled, every message is timestamped when it is sent, and
+//! `Receiver::latency` returns a histogram of how long received messages waited in the channel,
+//! from which percentiles of the queueing delay can be read.
+//!
+//! With the `sim` feature enabled, the `sim` module runs threads one at a time under a scheduler
+//! seeded by the test, with a virtual clock that jumps ahead whenever every thread is blocked, so
+//! that tests of code using channels are reproducible instead of timing-dependent.
+//!
 //! [`send`]: Sender::send
 //! [`recv`]: Receiver::recv
+//! [`JoinHandle`]: std::thread::JoinHandle
+//! [`send_cancellable`]: Sender::send_cancellable
+//! [`recv_cancellable`]: Receiver::recv_cancellable
 //! [`iter`]: Receiver::iter
 //! [`try_iter`]: Receiver::try_iter
 
@@ -336,36 +454,178 @@
 )]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(crossbeam_loom)]
+extern crate loom_crate as loom;
+
 use cfg_if::cfg_if;
 
+#[cfg(all(feature = "std", crossbeam_loom))]
+#[allow(unused_imports, dead_code)]
+mod primitive {
+    pub(crate) mod cell {
+        pub(crate) use loom::cell::UnsafeCell;
+    }
+    pub(crate) mod sync {
+        pub(crate) mod atomic {
+            pub(crate) use loom::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+        }
+        pub(crate) use loom::sync::Mutex;
+    }
+    pub(crate) mod thread {
+        pub(crate) use loom::thread::{current, park, yield_now, Thread, ThreadId};
+
+        // loom doesn't model time, so a timed park only lets other threads run. Callers check
+        // their deadline again afterwards.
+        pub(crate) fn park_timeout(_dur: std::time::Duration) {
+            yield_now();
+        }
+    }
+    pub(crate) use loom::thread_local;
+}
+#[cfg(all(feature = "std", not(crossbeam_loom)))]
+#[allow(unused_imports, dead_code)]
+mod primitive {
+    pub(crate) mod cell {
+        #[derive(Debug)]
+        #[repr(transparent)]
+        pub(crate) struct UnsafeCell<T>(::std::cell::UnsafeCell<T>);
+
+        // loom's UnsafeCell has a slightly different API than the standard library UnsafeCell.
+        // Since we want the rest of the code to be agnostic to whether it's running under loom or
+        // not, we write this small wrapper that provides the loom-supported API for the standard
+        // library UnsafeCell. This is also what the loom documentation recommends:
+        // https://github.com/tokio-rs/loom#handling-loom-api-differences
+        impl<T> UnsafeCell<T> {
+            #[inline]
+            pub(crate) fn new(data: T) -> UnsafeCell<T> {
+                UnsafeCell(::std::cell::UnsafeCell::new(data))
+            }
+
+            #[inline]
+            pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+                f(self.0.get())
+            }
+
+            #[inline]
+            pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+                f(self.0.get())
+            }
+        }
+    }
+    pub(crate) mod sync {
+        pub(crate) mod atomic {
+            pub(crate) use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+        }
+        pub(crate) use std::sync::Mutex;
+    }
+    pub(crate) mod thread {
+        pub(crate) use std::thread::{current, park, park_timeout, yield_now, Thread, ThreadId};
+    }
+    pub(crate) use std::thread_local;
+}
+
 cfg_if! {
     if #[cfg(feature = "std")] {
+        mod ack;
+        mod buffered;
+        mod cancel;
         mod channel;
+        mod clock;
         mod context;
         mod counter;
+        mod debounce;
+        #[cfg(feature = "deadlock-detection")]
+        mod deadlock;
         mod err;
+        mod events;
+        #[cfg(all(unix, feature = "fd"))]
+        mod fd;
         mod flavors;
+        #[cfg(feature = "async")]
+        mod future;
+        mod join;
+        #[cfg(feature = "timestamps")]
+        mod latency;
+        mod metrics;
+        mod put_back;
         mod select;
         mod select_macro;
+        mod select_set;
+        mod selector;
+        mod spin;
+        #[cfg(feature = "stats")]
+        mod stats;
+        #[cfg(feature = "async")]
+        mod sink;
+        #[cfg(feature = "async")]
+        mod timer;
+        mod throttle;
+        mod trace;
         mod utils;
         mod waker;
 
+        pub mod compat;
+        #[cfg(feature = "ffi")]
+        pub mod ffi;
+        pub mod registry;
+        #[cfg(feature = "sim")]
+        pub mod sim;
+        #[cfg(all(unix, feature = "signals"))]
+        pub mod signals;
+        #[cfg(feature = "futures-compat")]
+        pub mod futures_compat;
+        #[cfg(feature = "tokio-compat")]
+        pub mod tokio_compat;
+
         /// Crate internals used by the `select!` macro.
         #[doc(hidden)]
         pub mod internal {
             pub use crate::select::SelectHandle;
             pub use crate::select::{select, select_timeout, try_select};
+
+            #[cfg(feature = "deadlock-detection")]
+            pub use crate::deadlock::WaitFor;
         }
 
-        pub use crate::channel::{after, at, never, tick};
-        pub use crate::channel::{bounded, unbounded};
+        pub use crate::ack::{AckReceiver, Delivery};
+        pub use crate::buffered::BufferedSender;
+        pub use crate::put_back::PutBackReceiver;
+        pub use crate::cancel::{cancel_token, CancelSignal, CancelToken};
+        pub use crate::channel::{after, at, never, schedule, tick, tick_with, ticker};
+        pub use crate::channel::{after_with_clock, at_with_clock, tick_with_clock};
+        pub use crate::channel::{bounded, bounded_padded, bounded_with_metrics, bounded_with_spin};
+        pub use crate::channel::{unbounded, unbounded_with_metrics, unbounded_with_spin};
+        pub use crate::channel::{bounded_keep_open, unbounded_keep_open};
         pub use crate::channel::{IntoIter, Iter, TryIter};
         pub use crate::channel::{Receiver, Sender};
+        pub use crate::clock::{Clock, MockClock, SystemClock};
+        pub use crate::events::{ChannelEvent, EventKind};
+        pub use crate::metrics::ChannelMetrics;
+        #[cfg(feature = "timestamps")]
+        pub use crate::latency::LatencyHistogram;
+        pub use crate::flavors::tick::{MissedTicks, Ticker};
+        pub use crate::spin::SpinPolicy;
+        #[cfg(feature = "stats")]
+        pub use crate::stats::ChannelStats;
+        #[cfg(feature = "async")]
+        pub use crate::future::{ReadyChunks, ReadyFut, RecvFut, SelectFut, SendFut};
+
+        pub use crate::debounce::debounce;
+        #[cfg(all(unix, feature = "fd"))]
+        pub use crate::fd::{readable, writable, ReadyFd};
+        pub use crate::join::joined;
+        pub use crate::throttle::{throttle, ThrottlePolicy};
 
-        pub use crate::select::{Select, SelectedOperation};
+        pub use crate::select::{Fairness, Select, SelectStats, SelectedOperation};
+        pub use crate::select::{Notifier, Selectable};
+        pub use crate::select_set::SelectSet;
+        pub use crate::selector::Selector;
+        #[cfg(feature = "async")]
+        pub use crate::sink::SendSink;
 
         pub use crate::err::{ReadyTimeoutError, SelectTimeoutError, TryReadyError, TrySelectError};
-        pub use crate::err::{RecvError, RecvTimeoutError, TryRecvError};
-        pub use crate::err::{SendError, SendTimeoutError, TrySendError};
+        pub use crate::err::{RecvCancelError, RecvError, RecvTimeoutError, TryRecvError};
+        pub use crate::err::{PutBackError, RecvPoisonError};
+        pub use crate::err::{SendCancelError, SendError, SendTimeoutError, TrySendError};
     }
 }
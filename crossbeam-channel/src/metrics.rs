@@ -0,0 +1,141 @@
+//! Hooks for observing what happens in a channel.
+
+use std::sync::Arc;
+
+/// Callbacks invoked on key events in a channel.
+///
+/// A `ChannelMetrics` is attached to a channel with [`bounded_with_metrics`] or
+/// [`unbounded_with_metrics`], together with a name for the channel. Every callback receives that
+/// name and the number of messages in the channel right after the event, which makes it easy to
+/// export queue depths and block counts without wrapping every call site. All methods except
+/// [`on_send_batch`] do nothing by default, so an implementation only needs to override the ones
+/// it's interested in.
+///
+/// Callbacks run on the thread that triggered the event, while the operation is in progress, so
+/// they should be quick and must not block on the same channel.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use crossbeam_channel::{unbounded_with_metrics, ChannelMetrics};
+///
+/// #[derive(Default)]
+/// struct Depth {
+///     lens: Mutex<Vec<usize>>,
+/// }
+///
+/// impl ChannelMetrics for Depth {
+///     fn on_send(&self, _name: &str, len: usize) {
+///         self.lens.lock().unwrap().push(len);
+///     }
+/// }
+///
+/// let depth = Arc::new(Depth::default());
+/// let (s, r) = unbounded_with_metrics("jobs", depth.clone());
+///
+/// s.send(1).unwrap();
+/// s.send(2).unwrap();
+/// r.recv().unwrap();
+/// s.send(3).unwrap();
+///
+/// assert_eq!(*depth.lens.lock().unwrap(), [1, 2, 2]);
+/// ```
+///
+/// [`bounded_with_metrics`]: crate::bounded_with_metrics
+/// [`unbounded_with_metrics`]: crate::unbounded_with_metrics
+/// [`on_send_batch`]: ChannelMetrics::on_send_batch
+pub trait ChannelMetrics: Send + Sync {
+    /// Called after a message has been sent into the channel.
+    fn on_send(&self, name: &str, len: usize) {
+        let _ = (name, len);
+    }
+
+    /// Called after a batch of `count` messages has been sent into the channel at once, e.g. by
+    /// flushing a [`BufferedSender`].
+    ///
+    /// `len` is the number of messages in the channel after the whole batch was sent. A batch of a
+    /// single message is reported with [`on_send`] instead. By default, this calls [`on_send`] once
+    /// per message with that length.
+    ///
+    /// [`BufferedSender`]: crate::BufferedSender
+    /// [`on_send`]: ChannelMetrics::on_send
+    fn on_send_batch(&self, name: &str, count: usize, len: usize) {
+        for _ in 0..count {
+            self.on_send(name, len);
+        }
+    }
+
+    /// Called after a message has been received from the channel.
+    fn on_recv(&self, name: &str, len: usize) {
+        let _ = (name, len);
+    }
+
+    /// Called when a send or receive operation is about to block the current thread.
+    ///
+    /// Operations waiting in a [`Select`] don't trigger this callback.
+    ///
+    /// [`Select`]: crate::Select
+    fn on_block(&self, name: &str, len: usize) {
+        let _ = (name, len);
+    }
+
+    /// Called when the channel is destroyed, with the number of messages that were never
+    /// received.
+    ///
+    /// Messages still in the channel when the last receiver was dropped are counted too, even if
+    /// the channel freed them right away. A count above zero usually means work was lost on
+    /// shutdown.
+    fn on_drop(&self, name: &str, len: usize) {
+        let _ = (name, len);
+    }
+}
+
+/// A named channel's metrics.
+pub(crate) struct Metrics {
+    /// The name passed to every callback.
+    name: Box<str>,
+
+    /// The callbacks.
+    hooks: Arc<dyn ChannelMetrics>,
+}
+
+impl Metrics {
+    /// Creates metrics that report events of the channel called `name` to `hooks`.
+    pub(crate) fn new(name: &str, hooks: Arc<dyn ChannelMetrics>) -> Metrics {
+        Metrics {
+            name: name.into(),
+            hooks,
+        }
+    }
+
+    /// Returns the channel's name.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reports a sent message.
+    pub(crate) fn send(&self, len: usize) {
+        self.hooks.on_send(&self.name, len);
+    }
+
+    /// Reports `count` messages sent as a batch.
+    pub(crate) fn send_batch(&self, count: usize, len: usize) {
+        self.hooks.on_send_batch(&self.name, count, len);
+    }
+
+    /// Reports a received message.
+    pub(crate) fn recv(&self, len: usize) {
+        self.hooks.on_recv(&self.name, len);
+    }
+
+    /// Reports an operation that is about to block.
+    pub(crate) fn block(&self, len: usize) {
+        self.hooks.on_block(&self.name, len);
+    }
+
+    /// Reports that the channel is being destroyed.
+    pub(crate) fn destroy(&self, len: usize) {
+        self.hooks.on_drop(&self.name, len);
+    }
+}
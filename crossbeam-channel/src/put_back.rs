@@ -0,0 +1,150 @@
+//! Receivers that can put a message back.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::channel::Receiver;
+use crate::err::{PutBackError, RecvError, RecvTimeoutError, TryRecvError};
+
+/// A receiver that can put back one message, to be received again before any other.
+///
+/// Created with [`Receiver::with_put_back`]. This is handy for parsers and other consumers that
+/// only know they have read too far after receiving the next message: instead of keeping their
+/// own pushback buffer, they [put it back] and receive it again later.
+///
+/// At most one message can be put back at a time. It belongs to this receiver, so it is never
+/// received by other receivers of the same channel, and it is received even after the channel
+/// becomes disconnected.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::unbounded;
+///
+/// let (s, r) = unbounded();
+/// let mut r = r.with_put_back();
+/// for token in vec!["1", "2", "+", "3"] {
+///     s.send(token).unwrap();
+/// }
+/// drop(s);
+///
+/// // Read a run of digits, stopping at the first token that isn't one.
+/// let mut digits = Vec::new();
+/// while let Ok(token) = r.recv() {
+///     if token.parse::<i32>().is_err() {
+///         r.put_back(token).unwrap();
+///         break;
+///     }
+///     digits.push(token);
+/// }
+///
+/// assert_eq!(digits, ["1", "2"]);
+/// assert_eq!(r.recv(), Ok("+"));
+/// assert_eq!(r.recv(), Ok("3"));
+/// ```
+///
+/// [put it back]: PutBackReceiver::put_back
+pub struct PutBackReceiver<T> {
+    /// The receiving side of the channel.
+    receiver: Receiver<T>,
+
+    /// The message that was put back, waiting to be received again.
+    slot: Option<T>,
+}
+
+impl<T> PutBackReceiver<T> {
+    /// Wraps a receiver.
+    pub(crate) fn new(receiver: Receiver<T>) -> PutBackReceiver<T> {
+        PutBackReceiver {
+            receiver,
+            slot: None,
+        }
+    }
+
+    /// Puts a message back, so that it is the next one received.
+    ///
+    /// If a message was already put back and hasn't been received again yet, this fails and
+    /// returns the new message inside the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, PutBackError};
+    ///
+    /// let (s, r) = unbounded();
+    /// let mut r = r.with_put_back();
+    /// s.send(1).unwrap();
+    /// s.send(2).unwrap();
+    ///
+    /// let a = r.recv().unwrap();
+    /// let b = r.recv().unwrap();
+    /// assert_eq!(r.put_back(b), Ok(()));
+    /// assert_eq!(r.put_back(a), Err(PutBackError(1)));
+    /// assert_eq!(r.recv(), Ok(2));
+    /// ```
+    pub fn put_back(&mut self, msg: T) -> Result<(), PutBackError<T>> {
+        if self.slot.is_some() {
+            return Err(PutBackError(msg));
+        }
+        self.slot = Some(msg);
+        Ok(())
+    }
+
+    /// Attempts to receive a message without blocking.
+    ///
+    /// A message that was put back is received first.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.slot.take() {
+            Some(msg) => Ok(msg),
+            None => self.receiver.try_recv(),
+        }
+    }
+
+    /// Blocks the current thread until a message is received.
+    ///
+    /// A message that was put back is received first, without blocking.
+    pub fn recv(&mut self) -> Result<T, RecvError> {
+        match self.slot.take() {
+            Some(msg) => Ok(msg),
+            None => self.receiver.recv(),
+        }
+    }
+
+    /// Waits for a message to be received, but only for a limited time.
+    ///
+    /// A message that was put back is received first, without blocking.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match self.slot.take() {
+            Some(msg) => Ok(msg),
+            None => self.receiver.recv_timeout(timeout),
+        }
+    }
+
+    /// Returns `true` if a message was put back and hasn't been received again yet.
+    pub fn has_put_back(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    /// Returns `true` if there is neither a message that was put back nor one in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.slot.is_none() && self.receiver.is_empty()
+    }
+
+    /// Returns the underlying receiver.
+    ///
+    /// Receiving through it skips the message that was put back.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    /// Unwraps the receiver, also returning the message that was put back, if any.
+    pub fn into_inner(self) -> (Receiver<T>, Option<T>) {
+        (self.receiver, self.slot)
+    }
+}
+
+impl<T> fmt::Debug for PutBackReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("PutBackReceiver { .. }")
+    }
+}
@@ -0,0 +1,512 @@
+//! A process-wide table of live channels.
+//!
+//! The registry is disabled by default. Once [`enable`] has been called, every bounded or
+//! unbounded channel created afterwards registers itself, and stays in the registry until it is
+//! destroyed. [`snapshot`] lists the registered channels with their current statistics, which is
+//! handy for a "show me all queues" debug endpoint in a long-running service.
+//!
+//! Channels created with [`bounded_with_metrics`] or [`unbounded_with_metrics`] are listed under
+//! their name. Timer channels like [`after`] and [`tick`] are never registered.
+//!
+//! Registered channels also remember which threads have sent and received messages, and
+//! [`dot`] draws them as a graph of senders, channels, and receivers, so that the topology of a
+//! pipeline can be visualized.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use crossbeam_channel::{registry, unbounded_with_metrics, ChannelMetrics};
+//!
+//! struct NoHooks;
+//! impl ChannelMetrics for NoHooks {}
+//!
+//! registry::enable();
+//! let (s, r) = unbounded_with_metrics("jobs", Arc::new(NoHooks));
+//! s.send(1).unwrap();
+//! s.send(2).unwrap();
+//!
+//! let jobs = registry::snapshot()
+//!     .into_iter()
+//!     .find(|c| c.name() == Some("jobs"))
+//!     .unwrap();
+//! assert_eq!(jobs.capacity(), None);
+//! assert_eq!(jobs.len(), 2);
+//! assert_eq!(jobs.senders(), 1);
+//! assert_eq!(jobs.receivers(), 1);
+//!
+//! drop(r);
+//! drop(s);
+//! assert!(registry::snapshot().iter().all(|c| c.name() != Some("jobs")));
+//! ```
+//!
+//! [`bounded_with_metrics`]: crate::bounded_with_metrics
+//! [`unbounded_with_metrics`]: crate::unbounded_with_metrics
+//! [`after`]: crate::after
+//! [`tick`]: crate::tick
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+
+/// Set once the registry has been enabled.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Statistics of a registered channel, as returned by [`snapshot`].
+#[derive(Clone, Debug)]
+pub struct ChannelInfo {
+    /// The channel's name, if it has one.
+    name: Option<String>,
+
+    /// The channel's capacity, or `None` if it is unbounded.
+    capacity: Option<usize>,
+
+    /// The number of messages in the channel.
+    len: usize,
+
+    /// The number of senders connected to the channel.
+    senders: usize,
+
+    /// The number of receivers connected to the channel.
+    receivers: usize,
+
+    /// Names of the threads that have recently sent messages into the channel.
+    sender_threads: Vec<String>,
+
+    /// Names of the threads that have recently received messages from the channel.
+    receiver_threads: Vec<String>,
+
+    /// Names of the threads blocked sending into the channel.
+    blocked_senders: Vec<String>,
+
+    /// Names of the threads blocked receiving from the channel.
+    blocked_receivers: Vec<String>,
+}
+
+impl ChannelInfo {
+    /// Creates statistics for a channel in the given state.
+    pub(crate) fn new(
+        name: Option<&str>,
+        capacity: Option<usize>,
+        len: usize,
+        senders: usize,
+        receivers: usize,
+    ) -> ChannelInfo {
+        ChannelInfo {
+            name: name.map(String::from),
+            capacity,
+            len,
+            senders,
+            receivers,
+            sender_threads: Vec::new(),
+            receiver_threads: Vec::new(),
+            blocked_senders: Vec::new(),
+            blocked_receivers: Vec::new(),
+        }
+    }
+
+    /// Adds the names of the threads that have used the channel and of those blocked on it.
+    pub(crate) fn with_threads(
+        mut self,
+        registration: &Registration,
+        blocked_senders: Vec<String>,
+        blocked_receivers: Vec<String>,
+    ) -> ChannelInfo {
+        self.sender_threads = registration.senders.names();
+        self.receiver_threads = registration.receivers.names();
+        self.blocked_senders = blocked_senders;
+        self.blocked_receivers = blocked_receivers;
+        self
+    }
+
+    /// Returns the name the channel was created with, or `None` if it has no name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// Returns the channel's capacity, or `None` if it is unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Returns the number of messages that were in the channel when the snapshot was taken.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the channel was empty when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of senders connected to the channel.
+    pub fn senders(&self) -> usize {
+        self.senders
+    }
+
+    /// Returns the number of receivers connected to the channel.
+    pub fn receivers(&self) -> usize {
+        self.receivers
+    }
+
+    /// Returns the names of the threads that have sent messages into the channel, sorted.
+    ///
+    /// Only the 32 threads that most recently started sending are listed. Threads without a name
+    /// are listed by their ID.
+    pub fn sender_threads(&self) -> &[String] {
+        &self.sender_threads
+    }
+
+    /// Returns the names of the threads that have received messages from the channel, sorted.
+    ///
+    /// Only the 32 threads that most recently started receiving are listed. Threads without a
+    /// name are listed by their ID.
+    pub fn receiver_threads(&self) -> &[String] {
+        &self.receiver_threads
+    }
+
+    /// Returns the names of the threads that were blocked sending into the channel when the
+    /// snapshot was taken.
+    pub fn blocked_senders(&self) -> &[String] {
+        &self.blocked_senders
+    }
+
+    /// Returns the names of the threads that were blocked receiving from the channel when the
+    /// snapshot was taken.
+    pub fn blocked_receivers(&self) -> &[String] {
+        &self.blocked_receivers
+    }
+}
+
+/// A channel flavor whose state can be listed in the registry.
+pub(crate) trait Inspect {
+    /// Returns the channel's name, if it has one.
+    fn name(&self) -> Option<&str>;
+
+    /// Returns the channel's capacity, or `None` if it is unbounded.
+    fn capacity(&self) -> Option<usize>;
+
+    /// Returns the number of messages in the channel.
+    fn len(&self) -> usize;
+
+    /// Returns the names of threads blocked sending into the channel.
+    fn blocked_sender_names(&self) -> Vec<String>;
+
+    /// Returns the names of threads blocked receiving from the channel.
+    fn blocked_receiver_names(&self) -> Vec<String>;
+}
+
+/// A registered channel.
+struct Entry {
+    /// Pointer to the channel's shared state.
+    data: *const (),
+
+    /// Reads the statistics of the channel behind `data`.
+    info: unsafe fn(*const ()) -> ChannelInfo,
+}
+
+// An entry is only read while the registry is locked, and the channel removes it from the
+// registry before it is destroyed.
+unsafe impl Send for Entry {}
+
+/// The registered channels.
+struct Registry {
+    /// Registered channels, keyed by the order in which they were registered.
+    entries: BTreeMap<u64, Entry>,
+
+    /// The key of the next registered channel.
+    next: u64,
+}
+
+/// Returns the registry.
+fn registry() -> &'static Mutex<Registry> {
+    static INIT: Once = Once::new();
+    static mut REGISTRY: *const Mutex<Registry> = ptr::null();
+
+    INIT.call_once(|| {
+        let registry: &'static Mutex<Registry> = Box::leak(Box::new(Mutex::new(Registry {
+            entries: BTreeMap::new(),
+            next: 0,
+        })));
+        unsafe { REGISTRY = registry }
+    });
+
+    unsafe { &*REGISTRY }
+}
+
+/// Keeps a channel in the registry until it is dropped.
+pub(crate) struct Registration {
+    /// The channel's key in the registry.
+    key: u64,
+
+    /// The threads that have sent messages into the channel.
+    senders: Users,
+
+    /// The threads that have received messages from the channel.
+    receivers: Users,
+}
+
+impl Registration {
+    /// Notes that the current thread has sent a message into the channel.
+    #[inline]
+    pub(crate) fn sent(&self) {
+        self.senders.used();
+    }
+
+    /// Notes that the current thread has received a message from the channel.
+    #[inline]
+    pub(crate) fn received(&self) {
+        self.receivers.used();
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let mut registry = registry().lock().unwrap();
+        registry.entries.remove(&self.key);
+    }
+}
+
+/// The number of recent users of one side of a channel that are noted without locking.
+const RECENT: usize = 4;
+
+/// The number of users of one side of a channel whose names are kept.
+///
+/// A long-lived channel may be used by many short-lived threads, so only the most recent ones are
+/// remembered.
+const MAX_USERS: usize = 32;
+
+/// The threads that have used one side of a registered channel.
+struct Users {
+    /// IDs of threads that have recently used this side, or 0 for empty slots.
+    ///
+    /// A thread always goes into the same slot, so a side used by a few threads usually finds
+    /// them here, and the lock only has to be taken when another thread shows up.
+    recent: [AtomicUsize; RECENT],
+
+    /// Names of the threads that have most recently used this side, least recent first.
+    names: Mutex<VecDeque<String>>,
+}
+
+impl Users {
+    /// Creates an empty set of users.
+    fn new() -> Users {
+        Users {
+            recent: Default::default(),
+            names: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Adds the current thread to the users.
+    fn used(&self) {
+        thread_local! {
+            /// A number identifying the current thread, or 0 until it has been assigned.
+            static ID: Cell<usize> = Cell::new(0);
+        }
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+        let id = ID
+            .try_with(|id| {
+                if id.get() == 0 {
+                    id.set(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+                }
+                id.get()
+            })
+            .unwrap_or(0);
+
+        let slot = &self.recent[id % RECENT];
+        if id == 0 || slot.load(Ordering::Relaxed) != id {
+            slot.store(id, Ordering::Relaxed);
+
+            let thread = thread::current();
+            let name = match thread.name() {
+                Some(name) => name.to_string(),
+                None => format!("{:?}", thread.id()),
+            };
+
+            let mut names = self.names.lock().unwrap();
+            if let Some(i) = names.iter().position(|n| *n == name) {
+                names.remove(i);
+            } else if names.len() == MAX_USERS {
+                names.pop_front();
+            }
+            names.push_back(name);
+        }
+    }
+
+    /// Returns the names of the users, sorted.
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.names.lock().unwrap().iter().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Registers the channel behind `data` if the registry is enabled.
+///
+/// # Safety
+///
+/// `info` must be safe to call with `data` until the returned registration is dropped.
+pub(crate) unsafe fn register(
+    data: *const (),
+    info: unsafe fn(*const ()) -> ChannelInfo,
+) -> Option<Registration> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let mut registry = registry().lock().unwrap();
+    let key = registry.next;
+    registry.next += 1;
+    registry.entries.insert(key, Entry { data, info });
+    Some(Registration {
+        key,
+        senders: Users::new(),
+        receivers: Users::new(),
+    })
+}
+
+/// Enables the registry.
+///
+/// Every bounded or unbounded channel created after this call is listed by [`snapshot`] until it
+/// is destroyed. Channels created before the registry was enabled are not listed. The registry
+/// can't be disabled again.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns `true` if the registry has been enabled.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the statistics of all registered channels, in the order they were created.
+///
+/// Returns an empty list if the registry hasn't been enabled.
+pub fn snapshot() -> Vec<ChannelInfo> {
+    entries().into_iter().map(|(_, info)| info).collect()
+}
+
+/// Returns the keys and statistics of all registered channels, in the order they were created.
+fn entries() -> Vec<(u64, ChannelInfo)> {
+    let registry = registry().lock().unwrap();
+    registry
+        .entries
+        .iter()
+        .map(|(&key, e)| (key, unsafe { (e.info)(e.data) }))
+        .collect()
+}
+
+/// Returns the graph of registered channels and the threads using them in DOT format.
+///
+/// Every channel is a box labeled with its name and length, and every thread that has sent or
+/// received a message, or is blocked doing so, is an ellipse labeled with its name. Edges lead
+/// from senders to channels and from channels to receivers, and are dashed for blocked threads.
+/// The output can be rendered with Graphviz, for example with `dot -Tsvg`.
+///
+/// Returns an empty graph if the registry hasn't been enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use crossbeam_channel::{registry, unbounded_with_metrics, ChannelMetrics};
+///
+/// struct NoHooks;
+/// impl ChannelMetrics for NoHooks {}
+///
+/// registry::enable();
+/// let (s, r) = unbounded_with_metrics("results", Arc::new(NoHooks));
+/// thread::Builder::new()
+///     .name("worker".to_string())
+///     .spawn(move || s.send(1).unwrap())
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// r.recv().unwrap();
+///
+/// let dot = registry::dot();
+/// assert!(dot.starts_with("digraph channels {"));
+/// assert!(dot.contains(r#""thread:worker" [label="worker"];"#));
+/// println!("{}", dot);
+/// ```
+pub fn dot() -> String {
+    let channels = entries();
+
+    // Every thread is declared once, however many channels it uses.
+    let mut threads = BTreeSet::new();
+    for (_, info) in &channels {
+        threads.extend(info.sender_threads.iter().chain(&info.blocked_senders));
+        threads.extend(info.receiver_threads.iter().chain(&info.blocked_receivers));
+    }
+
+    let mut out = String::from("digraph channels {\n");
+    for thread in &threads {
+        let _ = writeln!(out, "    \"thread:{0}\" [label=\"{0}\"];", escape(thread));
+    }
+    for (key, info) in &channels {
+        let name = info.name.as_ref().map_or("unnamed", String::as_str);
+        let len = match info.capacity {
+            Some(cap) => format!("{}/{}", info.len, cap),
+            None => info.len.to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "    \"channel:{}\" [shape=box, label=\"{}\\n{}\"];",
+            key,
+            escape(name),
+            len
+        );
+
+        for (thread, blocked) in sides(&info.sender_threads, &info.blocked_senders) {
+            let _ = writeln!(
+                out,
+                "    \"thread:{}\" -> \"channel:{}\"{};",
+                escape(thread),
+                key,
+                if blocked { " [style=dashed]" } else { "" }
+            );
+        }
+        for (thread, blocked) in sides(&info.receiver_threads, &info.blocked_receivers) {
+            let _ = writeln!(
+                out,
+                "    \"channel:{}\" -> \"thread:{}\"{};",
+                key,
+                escape(thread),
+                if blocked { " [style=dashed]" } else { "" }
+            );
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Merges the threads that have used one side of a channel with those blocked on it, noting
+/// which ones are blocked.
+fn sides<'a>(used: &'a [String], blocked: &'a [String]) -> BTreeMap<&'a str, bool> {
+    let mut threads: BTreeMap<&str, bool> = used.iter().map(|t| (t.as_str(), false)).collect();
+    for t in blocked {
+        threads.insert(t, true);
+    }
+    threads
+}
+
+/// Escapes `s` for use in a quoted DOT string.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -1,19 +1,32 @@
 //! Interface to the select mechanism.
 
+use std::cell::Cell;
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{self, Poll};
 use std::time::{Duration, Instant};
 
 use crossbeam_utils::Backoff;
 
 use crate::channel::{self, Receiver, Sender};
 use crate::context::Context;
+#[cfg(feature = "deadlock-detection")]
+use crate::deadlock::{self, WaitFor};
 use crate::err::{ReadyTimeoutError, TryReadyError};
-use crate::err::{RecvError, SendError};
+use crate::err::{RecvError, RecvTimeoutError, SendError, SendTimeoutError};
 use crate::err::{SelectTimeoutError, TrySelectError};
 use crate::flavors;
+#[cfg(feature = "async")]
+use crate::future::{ReadyFut, SelectFut};
+#[cfg(feature = "async")]
+use crate::timer;
 use crate::utils;
+use crate::waker::{Listener, SyncWaker};
 
 /// Temporary data that gets initialized during select or a blocking operation, and is consumed by
 /// `read` or `write`.
@@ -27,8 +40,10 @@ pub struct Token {
     pub(crate) list: flavors::list::ListToken,
     #[allow(dead_code)]
     pub(crate) never: flavors::never::NeverToken,
+    pub(crate) schedule: flavors::schedule::ScheduleToken,
     pub(crate) tick: flavors::tick::TickToken,
     pub(crate) zero: flavors::zero::ZeroToken,
+    pub(crate) expired: bool,
 }
 
 /// Identifier associated with an operation by a specific thread on a specific channel.
@@ -120,6 +135,23 @@ pub trait SelectHandle {
 
     /// Unregisters an operation for readiness notification.
     fn unwatch(&self, oper: Operation);
+
+    /// Registers a persistent listener that gets notified whenever the operation might have
+    /// become ready.
+    fn listen(&self, listener: &Arc<Listener>, index: usize);
+
+    /// Unregisters a persistent listener.
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize);
+
+    /// Registers a task that gets woken up once the operation might have become ready.
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker);
+
+    /// Returns the channel operation a thread blocked on this handle waits on, if there is one.
+    #[cfg(feature = "deadlock-detection")]
+    fn wait_for(&self) -> Option<WaitFor> {
+        None
+    }
 }
 
 impl<T: SelectHandle> SelectHandle for &T {
@@ -154,6 +186,441 @@ impl<T: SelectHandle> SelectHandle for &T {
     fn unwatch(&self, oper: Operation) {
         (**self).unwatch(oper)
     }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        (**self).listen(listener, index)
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        (**self).unlisten(listener, index)
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        (**self).watch_task(waker)
+    }
+
+    #[cfg(feature = "deadlock-detection")]
+    fn wait_for(&self) -> Option<WaitFor> {
+        (**self).wait_for()
+    }
+}
+
+/// A user-defined primitive that can participate in selection.
+///
+/// Implementing this trait allows types from other crates, such as latches, queues, or timers, to
+/// be added to a [`Select`] with [`Select::custom`] and waited on together with channels.
+///
+/// A primitive is selected as soon as it reports to be ready. The selecting thread then performs
+/// the actual operation on the primitive itself, so it must cope with the primitive no longer
+/// being ready by then, just like with [`Select::ready`].
+///
+/// # Invariants
+///
+/// Every time [`is_ready`] might change from `false` to `true`, the primitive must call
+/// [`Notifier::notify_one`] or [`Notifier::notify_all`] on the notifier returned by
+/// [`notifier`], after the change is visible to other threads. Otherwise a thread blocked in
+/// selection might never wake up.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use crossbeam_channel::{unbounded, Notifier, Select, Selectable};
+///
+/// // A flag that can be set once and stays set.
+/// struct Flag {
+///     set: AtomicBool,
+///     notifier: Notifier,
+/// }
+///
+/// impl Flag {
+///     fn set(&self) {
+///         self.set.store(true, Ordering::SeqCst);
+///         self.notifier.notify_all();
+///     }
+/// }
+///
+/// impl Selectable for Flag {
+///     fn is_ready(&self) -> bool {
+///         self.set.load(Ordering::SeqCst)
+///     }
+///
+///     fn notifier(&self) -> &Notifier {
+///         &self.notifier
+///     }
+/// }
+///
+/// let flag = Arc::new(Flag {
+///     set: AtomicBool::new(false),
+///     notifier: Notifier::new(),
+/// });
+/// let (_s, r) = unbounded::<i32>();
+///
+/// let f = flag.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     f.set();
+/// });
+///
+/// let mut sel = Select::new();
+/// let oper1 = sel.recv(&r);
+/// let oper2 = sel.custom(&*flag);
+///
+/// // The flag gets set while the channel stays empty.
+/// let oper = sel.select();
+/// assert_eq!(oper.index(), oper2);
+/// oper.custom(&*flag);
+/// ```
+///
+/// [`is_ready`]: Selectable::is_ready
+/// [`notifier`]: Selectable::notifier
+pub trait Selectable {
+    /// Returns `true` if the primitive is ready.
+    fn is_ready(&self) -> bool;
+
+    /// Returns the notifier used to wake up threads waiting for the primitive.
+    fn notifier(&self) -> &Notifier;
+
+    /// Returns a deadline after which the primitive becomes ready, if there is one.
+    ///
+    /// Blocked threads wake up at the deadline to check readiness again without being notified.
+    fn deadline(&self) -> Option<Instant> {
+        None
+    }
+}
+
+/// Wakes up threads selecting over a [`Selectable`] primitive.
+///
+/// See [`Selectable`] for an example.
+pub struct Notifier {
+    /// Threads blocked in selection.
+    pub(crate) waker: SyncWaker,
+}
+
+unsafe impl Send for Notifier {}
+unsafe impl Sync for Notifier {}
+
+impl Notifier {
+    /// Creates a new notifier.
+    pub fn new() -> Notifier {
+        Notifier {
+            waker: SyncWaker::new(),
+        }
+    }
+
+    /// Wakes up one thread blocked in selection, and all threads waiting for readiness.
+    ///
+    /// This is suitable when the primitive can only be consumed by one thread at a time, such as
+    /// a queue receiving a single item.
+    pub fn notify_one(&self) {
+        self.waker.notify();
+    }
+
+    /// Wakes up all threads blocked in selection or waiting for readiness.
+    pub fn notify_all(&self) {
+        // Waking up selectors as disconnected makes them re-check readiness of every operation.
+        self.waker.disconnect();
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Notifier {
+        Notifier::new()
+    }
+}
+
+impl fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Notifier { .. }")
+    }
+}
+
+/// Adapts a [`Selectable`] primitive to the interface used by select.
+#[repr(transparent)]
+struct Custom<S>(S);
+
+impl<S: Selectable> SelectHandle for Custom<S> {
+    fn try_select(&self, _token: &mut Token) -> bool {
+        self.0.is_ready()
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.0.deadline()
+    }
+
+    fn register(&self, oper: Operation, cx: &Context) -> bool {
+        self.0.notifier().waker.register(oper, cx);
+        self.0.is_ready()
+    }
+
+    fn unregister(&self, oper: Operation) {
+        self.0.notifier().waker.unregister(oper);
+    }
+
+    fn accept(&self, _token: &mut Token, _cx: &Context) -> bool {
+        self.0.is_ready()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.0.is_ready()
+    }
+
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        self.0.notifier().waker.watch(oper, cx);
+        self.0.is_ready()
+    }
+
+    fn unwatch(&self, oper: Operation) {
+        self.0.notifier().waker.unwatch(oper);
+    }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.notifier().waker.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.0.notifier().waker.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.0.notifier().waker.watch_task(waker);
+    }
+}
+
+/// The number of operations a [`Select`] can hold without allocating.
+const INLINE_LEN: usize = 8;
+
+/// An operation in a [`Select`]: its handle, index, and the address of its channel.
+type Handle<'a> = (&'a dyn SelectHandle, usize, *const u8);
+
+/// Fills unused slots of an inline list of operations. It is never ready.
+struct Vacant;
+
+impl SelectHandle for Vacant {
+    fn try_select(&self, _token: &mut Token) -> bool {
+        false
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    fn register(&self, _oper: Operation, _cx: &Context) -> bool {
+        false
+    }
+
+    fn unregister(&self, _oper: Operation) {}
+
+    fn accept(&self, _token: &mut Token, _cx: &Context) -> bool {
+        false
+    }
+
+    fn is_ready(&self) -> bool {
+        false
+    }
+
+    fn watch(&self, _oper: Operation, _cx: &Context) -> bool {
+        false
+    }
+
+    fn unwatch(&self, _oper: Operation) {}
+
+    fn listen(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    fn unlisten(&self, _listener: &Arc<Listener>, _index: usize) {}
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, _waker: &task::Waker) {}
+}
+
+/// The operation in unused slots of an inline list.
+const VACANT: Handle<'static> = (&Vacant, 0, ptr::null());
+
+/// A list of operations that is stored inline until it grows beyond [`INLINE_LEN`].
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+enum Handles<'a> {
+    /// The first `len` slots are in use.
+    Inline(usize, [Handle<'a>; INLINE_LEN]),
+
+    /// The list has outgrown the inline storage.
+    Heap(Vec<Handle<'a>>),
+}
+
+impl<'a> Handles<'a> {
+    /// Creates an empty list.
+    fn new() -> Handles<'a> {
+        Handles::Inline(0, [VACANT; INLINE_LEN])
+    }
+
+    /// Appends an operation, moving the list to the heap if it is full.
+    fn push(&mut self, handle: Handle<'a>) {
+        match self {
+            Handles::Inline(len, buf) if *len < INLINE_LEN => {
+                buf[*len] = handle;
+                *len += 1;
+            }
+            Handles::Inline(_, buf) => {
+                let mut v = Vec::with_capacity(INLINE_LEN * 2);
+                v.extend_from_slice(buf);
+                v.push(handle);
+                *self = Handles::Heap(v);
+            }
+            Handles::Heap(v) => v.push(handle),
+        }
+    }
+
+    /// Removes the operation at position `i`, preserving the order of the others.
+    fn remove(&mut self, i: usize) {
+        match self {
+            Handles::Inline(len, buf) => {
+                buf[i..*len].rotate_left(1);
+                *len -= 1;
+                buf[*len] = VACANT;
+            }
+            Handles::Heap(v) => {
+                v.remove(i);
+            }
+        }
+    }
+
+    /// Removes the operation at position `i`, replacing it with the last one.
+    fn swap_remove(&mut self, i: usize) {
+        match self {
+            Handles::Inline(len, buf) => {
+                *len -= 1;
+                buf.swap(i, *len);
+                buf[*len] = VACANT;
+            }
+            Handles::Heap(v) => {
+                v.swap_remove(i);
+            }
+        }
+    }
+}
+
+impl<'a> Deref for Handles<'a> {
+    type Target = [Handle<'a>];
+
+    fn deref(&self) -> &[Handle<'a>] {
+        match self {
+            Handles::Inline(len, buf) => &buf[..*len],
+            Handles::Heap(v) => v,
+        }
+    }
+}
+
+impl<'a> DerefMut for Handles<'a> {
+    fn deref_mut(&mut self) -> &mut [Handle<'a>] {
+        match self {
+            Handles::Inline(len, buf) => &mut buf[..*len],
+            Handles::Heap(v) => v,
+        }
+    }
+}
+
+/// Wraps an operation that times out at its own deadline.
+#[derive(Clone, Copy)]
+struct Expiring<'a> {
+    /// The wrapped operation.
+    handle: &'a dyn SelectHandle,
+
+    /// The deadline of the operation, if it has one.
+    deadline: Option<Instant>,
+
+    /// The current time, shared by all operations and read at most once per selection round.
+    now: &'a Cell<Option<Instant>>,
+}
+
+impl Expiring<'_> {
+    /// Returns `true` if the deadline has passed.
+    ///
+    /// The clock is only read if the operation has a deadline and no other operation has read it
+    /// since the last round ended.
+    fn is_expired(&self) -> bool {
+        match self.deadline {
+            None => false,
+            Some(d) => {
+                let now = self.now.get().unwrap_or_else(|| {
+                    let now = utils::now();
+                    self.now.set(Some(now));
+                    now
+                });
+                now >= d
+            }
+        }
+    }
+
+    /// Forgets the cached time so that the next round reads the clock again.
+    fn end_round(&self) {
+        self.now.set(None);
+    }
+}
+
+impl SelectHandle for Expiring<'_> {
+    fn try_select(&self, token: &mut Token) -> bool {
+        if self.handle.try_select(token) {
+            true
+        } else if self.is_expired() {
+            token.expired = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        // This is called right before blocking, after which the cached time is stale.
+        self.end_round();
+        match (self.handle.deadline(), self.deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn register(&self, oper: Operation, cx: &Context) -> bool {
+        self.handle.register(oper, cx) || self.is_expired()
+    }
+
+    fn unregister(&self, oper: Operation) {
+        self.end_round();
+        self.handle.unregister(oper);
+    }
+
+    fn accept(&self, token: &mut Token, cx: &Context) -> bool {
+        self.handle.accept(token, cx)
+    }
+
+    fn is_ready(&self) -> bool {
+        self.handle.is_ready() || self.is_expired()
+    }
+
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        self.handle.watch(oper, cx) || self.is_expired()
+    }
+
+    fn unwatch(&self, oper: Operation) {
+        self.end_round();
+        self.handle.unwatch(oper);
+    }
+
+    fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        self.handle.listen(listener, index);
+    }
+
+    fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        self.handle.unlisten(listener, index);
+    }
+
+    #[cfg(feature = "async")]
+    fn watch_task(&self, waker: &task::Waker) {
+        self.handle.watch_task(waker);
+    }
 }
 
 /// Determines when a select operation should time out.
@@ -176,6 +643,7 @@ enum Timeout {
 fn run_select(
     handles: &mut [(&dyn SelectHandle, usize, *const u8)],
     timeout: Timeout,
+    is_biased: bool,
 ) -> Option<(Token, usize, *const u8)> {
     if handles.is_empty() {
         // Wait until the timeout and return.
@@ -192,8 +660,10 @@ fn run_select(
         }
     }
 
-    // Shuffle the operations for fairness.
-    utils::shuffle(handles);
+    if !is_biased {
+        // Shuffle the operations for fairness.
+        utils::shuffle(handles);
+    }
 
     // Create a token, which serves as a temporary variable that gets initialized in this function
     // and is later used by a call to `channel::read()` or `channel::write()` that completes the
@@ -207,6 +677,10 @@ fn run_select(
         }
     }
 
+    // Blocking without a timeout is done in slices, looking for a deadlock after each one.
+    #[cfg(feature = "deadlock-detection")]
+    let mut waiting = None;
+
     loop {
         // Prepare for blocking.
         let res = Context::with(|cx| {
@@ -247,7 +721,10 @@ fn run_select(
                 // earliest deadline.
                 let mut deadline: Option<Instant> = match timeout {
                     Timeout::Now => return None,
+                    #[cfg(not(feature = "deadlock-detection"))]
                     Timeout::Never => None,
+                    #[cfg(feature = "deadlock-detection")]
+                    Timeout::Never => Some(utils::now() + deadlock::CHECK_INTERVAL),
                     Timeout::At(when) => Some(when),
                 };
                 for &(handle, _, _) in handles.iter() {
@@ -310,9 +787,14 @@ fn run_select(
 
         match timeout {
             Timeout::Now => return None,
-            Timeout::Never => {}
+            Timeout::Never => {
+                #[cfg(feature = "deadlock-detection")]
+                waiting
+                    .get_or_insert_with(|| deadlock::Waiting::new(wait_for(handles)))
+                    .check();
+            }
             Timeout::At(when) => {
-                if Instant::now() >= when {
+                if utils::now() >= when {
                     return None;
                 }
             }
@@ -320,10 +802,17 @@ fn run_select(
     }
 }
 
+/// Returns the channel operations a thread blocked on `handles` waits on.
+#[cfg(feature = "deadlock-detection")]
+fn wait_for(handles: &[(&dyn SelectHandle, usize, *const u8)]) -> Vec<WaitFor> {
+    handles.iter().filter_map(|h| h.0.wait_for()).collect()
+}
+
 /// Runs until one of the operations becomes ready, potentially blocking the current thread.
 fn run_ready(
     handles: &mut [(&dyn SelectHandle, usize, *const u8)],
     timeout: Timeout,
+    is_biased: bool,
 ) -> Option<usize> {
     if handles.is_empty() {
         // Wait until the timeout and return.
@@ -340,8 +829,14 @@ fn run_ready(
         }
     }
 
-    // Shuffle the operations for fairness.
-    utils::shuffle(handles);
+    if !is_biased {
+        // Shuffle the operations for fairness.
+        utils::shuffle(handles);
+    }
+
+    // Blocking without a timeout is done in slices, looking for a deadlock after each one.
+    #[cfg(feature = "deadlock-detection")]
+    let mut waiting = None;
 
     loop {
         let backoff = Backoff::new();
@@ -363,9 +858,14 @@ fn run_ready(
         // Check for timeout.
         match timeout {
             Timeout::Now => return None,
-            Timeout::Never => {}
+            Timeout::Never => {
+                #[cfg(feature = "deadlock-detection")]
+                waiting
+                    .get_or_insert_with(|| deadlock::Waiting::new(wait_for(handles)))
+                    .check();
+            }
             Timeout::At(when) => {
-                if Instant::now() >= when {
+                if utils::now() >= when {
                     return None;
                 }
             }
@@ -402,7 +902,10 @@ fn run_ready(
                 // earliest deadline.
                 let mut deadline: Option<Instant> = match timeout {
                     Timeout::Now => unreachable!(),
+                    #[cfg(not(feature = "deadlock-detection"))]
                     Timeout::Never => None,
+                    #[cfg(feature = "deadlock-detection")]
+                    Timeout::Never => Some(utils::now() + deadlock::CHECK_INTERVAL),
                     Timeout::At(when) => Some(when),
                 };
                 for &(handle, _, _) in handles.iter() {
@@ -449,8 +952,9 @@ fn run_ready(
 #[inline]
 pub fn try_select<'a>(
     handles: &mut [(&'a dyn SelectHandle, usize, *const u8)],
+    is_biased: bool,
 ) -> Result<SelectedOperation<'a>, TrySelectError> {
-    match run_select(handles, Timeout::Now) {
+    match run_select(handles, Timeout::Now, is_biased) {
         None => Err(TrySelectError),
         Some((token, index, ptr)) => Ok(SelectedOperation {
             token,
@@ -466,12 +970,13 @@ pub fn try_select<'a>(
 #[inline]
 pub fn select<'a>(
     handles: &mut [(&'a dyn SelectHandle, usize, *const u8)],
+    is_biased: bool,
 ) -> SelectedOperation<'a> {
     if handles.is_empty() {
         panic!("no operations have been added to `Select`");
     }
 
-    let (token, index, ptr) = run_select(handles, Timeout::Never).unwrap();
+    let (token, index, ptr) = run_select(handles, Timeout::Never, is_biased).unwrap();
     SelectedOperation {
         token,
         index,
@@ -486,8 +991,13 @@ pub fn select<'a>(
 pub fn select_timeout<'a>(
     handles: &mut [(&'a dyn SelectHandle, usize, *const u8)],
     timeout: Duration,
+    is_biased: bool,
 ) -> Result<SelectedOperation<'a>, SelectTimeoutError> {
-    select_deadline(handles, utils::convert_timeout_to_deadline(timeout))
+    select_deadline(
+        handles,
+        utils::convert_timeout_to_deadline(timeout),
+        is_biased,
+    )
 }
 
 /// Blocks until a given deadline, or until one of the operations becomes ready and selects it.
@@ -495,8 +1005,9 @@ pub fn select_timeout<'a>(
 pub(crate) fn select_deadline<'a>(
     handles: &mut [(&'a dyn SelectHandle, usize, *const u8)],
     deadline: Instant,
+    is_biased: bool,
 ) -> Result<SelectedOperation<'a>, SelectTimeoutError> {
-    match run_select(handles, Timeout::At(deadline)) {
+    match run_select(handles, Timeout::At(deadline), is_biased) {
         None => Err(SelectTimeoutError),
         Some((token, index, ptr)) => Ok(SelectedOperation {
             token,
@@ -507,51 +1018,221 @@ pub(crate) fn select_deadline<'a>(
     }
 }
 
-/// Selects from a set of channel operations.
+/// The policy used by [`Select`] to choose among operations that are ready at the same time.
 ///
-/// `Select` allows you to define a set of channel operations, wait until any one of them becomes
-/// ready, and finally execute it. If multiple operations are ready at the same time, a random one
-/// among them is selected.
+/// # Examples
 ///
-/// An operation is considered to be ready if it doesn't have to block. Note that it is ready even
-/// when it will simply return an error because the channel is disconnected.
+/// ```
+/// use crossbeam_channel::{unbounded, Fairness, Select};
 ///
-/// The [`select!`] macro is a convenience wrapper around `Select`. However, it cannot select over a
-/// dynamically created list of channel operations.
+/// let (s1, r1) = unbounded();
+/// let (s2, r2) = unbounded();
+/// for i in 0..2 {
+///     s1.send(i).unwrap();
+///     s2.send(i).unwrap();
+/// }
 ///
-/// [`select!`]: crate::select!
+/// let mut sel = Select::with_fairness(Fairness::RoundRobin);
+/// let oper1 = sel.recv(&r1);
+/// let oper2 = sel.recv(&r2);
 ///
-/// Once a list of operations has been built with `Select`, there are two different ways of
-/// proceeding:
+/// // Both operations stay ready, so they take turns.
+/// let oper = sel.select();
+/// assert_eq!(oper.index(), oper1);
+/// assert_eq!(oper.recv(&r1), Ok(0));
 ///
-/// * Select an operation with [`try_select`], [`select`], or [`select_timeout`]. If successful,
-///   the returned selected operation has already begun and **must** be completed. If we don't
-///   complete it, a panic will occur.
+/// let oper = sel.select();
+/// assert_eq!(oper.index(), oper2);
+/// assert_eq!(oper.recv(&r2), Ok(0));
 ///
-/// * Wait for an operation to become ready with [`try_ready`], [`ready`], or [`ready_timeout`]. If
-///   successful, we may attempt to execute the operation, but are not obliged to. In fact, it's
-///   possible for another thread to make the operation not ready just before we try executing it,
-///   so it's wise to use a retry loop. However, note that these methods might return with success
-///   spuriously, so it's a good idea to always double check if the operation is really ready.
+/// let oper = sel.select();
+/// assert_eq!(oper.index(), oper1);
+/// assert_eq!(oper.recv(&r1), Ok(1));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fairness {
+    /// Chooses a random operation with uniform probability.
+    ///
+    /// This is the default policy.
+    Random,
+
+    /// Chooses the operation that was added first, i.e. the one with the lowest index.
+    Biased,
+
+    /// Chooses the first operation added after the most recently selected one, wrapping around
+    /// to the beginning of the list.
+    RoundRobin,
+
+    /// Chooses a random operation with probability proportional to its weight.
+    ///
+    /// Every operation has weight 1 unless changed with [`Select::set_weight`]. Operations with
+    /// weight 0 are chosen only if no other operation is ready. If the operation drawn by weight
+    /// isn't ready, a ready one among the others with a nonzero weight is chosen uniformly.
+    Weighted,
+}
+
+impl Default for Fairness {
+    fn default() -> Fairness {
+        Fairness::Random
+    }
+}
+
+/// Statistics about the operations chosen by a [`Select`].
 ///
-/// # Examples
+/// Statistics are only recorded after [`Select::enable_stats`] has been called, and can then be
+/// retrieved with [`Select::stats`]. They help verify that a consumer of several channels isn't
+/// starving one of its inputs, e.g. because another one is always ready.
 ///
-/// Use [`select`] to receive a message from a list of receivers:
+/// Both the selection methods and the `ready` methods are counted, but `try_ready_all` and the
+/// `wait_all` methods are not.
+///
+/// # Examples
 ///
 /// ```
-/// use crossbeam_channel::{Receiver, RecvError, Select};
+/// use std::time::Duration;
+/// use crossbeam_channel::{unbounded, Select};
 ///
-/// fn recv_multiple<T>(rs: &[Receiver<T>]) -> Result<T, RecvError> {
-///     // Build a list of operations.
-///     let mut sel = Select::new();
-///     for r in rs {
-///         sel.recv(r);
-///     }
+/// let (s1, r1) = unbounded();
+/// let (_s2, r2) = unbounded::<i32>();
+/// s1.send(10).unwrap();
 ///
-///     // Complete the selected operation.
-///     let oper = sel.select();
-///     let index = oper.index();
-///     oper.recv(&rs[index])
+/// let mut sel = Select::new();
+/// let oper1 = sel.recv(&r1);
+/// let oper2 = sel.recv(&r2);
+/// sel.enable_stats();
+///
+/// let oper = sel.select();
+/// assert_eq!(oper.recv(&r1), Ok(10));
+/// assert!(sel.select_timeout(Duration::from_millis(10)).is_err());
+///
+/// let stats = sel.stats().unwrap();
+/// assert_eq!(stats.wins(oper1), 1);
+/// assert_eq!(stats.wins(oper2), 0);
+/// assert_eq!(stats.timeouts(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SelectStats {
+    /// How many times each operation was chosen, indexed by operation index.
+    wins: Vec<u64>,
+
+    /// Time spent blocking before each operation was chosen, indexed by operation index.
+    blocked: Vec<Duration>,
+
+    /// How many times no operation was chosen.
+    timeouts: u64,
+
+    /// Time spent blocking before no operation was chosen.
+    timeouts_blocked: Duration,
+}
+
+impl SelectStats {
+    /// Returns how many times the operation with the given index was chosen.
+    pub fn wins(&self, index: usize) -> u64 {
+        self.wins.get(index).cloned().unwrap_or(0)
+    }
+
+    /// Returns how long selection blocked in total before choosing the operation with the given
+    /// index.
+    ///
+    /// This is the time from the start of a selection call to the moment the operation was chosen,
+    /// summed over all calls that chose it.
+    pub fn blocked(&self, index: usize) -> Duration {
+        self.blocked.get(index).cloned().unwrap_or_default()
+    }
+
+    /// Returns how many times no operation was chosen, either because none was ready for a
+    /// non-blocking call or because a timeout elapsed.
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts
+    }
+
+    /// Returns how many times any operation was chosen.
+    pub fn total_wins(&self) -> u64 {
+        self.wins.iter().sum()
+    }
+
+    /// Returns how long selection blocked in total, including calls that timed out.
+    pub fn total_blocked(&self) -> Duration {
+        self.blocked
+            .iter()
+            .fold(self.timeouts_blocked, |total, &blocked| total + blocked)
+    }
+
+    /// Records the outcome of a selection call that started at `start`.
+    fn record(&mut self, start: Instant, index: Option<usize>) {
+        let elapsed = start.elapsed();
+        match index {
+            Some(index) => {
+                if index >= self.wins.len() {
+                    self.wins.resize(index + 1, 0);
+                    self.blocked.resize(index + 1, Duration::from_secs(0));
+                }
+                self.wins[index] += 1;
+                self.blocked[index] += elapsed;
+            }
+            None => {
+                self.timeouts += 1;
+                self.timeouts_blocked += elapsed;
+            }
+        }
+    }
+}
+
+/// Selects from a set of channel operations.
+///
+/// `Select` allows you to define a set of channel operations, wait until any one of them becomes
+/// ready, and finally execute it. If multiple operations are ready at the same time, a random one
+/// among them is selected.
+///
+/// An operation is considered to be ready if it doesn't have to block. Note that it is ready even
+/// when it will simply return an error because the channel is disconnected.
+///
+/// The [`select!`] macro is a convenience wrapper around `Select`. However, it cannot select over a
+/// dynamically created list of channel operations.
+///
+/// If the order in which ready operations are picked matters, create the list with
+/// [`new_biased`] instead of [`new`]. A biased `Select` always prefers the operation that was added
+/// first, just like the [`select_biased!`] macro. Other policies, such as round-robin or weighted
+/// selection, can be chosen with [`with_fairness`].
+///
+/// A `Select` with up to 8 operations keeps them inline and doesn't allocate, so it can be created
+/// anew on every iteration of a hot loop. Blocking may still allocate while registering with the
+/// channels.
+///
+/// [`select!`]: crate::select!
+/// [`select_biased!`]: crate::select_biased!
+///
+/// Once a list of operations has been built with `Select`, there are two different ways of
+/// proceeding:
+///
+/// * Select an operation with [`try_select`], [`select`], or [`select_timeout`]. If successful,
+///   the returned selected operation has already begun and **must** be completed. If we don't
+///   complete it, a panic will occur.
+///
+/// * Wait for an operation to become ready with [`try_ready`], [`ready`], or [`ready_timeout`]. If
+///   successful, we may attempt to execute the operation, but are not obliged to. In fact, it's
+///   possible for another thread to make the operation not ready just before we try executing it,
+///   so it's wise to use a retry loop. However, note that these methods might return with success
+///   spuriously, so it's a good idea to always double check if the operation is really ready.
+///
+/// # Examples
+///
+/// Use [`select`] to receive a message from a list of receivers:
+///
+/// ```
+/// use crossbeam_channel::{Receiver, RecvError, Select};
+///
+/// fn recv_multiple<T>(rs: &[Receiver<T>]) -> Result<T, RecvError> {
+///     // Build a list of operations.
+///     let mut sel = Select::new();
+///     for r in rs {
+///         sel.recv(r);
+///     }
+///
+///     // Complete the selected operation.
+///     let oper = sel.select();
+///     let index = oper.index();
+///     oper.recv(&rs[index])
 /// }
 /// ```
 ///
@@ -585,6 +1266,9 @@ pub(crate) fn select_deadline<'a>(
 /// }
 /// ```
 ///
+/// [`new`]: Select::new
+/// [`new_biased`]: Select::new_biased
+/// [`with_fairness`]: Select::with_fairness
 /// [`try_select`]: Select::try_select
 /// [`select`]: Select::select
 /// [`select_timeout`]: Select::select_timeout
@@ -593,10 +1277,29 @@ pub(crate) fn select_deadline<'a>(
 /// [`ready_timeout`]: Select::ready_timeout
 pub struct Select<'a> {
     /// A list of senders and receivers participating in selection.
-    handles: Vec<(&'a dyn SelectHandle, usize, *const u8)>,
+    handles: Handles<'a>,
 
     /// The next index to assign to an operation.
     next_index: usize,
+
+    /// The policy for choosing among ready operations.
+    fairness: Fairness,
+
+    /// Weights of operations, indexed by operation index.
+    ///
+    /// Operations beyond the end of the list have weight 1.
+    weights: Vec<u32>,
+
+    /// The index of the most recently selected operation.
+    last_selected: Option<usize>,
+
+    /// Deadlines of operations, indexed by operation index.
+    ///
+    /// Operations beyond the end of the list have no deadline.
+    deadlines: Vec<Option<Instant>>,
+
+    /// Statistics about chosen operations, if enabled.
+    stats: Option<SelectStats>,
 }
 
 unsafe impl Send for Select<'_> {}
@@ -617,9 +1320,116 @@ impl<'a> Select<'a> {
     /// ```
     pub fn new() -> Select<'a> {
         Select {
-            handles: Vec::with_capacity(4),
+            handles: Handles::new(),
             next_index: 0,
+            fairness: Fairness::Random,
+            weights: Vec::new(),
+            last_selected: None,
+            deadlines: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Creates an empty list of channel operations with biased selection.
+    ///
+    /// When multiple handles are ready, this will select the operation that was added first, i.e.
+    /// the one with the lowest index, instead of a random one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
+    /// s1.send(10).unwrap();
+    /// s2.send(20).unwrap();
+    ///
+    /// let mut sel = Select::new_biased();
+    /// let oper1 = sel.recv(&r1);
+    /// let oper2 = sel.recv(&r2);
+    ///
+    /// // Both operations are ready, but the first one is always preferred.
+    /// let oper = sel.select();
+    /// assert_eq!(oper.index(), oper1);
+    /// assert_eq!(oper.recv(&r1), Ok(10));
+    /// ```
+    pub fn new_biased() -> Select<'a> {
+        Select::with_fairness(Fairness::Biased)
+    }
+
+    /// Creates an empty list of channel operations with the given fairness policy.
+    ///
+    /// The policy decides which operation is selected when multiple operations are ready at the
+    /// same time. See [`Fairness`] for the available policies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Fairness, Select};
+    ///
+    /// let (s1, r1) = unbounded::<i32>();
+    /// let (s2, r2) = unbounded::<i32>();
+    ///
+    /// let mut sel = Select::with_fairness(Fairness::Weighted);
+    /// let oper1 = sel.recv(&r1);
+    /// let oper2 = sel.recv(&r2);
+    ///
+    /// // When both are ready, the first operation is chosen three times as often.
+    /// sel.set_weight(oper1, 3);
+    /// ```
+    pub fn with_fairness(fairness: Fairness) -> Select<'a> {
+        Select {
+            fairness,
+            ..Select::new()
+        }
+    }
+
+    /// Sets the weight of a previously added operation.
+    ///
+    /// Weights are only used with [`Fairness::Weighted`]. Every operation starts with weight 1.
+    ///
+    /// # Panics
+    ///
+    /// An attempt to set the weight of a non-existing or already removed operation will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Fairness, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
+    /// s1.send(10).unwrap();
+    /// s2.send(20).unwrap();
+    ///
+    /// let mut sel = Select::with_fairness(Fairness::Weighted);
+    /// let oper1 = sel.recv(&r1);
+    /// let oper2 = sel.recv(&r2);
+    ///
+    /// // An operation with weight 0 is never chosen while another one is ready.
+    /// sel.set_weight(oper1, 0);
+    ///
+    /// let oper = sel.select();
+    /// assert_eq!(oper.index(), oper2);
+    /// assert_eq!(oper.recv(&r2), Ok(20));
+    /// ```
+    pub fn set_weight(&mut self, index: usize, weight: u32) {
+        assert!(
+            index < self.next_index,
+            "index out of bounds; {} >= {}",
+            index,
+            self.next_index,
+        );
+        assert!(
+            self.handles.iter().any(|(_, i, _)| *i == index),
+            "no operation with this index"
+        );
+
+        if self.weights.len() < self.next_index {
+            self.weights.resize(self.next_index, 1);
         }
+        self.weights[index] = weight;
     }
 
     /// Adds a send operation.
@@ -666,6 +1476,26 @@ impl<'a> Select<'a> {
         i
     }
 
+    /// Adds an operation on a user-defined [`Selectable`] primitive.
+    ///
+    /// Returns the index of the added operation.
+    ///
+    /// If the operation is selected with one of the `select` methods, it must be completed with
+    /// [`SelectedOperation::custom`], after which the primitive can be used directly.
+    ///
+    /// # Examples
+    ///
+    /// See [`Selectable`] for a full example.
+    pub fn custom<S: Selectable>(&mut self, s: &'a S) -> usize {
+        let i = self.next_index;
+        let ptr = s as *const S as *const u8;
+        // `Custom<S>` is a transparent wrapper, so it has the same layout as `S`.
+        let handle = unsafe { &*(s as *const S as *const Custom<S>) };
+        self.handles.push((handle, i, ptr));
+        self.next_index += 1;
+        i
+    }
+
     /// Removes a previously added operation.
     ///
     /// This is useful when an operation is selected because the channel got disconnected and we
@@ -718,7 +1548,137 @@ impl<'a> Select<'a> {
             .expect("no operation with this index")
             .0;
 
-        self.handles.swap_remove(i);
+        match self.fairness {
+            // Preserve the order of the remaining operations.
+            Fairness::Biased | Fairness::RoundRobin => {
+                self.handles.remove(i);
+            }
+            Fairness::Random | Fairness::Weighted => {
+                self.handles.swap_remove(i);
+            }
+        }
+    }
+
+    /// Sets a deadline for a previously added operation.
+    ///
+    /// If the operation doesn't become ready before the deadline, it gets selected anyway and
+    /// reports a timeout: [`SelectedOperation::recv_timeout`] returns
+    /// [`RecvTimeoutError::Timeout`] and [`SelectedOperation::send_timeout`] returns
+    /// [`SendTimeoutError::Timeout`]. Other operations keep waiting as usual, so every operation
+    /// can have its own deadline.
+    ///
+    /// An operation whose deadline has passed stays ready, so it should usually be removed with
+    /// [`remove`] once it has timed out. The `ready` methods report the operation as ready too,
+    /// without telling the reason apart.
+    ///
+    /// # Panics
+    ///
+    /// An attempt to set the deadline of a non-existing operation will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use crossbeam_channel::{unbounded, RecvTimeoutError, Select};
+    ///
+    /// let (_s1, r1) = unbounded::<i32>();
+    /// let (_s2, r2) = unbounded::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// let oper1 = sel.recv(&r1);
+    /// let oper2 = sel.recv(&r2);
+    ///
+    /// // Give up on the first operation after 100 ms, but keep waiting for the second one.
+    /// sel.set_deadline(oper1, Instant::now() + Duration::from_millis(100));
+    ///
+    /// let oper = sel.select();
+    /// assert_eq!(oper.index(), oper1);
+    /// assert_eq!(oper.recv_timeout(&r1), Err(RecvTimeoutError::Timeout));
+    /// sel.remove(oper1);
+    ///
+    /// assert!(sel.try_select().is_err());
+    /// ```
+    ///
+    /// [`remove`]: Select::remove
+    pub fn set_deadline(&mut self, index: usize, deadline: Instant) {
+        assert!(
+            index < self.next_index,
+            "index out of bounds; {} >= {}",
+            index,
+            self.next_index,
+        );
+
+        if self.deadlines.len() < self.next_index {
+            self.deadlines.resize(self.next_index, None);
+        }
+        self.deadlines[index] = Some(deadline);
+    }
+
+    /// Sets a timeout for a previously added operation.
+    ///
+    /// This is a shorthand for [`set_deadline`] with a deadline `timeout` from now.
+    ///
+    /// # Panics
+    ///
+    /// An attempt to set the timeout of a non-existing operation will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{bounded, Select, SendTimeoutError};
+    ///
+    /// let (s, _r) = bounded(0);
+    ///
+    /// let mut sel = Select::new();
+    /// let oper = sel.send(&s);
+    /// sel.set_timeout(oper, Duration::from_millis(10));
+    ///
+    /// let oper = sel.select();
+    /// assert_eq!(oper.send_timeout(&s, 1), Err(SendTimeoutError::Timeout(1)));
+    /// ```
+    ///
+    /// [`set_deadline`]: Select::set_deadline
+    pub fn set_timeout(&mut self, index: usize, timeout: Duration) {
+        self.set_deadline(index, utils::convert_timeout_to_deadline(timeout));
+    }
+
+    /// Starts recording statistics about chosen operations.
+    ///
+    /// If statistics are already being recorded, they are reset. Recording measures the time of
+    /// every selection call, so it is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s, r) = unbounded();
+    /// s.send(10).unwrap();
+    ///
+    /// let mut sel = Select::new();
+    /// let oper = sel.recv(&r);
+    /// assert!(sel.stats().is_none());
+    ///
+    /// sel.enable_stats();
+    /// assert_eq!(sel.ready(), oper);
+    /// assert_eq!(sel.stats().unwrap().wins(oper), 1);
+    ///
+    /// // Start over.
+    /// sel.enable_stats();
+    /// assert_eq!(sel.stats().unwrap().wins(oper), 0);
+    /// ```
+    pub fn enable_stats(&mut self) {
+        self.stats = Some(SelectStats::default());
+    }
+
+    /// Returns the statistics recorded since [`enable_stats`] was last called.
+    ///
+    /// Returns `None` if statistics are not being recorded.
+    ///
+    /// [`enable_stats`]: Select::enable_stats
+    pub fn stats(&self) -> Option<&SelectStats> {
+        self.stats.as_ref()
     }
 
     /// Attempts to select one of the operations without blocking.
@@ -760,7 +1720,7 @@ impl<'a> Select<'a> {
     /// }
     /// ```
     pub fn try_select(&mut self) -> Result<SelectedOperation<'a>, TrySelectError> {
-        try_select(&mut self.handles)
+        self.run_select(Timeout::Now).ok_or(TrySelectError)
     }
 
     /// Blocks until one of the operations becomes ready and selects it.
@@ -807,7 +1767,11 @@ impl<'a> Select<'a> {
     /// }
     /// ```
     pub fn select(&mut self) -> SelectedOperation<'a> {
-        select(&mut self.handles)
+        if self.handles.is_empty() {
+            panic!("no operations have been added to `Select`");
+        }
+
+        self.run_select(Timeout::Never).unwrap()
     }
 
     /// Blocks for a limited time until one of the operations becomes ready and selects it.
@@ -857,7 +1821,7 @@ impl<'a> Select<'a> {
         &mut self,
         timeout: Duration,
     ) -> Result<SelectedOperation<'a>, SelectTimeoutError> {
-        select_timeout(&mut self.handles, timeout)
+        self.select_deadline(utils::convert_timeout_to_deadline(timeout))
     }
 
     /// Blocks until a given deadline, or until one of the operations becomes ready and selects it.
@@ -909,28 +1873,79 @@ impl<'a> Select<'a> {
         &mut self,
         deadline: Instant,
     ) -> Result<SelectedOperation<'a>, SelectTimeoutError> {
-        select_deadline(&mut self.handles, deadline)
+        self.run_select(Timeout::At(deadline))
+            .ok_or(SelectTimeoutError)
     }
 
-    /// Attempts to find a ready operation without blocking.
+    /// Returns a future that waits until one of the operations becomes ready and selects it.
     ///
-    /// If an operation is ready, its index is returned. If multiple operations are ready at the
-    /// same time, a random one among them is chosen. If none of the operations are ready, an error
-    /// is returned.
+    /// This is the async counterpart of [`select`]: instead of blocking the thread, the future
+    /// returns [`Poll::Pending`] and wakes the task once one of the operations might have become
+    /// ready. If multiple operations are ready at the same time, one among them is selected
+    /// according to the fairness policy. Deadlines set with [`set_deadline`] are honored too.
     ///
-    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
-    /// even when it will simply return an error because the channel is disconnected.
+    /// The selected operation must be completed with [`SelectedOperation::send`]
+    /// or [`SelectedOperation::recv`].
     ///
-    /// Note that this method might return with success spuriously, so it's a good idea to always
-    /// double check if the operation is really ready.
+    /// A zero-capacity channel only becomes ready while a thread is blocked on the other side, so
+    /// two tasks can't exchange messages over such a channel.
+    ///
+    /// This method is only available with the `async` feature enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to `Select`.
     ///
     /// # Examples
     ///
     /// ```
-    /// use crossbeam_channel::{unbounded, Select};
+    /// use crossbeam_channel::{Receiver, Select};
     ///
-    /// let (s1, r1) = unbounded();
-    /// let (s2, r2) = unbounded();
+    /// async fn first(r1: &Receiver<i32>, r2: &Receiver<i32>) -> i32 {
+    ///     let mut sel = Select::new();
+    ///     let oper1 = sel.recv(r1);
+    ///     let oper2 = sel.recv(r2);
+    ///
+    ///     let oper = sel.select_async().await;
+    ///     match oper.index() {
+    ///         i if i == oper1 => oper.recv(r1).unwrap(),
+    ///         i if i == oper2 => oper.recv(r2).unwrap(),
+    ///         _ => unreachable!(),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`select`]: Select::select
+    /// [`set_deadline`]: Select::set_deadline
+    /// [`Poll::Pending`]: std::task::Poll::Pending
+    #[cfg(feature = "async")]
+    pub fn select_async(&mut self) -> SelectFut<'_, 'a> {
+        if self.handles.is_empty() {
+            panic!("no operations have been added to `Select`");
+        }
+
+        SelectFut::new(self)
+    }
+
+    /// Attempts to find a ready operation without blocking.
+    ///
+    /// If an operation is ready, its index is returned. If multiple operations are ready at the
+    /// same time, a random one among them is chosen. If none of the operations are ready, an error
+    /// is returned.
+    ///
+    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
+    /// even when it will simply return an error because the channel is disconnected.
+    ///
+    /// Note that this method might return with success spuriously, so it's a good idea to always
+    /// double check if the operation is really ready.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
     ///
     /// s1.send(10).unwrap();
     /// s2.send(20).unwrap();
@@ -948,10 +1963,7 @@ impl<'a> Select<'a> {
     /// }
     /// ```
     pub fn try_ready(&mut self) -> Result<usize, TryReadyError> {
-        match run_ready(&mut self.handles, Timeout::Now) {
-            None => Err(TryReadyError),
-            Some(index) => Ok(index),
-        }
+        self.run_ready(Timeout::Now).ok_or(TryReadyError)
     }
 
     /// Blocks until one of the operations becomes ready.
@@ -1001,7 +2013,7 @@ impl<'a> Select<'a> {
             panic!("no operations have been added to `Select`");
         }
 
-        run_ready(&mut self.handles, Timeout::Never).unwrap()
+        self.run_ready(Timeout::Never).unwrap()
     }
 
     /// Blocks for a limited time until one of the operations becomes ready.
@@ -1091,11 +2103,519 @@ impl<'a> Select<'a> {
     /// }
     /// ```
     pub fn ready_deadline(&mut self, deadline: Instant) -> Result<usize, ReadyTimeoutError> {
-        match run_ready(&mut self.handles, Timeout::At(deadline)) {
-            None => Err(ReadyTimeoutError),
-            Some(index) => Ok(index),
+        self.run_ready(Timeout::At(deadline))
+            .ok_or(ReadyTimeoutError)
+    }
+
+    /// Returns a future that waits until one of the operations becomes ready.
+    ///
+    /// This is the async counterpart of [`ready`]. The future resolves to the index of a ready
+    /// operation, which the task then performs itself, for example with [`Receiver::try_recv`].
+    ///
+    /// Note that the future might resolve spuriously, so it's a good idea to always double check
+    /// if the operation is really ready.
+    ///
+    /// This method is only available with the `async` feature enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to `Select`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{Receiver, Select, TryRecvError};
+    ///
+    /// async fn drain(r1: &Receiver<i32>, r2: &Receiver<i32>) -> Vec<i32> {
+    ///     let mut sel = Select::new();
+    ///     sel.recv(r1);
+    ///     sel.recv(r2);
+    ///
+    ///     let mut msgs = Vec::new();
+    ///     loop {
+    ///         let i = sel.ready_async().await;
+    ///         let r = if i == 0 { r1 } else { r2 };
+    ///         match r.try_recv() {
+    ///             Ok(msg) => msgs.push(msg),
+    ///             Err(TryRecvError::Empty) => {}
+    ///             Err(TryRecvError::Disconnected) => return msgs,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`ready`]: Select::ready
+    #[cfg(feature = "async")]
+    pub fn ready_async(&mut self) -> ReadyFut<'_, 'a> {
+        if self.handles.is_empty() {
+            panic!("no operations have been added to `Select`");
+        }
+
+        ReadyFut::new(self)
+    }
+
+    /// Returns the indices of all operations that are ready, without blocking.
+    ///
+    /// The indices are checked in a single pass over the list of operations and returned in
+    /// ascending order. If none of the operations are ready, the returned list is empty.
+    ///
+    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
+    /// even when it will simply return an error because the channel is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (_s2, r2) = unbounded::<i32>();
+    /// let (s3, r3) = unbounded();
+    ///
+    /// s1.send(10).unwrap();
+    /// s3.send(30).unwrap();
+    ///
+    /// let mut sel = Select::new();
+    /// let oper1 = sel.recv(&r1);
+    /// let oper2 = sel.recv(&r2);
+    /// let oper3 = sel.recv(&r3);
+    ///
+    /// assert_eq!(sel.try_ready_all(), vec![oper1, oper3]);
+    /// ```
+    pub fn try_ready_all(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .handles
+            .iter()
+            .filter(|(handle, _, _)| handle.is_ready())
+            .map(|&(_, i, _)| i)
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Blocks until at least one of the operations becomes ready, then returns the indices of all
+    /// operations that are ready.
+    ///
+    /// This is useful in event loops that want to service every ready channel on each wakeup
+    /// instead of selecting once per operation. The indices are returned in ascending order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to `Select`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     s1.send(10).unwrap();
+    ///     s2.send(20).unwrap();
+    /// });
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&r1);
+    /// sel.recv(&r2);
+    ///
+    /// // Service all ready channels on each wakeup.
+    /// let mut received = 0;
+    /// while received < 2 {
+    ///     for i in sel.ready_all() {
+    ///         let r = if i == 0 { &r1 } else { &r2 };
+    ///         if r.try_recv().is_ok() {
+    ///             received += 1;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn ready_all(&mut self) -> Vec<usize> {
+        loop {
+            self.ready();
+            let indices = self.try_ready_all();
+            // The readiness might have been spurious or already consumed by another thread.
+            if !indices.is_empty() {
+                return indices;
+            }
+        }
+    }
+
+    /// Blocks for a limited time until at least one of the operations becomes ready, then returns
+    /// the indices of all operations that are ready.
+    ///
+    /// If none of the operations become ready for the specified duration, an error is returned.
+    /// The indices are returned in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// let oper = sel.recv(&r);
+    ///
+    /// assert!(sel.ready_all_timeout(Duration::from_millis(100)).is_err());
+    ///
+    /// s.send(1).unwrap();
+    /// assert_eq!(sel.ready_all_timeout(Duration::from_millis(100)), Ok(vec![oper]));
+    /// ```
+    pub fn ready_all_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Vec<usize>, ReadyTimeoutError> {
+        let deadline = utils::convert_timeout_to_deadline(timeout);
+        loop {
+            self.ready_deadline(deadline)?;
+            let indices = self.try_ready_all();
+            if !indices.is_empty() {
+                return Ok(indices);
+            }
+        }
+    }
+
+    /// Checks whether all of the operations are ready at the same time, without blocking.
+    ///
+    /// Returns an error if any of the operations is not ready. This is the counterpart of
+    /// [`try_ready`], which succeeds if any one of the operations is ready.
+    ///
+    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
+    /// even when it will simply return an error because the channel is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&r1);
+    /// sel.recv(&r2);
+    ///
+    /// s1.send(10).unwrap();
+    /// assert!(sel.try_wait_all().is_err());
+    ///
+    /// s2.send(20).unwrap();
+    /// assert_eq!(sel.try_wait_all(), Ok(()));
+    /// ```
+    ///
+    /// [`try_ready`]: Select::try_ready
+    pub fn try_wait_all(&mut self) -> Result<(), TryReadyError> {
+        if self.run_wait_all(Timeout::Now) {
+            Ok(())
+        } else {
+            Err(TryReadyError)
+        }
+    }
+
+    /// Blocks until all of the operations are ready at the same time.
+    ///
+    /// This is the counterpart of [`ready`], which waits for any one of the operations. It is
+    /// useful for barrier-style coordination, e.g. waiting until there is room in every one of
+    /// several output channels before producing a batch of messages.
+    ///
+    /// Readiness is only a snapshot: another thread may make an operation block again before it is
+    /// performed, unless the current thread is the only sender (or receiver) on that channel. The
+    /// operations have to be performed on the channels directly, e.g. with [`Sender::try_send`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{bounded, Select};
+    ///
+    /// let (s1, r1) = bounded(1);
+    /// let (s2, r2) = bounded(1);
+    /// s1.send(1).unwrap();
+    /// s2.send(2).unwrap();
+    ///
+    /// let (r1, r2) = (r1.clone(), r2.clone());
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     r1.recv().unwrap();
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     r2.recv().unwrap();
+    /// });
+    ///
+    /// let mut sel = Select::new();
+    /// sel.send(&s1);
+    /// sel.send(&s2);
+    ///
+    /// // Wait until both channels have room, then send to both of them.
+    /// sel.wait_all();
+    /// s1.try_send(3).unwrap();
+    /// s2.try_send(4).unwrap();
+    /// ```
+    ///
+    /// [`ready`]: Select::ready
+    pub fn wait_all(&mut self) {
+        self.run_wait_all(Timeout::Never);
+    }
+
+    /// Blocks for a limited time until all of the operations are ready at the same time.
+    ///
+    /// If the operations don't all become ready for the specified duration, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (_s2, r2) = unbounded::<i32>();
+    /// s1.send(10).unwrap();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&r1);
+    /// sel.recv(&r2);
+    ///
+    /// assert!(sel.wait_all_timeout(Duration::from_millis(100)).is_err());
+    /// ```
+    pub fn wait_all_timeout(&mut self, timeout: Duration) -> Result<(), ReadyTimeoutError> {
+        self.wait_all_deadline(utils::convert_timeout_to_deadline(timeout))
+    }
+
+    /// Blocks until a given deadline, or until all of the operations are ready at the same time.
+    ///
+    /// If the operations don't all become ready before the deadline, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use crossbeam_channel::{unbounded, Select};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded();
+    /// s1.send(10).unwrap();
+    /// s2.send(20).unwrap();
+    ///
+    /// let mut sel = Select::new();
+    /// sel.recv(&r1);
+    /// sel.recv(&r2);
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    /// assert_eq!(sel.wait_all_deadline(deadline), Ok(()));
+    /// ```
+    pub fn wait_all_deadline(&mut self, deadline: Instant) -> Result<(), ReadyTimeoutError> {
+        if self.run_wait_all(Timeout::At(deadline)) {
+            Ok(())
+        } else {
+            Err(ReadyTimeoutError)
+        }
+    }
+
+    /// Selects an operation and records it as the most recently selected one.
+    fn run_select(&mut self, timeout: Timeout) -> Option<SelectedOperation<'a>> {
+        let start = self.stats.as_ref().map(|_| Instant::now());
+        let res = self.arrange(|handles, is_biased| run_select(handles, timeout, is_biased));
+        self.record(start, res.as_ref().map(|&(_, index, _)| index));
+
+        let (token, index, ptr) = res?;
+        self.last_selected = Some(index);
+        Some(SelectedOperation {
+            token,
+            index,
+            ptr,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Finds a ready operation and records it as the most recently selected one.
+    fn run_ready(&mut self, timeout: Timeout) -> Option<usize> {
+        let start = self.stats.as_ref().map(|_| Instant::now());
+        let res = self.arrange(|handles, is_biased| run_ready(handles, timeout, is_biased));
+        self.record(start, res);
+
+        let index = res?;
+        self.last_selected = Some(index);
+        Some(index)
+    }
+
+    /// Selects an operation without blocking, or registers the task to be woken up if none is
+    /// ready.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_select(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        start: Option<Instant>,
+    ) -> Poll<SelectedOperation<'a>> {
+        let res = self.arrange_async(cx, |handles, is_biased| {
+            run_select(handles, Timeout::Now, is_biased)
+        });
+
+        let (token, index, ptr) = match res {
+            None => return Poll::Pending,
+            Some(res) => res,
+        };
+        self.record(start, Some(index));
+        self.last_selected = Some(index);
+        Poll::Ready(SelectedOperation {
+            token,
+            index,
+            ptr,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Finds a ready operation without blocking, or registers the task to be woken up if none is
+    /// ready.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_ready(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        start: Option<Instant>,
+    ) -> Poll<usize> {
+        let res = self.arrange_async(cx, |handles, is_biased| {
+            run_ready(handles, Timeout::Now, is_biased)
+        });
+
+        let index = match res {
+            None => return Poll::Pending,
+            Some(index) => index,
+        };
+        self.record(start, Some(index));
+        self.last_selected = Some(index);
+        Poll::Ready(index)
+    }
+
+    /// Runs `f` on the arranged list of operations, registering the task with all of them if `f`
+    /// finds nothing ready.
+    #[cfg(feature = "async")]
+    fn arrange_async<R, F>(&mut self, cx: &mut task::Context<'_>, f: F) -> Option<R>
+    where
+        F: for<'h> Fn(&mut [(&'h dyn SelectHandle, usize, *const u8)], bool) -> Option<R>,
+    {
+        self.arrange(|handles, is_biased| {
+            if let Some(res) = f(handles, is_biased) {
+                return Some(res);
+            }
+
+            // Ask to be woken up, then check again in case an operation became ready in the
+            // meantime.
+            let mut deadline: Option<Instant> = None;
+            for &(handle, _, _) in handles.iter() {
+                handle.watch_task(cx.waker());
+                if let Some(d) = handle.deadline() {
+                    deadline = Some(deadline.map_or(d, |x| x.min(d)));
+                }
+            }
+            if let Some(deadline) = deadline {
+                timer::wake_at(deadline, cx.waker());
+            }
+
+            f(handles, is_biased)
+        })
+    }
+
+    /// Records the outcome of a call that started at `start`, if statistics are enabled.
+    fn record(&mut self, start: Option<Instant>, index: Option<usize>) {
+        if let (Some(stats), Some(start)) = (self.stats.as_mut(), start) {
+            stats.record(start, index);
         }
     }
+
+    /// Waits until all operations are ready, returning `false` on timeout.
+    fn run_wait_all(&mut self, timeout: Timeout) -> bool {
+        self.arrange(|handles, _| loop {
+            // Block on the first operation that isn't ready yet, then check all of them again
+            // because the others might have stopped being ready in the meantime.
+            let i = match handles.iter().position(|(handle, _, _)| !handle.is_ready()) {
+                None => return true,
+                Some(i) => i,
+            };
+            if run_ready(&mut handles[i..=i], timeout, true).is_none() {
+                return false;
+            }
+        })
+    }
+
+    /// Arranges the list of operations according to the fairness policy and runs `f` on it.
+    ///
+    /// The second argument passed to `f` is `true` if the list must not be shuffled any further.
+    /// Operations with a deadline are wrapped so that they become ready once it passes.
+    fn arrange<R, F>(&mut self, f: F) -> R
+    where
+        F: for<'h> FnOnce(&mut [(&'h dyn SelectHandle, usize, *const u8)], bool) -> R,
+    {
+        let mut rotation = 0;
+        let is_biased = match self.fairness {
+            Fairness::Random => false,
+            Fairness::Biased => true,
+            Fairness::RoundRobin => {
+                // Operations are kept in the order they were added, so start right after the most
+                // recently selected one and restore the order afterwards.
+                if let Some(last) = self.last_selected {
+                    rotation = self
+                        .handles
+                        .iter()
+                        .position(|&(_, i, _)| i > last)
+                        .unwrap_or(0);
+                }
+                self.handles.rotate_left(rotation);
+                true
+            }
+            Fairness::Weighted => {
+                let weights = &self.weights;
+                utils::weighted_shuffle(&mut self.handles, |&(_, i, _)| {
+                    weights.get(i).cloned().unwrap_or(1)
+                });
+                true
+            }
+        };
+
+        let res = if self.deadlines.iter().any(Option::is_some) {
+            let deadlines = &self.deadlines;
+            let now = Cell::new(None);
+            let expiring = |&(handle, i, _): &Handle<'a>| Expiring {
+                handle,
+                deadline: deadlines.get(i).cloned().unwrap_or(None),
+                now: &now,
+            };
+
+            let len = self.handles.len();
+            if len <= INLINE_LEN {
+                // Wrap the operations on the stack to avoid allocating.
+                let mut wrapped = [Expiring {
+                    handle: VACANT.0,
+                    deadline: None,
+                    now: &now,
+                }; INLINE_LEN];
+                for (e, h) in wrapped.iter_mut().zip(self.handles.iter()) {
+                    *e = expiring(h);
+                }
+
+                let mut handles = [VACANT; INLINE_LEN];
+                for ((h, e), &(_, i, ptr)) in handles.iter_mut().zip(&wrapped).zip(&*self.handles) {
+                    *h = (e, i, ptr);
+                }
+                f(&mut handles[..len], is_biased)
+            } else {
+                let wrapped: Vec<Expiring<'_>> = self.handles.iter().map(expiring).collect();
+                let mut handles: Vec<(&dyn SelectHandle, usize, *const u8)> = wrapped
+                    .iter()
+                    .zip(self.handles.iter())
+                    .map(|(e, &(_, i, ptr))| (e as &dyn SelectHandle, i, ptr))
+                    .collect();
+                f(&mut handles, is_biased)
+            }
+        } else {
+            f(&mut self.handles, is_biased)
+        };
+
+        self.handles.rotate_right(rotation);
+        res
+    }
 }
 
 impl<'a> Clone for Select<'a> {
@@ -1103,6 +2623,11 @@ impl<'a> Clone for Select<'a> {
         Select {
             handles: self.handles.clone(),
             next_index: self.next_index,
+            fairness: self.fairness,
+            weights: self.weights.clone(),
+            last_selected: self.last_selected,
+            deadlines: self.deadlines.clone(),
+            stats: self.stats.clone(),
         }
     }
 }
@@ -1123,6 +2648,10 @@ impl fmt::Debug for Select<'_> {
 ///
 /// To complete the operation, call [`send`] or [`recv`].
 ///
+/// Selecting a receive operation claims the message it will receive, and timeouts and failed
+/// selections never claim anything. So a message is either still in the channel or about to be
+/// returned by [`recv`], and none is lost when a selection times out or its future is dropped.
+///
 /// # Panics
 ///
 /// Forgetting to complete the operation is an error and might lead to deadlocks. If a
@@ -1181,7 +2710,8 @@ impl SelectedOperation<'_> {
     ///
     /// # Panics
     ///
-    /// Panics if an incorrect [`Sender`] reference is passed.
+    /// Panics if an incorrect [`Sender`] reference is passed, or if the operation timed out. Use
+    /// [`send_timeout`] to complete operations that have a deadline.
     ///
     /// # Examples
     ///
@@ -1198,11 +2728,17 @@ impl SelectedOperation<'_> {
     /// assert_eq!(oper.index(), oper1);
     /// assert_eq!(oper.send(&s, 10), Err(SendError(10)));
     /// ```
+    ///
+    /// [`send_timeout`]: SelectedOperation::send_timeout
     pub fn send<T>(mut self, s: &Sender<T>, msg: T) -> Result<(), SendError<T>> {
         assert!(
             s as *const Sender<T> as *const u8 == self.ptr,
             "passed a sender that wasn't selected",
         );
+        if self.token.expired {
+            mem::forget(self);
+            panic!("the selected operation timed out; complete it with `send_timeout`");
+        }
         let res = unsafe { channel::write(s, &mut self.token, msg) };
         mem::forget(self);
         res.map_err(SendError)
@@ -1215,7 +2751,8 @@ impl SelectedOperation<'_> {
     ///
     /// # Panics
     ///
-    /// Panics if an incorrect [`Receiver`] reference is passed.
+    /// Panics if an incorrect [`Receiver`] reference is passed, or if the operation timed out. Use
+    /// [`recv_timeout`] to complete operations that have a deadline.
     ///
     /// # Examples
     ///
@@ -1232,15 +2769,126 @@ impl SelectedOperation<'_> {
     /// assert_eq!(oper.index(), oper1);
     /// assert_eq!(oper.recv(&r), Err(RecvError));
     /// ```
+    ///
+    /// [`recv_timeout`]: SelectedOperation::recv_timeout
     pub fn recv<T>(mut self, r: &Receiver<T>) -> Result<T, RecvError> {
         assert!(
             r as *const Receiver<T> as *const u8 == self.ptr,
             "passed a receiver that wasn't selected",
         );
+        if self.token.expired {
+            mem::forget(self);
+            panic!("the selected operation timed out; complete it with `recv_timeout`");
+        }
         let res = unsafe { channel::read(r, &mut self.token) };
         mem::forget(self);
         res.map_err(|_| RecvError)
     }
+
+    /// Completes the send operation, reporting a timeout if its deadline has passed.
+    ///
+    /// The passed [`Sender`] reference must be the same one that was used in [`Select::send`]
+    /// when the operation was added. If the operation was selected because its deadline set with
+    /// [`Select::set_deadline`] has passed, the message is returned in
+    /// [`SendTimeoutError::Timeout`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if an incorrect [`Sender`] reference is passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{bounded, Select, SendTimeoutError};
+    ///
+    /// let (s, r) = bounded(1);
+    ///
+    /// let mut sel = Select::new();
+    /// let oper1 = sel.send(&s);
+    /// sel.set_timeout(oper1, Duration::from_millis(10));
+    ///
+    /// let oper = sel.select();
+    /// assert_eq!(oper.send_timeout(&s, 1), Ok(()));
+    ///
+    /// // The channel is now full, so the operation times out.
+    /// let oper = sel.select();
+    /// assert_eq!(oper.send_timeout(&s, 2), Err(SendTimeoutError::Timeout(2)));
+    /// assert_eq!(r.try_recv(), Ok(1));
+    /// ```
+    pub fn send_timeout<T>(mut self, s: &Sender<T>, msg: T) -> Result<(), SendTimeoutError<T>> {
+        assert!(
+            s as *const Sender<T> as *const u8 == self.ptr,
+            "passed a sender that wasn't selected",
+        );
+        if self.token.expired {
+            mem::forget(self);
+            return Err(SendTimeoutError::Timeout(msg));
+        }
+        let res = unsafe { channel::write(s, &mut self.token, msg) };
+        mem::forget(self);
+        res.map_err(SendTimeoutError::Disconnected)
+    }
+
+    /// Completes the receive operation, reporting a timeout if its deadline has passed.
+    ///
+    /// The passed [`Receiver`] reference must be the same one that was used in [`Select::recv`]
+    /// when the operation was added. If the operation was selected because its deadline set with
+    /// [`Select::set_deadline`] has passed, [`RecvTimeoutError::Timeout`] is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an incorrect [`Receiver`] reference is passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, RecvTimeoutError, Select};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut sel = Select::new();
+    /// let oper1 = sel.recv(&r);
+    /// sel.set_timeout(oper1, Duration::from_millis(10));
+    ///
+    /// let oper = sel.select();
+    /// assert_eq!(oper.recv_timeout(&r), Err(RecvTimeoutError::Timeout));
+    /// ```
+    pub fn recv_timeout<T>(mut self, r: &Receiver<T>) -> Result<T, RecvTimeoutError> {
+        assert!(
+            r as *const Receiver<T> as *const u8 == self.ptr,
+            "passed a receiver that wasn't selected",
+        );
+        if self.token.expired {
+            mem::forget(self);
+            return Err(RecvTimeoutError::Timeout);
+        }
+        let res = unsafe { channel::read(r, &mut self.token) };
+        mem::forget(self);
+        res.map_err(|_| RecvTimeoutError::Disconnected)
+    }
+
+    /// Completes an operation on a user-defined [`Selectable`] primitive.
+    ///
+    /// The passed reference must be the same one that was used in [`Select::custom`] when the
+    /// operation was added. This method does nothing with the primitive; it only marks the
+    /// selected operation as completed, after which the primitive can be used directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an incorrect reference is passed.
+    ///
+    /// # Examples
+    ///
+    /// See [`Selectable`] for a full example.
+    pub fn custom<S: Selectable>(self, s: &S) {
+        assert!(
+            s as *const S as *const u8 == self.ptr,
+            "passed a primitive that wasn't selected",
+        );
+        mem::forget(self);
+    }
 }
 
 impl fmt::Debug for SelectedOperation<'_> {
@@ -15,8 +15,15 @@
 /// 1. `@init`: Attempts to optimize `select!` away and initializes the list of handles.
 /// 1. `@count`: Counts the listed cases.
 /// 3. `@add`: Adds send/receive operations to the list of handles and starts selection.
+///    Operations whose guard evaluates to `false` are left out.
 /// 4. `@complete`: Completes the selected send/receive operation.
 ///
+/// The `@loop` stage turns the cases of `select_loop!` into `select!` cases guarded by whether
+/// their channels are still connected.
+///
+/// The entry points take an optional `@biased` flag, which is passed through every stage until
+/// selection starts. Without it, operations are selected fairly.
+///
 /// If the parsing stage encounters a syntax error or the codegen stage ends up with too many
 /// cases to process, the macro fails with a compile-time error.
 #[doc(hidden)]
@@ -24,11 +31,13 @@
 macro_rules! crossbeam_channel_internal {
     // The list is empty. Now check the arguments of each processed case.
     (@list
+        $biased:tt
         ()
         ($($head:tt)*)
     ) => {
         $crate::crossbeam_channel_internal!(
             @case
+            $biased
             ($($head)*)
             ()
             ()
@@ -36,17 +45,20 @@ macro_rules! crossbeam_channel_internal {
     };
     // If necessary, insert an empty argument list after `default`.
     (@list
+        $biased:tt
         (default => $($tail:tt)*)
         ($($head:tt)*)
     ) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             (default() => $($tail)*)
             ($($head)*)
         )
     };
     // But print an error if `default` is followed by a `->`.
     (@list
+        $biased:tt
         (default -> $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -56,6 +68,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there's an `->` after the argument list in the default case.
     (@list
+        $biased:tt
         (default $args:tt -> $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -65,6 +78,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is a missing result in a recv case.
     (@list
+        $biased:tt
         (recv($($args:tt)*) => $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -74,6 +88,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is a missing result in a send case.
     (@list
+        $biased:tt
         (send($($args:tt)*) => $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -83,6 +98,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Make sure the arrow and the result are not repeated.
     (@list
+        $biased:tt
         ($case:ident $args:tt -> $res:tt -> $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -90,6 +106,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is a semicolon after the block.
     (@list
+        $biased:tt
         ($case:ident $args:tt $(-> $res:pat)* => $body:block; $($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -99,39 +116,46 @@ macro_rules! crossbeam_channel_internal {
     };
     // The first case is separated by a comma.
     (@list
-        ($case:ident ($($args:tt)*) $(-> $res:pat)* => $body:expr, $($tail:tt)*)
+        $biased:tt
+        ($case:ident ($($args:tt)*) $(-> $res:pat)* $(, if $guard:expr)? => $body:expr, $($tail:tt)*)
         ($($head:tt)*)
     ) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             ($($tail)*)
-            ($($head)* $case ($($args)*) $(-> $res)* => { $body },)
+            ($($head)* $case ($($args)*) $(-> $res)* $(, if $guard)? => { $body },)
         )
     };
     // Don't require a comma after the case if it has a proper block.
     (@list
-        ($case:ident ($($args:tt)*) $(-> $res:pat)* => $body:block $($tail:tt)*)
+        $biased:tt
+        ($case:ident ($($args:tt)*) $(-> $res:pat)* $(, if $guard:expr)? => $body:block $($tail:tt)*)
         ($($head:tt)*)
     ) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             ($($tail)*)
-            ($($head)* $case ($($args)*) $(-> $res)* => { $body },)
+            ($($head)* $case ($($args)*) $(-> $res)* $(, if $guard)? => { $body },)
         )
     };
     // Only one case remains.
     (@list
-        ($case:ident ($($args:tt)*) $(-> $res:pat)* => $body:expr $(,)?)
+        $biased:tt
+        ($case:ident ($($args:tt)*) $(-> $res:pat)* $(, if $guard:expr)? => $body:expr $(,)?)
         ($($head:tt)*)
     ) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             ()
-            ($($head)* $case ($($args)*) $(-> $res)* => { $body },)
+            ($($head)* $case ($($args)*) $(-> $res)* $(, if $guard)? => { $body },)
         )
     };
     // Diagnose and print an error.
     (@list
+        $biased:tt
         ($($tail:tt)*)
         ($($head:tt)*)
     ) => {
@@ -349,12 +373,14 @@ macro_rules! crossbeam_channel_internal {
 
     // Success! All cases were parsed.
     (@case
+        $biased:tt
         ()
         $cases:tt
         $default:tt
     ) => {
         $crate::crossbeam_channel_internal!(
             @init
+            $biased
             $cases
             $default
         )
@@ -362,20 +388,23 @@ macro_rules! crossbeam_channel_internal {
 
     // Check the format of a recv case.
     (@case
-        (recv($r:expr $(,)?) -> $res:pat => $body:tt, $($tail:tt)*)
+        $biased:tt
+        (recv($r:expr $(,)?) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
     ) => {
         $crate::crossbeam_channel_internal!(
             @case
+            $biased
             ($($tail)*)
-            ($($cases)* recv($r) -> $res => $body,)
+            ($($cases)* recv($r) -> $res $(, if $guard)? => $body,)
             $default
         )
     };
     // Print an error if the argument list is invalid.
     (@case
-        (recv($($args:tt)*) -> $res:pat => $body:tt, $($tail:tt)*)
+        $biased:tt
+        (recv($($args:tt)*) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
     ) => {
@@ -389,6 +418,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is no argument list.
     (@case
+        $biased:tt
         (recv $t:tt $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
@@ -404,20 +434,23 @@ macro_rules! crossbeam_channel_internal {
 
     // Check the format of a send case.
     (@case
-        (send($s:expr, $m:expr $(,)?) -> $res:pat => $body:tt, $($tail:tt)*)
+        $biased:tt
+        (send($s:expr, $m:expr $(,)?) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
     ) => {
         $crate::crossbeam_channel_internal!(
             @case
+            $biased
             ($($tail)*)
-            ($($cases)* send($s, $m) -> $res => $body,)
+            ($($cases)* send($s, $m) -> $res $(, if $guard)? => $body,)
             $default
         )
     };
     // Print an error if the argument list is invalid.
     (@case
-        (send($($args:tt)*) -> $res:pat => $body:tt, $($tail:tt)*)
+        $biased:tt
+        (send($($args:tt)*) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
     ) => {
@@ -431,6 +464,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is no argument list.
     (@case
+        $biased:tt
         (send $t:tt $($tail:tt)*)
         ($($cases:tt)*)
         $default:tt
@@ -444,14 +478,27 @@ macro_rules! crossbeam_channel_internal {
         )
     };
 
+    // Print an error if the default case has a guard.
+    (@case
+        $biased:tt
+        (default $args:tt, if $guard:expr => $body:tt, $($tail:tt)*)
+        $cases:tt
+        $default:tt
+    ) => {
+        compile_error!(
+            "`default` case cannot have a guard"
+        )
+    };
     // Check the format of a default case.
     (@case
+        $biased:tt
         (default() => $body:tt, $($tail:tt)*)
         $cases:tt
         ()
     ) => {
         $crate::crossbeam_channel_internal!(
             @case
+            $biased
             ($($tail)*)
             $cases
             (default() => $body,)
@@ -459,12 +506,14 @@ macro_rules! crossbeam_channel_internal {
     };
     // Check the format of a default case with timeout.
     (@case
+        $biased:tt
         (default($timeout:expr $(,)?) => $body:tt, $($tail:tt)*)
         $cases:tt
         ()
     ) => {
         $crate::crossbeam_channel_internal!(
             @case
+            $biased
             ($($tail)*)
             $cases
             (default($timeout) => $body,)
@@ -472,6 +521,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Check for duplicate default cases...
     (@case
+        $biased:tt
         (default $($tail:tt)*)
         $cases:tt
         ($($def:tt)+)
@@ -482,6 +532,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if the argument list is invalid.
     (@case
+        $biased:tt
         (default($($args:tt)*) => $body:tt, $($tail:tt)*)
         $cases:tt
         $default:tt
@@ -496,6 +547,7 @@ macro_rules! crossbeam_channel_internal {
     };
     // Print an error if there is an unexpected token after `default`.
     (@case
+        $biased:tt
         (default $t:tt $($tail:tt)*)
         $cases:tt
         $default:tt
@@ -511,6 +563,7 @@ macro_rules! crossbeam_channel_internal {
 
     // The case was not consumed, therefore it must be invalid.
     (@case
+        $biased:tt
         ($case:ident $($tail:tt)*)
         $cases:tt
         $default:tt
@@ -526,6 +579,7 @@ macro_rules! crossbeam_channel_internal {
 
     // Optimize `select!` into `try_recv()`.
     (@init
+        $biased:tt
         (recv($r:expr) -> $res:pat => $recv_body:tt,)
         (default() => $default_body:tt,)
     ) => {{
@@ -547,6 +601,7 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Optimize `select!` into `recv()`.
     (@init
+        $biased:tt
         (recv($r:expr) -> $res:pat => $body:tt,)
         ()
     ) => {{
@@ -561,6 +616,7 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Optimize `select!` into `recv_timeout()`.
     (@init
+        $biased:tt
         (recv($r:expr) -> $res:pat => $recv_body:tt,)
         (default($timeout:expr) => $default_body:tt,)
     ) => {{
@@ -642,6 +698,27 @@ macro_rules! crossbeam_channel_internal {
     //     }
     // }};
 
+    // Optimize `select!` into `send()`.
+    //
+    // The only operation always gets selected, so the message is evaluated either way.
+    (@init
+        $biased:tt
+        (send($s:expr, $m:expr) -> $res:pat => $body:tt,)
+        ()
+    ) => {{
+        match $s {
+            ref _s => {
+                let _s: &$crate::Sender<_> = _s;
+                let _res = _s.send($m);
+                let $res = _res;
+                $body
+            }
+        }
+    }};
+
+    // The message must not be evaluated if the `default` case runs, so the following two
+    // optimizations can't simply call `try_send()` and `send_timeout()`.
+    //
     // // Optimize `select!` into `try_send()`.
     // (@init
     //     (send($s:expr, $m:expr) -> $res:pat => $send_body:tt,)
@@ -654,18 +731,6 @@ macro_rules! crossbeam_channel_internal {
     //         }
     //     }
     // }};
-    // // Optimize `select!` into `send()`.
-    // (@init
-    //     (send($s:expr, $m:expr) -> $res:pat => $body:tt,)
-    //     ()
-    // ) => {{
-    //     match $s {
-    //         ref _s => {
-    //             let _s: &$crate::Sender<_> = _s;
-    //             // TODO(stjepang): Implement this optimization.
-    //         }
-    //     }
-    // }};
     // // Optimize `select!` into `send_timeout()`.
     // (@init
     //     (send($s:expr, $m:expr) -> $res:pat => $body:tt,)
@@ -681,6 +746,7 @@ macro_rules! crossbeam_channel_internal {
 
     // Create the list of handles and add operations to it.
     (@init
+        $biased:tt
         ($($cases:tt)*)
         $default:tt
     ) => {{
@@ -692,6 +758,7 @@ macro_rules! crossbeam_channel_internal {
 
         $crate::crossbeam_channel_internal!(
             @add
+            $biased
             _sel
             ($($cases)*)
             $default
@@ -737,12 +804,13 @@ macro_rules! crossbeam_channel_internal {
     (@count ()) => {
         0
     };
-    (@count ($oper:ident $args:tt -> $res:pat => $body:tt, $($cases:tt)*)) => {
+    (@count ($oper:ident $args:tt -> $res:pat $(, if $guard:expr)? => $body:tt, $($cases:tt)*)) => {
         1 + $crate::crossbeam_channel_internal!(@count ($($cases)*))
     };
 
     // Run blocking selection.
     (@add
+        $biased:tt
         $sel:ident
         ()
         ()
@@ -750,7 +818,7 @@ macro_rules! crossbeam_channel_internal {
         $cases:tt
     ) => {{
         let _oper: $crate::SelectedOperation<'_> = {
-            let _oper = $crate::internal::select(&mut $sel);
+            let _oper = $crate::internal::select(&mut $sel, $biased);
 
             // Erase the lifetime so that `sel` can be dropped early even without NLL.
             unsafe { ::std::mem::transmute(_oper) }
@@ -765,6 +833,7 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Run non-blocking selection.
     (@add
+        $biased:tt
         $sel:ident
         ()
         (default() => $body:tt,)
@@ -772,7 +841,7 @@ macro_rules! crossbeam_channel_internal {
         $cases:tt
     ) => {{
         let _oper: ::std::option::Option<$crate::SelectedOperation<'_>> = {
-            let _oper = $crate::internal::try_select(&mut $sel);
+            let _oper = $crate::internal::try_select(&mut $sel, $biased);
 
             // Erase the lifetime so that `sel` can be dropped early even without NLL.
             unsafe { ::std::mem::transmute(_oper) }
@@ -795,6 +864,7 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Run selection with a timeout.
     (@add
+        $biased:tt
         $sel:ident
         ()
         (default($timeout:expr) => $body:tt,)
@@ -802,7 +872,7 @@ macro_rules! crossbeam_channel_internal {
         $cases:tt
     ) => {{
         let _oper: ::std::option::Option<$crate::SelectedOperation<'_>> = {
-            let _oper = $crate::internal::select_timeout(&mut $sel, $timeout);
+            let _oper = $crate::internal::select_timeout(&mut $sel, $timeout, $biased);
 
             // Erase the lifetime so that `sel` can be dropped early even without NLL.
             unsafe { ::std::mem::transmute(_oper) }
@@ -825,6 +895,7 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Have we used up all labels?
     (@add
+        $biased:tt
         $sel:ident
         $input:tt
         $default:tt
@@ -835,8 +906,9 @@ macro_rules! crossbeam_channel_internal {
     };
     // Add a receive operation to `sel`.
     (@add
+        $biased:tt
         $sel:ident
-        (recv($r:expr) -> $res:pat => $body:tt, $($tail:tt)*)
+        (recv($r:expr) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         $default:tt
         (($i:tt $var:ident) $($labels:tt)*)
         ($($cases:tt)*)
@@ -852,10 +924,14 @@ macro_rules! crossbeam_channel_internal {
                     }
                     unbind(_r)
                 };
-                $sel[$i] = ($var, $i, $var as *const $crate::Receiver<_> as *const u8);
+                // If the guard is false, the placeholder stays in the list and is never ready.
+                if $crate::crossbeam_channel_internal!(@guard $($guard)?) {
+                    $sel[$i] = ($var, $i, $var as *const $crate::Receiver<_> as *const u8);
+                }
 
                 $crate::crossbeam_channel_internal!(
                     @add
+                    $biased
                     $sel
                     ($($tail)*)
                     $default
@@ -867,8 +943,9 @@ macro_rules! crossbeam_channel_internal {
     }};
     // Add a send operation to `sel`.
     (@add
+        $biased:tt
         $sel:ident
-        (send($s:expr, $m:expr) -> $res:pat => $body:tt, $($tail:tt)*)
+        (send($s:expr, $m:expr) -> $res:pat $(, if $guard:expr)? => $body:tt, $($tail:tt)*)
         $default:tt
         (($i:tt $var:ident) $($labels:tt)*)
         ($($cases:tt)*)
@@ -884,10 +961,14 @@ macro_rules! crossbeam_channel_internal {
                     }
                     unbind(_s)
                 };
-                $sel[$i] = ($var, $i, $var as *const $crate::Sender<_> as *const u8);
+                // If the guard is false, the placeholder stays in the list and is never ready.
+                if $crate::crossbeam_channel_internal!(@guard $($guard)?) {
+                    $sel[$i] = ($var, $i, $var as *const $crate::Sender<_> as *const u8);
+                }
 
                 $crate::crossbeam_channel_internal!(
                     @add
+                    $biased
                     $sel
                     ($($tail)*)
                     $default
@@ -898,6 +979,14 @@ macro_rules! crossbeam_channel_internal {
         }
     }};
 
+    // Evaluate the guard of a case, if there is one.
+    (@guard) => {
+        true
+    };
+    (@guard $guard:expr) => {
+        $guard
+    };
+
     // Complete a receive operation.
     (@complete
         $sel:ident
@@ -951,34 +1040,157 @@ macro_rules! crossbeam_channel_internal {
         )
     }};
 
-    // Catches a bug within this macro (should not happen).
-    (@$($tokens:tt)*) => {
+    // The loop is empty.
+    (@loop $alive:ident () () (0usize)) => {
+        compile_error!("empty `select_loop!` block")
+    };
+    // All cases are parsed. Keep selecting while any of them is alive.
+    (@loop $alive:ident () ($($cases:tt)*) ($($len:tt)*)) => {{
+        let mut $alive = [true; $($len)*];
+
+        while $alive.iter().any(|alive| *alive) {
+            $crate::select! { $($cases)* }
+        }
+    }};
+    // The case is followed by a comma.
+    (@loop
+        $alive:ident
+        (recv($r:expr $(,)?) -> $res:pat => $body:expr, $($tail:tt)*)
+        ($($cases:tt)*)
+        ($($i:tt)*)
+    ) => {
+        $crate::crossbeam_channel_internal!(
+            @loop
+            $alive
+            ($($tail)*)
+            ($($cases)* recv($r) -> _res, if $alive[$($i)*] => match _res {
+                ::std::result::Result::Ok($res) => { $body }
+                ::std::result::Result::Err(_) => $alive[$($i)*] = false,
+            },)
+            ($($i)* + 1)
+        )
+    };
+    // Don't require a comma after the case if it has a proper block.
+    (@loop
+        $alive:ident
+        (recv($r:expr $(,)?) -> $res:pat => $body:block $($tail:tt)*)
+        ($($cases:tt)*)
+        ($($i:tt)*)
+    ) => {
+        $crate::crossbeam_channel_internal!(
+            @loop
+            $alive
+            ($($tail)*)
+            ($($cases)* recv($r) -> _res, if $alive[$($i)*] => match _res {
+                ::std::result::Result::Ok($res) => $body
+                ::std::result::Result::Err(_) => $alive[$($i)*] = false,
+            },)
+            ($($i)* + 1)
+        )
+    };
+    // The send case is followed by a comma.
+    (@loop
+        $alive:ident
+        (send($s:expr, $m:expr $(,)?) => $body:expr, $($tail:tt)*)
+        ($($cases:tt)*)
+        ($($i:tt)*)
+    ) => {
+        $crate::crossbeam_channel_internal!(
+            @loop
+            $alive
+            ($($tail)*)
+            ($($cases)* send($s, $m) -> _res, if $alive[$($i)*] => match _res {
+                ::std::result::Result::Ok(()) => { $body }
+                ::std::result::Result::Err(_) => $alive[$($i)*] = false,
+            },)
+            ($($i)* + 1)
+        )
+    };
+    // Don't require a comma after the send case if it has a proper block.
+    (@loop
+        $alive:ident
+        (send($s:expr, $m:expr $(,)?) => $body:block $($tail:tt)*)
+        ($($cases:tt)*)
+        ($($i:tt)*)
+    ) => {
+        $crate::crossbeam_channel_internal!(
+            @loop
+            $alive
+            ($($tail)*)
+            ($($cases)* send($s, $m) -> _res, if $alive[$($i)*] => match _res {
+                ::std::result::Result::Ok(()) => $body
+                ::std::result::Result::Err(_) => $alive[$($i)*] = false,
+            },)
+            ($($i)* + 1)
+        )
+    };
+    // Only one case remains.
+    (@loop
+        $alive:ident
+        (recv($r:expr $(,)?) -> $res:pat => $body:expr)
+        $cases:tt
+        $i:tt
+    ) => {
+        $crate::crossbeam_channel_internal!(@loop $alive (recv($r) -> $res => $body,) $cases $i)
+    };
+    (@loop
+        $alive:ident
+        (send($s:expr, $m:expr $(,)?) => $body:expr)
+        $cases:tt
+        $i:tt
+    ) => {
+        $crate::crossbeam_channel_internal!(@loop $alive (send($s, $m) => $body,) $cases $i)
+    };
+    // Print an error for any other kind of case.
+    (@loop $alive:ident ($($tail:tt)*) $cases:tt $i:tt) => {
         compile_error!(
             concat!(
-                "internal error in crossbeam-channel: ",
-                stringify!(@$($tokens)*),
+                "expected a case of the form `recv(r) -> msg => body` or `send(s, msg) => body` ",
+                "in `select_loop!`, found `",
+                stringify!($($tail)*),
+                "`",
             )
         )
     };
 
-    // The entry points.
-    () => {
+    // The entry points. `@biased` tells whether operations listed first are preferred.
+    (@biased $biased:tt) => {
         compile_error!("empty `select!` block")
     };
-    ($($case:ident $(($($args:tt)*))* => $body:expr $(,)*)*) => {
+    (@biased $biased:tt $($case:ident $(($($args:tt)*))* => $body:expr $(,)*)*) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             ($($case $(($($args)*))* => { $body },)*)
             ()
         )
     };
-    ($($tokens:tt)*) => {
+    (@biased $biased:tt $($tokens:tt)*) => {
         $crate::crossbeam_channel_internal!(
             @list
+            $biased
             ($($tokens)*)
             ()
         )
     };
+
+    // Catches a bug within this macro (should not happen).
+    (@$($tokens:tt)*) => {
+        compile_error!(
+            concat!(
+                "internal error in crossbeam-channel: ",
+                stringify!(@$($tokens)*),
+            )
+        )
+    };
+
+    // Without `@biased`, operations are selected fairly.
+    ($($tokens:tt)*) => {
+        $crate::crossbeam_channel_internal!(
+            @biased false
+            $($tokens)*
+        )
+    };
 }
 
 /// Selects from a set of channel operations.
@@ -993,6 +1205,12 @@ macro_rules! crossbeam_channel_internal {
 /// An operation is considered to be ready if it doesn't have to block. Note that it is ready even
 /// when it will simply return an error because the channel is disconnected.
 ///
+/// A `send` or `recv` case can be followed by a guard, as in `recv(r) -> msg, if cond => ...`. If
+/// the guard evaluates to `false`, the operation is left out of this selection as if it was never
+/// ready. The channel expression of the case is still evaluated, but the message of a `send` case
+/// is only evaluated if the case gets selected. If all guards are `false` and there is no `default`
+/// case, the macro blocks forever.
+///
 /// The `select!` macro is a convenience wrapper around [`Select`]. However, it cannot select over a
 /// dynamically created list of channel operations.
 ///
@@ -1073,6 +1291,28 @@ macro_rules! crossbeam_channel_internal {
 /// }
 /// ```
 ///
+/// Skip operations whose guards are `false`:
+///
+/// ```
+/// use crossbeam_channel::{select, unbounded};
+///
+/// let (s, r) = unbounded();
+/// let (out_s, out_r) = unbounded();
+/// s.send(10).unwrap();
+///
+/// let mut queue = vec![1, 2, 3];
+/// let have_capacity = false;
+///
+/// // The message is ready, but only the send operation is allowed.
+/// select! {
+///     recv(r) -> msg, if have_capacity => panic!(),
+///     send(out_s, queue.pop().unwrap()) -> res, if !queue.is_empty() => res.unwrap(),
+/// }
+///
+/// assert_eq!(out_r.try_recv(), Ok(3));
+/// assert_eq!(r.try_recv(), Ok(10));
+/// ```
+///
 /// Optionally add a receive operation to `select!` using [`never`]:
 ///
 /// ```
@@ -1110,7 +1350,221 @@ macro_rules! crossbeam_channel_internal {
 macro_rules! select {
     ($($tokens:tt)*) => {
         $crate::crossbeam_channel_internal!(
+            @biased false
             $($tokens)*
         )
     };
 }
+
+/// Selects from a set of channel operations, preferring the operations listed first.
+///
+/// This macro is the same as [`select!`], except that if multiple operations are ready at the same
+/// time, the one that appears first in the list is selected instead of a random one.
+///
+/// This is useful when some operations must take priority over others, for example when a
+/// shutdown signal should be handled before any further messages are processed. Note that biased
+/// selection can starve the operations listed last if the first ones are always ready.
+///
+/// [`select!`]: crate::select!
+///
+/// # Examples
+///
+/// Always handle the shutdown signal before processing more messages:
+///
+/// ```
+/// use crossbeam_channel::{select_biased, unbounded};
+///
+/// let (shutdown_s, shutdown_r) = unbounded();
+/// let (data_s, data_r) = unbounded();
+///
+/// data_s.send(1).unwrap();
+/// shutdown_s.send(()).unwrap();
+///
+/// // Both operations are ready, but the first one takes priority.
+/// select_biased! {
+///     recv(shutdown_r) -> _ => println!("shutting down"),
+///     recv(data_r) -> msg => panic!("unexpected message: {:?}", msg),
+/// }
+/// ```
+#[macro_export]
+macro_rules! select_biased {
+    ($($tokens:tt)*) => {
+        $crate::crossbeam_channel_internal!(
+            @biased true
+            $($tokens)*
+        )
+    };
+}
+
+/// Repeatedly receives from and sends into a set of channels until all of them are disconnected.
+///
+/// This macro is a loop around [`select!`] with `recv` and `send` cases. A case is dropped from
+/// the loop as soon as its channel is disconnected, so that it doesn't keep getting selected just
+/// to report the disconnection. Once no cases remain, the loop ends.
+///
+/// A `recv(r) -> msg => body` case binds the received message itself rather than a `Result`,
+/// and is dropped once its channel is empty and disconnected. A `send(s, msg) => body` case runs
+/// its body after the message is sent, and is dropped once its channel is disconnected. The
+/// message expression is only evaluated when the case is selected, and the message is dropped if
+/// the channel turns out to be disconnected.
+///
+/// The bodies can use `break` and `continue` just like in an ordinary loop. The channel
+/// expressions are evaluated on every iteration. Note that receivers which never disconnect, such
+/// as [`never`] and [`tick`], keep the loop running until it is stopped with `break`.
+///
+/// [`select!`]: crate::select!
+/// [`never`]: super::never
+/// [`tick`]: super::tick
+///
+/// # Examples
+///
+/// Merge messages from two channels until both are closed:
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::{select_loop, unbounded};
+///
+/// let (s1, r1) = unbounded();
+/// let (s2, r2) = unbounded();
+///
+/// thread::spawn(move || {
+///     for i in 0..3 {
+///         s1.send(i).unwrap();
+///     }
+/// });
+/// thread::spawn(move || s2.send("done").unwrap());
+///
+/// let mut sum = 0;
+/// let mut words = Vec::new();
+///
+/// select_loop! {
+///     recv(r1) -> n => sum += n,
+///     recv(r2) -> word => words.push(word),
+/// }
+///
+/// assert_eq!(sum, 3);
+/// assert_eq!(words, ["done"]);
+/// ```
+///
+/// Stop early with `break`:
+///
+/// ```
+/// use crossbeam_channel::{select_loop, unbounded};
+///
+/// let (s, r) = unbounded();
+/// for i in 0..10 {
+///     s.send(i).unwrap();
+/// }
+///
+/// let mut received = Vec::new();
+///
+/// select_loop! {
+///     recv(r) -> n => {
+///         if n == 3 {
+///             break;
+///         }
+///         received.push(n);
+///     }
+/// }
+///
+/// assert_eq!(received, [0, 1, 2]);
+/// ```
+///
+/// Hand out jobs to two workers until both have quit:
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::{bounded, select_loop};
+///
+/// let (s1, r1) = bounded(0);
+/// let (s2, r2) = bounded(0);
+///
+/// let w1 = thread::spawn(move || r1.iter().take(3).count());
+/// let w2 = thread::spawn(move || r2.iter().take(5).count());
+///
+/// let mut next = 0;
+/// select_loop! {
+///     send(s1, next) => next += 1,
+///     send(s2, next) => next += 1,
+/// }
+///
+/// assert_eq!(next, 8);
+/// assert_eq!(w1.join().unwrap() + w2.join().unwrap(), 8);
+/// ```
+#[macro_export]
+macro_rules! select_loop {
+    ($($tokens:tt)*) => {
+        $crate::crossbeam_channel_internal!(
+            @loop
+            _alive
+            ($($tokens)*)
+            ()
+            (0usize)
+        )
+    };
+}
+
+/// Receives a message from one of several channels with different message types, wrapping it in
+/// a variant of a caller-defined enum.
+///
+/// Every case has the form `recv(r) => f`, where `f` turns the received message into the result,
+/// usually by naming a tuple variant such as `Event::Number`. Any closure or function works as
+/// well. The macro evaluates to `Ok` with the result of the selected case, or `Err(RecvError)` if
+/// the selected channel is empty and disconnected.
+///
+/// An optional `default` or `default(timeout)` case at the end gives the result to use if no
+/// channel is ready, just like in [`select!`]. It is wrapped in `Ok`.
+///
+/// [`select!`]: crate::select!
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::{select_enum, unbounded};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Event {
+///     Number(i32),
+///     Word(&'static str),
+///     Idle,
+/// }
+///
+/// let (s1, numbers) = unbounded();
+/// let (s2, words) = unbounded();
+///
+/// s2.send("hello").unwrap();
+///
+/// let event = select_enum! {
+///     recv(numbers) => Event::Number,
+///     recv(words) => Event::Word,
+/// };
+/// assert_eq!(event, Ok(Event::Word("hello")));
+///
+/// let event = select_enum! {
+///     recv(numbers) => Event::Number,
+///     recv(words) => Event::Word,
+///     default(Duration::from_millis(100)) => Event::Idle,
+/// };
+/// assert_eq!(event, Ok(Event::Idle));
+///
+/// drop(s1);
+/// let event = select_enum! {
+///     recv(numbers) => Event::Number,
+///     recv(words) => Event::Word,
+/// };
+/// assert!(event.is_err());
+/// ```
+#[macro_export]
+macro_rules! select_enum {
+    (
+        $(recv($r:expr $(,)?) => $f:expr),+
+        $(, default $(($timeout:expr $(,)?))? => $default:expr)?
+        $(,)?
+    ) => {
+        $crate::select! {
+            $(recv($r) -> _msg => ::std::result::Result::map(_msg, $f),)+
+            $(default $(($timeout))? => ::std::result::Result::Ok($default),)?
+        }
+    };
+}
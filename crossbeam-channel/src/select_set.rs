@@ -0,0 +1,459 @@
+//! Persistent set of operations for readiness selection.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::channel::{Receiver, Sender};
+use crate::err::{ReadyTimeoutError, TryReadyError};
+use crate::select::SelectHandle;
+use crate::utils;
+use crate::waker::Listener;
+
+/// A persistent set of channel operations for readiness selection.
+///
+/// Unlike [`Select`], which registers every operation with its channel on each call and
+/// unregisters it afterwards, `SelectSet` registers operations once when they are added and keeps
+/// them registered until they are removed or the set is dropped. Channels notify the set whenever
+/// an operation might have become ready, so each call only checks those operations instead of the
+/// whole list. This makes `SelectSet` suitable for event loops waiting on hundreds of channels.
+/// Timer channels, such as those created by [`after`] and [`tick`], are kept ordered by deadline,
+/// so only the expired ones are checked after a wakeup.
+///
+/// Like [`Select::ready`], the methods of `SelectSet` only report which operation is ready. The
+/// operation must then be executed on the channel directly, e.g. with [`Receiver::try_recv`], and
+/// might fail if another thread got to it first.
+///
+/// Operations that are ready at the same time are reported in turns, so a busy channel cannot
+/// starve the others.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::{unbounded, SelectSet};
+///
+/// let rs: Vec<_> = (0..100)
+///     .map(|i| {
+///         let (s, r) = unbounded();
+///         thread::spawn(move || s.send(i).unwrap());
+///         r
+///     })
+///     .collect();
+///
+/// let mut set = SelectSet::new();
+/// for r in &rs {
+///     set.recv(r);
+/// }
+///
+/// let mut sum = 0;
+/// for _ in 0..100 {
+///     loop {
+///         let index = set.ready();
+///         if let Ok(msg) = rs[index].try_recv() {
+///             sum += msg;
+///             break;
+///         }
+///     }
+/// }
+/// assert_eq!(sum, 4950);
+/// ```
+///
+/// [`Select`]: super::Select
+/// [`Select::ready`]: super::Select::ready
+/// [`after`]: super::after
+/// [`tick`]: super::tick
+pub struct SelectSet<'a> {
+    /// Operations in the set, indexed by operation index, or `None` if removed.
+    handles: Vec<Option<&'a dyn SelectHandle>>,
+
+    /// Collects indices of operations notified by channels.
+    listener: Arc<Listener>,
+
+    /// Indices of operations that might be ready and need to be checked.
+    pending: VecDeque<usize>,
+
+    /// Flags telling which indices are in `pending`.
+    is_pending: Vec<bool>,
+
+    /// Operations whose readiness depends on time rather than notifications, ordered by deadline.
+    ///
+    /// A deadline in the queue may be earlier than the current deadline of the operation, e.g.
+    /// after a tick was received. Such entries are requeued when they come up. Deadlines that move
+    /// earlier are queued again when the operation is notified. Entries of removed operations are
+    /// dropped when they come up.
+    timers: BinaryHeap<Reverse<(Instant, usize)>>,
+
+    /// Scratch space for indices of expired operations.
+    expired: Vec<usize>,
+
+    /// Scratch space for indices taken from the listener.
+    notified: Vec<usize>,
+
+    /// The number of operations in the set.
+    len: usize,
+}
+
+unsafe impl Send for SelectSet<'_> {}
+unsafe impl Sync for SelectSet<'_> {}
+
+impl<'a> SelectSet<'a> {
+    /// Creates an empty set of channel operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::SelectSet;
+    ///
+    /// let mut set = SelectSet::new();
+    ///
+    /// // The set is empty, which means no operation can be ready.
+    /// assert!(set.try_ready().is_err());
+    /// ```
+    pub fn new() -> SelectSet<'a> {
+        SelectSet {
+            handles: Vec::new(),
+            listener: Arc::new(Listener::new()),
+            pending: VecDeque::new(),
+            is_pending: Vec::new(),
+            timers: BinaryHeap::new(),
+            expired: Vec::new(),
+            notified: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Adds a send operation.
+    ///
+    /// Returns the index of the added operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut set = SelectSet::new();
+    /// let index = set.send(&s);
+    /// ```
+    pub fn send<T>(&mut self, s: &'a Sender<T>) -> usize {
+        self.add(s)
+    }
+
+    /// Adds a receive operation.
+    ///
+    /// Returns the index of the added operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut set = SelectSet::new();
+    /// let index = set.recv(&r);
+    /// ```
+    pub fn recv<T>(&mut self, r: &'a Receiver<T>) -> usize {
+        self.add(r)
+    }
+
+    /// Removes a previously added operation.
+    ///
+    /// If new operations are added after removing some, the indices of removed operations will not
+    /// be reused.
+    ///
+    /// # Panics
+    ///
+    /// An attempt to remove a non-existing or already removed operation will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    /// s.send(1).unwrap();
+    ///
+    /// let mut set = SelectSet::new();
+    /// let index = set.recv(&r);
+    /// set.remove(index);
+    ///
+    /// assert!(set.try_ready().is_err());
+    /// ```
+    pub fn remove(&mut self, index: usize) {
+        assert!(
+            index < self.handles.len(),
+            "index out of bounds; {} >= {}",
+            index,
+            self.handles.len(),
+        );
+
+        let handle = self.handles[index]
+            .take()
+            .expect("no operation with this index");
+        handle.unlisten(&self.listener, index);
+        self.len -= 1;
+    }
+
+    /// Attempts to find a ready operation without blocking.
+    ///
+    /// If an operation is ready, its index is returned. If none of the operations are ready, an
+    /// error is returned.
+    ///
+    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
+    /// even when it will simply return an error because the channel is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// let mut set = SelectSet::new();
+    /// let index = set.recv(&r);
+    /// assert!(set.try_ready().is_err());
+    ///
+    /// s.send(1).unwrap();
+    /// assert_eq!(set.try_ready(), Ok(index));
+    /// ```
+    pub fn try_ready(&mut self) -> Result<usize, TryReadyError> {
+        self.run_ready(Some(Instant::now())).ok_or(TryReadyError)
+    }
+
+    /// Blocks until one of the operations becomes ready.
+    ///
+    /// Once an operation becomes ready, its index is returned.
+    ///
+    /// An operation is considered to be ready if it doesn't have to block. Note that it is ready
+    /// even when it will simply return an error because the channel is disconnected.
+    ///
+    /// Note that this method might return with success spuriously, so it's a good idea to always
+    /// double check if the operation is really ready.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to `SelectSet`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s1, r1) = unbounded();
+    /// let (s2, r2) = unbounded::<i32>();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     s1.send(10).unwrap();
+    /// });
+    ///
+    /// let mut set = SelectSet::new();
+    /// let oper1 = set.recv(&r1);
+    /// let oper2 = set.recv(&r2);
+    ///
+    /// assert_eq!(set.ready(), oper1);
+    /// assert_eq!(r1.try_recv(), Ok(10));
+    /// ```
+    pub fn ready(&mut self) -> usize {
+        if self.len == 0 {
+            panic!("no operations have been added to `SelectSet`");
+        }
+
+        self.run_ready(None).unwrap()
+    }
+
+    /// Blocks for a limited time until one of the operations becomes ready.
+    ///
+    /// If an operation becomes ready, its index is returned. If none of the operations become ready
+    /// for the specified duration, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut set = SelectSet::new();
+    /// set.recv(&r);
+    ///
+    /// assert!(set.ready_timeout(Duration::from_millis(100)).is_err());
+    /// ```
+    pub fn ready_timeout(&mut self, timeout: Duration) -> Result<usize, ReadyTimeoutError> {
+        self.ready_deadline(utils::convert_timeout_to_deadline(timeout))
+    }
+
+    /// Blocks until a given deadline, or until one of the operations becomes ready.
+    ///
+    /// If an operation becomes ready, its index is returned. If none of the operations become ready
+    /// before the deadline, an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use crossbeam_channel::{unbounded, SelectSet};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let mut set = SelectSet::new();
+    /// set.recv(&r);
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    /// assert!(set.ready_deadline(deadline).is_err());
+    /// ```
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Result<usize, ReadyTimeoutError> {
+        self.run_ready(Some(deadline)).ok_or(ReadyTimeoutError)
+    }
+
+    /// Adds an operation and registers the set with its channel.
+    fn add(&mut self, handle: &'a dyn SelectHandle) -> usize {
+        let index = self.handles.len();
+        handle.listen(&self.listener, index);
+        if let Some(d) = handle.deadline() {
+            self.timers.push(Reverse((d, index)));
+        }
+        self.handles.push(Some(handle));
+        self.is_pending.push(false);
+        self.len += 1;
+        self.enqueue(index);
+        index
+    }
+
+    /// Marks an operation as one that needs to be checked.
+    fn enqueue(&mut self, index: usize) {
+        if !self.is_pending[index] {
+            self.is_pending[index] = true;
+            self.pending.push_back(index);
+        }
+    }
+
+    /// Runs until one of the operations becomes ready or the deadline passes.
+    fn run_ready(&mut self, deadline: Option<Instant>) -> Option<usize> {
+        // Pick up notifications that arrived since the last call. Operations that stay ready, like
+        // those on disconnected channels, must not keep newly notified ones waiting.
+        self.collect(false, None);
+
+        loop {
+            // Check the operations that might be ready, oldest notifications first.
+            while let Some(index) = self.pending.pop_front() {
+                self.is_pending[index] = false;
+
+                if let Some(handle) = self.handles[index] {
+                    if handle.is_ready() {
+                        // The operation might still be ready next time, so check it again then.
+                        self.enqueue(index);
+                        return Some(index);
+                    }
+                }
+            }
+
+            // Compute how long to block, taking operations that become ready in time into account.
+            // Waking up too early is fine because the queue gets corrected in `collect`.
+            let mut wait_until = deadline;
+            if let Some(&Reverse((d, _))) = self.timers.peek() {
+                wait_until = Some(wait_until.map_or(d, |w| w.min(d)));
+            }
+
+            let now = self.collect(true, wait_until);
+
+            if self.pending.is_empty() {
+                if let Some(d) = deadline {
+                    if now.unwrap_or_else(Instant::now) >= d {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Enqueues notified and expired operations, blocking until there is a notification or the
+    /// deadline passes if `block` is `true`.
+    ///
+    /// Returns the current time if any operation has a deadline. Otherwise the clock isn't read.
+    fn collect(&mut self, block: bool, deadline: Option<Instant>) -> Option<Instant> {
+        if block {
+            self.listener.take(deadline, &mut self.notified);
+        } else {
+            self.listener.try_take(&mut self.notified);
+        }
+
+        for i in 0..self.notified.len() {
+            let index = self.notified[i];
+            self.enqueue(index);
+
+            // A custom primitive might have moved its deadline earlier before notifying the set.
+            if let Some(d) = self.handles[index].and_then(|h| h.deadline()) {
+                self.timers.push(Reverse((d, index)));
+            }
+        }
+        self.notified.clear();
+
+        let now = if self.timers.is_empty() {
+            None
+        } else {
+            Some(Instant::now())
+        };
+        if let Some(now) = now {
+            while let Some(&Reverse((d, index))) = self.timers.peek() {
+                if d > now {
+                    break;
+                }
+                self.timers.pop();
+
+                if let Some(handle) = self.handles[index] {
+                    match handle.deadline() {
+                        Some(d) if d > now => self.timers.push(Reverse((d, index))),
+                        Some(_) => {
+                            self.enqueue(index);
+                            self.expired.push(index);
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        // Operations that stay expired are checked again on the next call.
+        self.expired.sort_unstable();
+        self.expired.dedup();
+        for i in 0..self.expired.len() {
+            let index = self.expired[i];
+            if let Some(d) = self.handles[index].and_then(|h| h.deadline()) {
+                self.timers.push(Reverse((d, index)));
+            }
+        }
+        self.expired.clear();
+        now
+    }
+}
+
+impl Drop for SelectSet<'_> {
+    fn drop(&mut self) {
+        for (index, handle) in self.handles.iter().enumerate() {
+            if let Some(handle) = handle {
+                handle.unlisten(&self.listener, index);
+            }
+        }
+    }
+}
+
+impl Default for SelectSet<'_> {
+    fn default() -> Self {
+        SelectSet::new()
+    }
+}
+
+impl fmt::Debug for SelectSet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("SelectSet { .. }")
+    }
+}
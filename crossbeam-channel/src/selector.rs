@@ -0,0 +1,286 @@
+//! Selection with a closure for each operation.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::channel::{Receiver, Sender};
+use crate::err::{RecvError, SendError};
+use crate::select::{Select, SelectedOperation};
+use crate::utils;
+
+/// A handler for a selected operation.
+type Arm<'a, R> = Box<dyn FnOnce(SelectedOperation<'a>) -> R + 'a>;
+
+/// What to do if no operation is ready.
+enum Fallback<'a, R> {
+    /// Block until an operation becomes ready.
+    Block,
+
+    /// Run the closure if no operation becomes ready before the deadline.
+    Deadline(Instant, Box<dyn FnOnce() -> R + 'a>),
+
+    /// Run the closure if no operation is ready right away.
+    Default(Box<dyn FnOnce() -> R + 'a>),
+}
+
+/// Selects over a set of operations, running a closure for the one that completes.
+///
+/// `Selector` is an alternative to the [`select!`] macro for those who prefer plain methods, or
+/// need to add operations in a loop. Each operation comes with a closure that is called with the
+/// result of the operation if it gets selected, and [`wait`] returns what the closure returns.
+///
+/// Operations are selected randomly when several of them are ready, just like with [`Select`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::{unbounded, Selector};
+///
+/// let (s1, r1) = unbounded();
+/// let (s2, r2) = unbounded::<i32>();
+/// s1.send(10).unwrap();
+///
+/// let res = Selector::new()
+///     .recv(&r1, |msg| format!("r1: {:?}", msg))
+///     .recv(&r2, |msg| format!("r2: {:?}", msg))
+///     .timeout(Duration::from_secs(1), || "timed out".to_string())
+///     .wait();
+///
+/// assert_eq!(res, "r1: Ok(10)");
+/// ```
+///
+/// Adding operations in a loop:
+///
+/// ```
+/// use crossbeam_channel::{unbounded, Selector};
+///
+/// let (senders, receivers): (Vec<_>, Vec<_>) = (0..4).map(|_| unbounded()).unzip();
+/// senders[2].send("hello").unwrap();
+///
+/// let mut selector = Selector::new();
+/// for (i, r) in receivers.iter().enumerate() {
+///     selector = selector.recv(r, move |msg| (i, msg));
+/// }
+///
+/// assert_eq!(selector.wait(), (2, Ok("hello")));
+/// ```
+///
+/// [`select!`]: crate::select!
+/// [`wait`]: Selector::wait
+/// [`Select`]: super::Select
+pub struct Selector<'a, R> {
+    /// The underlying selection mechanism.
+    sel: Select<'a>,
+
+    /// Handlers for the operations, indexed by operation index.
+    arms: Vec<Arm<'a, R>>,
+
+    /// What to do if no operation is ready.
+    fallback: Fallback<'a, R>,
+}
+
+impl<'a, R> Selector<'a, R> {
+    /// Creates an empty selector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{never, Selector};
+    ///
+    /// let r = never::<i32>();
+    /// let res = Selector::new().recv(&r, |_| 1).default(|| 2).wait();
+    /// assert_eq!(res, 2);
+    /// ```
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Selector<'a, R> {
+        Selector {
+            sel: Select::new(),
+            arms: Vec::new(),
+            fallback: Fallback::Block,
+        }
+    }
+
+    /// Adds a send operation.
+    ///
+    /// The message is produced by `msg` only if the operation gets selected. Then `f` is called
+    /// with the result of the send operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// let res = Selector::new().send(&s, || 10, |res| res.is_ok()).wait();
+    /// assert!(res);
+    /// assert_eq!(r.try_recv(), Ok(10));
+    /// ```
+    pub fn send<T, M, F>(mut self, s: &'a Sender<T>, msg: M, f: F) -> Selector<'a, R>
+    where
+        M: FnOnce() -> T + 'a,
+        F: FnOnce(Result<(), SendError<T>>) -> R + 'a,
+    {
+        self.sel.send(s);
+        self.arms.push(Box::new(move |oper| f(oper.send(s, msg()))));
+        self
+    }
+
+    /// Adds a receive operation.
+    ///
+    /// If the operation gets selected, `f` is called with the result of the receive operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded();
+    /// s.send(10).unwrap();
+    ///
+    /// let res = Selector::new().recv(&r, |msg| msg.unwrap() * 2).wait();
+    /// assert_eq!(res, 20);
+    /// ```
+    pub fn recv<T, F>(mut self, r: &'a Receiver<T>, f: F) -> Selector<'a, R>
+    where
+        F: FnOnce(Result<T, RecvError>) -> R + 'a,
+    {
+        self.sel.recv(r);
+        self.arms.push(Box::new(move |oper| f(oper.recv(r))));
+        self
+    }
+
+    /// Sets a closure to run if no operation becomes ready for the specified duration.
+    ///
+    /// This replaces any previously set timeout, deadline, or default closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let res = Selector::new()
+    ///     .recv(&r, |_| "received")
+    ///     .timeout(Duration::from_millis(100), || "timed out")
+    ///     .wait();
+    /// assert_eq!(res, "timed out");
+    /// ```
+    pub fn timeout<F>(self, timeout: Duration, f: F) -> Selector<'a, R>
+    where
+        F: FnOnce() -> R + 'a,
+    {
+        self.deadline(utils::convert_timeout_to_deadline(timeout), f)
+    }
+
+    /// Sets a closure to run if no operation becomes ready before the deadline.
+    ///
+    /// This replaces any previously set timeout, deadline, or default closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    /// let deadline = Instant::now() + Duration::from_millis(100);
+    ///
+    /// let res = Selector::new()
+    ///     .recv(&r, |_| "received")
+    ///     .deadline(deadline, || "timed out")
+    ///     .wait();
+    /// assert_eq!(res, "timed out");
+    /// ```
+    pub fn deadline<F>(mut self, deadline: Instant, f: F) -> Selector<'a, R>
+    where
+        F: FnOnce() -> R + 'a,
+    {
+        self.fallback = Fallback::Deadline(deadline, Box::new(f));
+        self
+    }
+
+    /// Sets a closure to run if no operation is ready right away.
+    ///
+    /// This replaces any previously set timeout, deadline, or default closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded::<i32>();
+    ///
+    /// let res = Selector::new()
+    ///     .recv(&r, |_| "received")
+    ///     .default(|| "empty")
+    ///     .wait();
+    /// assert_eq!(res, "empty");
+    /// ```
+    pub fn default<F>(mut self, f: F) -> Selector<'a, R>
+    where
+        F: FnOnce() -> R + 'a,
+    {
+        self.fallback = Fallback::Default(Box::new(f));
+        self
+    }
+
+    /// Selects an operation, completes it, and returns the output of its closure.
+    ///
+    /// Without a timeout, deadline, or default closure, this method blocks until one of the
+    /// operations becomes ready.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added and there is no timeout, deadline, or default
+    /// closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use crossbeam_channel::{unbounded, Selector};
+    ///
+    /// let (s, r) = unbounded();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(100));
+    ///     s.send(10).unwrap();
+    /// });
+    ///
+    /// let res = Selector::new().recv(&r, |msg| msg.unwrap()).wait();
+    /// assert_eq!(res, 10);
+    /// ```
+    pub fn wait(self) -> R {
+        let Selector {
+            mut sel,
+            mut arms,
+            fallback,
+        } = self;
+
+        let oper = match fallback {
+            Fallback::Block => sel.select(),
+            Fallback::Deadline(deadline, f) => match sel.select_deadline(deadline) {
+                Ok(oper) => oper,
+                Err(_) => return f(),
+            },
+            Fallback::Default(f) => match sel.try_select() {
+                Ok(oper) => oper,
+                Err(_) => return f(),
+            },
+        };
+
+        let arm = arms.swap_remove(oper.index());
+        arm(oper)
+    }
+}
+
+impl<R> fmt::Debug for Selector<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Selector { .. }")
+    }
+}
@@ -0,0 +1,203 @@
+//! Channels that deliver OS signals.
+//!
+//! Signal handlers write to a socket registered with [`signal-hook`], and a background thread
+//! polls the sockets and turns every delivery into a message. The receivers can participate in
+//! selection just like any other channel, so a main loop can wait for a shutdown signal and for
+//! work at the same time.
+//!
+//! This module is only available on Unix with the `signals` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use crossbeam_channel::{select, signals, unbounded};
+//!
+//! let ctrl_c = signals::ctrl_c();
+//! let (work_s, work_r) = unbounded();
+//!
+//! work_s.send("job").unwrap();
+//!
+//! select! {
+//!     recv(ctrl_c) -> _ => println!("shutting down"),
+//!     recv(work_r) -> job => println!("received {:?}", job),
+//!     default(Duration::from_secs(1)) => println!("timed out"),
+//! }
+//! ```
+//!
+//! [`signal-hook`]: https://docs.rs/signal-hook
+
+use std::io::{self, Read};
+use std::os::raw::c_int;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use signal_hook::consts::SIGINT;
+use signal_hook::low_level::{self, pipe};
+use signal_hook::SigId;
+
+use crate::channel::{bounded, Receiver, Sender};
+use crate::err::TrySendError;
+
+/// How long a single `poll` call may block, in milliseconds.
+///
+/// The polling thread checks for disconnection of the receiver between calls.
+const POLL_INTERVAL_MS: libc::c_int = 100;
+
+/// The number of undelivered signals a receiver can hold before further ones are dropped.
+const CAPACITY: usize = 32;
+
+/// Creates a receiver that delivers a message every time the process receives `SIGINT`.
+///
+/// `SIGINT` is the signal sent to the foreground process when the user presses Ctrl-C. While the
+/// receiver is alive, the signal no longer terminates the process. The handler is unregistered
+/// shortly after the receiver is dropped.
+///
+/// Deliveries that arrive in quick succession may be reported as a single message. If the
+/// receiver falls behind, further signals are dropped until it catches up.
+///
+/// # Panics
+///
+/// Panics if the signal handler cannot be registered.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::signals;
+///
+/// let ctrl_c = signals::ctrl_c();
+///
+/// signal_hook::low_level::raise(signal_hook::consts::SIGINT).unwrap();
+/// assert_eq!(ctrl_c.recv_timeout(Duration::from_secs(1)), Ok(()));
+/// ```
+pub fn ctrl_c() -> Receiver<()> {
+    watch(&[SIGINT], |_| ()).expect("failed to register a handler for SIGINT")
+}
+
+/// Creates a receiver that delivers the signal number every time the process receives one of
+/// `signals`.
+///
+/// While the receiver is alive, the signals no longer trigger their default actions, such as
+/// terminating the process. The handlers are unregistered shortly after the receiver is dropped.
+///
+/// Deliveries of the same signal that arrive in quick succession may be reported as a single
+/// message. If the receiver falls behind, further signals are dropped until it catches up.
+///
+/// Signal numbers can be taken from the `libc` or `signal-hook` crates.
+///
+/// # Errors
+///
+/// Returns an error if a handler cannot be registered, e.g. for `SIGKILL` and other signals that
+/// cannot be caught.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use crossbeam_channel::signals;
+/// use signal_hook::consts::{SIGUSR1, SIGUSR2};
+///
+/// let r = signals::notify(&[SIGUSR1, SIGUSR2]).unwrap();
+///
+/// signal_hook::low_level::raise(SIGUSR2).unwrap();
+/// assert_eq!(r.recv_timeout(Duration::from_secs(1)), Ok(SIGUSR2));
+/// ```
+pub fn notify(signals: &[c_int]) -> io::Result<Receiver<c_int>> {
+    watch(signals, |signal| signal)
+}
+
+/// Handlers registered for a receiver, unregistered on drop.
+struct Registration {
+    ids: Vec<SigId>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        for &id in &self.ids {
+            low_level::unregister(id);
+        }
+    }
+}
+
+/// Registers handlers for `signals` and spawns a thread that turns them into messages.
+fn watch<T: Send + 'static>(signals: &[c_int], msg: fn(c_int) -> T) -> io::Result<Receiver<T>> {
+    let mut registration = Registration { ids: Vec::new() };
+    let mut pipes = Vec::with_capacity(signals.len());
+
+    for &signal in signals {
+        let (read, write) = UnixStream::pair()?;
+        read.set_nonblocking(true)?;
+        write.set_nonblocking(true)?;
+        registration.ids.push(pipe::register(signal, write)?);
+        pipes.push((signal, read));
+    }
+
+    let (s, r) = bounded(CAPACITY);
+
+    thread::Builder::new()
+        .name("crossbeam-channel-signals".to_string())
+        .spawn(move || run(pipes, registration, msg, s))?;
+
+    Ok(r)
+}
+
+/// Polls the sockets written by signal handlers until the receiver is dropped.
+fn run<T>(
+    mut pipes: Vec<(c_int, UnixStream)>,
+    _registration: Registration,
+    msg: fn(c_int) -> T,
+    s: Sender<T>,
+) {
+    let mut pollfds: Vec<libc::pollfd> = pipes
+        .iter()
+        .map(|(_, read)| libc::pollfd {
+            fd: read.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+    let mut buf = [0; 64];
+
+    while !s.is_disconnected() {
+        for pollfd in &mut pollfds {
+            pollfd.revents = 0;
+        }
+
+        let res = unsafe {
+            libc::poll(
+                pollfds.as_mut_ptr(),
+                pollfds.len() as libc::nfds_t,
+                POLL_INTERVAL_MS,
+            )
+        };
+        if res < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return;
+        }
+
+        for (pollfd, (signal, read)) in pollfds.iter().zip(pipes.iter_mut()) {
+            if pollfd.revents == 0 {
+                continue;
+            }
+
+            // Drain the socket so that a burst of deliveries is reported once.
+            let mut delivered = false;
+            while let Ok(n) = read.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                delivered = true;
+            }
+
+            if delivered {
+                if let Err(TrySendError::Disconnected(_)) = s.try_send(msg(*signal)) {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! A deterministic scheduler for testing code that uses channels.
+//!
+//! [`run`] starts a simulation in which threads take turns: exactly one simulated thread runs at
+//! any moment, and control only passes to another one when the running thread blocks on a
+//! channel, completes a send or a receive, or calls [`yield_now`] or [`sleep`]. At each of those
+//! points the next thread is picked by a random number generator seeded by the caller, so a seed
+//! always produces the same interleaving, and a failure found with one seed can be replayed.
+//!
+//! Time is virtual. Timeouts, deadlines and timer channels like [`after`] and [`tick`] read the
+//! simulation's [`clock`], which stands still while any thread can run. Once every thread is
+//! blocked, the clock jumps to the earliest deadline among them, so a test that waits for an
+//! hour-long timeout finishes instantly.
+//!
+//! If every thread is blocked and none of them has a deadline, the simulation is deadlocked and
+//! [`run`] panics. The deadlocked threads are never woken up and stay blocked forever.
+//!
+//! Only threads started with [`run`] and [`spawn`] are simulated, and they should only block on
+//! the send, receive and select operations of this crate, or through this module. Blocking in
+//! any other way, or busy-waiting without calling [`yield_now`], stalls the whole simulation.
+//!
+//! This module is only available with the `sim` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use crossbeam_channel::{bounded, sim};
+//!
+//! let received = sim::run(7, || {
+//!     let (s, r) = bounded(0);
+//!     let producer = sim::spawn(move || {
+//!         for i in 0..3 {
+//!             sim::sleep(Duration::from_secs(60));
+//!             s.send(i).unwrap();
+//!         }
+//!     });
+//!
+//!     // Three minutes pass in virtual time, but the test doesn't wait for them.
+//!     let start = sim::now();
+//!     let received = r.iter().collect::<Vec<_>>();
+//!     assert_eq!(sim::now() - start, Duration::from_secs(180));
+//!
+//!     producer.join().unwrap();
+//!     received
+//! });
+//! assert_eq!(received, [0, 1, 2]);
+//! ```
+//!
+//! [`after`]: crate::after
+//! [`tick`]: crate::tick
+
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::channel::{bounded, Receiver};
+use crate::clock::{Clock, MockClock};
+
+thread_local! {
+    /// The simulated thread running on this OS thread, if any.
+    static CURRENT: RefCell<Option<Handle>> = RefCell::new(None);
+}
+
+/// The state of a simulated thread.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// The thread can run when it gets its turn.
+    Runnable,
+
+    /// The thread is parked until it is unparked, or until the deadline if there is one.
+    Parked(Option<Instant>),
+
+    /// The thread has exited.
+    Finished,
+}
+
+/// A simulated thread.
+struct Task {
+    /// What the thread is doing.
+    status: Status,
+
+    /// Set if the thread was unparked while it wasn't parked, so that its next park returns
+    /// immediately.
+    token: bool,
+}
+
+/// The shared state of a simulation.
+struct State {
+    /// The thread whose turn it is, or `None` once the simulation has ended.
+    running: Option<usize>,
+
+    /// All threads, in the order they were spawned.
+    threads: Vec<Task>,
+
+    /// The state of the random number generator picking the next thread.
+    rng: u64,
+}
+
+impl State {
+    /// Returns a random number in range `0..n`.
+    fn random(&mut self, n: usize) -> usize {
+        // This is SplitMix64, which gives good numbers for any seed, including zero.
+        //
+        // Source: https://prng.di.unimi.it/splitmix64.c
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z % n as u64) as usize
+    }
+
+    /// Picks a random runnable thread.
+    fn pick(&mut self) -> Option<usize> {
+        let runnable = (0..self.threads.len())
+            .filter(|&id| self.threads[id].status == Status::Runnable)
+            .collect::<Vec<_>>();
+        if runnable.is_empty() {
+            None
+        } else {
+            let i = self.random(runnable.len());
+            Some(runnable[i])
+        }
+    }
+
+    /// Makes the threads whose deadline has passed runnable.
+    fn wake_expired(&mut self, now: Instant) {
+        for task in &mut self.threads {
+            if let Status::Parked(Some(d)) = task.status {
+                if d <= now {
+                    task.status = Status::Runnable;
+                }
+            }
+        }
+    }
+
+    /// Returns the earliest deadline of a parked thread.
+    fn next_deadline(&self) -> Option<Instant> {
+        self.threads
+            .iter()
+            .filter_map(|task| match task.status {
+                Status::Parked(deadline) => deadline,
+                _ => None,
+            })
+            .min()
+    }
+}
+
+/// A simulation.
+struct Sim {
+    /// The threads and whose turn it is.
+    state: Mutex<State>,
+
+    /// Signaled whenever the turn passes to another thread.
+    turn: Condvar,
+
+    /// The virtual clock.
+    clock: Arc<MockClock>,
+}
+
+impl Sim {
+    /// Starts a thread on `f`, which runs once the scheduler gives it a turn.
+    fn spawn<F, T>(self: &Arc<Self>, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            state.threads.push(Task {
+                status: Status::Runnable,
+                token: false,
+            });
+            state.threads.len() - 1
+        };
+        let handle = Handle {
+            sim: self.clone(),
+            id,
+        };
+
+        let (s, r) = bounded(1);
+        thread::Builder::new()
+            .name(format!("sim-{}", id))
+            .spawn(move || {
+                CURRENT.with(|current| *current.borrow_mut() = Some(handle.clone()));
+                handle.wait_turn(handle.sim.state.lock().unwrap());
+
+                let res = panic::catch_unwind(AssertUnwindSafe(f));
+                let _ = s.send(res);
+                drop(s);
+
+                let mut state = handle.sim.state.lock().unwrap();
+                state.threads[handle.id].status = Status::Finished;
+                drop(handle.sim.schedule(state));
+            })
+            .expect("failed to spawn a simulated thread");
+        JoinHandle { result: r }
+    }
+
+    /// Passes the turn to a random runnable thread.
+    ///
+    /// If no thread is runnable, the clock is advanced to the earliest deadline. If there is no
+    /// deadline either, the simulation ends.
+    fn schedule<'a>(&'a self, mut state: MutexGuard<'a, State>) -> MutexGuard<'a, State> {
+        loop {
+            state.wake_expired(self.clock.now());
+            if let Some(next) = state.pick() {
+                state.running = Some(next);
+                break;
+            }
+
+            match state.next_deadline() {
+                None => {
+                    state.running = None;
+                    break;
+                }
+                Some(deadline) => {
+                    // Advancing the clock wakes up threads blocked on timers driven by it, which
+                    // takes the lock.
+                    drop(state);
+                    let now = self.clock.now();
+                    if deadline > now {
+                        self.clock.advance(deadline - now);
+                    }
+                    state = self.state.lock().unwrap();
+                }
+            }
+        }
+
+        self.turn.notify_all();
+        state
+    }
+}
+
+/// A simulated thread, as seen by the crate's blocking operations.
+#[derive(Clone)]
+pub(crate) struct Handle {
+    /// The simulation the thread belongs to.
+    sim: Arc<Sim>,
+
+    /// The index of the thread in the simulation.
+    id: usize,
+}
+
+impl Handle {
+    /// Blocks until it is this thread's turn.
+    fn wait_turn(&self, mut state: MutexGuard<'_, State>) {
+        while state.running != Some(self.id) {
+            state = self.sim.turn.wait(state).unwrap();
+        }
+    }
+
+    /// Lets the scheduler pick the next thread to run, which may be this one.
+    fn yield_now(&self) {
+        let state = self.sim.state.lock().unwrap();
+        let state = self.sim.schedule(state);
+        self.wait_turn(state);
+    }
+
+    /// Parks this thread until it is unparked, or until the deadline if there is one.
+    pub(crate) fn park(&self, deadline: Option<Instant>) {
+        let mut state = self.sim.state.lock().unwrap();
+        let task = &mut state.threads[self.id];
+        if task.token {
+            task.token = false;
+            return;
+        }
+        task.status = Status::Parked(deadline);
+        let state = self.sim.schedule(state);
+        self.wait_turn(state);
+    }
+
+    /// Unparks this thread.
+    pub(crate) fn unpark(&self) {
+        let mut state = self.sim.state.lock().unwrap();
+        let task = &mut state.threads[self.id];
+        match task.status {
+            Status::Parked(_) => task.status = Status::Runnable,
+            Status::Runnable => task.token = true,
+            Status::Finished => {}
+        }
+    }
+
+    /// Returns the current virtual time.
+    pub(crate) fn now(&self) -> Instant {
+        self.sim.clock.now()
+    }
+
+    /// Parks this thread until the deadline, or forever if the deadline isn't specified.
+    pub(crate) fn sleep_until(&self, deadline: Option<Instant>) {
+        loop {
+            match deadline {
+                None => self.park(None),
+                Some(d) => {
+                    if self.now() >= d {
+                        break;
+                    }
+                    self.park(Some(d));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle").field("id", &self.id).finish()
+    }
+}
+
+/// Returns the simulated thread running on this OS thread, if any.
+pub(crate) fn current() -> Option<Handle> {
+    CURRENT
+        .try_with(|current| current.borrow().clone())
+        .ok()
+        .and_then(|handle| handle)
+}
+
+/// Gives other simulated threads a chance to run after a completed operation.
+///
+/// Does nothing outside of a simulation.
+#[inline]
+pub(crate) fn step() {
+    if let Some(handle) = current() {
+        handle.yield_now();
+    }
+}
+
+/// An owned permission to join a simulated thread.
+///
+/// Returned by [`spawn`].
+pub struct JoinHandle<T> {
+    /// Receives the result of the thread when it exits.
+    result: Receiver<thread::Result<T>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Waits for the thread to finish and returns its result.
+    ///
+    /// If the thread panicked, the panic payload is returned as an error.
+    pub fn join(self) -> thread::Result<T> {
+        self.result
+            .recv()
+            .expect("simulated thread exited without a result")
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("JoinHandle { .. }")
+    }
+}
+
+/// Runs `f` as the first thread of a new simulation and returns its result.
+///
+/// The interleaving of the simulated threads is determined by `seed`. The simulation ends once
+/// all threads, including the ones spawned by `f`, have exited.
+///
+/// # Panics
+///
+/// If `f` panics, the panic is propagated. Panics if the simulation deadlocks, that is if all
+/// threads that haven't exited are blocked without a deadline.
+pub fn run<F, T>(seed: u64, f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let sim = Arc::new(Sim {
+        state: Mutex::new(State {
+            running: None,
+            threads: Vec::new(),
+            rng: seed,
+        }),
+        turn: Condvar::new(),
+        clock: Arc::new(MockClock::new()),
+    });
+    let main = sim.spawn(f);
+
+    let mut state = sim.state.lock().unwrap();
+    state.running = Some(0);
+    sim.turn.notify_all();
+    while state.running.is_some() {
+        state = sim.turn.wait(state).unwrap();
+    }
+
+    let blocked = (0..state.threads.len())
+        .filter(|&id| state.threads[id].status != Status::Finished)
+        .map(|id| format!("`sim-{}`", id))
+        .collect::<Vec<_>>();
+    drop(state);
+
+    match main.result.try_recv() {
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Ok(Ok(res)) if blocked.is_empty() => res,
+        _ => panic!(
+            "deadlock in simulation: threads {} are blocked forever",
+            blocked.join(", ")
+        ),
+    }
+}
+
+/// Spawns a new simulated thread.
+///
+/// The new thread runs when the scheduler gives it a turn, which may be right away.
+///
+/// # Panics
+///
+/// Panics if called outside of a simulated thread.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let handle = current().expect("`sim::spawn` called outside of a simulation");
+    let join = handle.sim.spawn(f);
+    handle.yield_now();
+    join
+}
+
+/// Lets the scheduler pick the next thread to run, which may be the current one.
+///
+/// Outside of a simulation, this is the same as [`std::thread::yield_now`].
+pub fn yield_now() {
+    match current() {
+        None => thread::yield_now(),
+        Some(handle) => handle.yield_now(),
+    }
+}
+
+/// Blocks the current thread for `dur` of virtual time.
+///
+/// Outside of a simulation, this is the same as [`std::thread::sleep`].
+pub fn sleep(dur: Duration) {
+    match current() {
+        None => thread::sleep(dur),
+        Some(handle) => {
+            let deadline = handle.now() + dur;
+            handle.sleep_until(Some(deadline));
+        }
+    }
+}
+
+/// Returns the current virtual time.
+///
+/// Outside of a simulation, this is the same as [`Instant::now`].
+pub fn now() -> Instant {
+    match current() {
+        None => Instant::now(),
+        Some(handle) => handle.now(),
+    }
+}
+
+/// Returns the virtual clock of the current simulation.
+///
+/// The clock can be passed to functions like [`after_with_clock`], and advancing it manually
+/// wakes up simulated threads whose deadlines have passed.
+///
+/// # Panics
+///
+/// Panics if called outside of a simulated thread.
+///
+/// [`after_with_clock`]: crate::after_with_clock
+pub fn clock() -> Arc<MockClock> {
+    current()
+        .expect("`sim::clock` called outside of a simulation")
+        .sim
+        .clock
+        .clone()
+}
@@ -0,0 +1,95 @@
+//! Senders used as sinks in async code.
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::channel::Sender;
+use crate::err::{SendError, TrySendError};
+use crate::future;
+
+/// A sender that implements `Sink` from the `futures-sink` crate.
+///
+/// Created with [`Sender::sink`]. The sink holds at most one message that couldn't be sent yet
+/// because the channel was full. [`poll_ready`] and [`poll_flush`] try to send that message and
+/// return [`Poll::Pending`] until there is room for it, so a bounded channel slows down async
+/// producers the same way it blocks threads.
+///
+/// The channel is not disconnected by `poll_close`, which only flushes the pending message. It
+/// gets disconnected when the sink and all other senders are dropped.
+///
+/// A zero-capacity channel only accepts a message while a thread is blocked receiving from it, so
+/// a sink and a stream can't exchange messages over such a channel.
+///
+/// [`poll_ready`]: futures_sink::Sink::poll_ready
+/// [`poll_flush`]: futures_sink::Sink::poll_flush
+pub struct SendSink<T> {
+    /// The sending side of the channel.
+    sender: Sender<T>,
+
+    /// A message waiting for room in the channel.
+    pending: Option<T>,
+}
+
+impl<T> SendSink<T> {
+    /// Creates a sink sending messages into the channel of `sender`.
+    pub(crate) fn new(sender: Sender<T>) -> SendSink<T> {
+        SendSink {
+            sender,
+            pending: None,
+        }
+    }
+
+    /// Returns the sender used by the sink.
+    pub fn sender(&self) -> &Sender<T> {
+        &self.sender
+    }
+
+    /// Attempts to send the pending message, registering the task to be woken up if the channel
+    /// is full.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SendError<T>>> {
+        future::poll_send(&self.sender, &mut self.pending, cx)
+    }
+}
+
+impl<T> futures_sink::Sink<T> for SendSink<T> {
+    type Error = SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<T>>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: T) -> Result<(), SendError<T>> {
+        let this = self.get_mut();
+        assert!(
+            this.pending.is_none(),
+            "`start_send` called without `poll_ready` returning `Ready`"
+        );
+
+        match this.sender.try_send(msg) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Disconnected(msg)) => Err(SendError(msg)),
+            Err(TrySendError::Full(msg)) => {
+                this.pending = Some(msg);
+                Ok(())
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<T>>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), SendError<T>>> {
+        self.get_mut().poll_pending(cx)
+    }
+}
+
+// The pending message is never pinned.
+impl<T> Unpin for SendSink<T> {}
+
+impl<T> fmt::Debug for SendSink<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("SendSink { .. }")
+    }
+}
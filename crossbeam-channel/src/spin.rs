@@ -0,0 +1,149 @@
+//! Policy for waiting before a blocked thread gets parked.
+
+use std::cell::Cell;
+use std::sync::atomic;
+
+use crate::primitive::thread;
+
+/// The length of the longest spinning step, as a power of two.
+const MAX_SPIN_SHIFT: u32 = 6;
+
+/// The maximum number of spinning or yielding steps.
+const MAX_STEPS: u32 = 1024;
+
+/// How long blocking operations wait actively before parking the current thread.
+///
+/// When a send or receive operation can't complete right away, the thread first retries for a
+/// number of steps. During the spinning steps it busy-waits, with each step taking roughly twice
+/// as long as the previous one, up to a limit. During the yielding steps it gives up its time
+/// slice to other threads. If the operation still isn't ready after that, the thread is parked
+/// until another thread wakes it up.
+///
+/// Spinning keeps latency low when the other side responds within a few microseconds, at the
+/// cost of burning CPU time when it doesn't. Parking right away does the opposite. Deadlines are
+/// only checked before parking, so an operation with a timeout may take longer than the timeout
+/// by the time spent spinning and yielding.
+///
+/// The default policy spins for 7 steps and yields for 4 more, which is what every channel
+/// created by [`bounded`] and [`unbounded`] does. Other policies can be chosen with
+/// [`bounded_with_spin`] and [`unbounded_with_spin`].
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{bounded_with_spin, SpinPolicy};
+///
+/// // Never spin or yield, so that idle receivers don't use any CPU time.
+/// let (s, r) = bounded_with_spin(16, SpinPolicy::park());
+///
+/// s.send(1).unwrap();
+/// assert_eq!(r.recv(), Ok(1));
+/// ```
+///
+/// [`bounded`]: crate::bounded
+/// [`unbounded`]: crate::unbounded
+/// [`bounded_with_spin`]: crate::bounded_with_spin
+/// [`unbounded_with_spin`]: crate::unbounded_with_spin
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpinPolicy {
+    spins: u32,
+    yields: u32,
+}
+
+impl SpinPolicy {
+    /// Creates a policy that spins for `spins` steps and then yields for `yields` steps.
+    ///
+    /// Spinning steps stop getting longer after the seventh one. Both numbers are capped at 1024.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::SpinPolicy;
+    ///
+    /// // Spin a little longer than the default, but never yield.
+    /// let policy = SpinPolicy::new(10, 0);
+    /// assert_eq!(policy.spins(), 10);
+    /// assert_eq!(policy.yields(), 0);
+    /// ```
+    pub fn new(spins: u32, yields: u32) -> SpinPolicy {
+        SpinPolicy {
+            spins: spins.min(MAX_STEPS),
+            yields: yields.min(MAX_STEPS),
+        }
+    }
+
+    /// Creates a policy that parks the thread as soon as an operation has to wait.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossbeam_channel::SpinPolicy;
+    ///
+    /// assert_eq!(SpinPolicy::park(), SpinPolicy::new(0, 0));
+    /// ```
+    pub fn park() -> SpinPolicy {
+        SpinPolicy::new(0, 0)
+    }
+
+    /// Returns the number of spinning steps.
+    pub fn spins(&self) -> u32 {
+        self.spins
+    }
+
+    /// Returns the number of yielding steps.
+    pub fn yields(&self) -> u32 {
+        self.yields
+    }
+}
+
+impl Default for SpinPolicy {
+    fn default() -> SpinPolicy {
+        SpinPolicy::new(MAX_SPIN_SHIFT + 1, 4)
+    }
+}
+
+/// Waits actively according to a [`SpinPolicy`].
+pub(crate) struct SpinWait {
+    /// The policy being followed.
+    policy: SpinPolicy,
+
+    /// The number of steps taken so far.
+    step: Cell<u32>,
+}
+
+impl SpinWait {
+    /// Creates a new `SpinWait` following `policy`.
+    #[inline]
+    pub(crate) fn new(policy: SpinPolicy) -> SpinWait {
+        SpinWait {
+            policy,
+            step: Cell::new(0),
+        }
+    }
+
+    /// Takes the next step, either spinning or yielding.
+    #[inline]
+    pub(crate) fn snooze(&self) {
+        let step = self.step.get();
+        if step < self.policy.spins {
+            for _ in 0..1 << step.min(MAX_SPIN_SHIFT) {
+                // TODO: once we bump the minimum required Rust version to 1.49+,
+                // use [`core::hint::spin_loop`] instead.
+                #[allow(deprecated)]
+                atomic::spin_loop_hint();
+            }
+        } else {
+            thread::yield_now();
+        }
+
+        if !self.is_completed() {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Returns `true` if all steps have been taken and the thread should be parked.
+    #[inline]
+    pub(crate) fn is_completed(&self) -> bool {
+        self.step.get() >= self.policy.spins + self.policy.yields
+    }
+}
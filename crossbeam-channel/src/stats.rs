@@ -0,0 +1,135 @@
+//! Lifetime counters of channel operations.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crossbeam_utils::CachePadded;
+
+/// Counters of operations on a channel, updated as they happen.
+///
+/// Each counter is padded so that senders and receivers don't contend on the same cache line.
+pub(crate) struct Counters {
+    /// The number of sent messages.
+    sent: CachePadded<AtomicUsize>,
+
+    /// The number of received messages.
+    received: CachePadded<AtomicUsize>,
+
+    /// The number of times `try_send` found the channel full.
+    full: CachePadded<AtomicUsize>,
+
+    /// The number of times `try_recv` found the channel empty.
+    empty: CachePadded<AtomicUsize>,
+}
+
+impl Counters {
+    /// Creates counters at zero.
+    pub(crate) fn new() -> Counters {
+        Counters {
+            sent: CachePadded::new(AtomicUsize::new(0)),
+            received: CachePadded::new(AtomicUsize::new(0)),
+            full: CachePadded::new(AtomicUsize::new(0)),
+            empty: CachePadded::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Counts `count` sent messages.
+    #[inline]
+    pub(crate) fn sent(&self, count: usize) {
+        self.sent.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Counts a received message.
+    #[inline]
+    pub(crate) fn received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a `try_send` that found the channel full.
+    #[inline]
+    pub(crate) fn full(&self) {
+        self.full.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a `try_recv` that found the channel empty.
+    #[inline]
+    pub(crate) fn empty(&self) {
+        self.empty.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current values of the counters.
+    pub(crate) fn snapshot(&self) -> ChannelStats {
+        ChannelStats {
+            sent: self.sent.load(Ordering::Relaxed) as u64,
+            received: self.received.load(Ordering::Relaxed) as u64,
+            rejected_full: self.full.load(Ordering::Relaxed) as u64,
+            rejected_empty: self.empty.load(Ordering::Relaxed) as u64,
+        }
+    }
+}
+
+/// Counts of operations performed on a channel since it was created.
+///
+/// Returned by [`Sender::stats`] and [`Receiver::stats`]. The counters are updated with relaxed
+/// atomics, so a snapshot taken while other threads use the channel may be slightly out of date,
+/// but comparing two snapshots gives the throughput and the rejection rates in between.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::bounded;
+///
+/// let (s, r) = bounded(1);
+/// s.try_send(1).unwrap();
+/// assert!(s.try_send(2).is_err());
+/// r.recv().unwrap();
+/// assert!(r.try_recv().is_err());
+///
+/// let stats = s.stats();
+/// assert_eq!(stats.sent(), 1);
+/// assert_eq!(stats.received(), 1);
+/// assert_eq!(stats.rejected_full(), 1);
+/// assert_eq!(stats.rejected_empty(), 1);
+/// ```
+///
+/// [`Sender::stats`]: crate::Sender::stats
+/// [`Receiver::stats`]: crate::Receiver::stats
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChannelStats {
+    /// The number of sent messages.
+    sent: u64,
+
+    /// The number of received messages.
+    received: u64,
+
+    /// The number of times `try_send` found the channel full.
+    rejected_full: u64,
+
+    /// The number of times `try_recv` found the channel empty.
+    rejected_empty: u64,
+}
+
+impl ChannelStats {
+    /// Returns the number of messages sent into the channel.
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    /// Returns the number of messages received from the channel.
+    pub fn received(&self) -> u64 {
+        self.received
+    }
+
+    /// Returns the number of times [`Sender::try_send`] failed because the channel was full.
+    ///
+    /// [`Sender::try_send`]: crate::Sender::try_send
+    pub fn rejected_full(&self) -> u64 {
+        self.rejected_full
+    }
+
+    /// Returns the number of times [`Receiver::try_recv`] failed because the channel was empty.
+    ///
+    /// [`Receiver::try_recv`]: crate::Receiver::try_recv
+    pub fn rejected_empty(&self) -> u64 {
+        self.rejected_empty
+    }
+}
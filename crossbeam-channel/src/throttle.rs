@@ -0,0 +1,91 @@
+//! Rate limiting of channels.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::channel::{bounded, Receiver};
+
+/// What a [`throttle`] channel does with messages that arrive faster than it delivers them.
+///
+/// [`throttle`]: crate::throttle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThrottlePolicy {
+    /// Keeps every message and delivers them one per interval, in order.
+    ///
+    /// Messages wait in the wrapped channel, so a bounded channel applies backpressure to its
+    /// senders.
+    Buffer,
+
+    /// Keeps only the latest message and drops the ones before it.
+    ///
+    /// This suits progress updates and other state where only the newest value matters.
+    Conflate,
+}
+
+/// Creates a receiver that delivers messages from `r` at most once per `interval`.
+///
+/// The first message is delivered as soon as it arrives. Each following message is delivered no
+/// sooner than `interval` after the previous one was received from the returned channel. What
+/// happens to messages arriving in between is decided by `policy`.
+///
+/// Messages are forwarded by a background thread. When `r` becomes disconnected, the remaining
+/// messages are delivered according to the policy, after which the returned channel becomes
+/// disconnected too. The thread also stops once the returned receiver is dropped and there is a
+/// message to deliver.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use crossbeam_channel::{throttle, unbounded, ThrottlePolicy};
+///
+/// let ms = |ms| Duration::from_millis(ms);
+///
+/// let (s, r) = unbounded();
+/// let r = throttle(r, ms(100), ThrottlePolicy::Conflate);
+///
+/// s.send(1).unwrap();
+/// assert_eq!(r.recv(), Ok(1));
+///
+/// // Messages sent during the interval are conflated into the latest one.
+/// let start = Instant::now();
+/// for i in 2..5 {
+///     s.send(i).unwrap();
+/// }
+/// assert_eq!(r.recv(), Ok(4));
+/// assert!(start.elapsed() >= ms(80));
+/// ```
+pub fn throttle<T: Send + 'static>(
+    r: Receiver<T>,
+    interval: Duration,
+    policy: ThrottlePolicy,
+) -> Receiver<T> {
+    let (s, out) = bounded(0);
+
+    thread::Builder::new()
+        .name("crossbeam-channel-throttle".to_string())
+        .spawn(move || {
+            let mut next = Instant::now();
+
+            while let Ok(mut msg) = r.recv() {
+                let now = Instant::now();
+                if now < next {
+                    thread::sleep(next - now);
+                }
+
+                if policy == ThrottlePolicy::Conflate {
+                    if let Some(last) = r.try_iter().last() {
+                        msg = last;
+                    }
+                }
+
+                if s.send(msg).is_err() {
+                    return;
+                }
+                next = Instant::now() + interval;
+            }
+        })
+        .expect("failed to spawn a thread for throttling a channel");
+
+    out
+}
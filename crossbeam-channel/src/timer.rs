@@ -0,0 +1,115 @@
+//! A background thread that wakes up tasks at deadlines.
+//!
+//! Threads blocked on timer channels simply park until the deadline. Tasks can't do that, so they
+//! ask this thread to wake them up instead. The thread is spawned on first use.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::ptr;
+use std::sync::{Condvar, Mutex, Once};
+use std::task;
+use std::thread;
+use std::time::Instant;
+
+/// Tasks waiting to be woken up, grouped by deadline.
+///
+/// Every deadline is in the heap exactly once, so registering a task costs a hash map lookup and
+/// at most one heap push, plus a scan over the tasks waiting for the very same instant.
+struct Pending {
+    /// Deadlines with at least one task, earliest first.
+    deadlines: BinaryHeap<Reverse<Instant>>,
+
+    /// The wakers of the tasks waiting for each deadline.
+    wakers: HashMap<Instant, Vec<task::Waker>>,
+}
+
+/// State shared with the timer thread.
+struct Timers {
+    /// Pending tasks.
+    pending: Mutex<Pending>,
+
+    /// Signaled when a deadline becomes the earliest one.
+    cond: Condvar,
+}
+
+/// Returns the timers, spawning the timer thread if it isn't running yet.
+fn timers() -> &'static Timers {
+    static INIT: Once = Once::new();
+    static mut TIMERS: *const Timers = ptr::null();
+
+    INIT.call_once(|| {
+        let timers: &'static Timers = Box::leak(Box::new(Timers {
+            pending: Mutex::new(Pending {
+                deadlines: BinaryHeap::new(),
+                wakers: HashMap::new(),
+            }),
+            cond: Condvar::new(),
+        }));
+        unsafe { TIMERS = timers }
+
+        thread::Builder::new()
+            .name("crossbeam-channel-timer".to_string())
+            .spawn(move || run(timers))
+            .expect("failed to spawn the timer thread");
+    });
+
+    unsafe { &*TIMERS }
+}
+
+/// Wakes up tasks as their deadlines expire.
+fn run(timers: &Timers) {
+    let mut expired = Vec::new();
+    let mut pending = timers.pending.lock().unwrap();
+
+    loop {
+        let now = Instant::now();
+        while pending.deadlines.peek().map_or(false, |d| d.0 <= now) {
+            let when = pending.deadlines.pop().unwrap().0;
+            if let Some(wakers) = pending.wakers.remove(&when) {
+                expired.extend(wakers);
+            }
+        }
+
+        if !expired.is_empty() {
+            // Wake tasks without holding the lock in case they get polled right away.
+            drop(pending);
+            for waker in expired.drain(..) {
+                waker.wake();
+            }
+            pending = timers.pending.lock().unwrap();
+            continue;
+        }
+
+        pending = match pending.deadlines.peek() {
+            Some(d) => {
+                let timeout = d.0 - now;
+                timers.cond.wait_timeout(pending, timeout).unwrap().0
+            }
+            None => timers.cond.wait(pending).unwrap(),
+        };
+    }
+}
+
+/// Wakes up a task at instant `when`.
+pub(crate) fn wake_at(when: Instant, waker: &task::Waker) {
+    let timers = timers();
+    let mut guard = timers.pending.lock().unwrap();
+    let pending = &mut *guard;
+
+    match pending.wakers.entry(when) {
+        Entry::Occupied(mut e) => {
+            if !e.get().iter().any(|w| w.will_wake(waker)) {
+                e.get_mut().push(waker.clone());
+            }
+        }
+        Entry::Vacant(e) => {
+            let earliest = pending.deadlines.peek().map_or(true, |d| when < d.0);
+            e.insert(vec![waker.clone()]);
+            pending.deadlines.push(Reverse(when));
+            if earliest {
+                timers.cond.notify_one();
+            }
+        }
+    }
+}
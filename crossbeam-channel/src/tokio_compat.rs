@@ -0,0 +1,204 @@
+//! Adapters between crossbeam channels and [`tokio`] channels.
+//!
+//! Each adapter spawns a background thread that forwards messages from one kind of channel to the
+//! other. Messages are handed over one at a time, so a full channel on the receiving side blocks
+//! the forwarding thread, which in turn stops taking messages from the sending side. Backpressure
+//! therefore works across the bridge the same way it works within a single channel.
+//!
+//! The forwarding thread uses the blocking methods of [`tokio::sync::mpsc`], which don't need a
+//! runtime. The async side of the bridge can be used from any runtime.
+//!
+//! This module is only available with the `tokio-compat` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use crossbeam_channel::tokio_compat;
+//!
+//! // A tokio channel whose receiving side is consumed by synchronous code.
+//! let (tx, rx) = tokio::sync::mpsc::channel(16);
+//! let r = tokio_compat::from_receiver(rx);
+//!
+//! tx.blocking_send(1).unwrap();
+//! tx.blocking_send(2).unwrap();
+//! drop(tx);
+//!
+//! assert_eq!(r.iter().collect::<Vec<_>>(), [1, 2]);
+//! ```
+//!
+//! [`tokio`]: https://docs.rs/tokio
+//! [`tokio::sync::mpsc`]: https://docs.rs/tokio/1/tokio/sync/mpsc/index.html
+
+use std::thread;
+use std::time::Duration;
+
+use ::tokio::sync::mpsc;
+
+use crate::channel::{bounded, Receiver, Sender};
+use crate::err::RecvTimeoutError;
+
+/// How long the forwarding thread may block on a crossbeam channel at a time.
+///
+/// The thread checks whether the tokio channel has been closed between waits.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Creates a crossbeam receiver that delivers messages from a tokio receiver.
+///
+/// The returned channel has zero capacity, so messages stay in the tokio channel until they are
+/// received from the crossbeam side, and tokio senders wait for room as usual. Once all tokio
+/// senders are dropped and the remaining messages are delivered, the returned channel becomes
+/// disconnected.
+///
+/// If the returned receiver is dropped, the tokio channel is closed as soon as the next message
+/// arrives. That message is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use crossbeam_channel::tokio_compat;
+///
+/// let (tx, rx) = tokio::sync::mpsc::channel(1);
+/// let r = tokio_compat::from_receiver(rx);
+///
+/// thread::spawn(move || {
+///     for i in 0..3 {
+///         tx.blocking_send(i).unwrap();
+///     }
+/// });
+///
+/// assert_eq!(r.iter().sum::<i32>(), 3);
+/// ```
+pub fn from_receiver<T: Send + 'static>(mut rx: mpsc::Receiver<T>) -> Receiver<T> {
+    let (s, r) = bounded(0);
+
+    spawn(move || {
+        while let Some(msg) = rx.blocking_recv() {
+            if s.send(msg).is_err() {
+                return;
+            }
+        }
+    });
+
+    r
+}
+
+/// Creates a crossbeam sender that sends messages into a tokio sender.
+///
+/// The returned channel has zero capacity, so a send blocks until the forwarding thread takes the
+/// message, and the thread only takes the next one once the previous one was accepted by the tokio
+/// channel. Once the tokio receiver is dropped or closed, the returned channel becomes
+/// disconnected shortly afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::tokio_compat;
+///
+/// let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+/// let s = tokio_compat::from_sender(tx);
+///
+/// s.send("hello").unwrap();
+/// assert_eq!(rx.blocking_recv(), Some("hello"));
+/// ```
+pub fn from_sender<T: Send + 'static>(tx: mpsc::Sender<T>) -> Sender<T> {
+    let (s, r) = bounded(0);
+
+    spawn(move || forward(&r, &tx));
+
+    s
+}
+
+/// Creates a tokio receiver that delivers messages from a crossbeam receiver.
+///
+/// The tokio channel has room for a single message, so at most two messages are taken from `r`
+/// before the tokio receiver catches up: one in the tokio channel and one held by the forwarding
+/// thread. Once `r` becomes disconnected and the remaining messages are delivered, the tokio
+/// channel is closed.
+///
+/// If the tokio receiver is dropped, the forwarding thread stops shortly afterwards and drops
+/// `r`, along with any message it was holding.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{tokio_compat, unbounded};
+///
+/// let (s, r) = unbounded();
+/// let mut rx = tokio_compat::into_receiver(r);
+///
+/// s.send(1).unwrap();
+/// drop(s);
+///
+/// assert_eq!(rx.blocking_recv(), Some(1));
+/// assert_eq!(rx.blocking_recv(), None);
+/// ```
+pub fn into_receiver<T: Send + 'static>(r: Receiver<T>) -> mpsc::Receiver<T> {
+    let (tx, rx) = mpsc::channel(1);
+
+    spawn(move || forward(&r, &tx));
+
+    rx
+}
+
+/// Creates a tokio sender that sends messages into a crossbeam sender.
+///
+/// The tokio channel has room for a single message, and the forwarding thread blocks until each
+/// message is accepted by the crossbeam channel, so tokio senders wait whenever that channel is
+/// full. Once all tokio senders are dropped and the remaining messages are delivered, the
+/// forwarding thread drops `s`.
+///
+/// If all crossbeam receivers are dropped, the tokio channel is closed as soon as the next message
+/// arrives. That message is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use crossbeam_channel::{bounded, tokio_compat};
+///
+/// let (s, r) = bounded(1);
+/// let tx = tokio_compat::into_sender(s);
+///
+/// tx.blocking_send(7).unwrap();
+/// assert_eq!(r.recv(), Ok(7));
+/// ```
+pub fn into_sender<T: Send + 'static>(s: Sender<T>) -> mpsc::Sender<T> {
+    let (tx, mut rx) = mpsc::channel(1);
+
+    spawn(move || {
+        while let Some(msg) = rx.blocking_recv() {
+            if s.send(msg).is_err() {
+                return;
+            }
+        }
+    });
+
+    tx
+}
+
+/// Forwards messages from `r` to `tx` until either side is disconnected.
+fn forward<T>(r: &Receiver<T>, tx: &mpsc::Sender<T>) {
+    loop {
+        match r.recv_timeout(POLL_INTERVAL) {
+            Ok(msg) => {
+                if tx.blocking_send(msg).is_err() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if tx.is_closed() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Spawns a forwarding thread.
+fn spawn<F: FnOnce() + Send + 'static>(f: F) {
+    thread::Builder::new()
+        .name("crossbeam-channel-tokio".to_string())
+        .spawn(f)
+        .expect("failed to spawn a thread for forwarding messages");
+}
@@ -0,0 +1,58 @@
+//! Tracing of blocking channel operations.
+//!
+//! With the `tracing` feature enabled, every send or receive operation that has to block emits a
+//! `channel_wait` span, and an event with the time spent waiting once the thread resumes. Without
+//! the feature, waiting is a plain call to [`Context::wait_until_with`].
+
+use std::time::Instant;
+
+use crate::context::Context;
+use crate::metrics::Metrics;
+use crate::select::Selected;
+use crate::spin::SpinPolicy;
+
+/// Waits until an operation is selected and returns it, tracing the wait if it actually blocks.
+///
+/// `op` is the kind of operation (`"send"` or `"recv"`), and the channel's name is taken from its
+/// `metrics`, if it has any.
+#[cfg(feature = "tracing")]
+pub(crate) fn wait(
+    cx: &Context,
+    deadline: Option<Instant>,
+    spin: SpinPolicy,
+    op: &'static str,
+    metrics: Option<&Metrics>,
+) -> Selected {
+    // The operation has already completed or been aborted, so there's nothing to trace.
+    if cx.selected() != Selected::Waiting {
+        return cx.wait_until_with(deadline, spin);
+    }
+
+    let span = tracing::debug_span!("channel_wait", op, channel = tracing::field::Empty);
+    if let Some(metrics) = metrics {
+        span.record("channel", metrics.name());
+    }
+    let _enter = span.enter();
+
+    let start = Instant::now();
+    let sel = cx.wait_until_with(deadline, spin);
+    tracing::debug!(
+        parked_us = start.elapsed().as_micros() as u64,
+        outcome = ?sel,
+        "channel wait finished"
+    );
+    sel
+}
+
+/// Waits until an operation is selected and returns it.
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn wait(
+    cx: &Context,
+    deadline: Option<Instant>,
+    spin: SpinPolicy,
+    _op: &'static str,
+    _metrics: Option<&Metrics>,
+) -> Selected {
+    cx.wait_until_with(deadline, spin)
+}
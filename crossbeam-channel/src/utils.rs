@@ -2,9 +2,35 @@
 
 use std::cell::Cell;
 use std::num::Wrapping;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 
+thread_local! {
+    static RNG: Cell<Wrapping<u32>> = Cell::new(Wrapping(1_406_868_647));
+}
+
+/// Returns a random number in range `0..n`, or `None` if the generator is unavailable.
+fn random(n: u64) -> Option<u64> {
+    RNG.try_with(|rng| {
+        // This is the 32-bit variant of Xorshift.
+        //
+        // Source: https://en.wikipedia.org/wiki/Xorshift
+        let mut x = rng.get();
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        rng.set(x);
+
+        // This is a fast alternative to `x % n`.
+        //
+        // Author: Daniel Lemire
+        // Source: https://lemire.me/blog/2016/06/27/a-fast-alternative-to-the-modulo-reduction/
+        ((x.0 as u128).wrapping_mul(n as u128) >> 32) as u64
+    })
+    .ok()
+}
+
 /// Randomly shuffles a slice.
 pub(crate) fn shuffle<T>(v: &mut [T]) {
     let len = v.len();
@@ -12,42 +38,80 @@ pub(crate) fn shuffle<T>(v: &mut [T]) {
         return;
     }
 
-    thread_local! {
-        static RNG: Cell<Wrapping<u32>> = Cell::new(Wrapping(1_406_868_647));
+    for i in 1..len {
+        match random(i as u64 + 1) {
+            None => return,
+            Some(j) => v.swap(i, j as usize),
+        }
     }
+}
 
-    let _ = RNG.try_with(|rng| {
-        for i in 1..len {
-            // This is the 32-bit variant of Xorshift.
-            //
-            // Source: https://en.wikipedia.org/wiki/Xorshift
-            let mut x = rng.get();
-            x ^= x << 13;
-            x ^= x >> 17;
-            x ^= x << 5;
-            rng.set(x);
-
-            let x = x.0;
-            let n = i + 1;
-
-            // This is a fast alternative to `let j = x % n`.
-            //
-            // Author: Daniel Lemire
-            // Source: https://lemire.me/blog/2016/06/27/a-fast-alternative-to-the-modulo-reduction/
-            let j = ((x as u64).wrapping_mul(n as u64) >> 32) as u32 as usize;
-
-            v.swap(i, j);
+/// Randomly shuffles a slice so that an element chosen by weight comes first.
+///
+/// The first element is chosen with probability proportional to its weight. The other elements
+/// with a nonzero weight follow in random order, and elements with zero weight end up last, in
+/// random order. This takes linear time.
+pub(crate) fn weighted_shuffle<T, F>(v: &mut [T], weight: F)
+where
+    F: Fn(&T) -> u32,
+{
+    // Move the elements with a nonzero weight to the front.
+    let mut nonzero = 0;
+    let mut total: u64 = 0;
+    for i in 0..v.len() {
+        let w = weight(&v[i]) as u64;
+        if w > 0 {
+            total += w;
+            v.swap(nonzero, i);
+            nonzero += 1;
         }
-    });
+    }
+
+    let (front, back) = v.split_at_mut(nonzero);
+    shuffle(back);
+    if front.is_empty() {
+        return;
+    }
+
+    if let Some(mut r) = random(total) {
+        for j in 0..front.len() {
+            let w = weight(&front[j]) as u64;
+            if r < w {
+                front.swap(0, j);
+                break;
+            }
+            r -= w;
+        }
+    }
+    shuffle(&mut front[1..]);
+}
+
+/// Returns the current time, which is virtual on simulated threads.
+#[inline]
+pub(crate) fn now() -> Instant {
+    #[cfg(feature = "sim")]
+    {
+        if let Some(sim) = crate::sim::current() {
+            return sim.now();
+        }
+    }
+    Instant::now()
 }
 
 /// Sleeps until the deadline, or forever if the deadline isn't specified.
 pub(crate) fn sleep_until(deadline: Option<Instant>) {
+    #[cfg(feature = "sim")]
+    {
+        if let Some(sim) = crate::sim::current() {
+            return sim.sleep_until(deadline);
+        }
+    }
+
     loop {
         match deadline {
             None => thread::sleep(Duration::from_secs(1000)),
             Some(d) => {
-                let now = Instant::now();
+                let now = now();
                 if now >= d {
                     break;
                 }
@@ -59,8 +123,71 @@ pub(crate) fn sleep_until(deadline: Option<Instant>) {
 
 // https://github.com/crossbeam-rs/crossbeam/issues/795
 pub(crate) fn convert_timeout_to_deadline(timeout: Duration) -> Instant {
-    match Instant::now().checked_add(timeout) {
+    match now().checked_add(timeout) {
         Some(deadline) => deadline,
-        None => Instant::now() + Duration::from_secs(86400 * 365 * 30),
+        None => now() + Duration::from_secs(86400 * 365 * 30),
+    }
+}
+
+/// The highest value observed since tracking started.
+pub(crate) struct Watermark {
+    /// Set once tracking has started, so that channels that don't track skip computing the value.
+    enabled: AtomicBool,
+
+    /// The highest value observed so far.
+    value: AtomicUsize,
+}
+
+impl Watermark {
+    /// Creates a watermark that isn't tracking yet.
+    pub(crate) fn new() -> Watermark {
+        Watermark {
+            enabled: AtomicBool::new(false),
+            value: AtomicUsize::new(0),
+        }
+    }
+
+    /// Starts tracking from `value`.
+    pub(crate) fn start(&self, value: usize) {
+        self.value.store(value, Ordering::Relaxed);
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Raises the watermark to the value computed by `value` if it is higher.
+    ///
+    /// The value is only computed if tracking has started.
+    #[inline]
+    pub(crate) fn observe<F: FnOnce() -> usize>(&self, value: F) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let value = value();
+        let mut current = self.value.load(Ordering::Relaxed);
+        while value > current {
+            match self.value.compare_exchange_weak(
+                current,
+                value,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(c) => current = c,
+            }
+        }
+    }
+
+    /// Returns the highest value observed so far, or `None` if tracking hasn't started.
+    pub(crate) fn get(&self) -> Option<usize> {
+        if self.enabled.load(Ordering::Relaxed) {
+            Some(self.value.load(Ordering::Relaxed))
+        } else {
+            None
+        }
+    }
+
+    /// Lowers the watermark to `value`.
+    pub(crate) fn reset(&self, value: usize) {
+        self.value.store(value, Ordering::Relaxed);
     }
 }
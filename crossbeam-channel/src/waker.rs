@@ -1,11 +1,19 @@
 //! Waking mechanism for threads blocked on channel operations.
 
 use std::ptr;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
-use std::thread::{self, ThreadId};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Condvar};
+#[cfg(feature = "async")]
+use std::task;
+use std::time::Instant;
 
 use crate::context::Context;
+#[cfg(all(unix, feature = "fd"))]
+use crate::fd::Pipe;
+use crate::primitive::sync::atomic::AtomicBool;
+use crate::primitive::sync::Mutex;
+use crate::primitive::thread::{self, ThreadId};
+use crate::primitive::thread_local;
 use crate::select::{Operation, Selected};
 
 /// Represents a thread blocked on a specific channel operation.
@@ -30,6 +38,13 @@ pub(crate) struct Waker {
 
     /// A list of operations waiting to be ready.
     observers: Vec<Entry>,
+
+    /// A list of persistent listeners and the indices of their operations.
+    listeners: Vec<(Arc<Listener>, usize)>,
+
+    /// A list of tasks waiting for an operation to be ready.
+    #[cfg(feature = "async")]
+    tasks: Vec<task::Waker>,
 }
 
 impl Waker {
@@ -39,9 +54,41 @@ impl Waker {
         Waker {
             selectors: Vec::new(),
             observers: Vec::new(),
+            listeners: Vec::new(),
+            #[cfg(feature = "async")]
+            tasks: Vec::new(),
         }
     }
 
+    /// Returns `true` if nothing is registered with the waker.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        let is_empty =
+            self.selectors.is_empty() && self.observers.is_empty() && self.listeners.is_empty();
+        #[cfg(feature = "async")]
+        let is_empty = is_empty && self.tasks.is_empty();
+        is_empty
+    }
+
+    /// Returns the operations of threads that are still blocked on the waker.
+    fn blocked(&self) -> impl Iterator<Item = &Entry> {
+        self.selectors
+            .iter()
+            .chain(self.observers.iter())
+            .filter(|e| e.cx.selected() == Selected::Waiting)
+    }
+
+    /// Returns the number of threads blocked on the waker.
+    #[inline]
+    pub(crate) fn waiting(&self) -> usize {
+        self.blocked().count()
+    }
+
+    /// Returns the names of threads blocked on the waker.
+    pub(crate) fn waiting_names(&self) -> Vec<String> {
+        self.blocked().map(|e| e.cx.thread_name()).collect()
+    }
+
     /// Registers a select operation.
     #[inline]
     pub(crate) fn register(&mut self, oper: Operation, cx: &Context) {
@@ -135,6 +182,31 @@ impl Waker {
         self.observers.retain(|e| e.oper != oper);
     }
 
+    /// Registers a persistent listener for an operation.
+    #[inline]
+    pub(crate) fn listen(&mut self, listener: &Arc<Listener>, index: usize) {
+        self.listeners.push((listener.clone(), index));
+    }
+
+    /// Unregisters a persistent listener for an operation.
+    #[inline]
+    pub(crate) fn unlisten(&mut self, listener: &Arc<Listener>, index: usize) {
+        self.listeners
+            .retain(|(l, i)| !(Arc::ptr_eq(l, listener) && *i == index));
+    }
+
+    /// Registers a task waiting for an operation to be ready.
+    ///
+    /// The task is woken up once, on the next notification. Tasks don't have to unregister, so
+    /// a future dropped while waiting leaves its waker behind until then.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) fn watch_task(&mut self, waker: &task::Waker) {
+        if !self.tasks.iter().any(|w| w.will_wake(waker)) {
+            self.tasks.push(waker.clone());
+        }
+    }
+
     /// Notifies all operations waiting to be ready.
     #[inline]
     pub(crate) fn notify(&mut self) {
@@ -143,6 +215,15 @@ impl Waker {
                 entry.cx.unpark();
             }
         }
+
+        for (listener, index) in self.listeners.iter() {
+            listener.notify(*index);
+        }
+
+        #[cfg(feature = "async")]
+        for waker in self.tasks.drain(..) {
+            waker.wake();
+        }
     }
 
     /// Notifies all registered operations that the channel is disconnected.
@@ -168,6 +249,111 @@ impl Drop for Waker {
     fn drop(&mut self) {
         debug_assert_eq!(self.selectors.len(), 0);
         debug_assert_eq!(self.observers.len(), 0);
+        debug_assert_eq!(self.listeners.len(), 0);
+    }
+}
+
+/// A persistent subscriber to readiness notifications of many operations.
+///
+/// Unlike observers, listeners stay registered after being notified, and collect the indices of
+/// operations that might have become ready until they are taken.
+// This is a private API that is used by the select mechanism.
+#[derive(Debug)]
+pub struct Listener {
+    /// Indices of notified operations, and flags telling which indices are already queued.
+    inner: Mutex<(Vec<usize>, Vec<bool>)>,
+
+    /// Signaled when a new index gets queued.
+    cond: Condvar,
+
+    /// A pipe signaled on every notification, if any.
+    #[cfg(all(unix, feature = "fd"))]
+    pipe: Option<Pipe>,
+}
+
+impl Listener {
+    /// Creates a new `Listener`.
+    pub(crate) fn new() -> Self {
+        Listener {
+            inner: Mutex::new((Vec::new(), Vec::new())),
+            cond: Condvar::new(),
+            #[cfg(all(unix, feature = "fd"))]
+            pipe: None,
+        }
+    }
+
+    /// Creates a new `Listener` that also signals `pipe` on every notification.
+    #[cfg(all(unix, feature = "fd"))]
+    pub(crate) fn with_pipe(pipe: Pipe) -> Self {
+        Listener {
+            pipe: Some(pipe),
+            ..Listener::new()
+        }
+    }
+
+    /// Returns the pipe signaled on every notification, if any.
+    #[cfg(all(unix, feature = "fd"))]
+    pub(crate) fn pipe(&self) -> Option<&Pipe> {
+        self.pipe.as_ref()
+    }
+
+    /// Queues the index of an operation that might have become ready.
+    pub(crate) fn notify(&self, index: usize) {
+        #[cfg(all(unix, feature = "fd"))]
+        {
+            if let Some(pipe) = &self.pipe {
+                pipe.signal();
+            }
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let (queue, queued) = &mut *inner;
+        if queued.len() <= index {
+            queued.resize(index + 1, false);
+        }
+        if !queued[index] {
+            queued[index] = true;
+            queue.push(index);
+            self.cond.notify_one();
+        }
+    }
+
+    /// Moves all queued indices into `out`, waiting until there is at least one or the deadline
+    /// passes.
+    pub(crate) fn take(&self, deadline: Option<Instant>, out: &mut Vec<usize>) {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            if !inner.0.is_empty() {
+                break;
+            }
+            match deadline {
+                None => inner = self.cond.wait(inner).unwrap(),
+                Some(d) => {
+                    let now = Instant::now();
+                    if now >= d {
+                        break;
+                    }
+                    inner = self.cond.wait_timeout(inner, d - now).unwrap().0;
+                }
+            }
+        }
+
+        Listener::drain(&mut inner, out);
+    }
+
+    /// Moves all queued indices into `out` without waiting.
+    pub(crate) fn try_take(&self, out: &mut Vec<usize>) {
+        let mut inner = self.inner.lock().unwrap();
+        Listener::drain(&mut inner, out);
+    }
+
+    /// Moves the indices in `inner` into `out`.
+    fn drain(inner: &mut (Vec<usize>, Vec<bool>), out: &mut Vec<usize>) {
+        let (queue, queued) = inner;
+        for index in queue.drain(..) {
+            queued[index] = false;
+            out.push(index);
+        }
     }
 }
 
@@ -192,15 +378,23 @@ impl SyncWaker {
         }
     }
 
+    /// Returns the number of threads blocked on the waker.
+    #[inline]
+    pub(crate) fn waiting(&self) -> usize {
+        self.inner.lock().unwrap().waiting()
+    }
+
+    /// Returns the names of threads blocked on the waker.
+    pub(crate) fn waiting_names(&self) -> Vec<String> {
+        self.inner.lock().unwrap().waiting_names()
+    }
+
     /// Registers the current thread with an operation.
     #[inline]
     pub(crate) fn register(&self, oper: Operation, cx: &Context) {
         let mut inner = self.inner.lock().unwrap();
         inner.register(oper, cx);
-        self.is_empty.store(
-            inner.selectors.is_empty() && inner.observers.is_empty(),
-            Ordering::SeqCst,
-        );
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
     }
 
     /// Unregisters an operation previously registered by the current thread.
@@ -208,10 +402,7 @@ impl SyncWaker {
     pub(crate) fn unregister(&self, oper: Operation) -> Option<Entry> {
         let mut inner = self.inner.lock().unwrap();
         let entry = inner.unregister(oper);
-        self.is_empty.store(
-            inner.selectors.is_empty() && inner.observers.is_empty(),
-            Ordering::SeqCst,
-        );
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
         entry
     }
 
@@ -223,10 +414,7 @@ impl SyncWaker {
             if !self.is_empty.load(Ordering::SeqCst) {
                 inner.try_select();
                 inner.notify();
-                self.is_empty.store(
-                    inner.selectors.is_empty() && inner.observers.is_empty(),
-                    Ordering::SeqCst,
-                );
+                self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
             }
         }
     }
@@ -236,10 +424,7 @@ impl SyncWaker {
     pub(crate) fn watch(&self, oper: Operation, cx: &Context) {
         let mut inner = self.inner.lock().unwrap();
         inner.watch(oper, cx);
-        self.is_empty.store(
-            inner.selectors.is_empty() && inner.observers.is_empty(),
-            Ordering::SeqCst,
-        );
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
     }
 
     /// Unregisters an operation waiting to be ready.
@@ -247,10 +432,32 @@ impl SyncWaker {
     pub(crate) fn unwatch(&self, oper: Operation) {
         let mut inner = self.inner.lock().unwrap();
         inner.unwatch(oper);
-        self.is_empty.store(
-            inner.selectors.is_empty() && inner.observers.is_empty(),
-            Ordering::SeqCst,
-        );
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
+    }
+
+    /// Registers a persistent listener for an operation.
+    #[inline]
+    pub(crate) fn listen(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.listen(listener, index);
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
+    }
+
+    /// Unregisters a persistent listener for an operation.
+    #[inline]
+    pub(crate) fn unlisten(&self, listener: &Arc<Listener>, index: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unlisten(listener, index);
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
+    }
+
+    /// Registers a task waiting for an operation to be ready.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) fn watch_task(&self, waker: &task::Waker) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.watch_task(waker);
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
     }
 
     /// Notifies all threads that the channel is disconnected.
@@ -258,16 +465,15 @@ impl SyncWaker {
     pub(crate) fn disconnect(&self) {
         let mut inner = self.inner.lock().unwrap();
         inner.disconnect();
-        self.is_empty.store(
-            inner.selectors.is_empty() && inner.observers.is_empty(),
-            Ordering::SeqCst,
-        );
+        self.is_empty.store(inner.is_empty(), Ordering::SeqCst);
     }
 }
 
 impl Drop for SyncWaker {
     #[inline]
     fn drop(&mut self) {
+        // Tasks may leave their wakers behind. The inner `Waker` checks everything else.
+        #[cfg(not(feature = "async"))]
         debug_assert!(self.is_empty.load(Ordering::SeqCst));
     }
 }
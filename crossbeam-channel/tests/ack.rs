@@ -0,0 +1,173 @@
+//! Tests for acknowledging receivers.
+
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, unbounded, RecvError, RecvTimeoutError, TryRecvError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn ack() {
+    let (s, r) = unbounded();
+    let r = r.acked();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    drop(s);
+
+    let a = r.recv().unwrap();
+    let b = r.recv().unwrap();
+    assert_eq!(r.pending(), 2);
+    assert_eq!(a.ack(), 1);
+    assert_eq!(b.ack(), 2);
+    assert_eq!(r.pending(), 0);
+    assert_eq!(r.recv().err(), Some(RecvError));
+}
+
+#[test]
+fn nack_redelivers_first() {
+    let (s, r) = unbounded();
+    let r = r.acked();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    let msg = r.recv().unwrap();
+    assert_eq!(*msg, 1);
+    msg.nack();
+    assert_eq!(r.pending(), 0);
+
+    assert_eq!(r.recv().unwrap().ack(), 1);
+    assert_eq!(r.recv().unwrap().ack(), 2);
+}
+
+#[test]
+fn drop_redelivers() {
+    let (s, r) = unbounded();
+    let r = r.acked();
+    s.send(String::from("job")).unwrap();
+
+    {
+        let mut msg = r.recv().unwrap();
+        msg.push_str(" (retried)");
+    }
+    assert_eq!(r.recv().unwrap().ack(), "job (retried)");
+}
+
+#[test]
+fn panicking_consumer() {
+    let (s, r) = bounded(1);
+    let r = r.acked();
+    s.send(7).unwrap();
+    drop(s);
+
+    let r2 = r.clone();
+    let consumer = thread::spawn(move || {
+        let _msg = r2.recv().unwrap();
+        panic!("consumer failed");
+    });
+    assert!(consumer.join().is_err());
+
+    assert_eq!(r.recv().unwrap().ack(), 7);
+    assert_eq!(r.try_recv().err(), Some(TryRecvError::Disconnected));
+}
+
+#[test]
+fn waits_for_pending() {
+    let (s, r) = unbounded();
+    let r = r.acked();
+    s.send(1).unwrap();
+    drop(s);
+
+    let msg = r.recv().unwrap();
+    assert_eq!(r.try_recv().err(), Some(TryRecvError::Empty));
+    assert_eq!(
+        r.recv_timeout(ms(50)).err(),
+        Some(RecvTimeoutError::Timeout)
+    );
+
+    // A receiver blocked on a disconnected channel gets the message given back.
+    scope(|scope| {
+        scope.spawn(|_| assert_eq!(r.recv().unwrap().ack(), 1));
+        thread::sleep(ms(100));
+        msg.nack();
+    })
+    .unwrap();
+
+    // And fails once the last delivery is acknowledged.
+    let (s, r) = unbounded();
+    let r = r.acked();
+    s.send(1).unwrap();
+    drop(s);
+    let msg = r.recv().unwrap();
+    scope(|scope| {
+        scope.spawn(|_| assert_eq!(r.recv().err(), Some(RecvError)));
+        thread::sleep(ms(100));
+        msg.ack();
+    })
+    .unwrap();
+}
+
+#[test]
+fn recv_timeout() {
+    let (s, r) = bounded::<i32>(0);
+    let r = r.acked();
+    assert_eq!(
+        r.recv_timeout(ms(50)).err(),
+        Some(RecvTimeoutError::Timeout)
+    );
+    drop(s);
+    assert_eq!(
+        r.recv_timeout(ms(50)).err(),
+        Some(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn at_least_once() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(16);
+    let r = r.acked();
+    let acked = scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+            drop(s);
+        });
+
+        let consumers = (0..4)
+            .map(|c| {
+                let r = r.clone();
+                scope.spawn(move |_| {
+                    let mut acked = Vec::new();
+                    let mut n = 0;
+                    while let Ok(msg) = r.recv() {
+                        n += 1;
+                        // Every third message is given back once.
+                        if (n + c) % 3 == 0 {
+                            msg.nack();
+                        } else {
+                            acked.push(msg.ack());
+                        }
+                    }
+                    acked
+                })
+            })
+            .collect::<Vec<_>>();
+        consumers
+            .into_iter()
+            .flat_map(|c| c.join().unwrap())
+            .collect::<Vec<_>>()
+    })
+    .unwrap();
+
+    let unique = acked.iter().collect::<HashSet<_>>();
+    assert_eq!(acked.len(), COUNT);
+    assert_eq!(unique.len(), COUNT);
+    assert_eq!(r.pending(), 0);
+}
@@ -1,12 +1,13 @@
 //! Tests for the array channel flavor.
 
 use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
 
-use crossbeam_channel::{bounded, select, Receiver};
+use crossbeam_channel::{bounded, select, Receiver, Select};
 use crossbeam_channel::{RecvError, RecvTimeoutError, TryRecvError};
 use crossbeam_channel::{SendError, SendTimeoutError, TrySendError};
 use crossbeam_utils::thread::scope;
@@ -316,6 +317,138 @@ fn len() {
     assert_eq!(r.len(), 0);
 }
 
+#[test]
+fn debug() {
+    let (s, r) = bounded(3);
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(
+        format!("{:?}", s),
+        "Sender { flavor: \"array\", capacity: Some(3), len: 2, disconnected: false, \
+         blocked_senders: 0, blocked_receivers: 0 }"
+    );
+
+    r.recv().unwrap();
+    r.recv().unwrap();
+    scope(|scope| {
+        scope.spawn(|_| r.recv());
+        thread::sleep(ms(100));
+        assert!(format!("{:?}", s).contains("blocked_receivers: 1"));
+        s.send(3).unwrap();
+    })
+    .unwrap();
+
+    drop(s);
+    assert!(format!("{:?}", r).starts_with("Receiver { flavor: \"array\""));
+    assert!(format!("{:?}", r).contains("disconnected: true"));
+}
+
+#[test]
+fn max_len_seen() {
+    let (s, r) = bounded(10);
+    assert_eq!(s.max_len_seen(), 0);
+    s.track_max_len();
+
+    for lap in 0..3 {
+        for i in 0..7 {
+            s.send(i).unwrap();
+        }
+        for _ in 0..7 {
+            r.recv().unwrap();
+        }
+        assert_eq!(r.max_len_seen(), 7, "lap {}", lap);
+    }
+
+    s.send(0).unwrap();
+    r.reset_max_len_seen();
+    assert_eq!(s.max_len_seen(), 1);
+    r.recv().unwrap();
+
+    // Batched sends count too.
+    let mut buffered = s.buffered(5);
+    for i in 0..5 {
+        buffered.send(i).unwrap();
+    }
+    buffered.flush().unwrap();
+    assert_eq!(r.max_len_seen(), 5);
+}
+
+#[test]
+fn blocked() {
+    let (s, r) = bounded(1);
+    assert_eq!(s.blocked_senders(), 0);
+    assert_eq!(r.blocked_receivers(), 0);
+
+    scope(|scope| {
+        scope
+            .builder()
+            .name("receiver".to_string())
+            .spawn(|_| r.recv())
+            .unwrap();
+        scope.spawn(|_| {
+            select! {
+                recv(r) -> _ => {}
+            }
+        });
+        thread::sleep(ms(100));
+        assert_eq!(s.blocked_receivers(), 2);
+        assert!(r.blocked_receiver_names().contains(&"receiver".to_string()));
+
+        s.send(1).unwrap();
+        s.send(2).unwrap();
+    })
+    .unwrap();
+    assert_eq!(r.blocked_receivers(), 0);
+
+    s.send(1).unwrap();
+    scope(|scope| {
+        scope.spawn(|_| s.send(2));
+        thread::sleep(ms(100));
+        assert_eq!(r.blocked_senders(), 1);
+        assert_eq!(r.blocked_sender_names().len(), 1);
+        r.recv().unwrap();
+    })
+    .unwrap();
+    assert_eq!(s.blocked_senders(), 0);
+}
+
+#[test]
+fn len_under_churn() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 25_000;
+    const CAP: usize = 4;
+
+    let (s, r) = bounded(CAP);
+
+    scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    r.recv().unwrap();
+                }
+            });
+        }
+
+        // Keep asking for the length while the channel is busy.
+        scope.spawn(|_| {
+            for _ in 0..COUNT {
+                let len = r.len();
+                assert!(len <= CAP);
+            }
+        });
+    })
+    .unwrap();
+
+    assert_eq!(s.len(), 0);
+}
+
 #[test]
 fn disconnect_wakes_sender() {
     let (s, r) = bounded(1);
@@ -409,6 +542,87 @@ fn mpmc() {
     }
 }
 
+#[test]
+fn zero_sized() {
+    const CAP: usize = 3;
+
+    let (s, r) = bounded::<()>(CAP);
+
+    for _ in 0..3 {
+        for i in 0..CAP {
+            assert_eq!(s.len(), i);
+            s.send(()).unwrap();
+        }
+        assert!(s.is_full());
+        assert_eq!(s.try_send(()), Err(TrySendError::Full(())));
+
+        for i in 0..CAP {
+            assert_eq!(r.len(), CAP - i);
+            r.recv().unwrap();
+        }
+        assert!(r.is_empty());
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    s.send(()).unwrap();
+    s.send(()).unwrap();
+    drop(s);
+    assert_eq!(r.recv(), Ok(()));
+    assert_eq!(r.recv(), Ok(()));
+    assert_eq!(r.recv(), Err(RecvError));
+}
+
+#[test]
+fn zero_sized_unwritten() {
+    let (s, r) = bounded::<()>(2);
+
+    // Reserve room for a message, but never write it.
+    let mut sel = Select::new();
+    sel.send(&s);
+    std::mem::forget(sel.select());
+
+    // Receivers must not see a message that was never sent.
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    s.send(()).unwrap();
+    assert_eq!(r.try_recv(), Ok(()));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn zero_sized_mpmc() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 25_000;
+    const THREADS: usize = 4;
+
+    let (s, r) = bounded::<()>(3);
+    let received = AtomicUsize::new(0);
+
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    r.recv().unwrap();
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+        }
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    s.send(()).unwrap();
+                }
+            });
+        }
+    })
+    .unwrap();
+
+    assert_eq!(received.load(Ordering::SeqCst), COUNT * THREADS);
+    assert!(r.is_empty());
+}
+
 #[test]
 fn stress_oneshot() {
     #[cfg(miri)]
@@ -554,6 +768,72 @@ fn drops() {
     }
 }
 
+#[test]
+fn panicking_drop() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Bomb(bool);
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+            if self.0 {
+                panic!("bomb");
+            }
+        }
+    }
+
+    // Whichever side is dropped last destroys the channel, and every message is dropped despite
+    // the panic.
+    for &receivers_first in &[false, true] {
+        DROPS.store(0, Ordering::SeqCst);
+        let (s, r) = bounded(50);
+        for i in 0..50 {
+            s.send(Bomb(i == 40)).unwrap();
+        }
+        // Wrap the messages around the end of the buffer.
+        for _ in 0..20 {
+            r.recv().unwrap();
+            s.send(Bomb(false)).unwrap();
+        }
+
+        if receivers_first {
+            drop(r);
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(s))).is_err());
+        } else {
+            drop(s);
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(r))).is_err());
+        }
+        assert_eq!(DROPS.load(Ordering::SeqCst), 70);
+    }
+}
+
+#[test]
+fn panicking_clear() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Bomb(bool);
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+            if self.0 {
+                panic!("bomb");
+            }
+        }
+    }
+
+    let (s, r) = bounded(10);
+    for i in 0..10 {
+        s.send(Bomb(i == 3)).unwrap();
+    }
+
+    // The messages after the one that panicked are still dropped.
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| r.clear())).is_err());
+    assert_eq!(DROPS.load(Ordering::SeqCst), 10);
+    assert!(r.is_empty());
+}
+
 #[test]
 fn linearizable() {
     #[cfg(miri)]
@@ -710,7 +990,9 @@ fn panic_on_drop() {
                 panic!("double drop");
             } else {
                 *self.0 = true;
-                panic!("first drop");
+                if !std::thread::panicking() {
+                    panic!("first drop");
+                }
             }
         }
     }
@@ -739,6 +1021,6 @@ fn panic_on_drop() {
         "first drop"
     );
     assert!(a);
-    // Elements after the panicked element will leak.
-    assert!(!b);
+    // Elements after the panicked element are still dropped.
+    assert!(b);
 }
@@ -0,0 +1,104 @@
+//! Tests that blocking operations don't allocate once a thread has blocked before.
+
+#![cfg(not(miri))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use crossbeam_utils::thread::scope;
+
+/// An allocator that counts allocations while `COUNTING` is set.
+struct Counter;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::SeqCst) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+const ROUNDS: usize = 1000;
+
+/// Returns the number of allocations made by both threads while they pass a message back and
+/// forth `ROUNDS` times.
+///
+/// Before counting, both threads block once, so that the channels have already allocated room for
+/// a waiting thread.
+fn ping_pong(
+    ping: (Sender<usize>, Receiver<usize>),
+    pong: (Sender<usize>, Receiver<usize>),
+) -> usize {
+    let (s1, r1) = ping;
+    let (s2, r2) = pong;
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            let msg = r1.recv().unwrap();
+            thread::sleep(ms(50));
+            s2.send(msg).unwrap();
+
+            for _ in 0..ROUNDS {
+                let msg = r1.recv().unwrap();
+                s2.send(msg).unwrap();
+            }
+        });
+
+        thread::sleep(ms(50));
+        s1.send(0).unwrap();
+        r2.recv().unwrap();
+
+        ALLOCATIONS.store(0, Ordering::SeqCst);
+        COUNTING.store(true, Ordering::SeqCst);
+        for i in 0..ROUNDS {
+            s1.send(i).unwrap();
+            assert_eq!(r2.recv(), Ok(i));
+        }
+        COUNTING.store(false, Ordering::SeqCst);
+        ALLOCATIONS.load(Ordering::SeqCst)
+    })
+    .unwrap()
+}
+
+// All checks are in a single test so that no other test allocates at the same time.
+#[test]
+fn blocking_does_not_allocate() {
+    // Every send blocks until the other thread receives.
+    assert_eq!(ping_pong(bounded(0), bounded(0)), 0);
+
+    // Receivers block until the other thread sends.
+    assert_eq!(ping_pong(bounded(1), bounded(1)), 0);
+
+    // Each list channel allocates its second block, and then keeps reusing the two.
+    assert!(ping_pong(unbounded(), unbounded()) <= 2);
+
+    // Timeouts go through the same path.
+    let (s, r) = bounded::<i32>(1);
+    r.recv_timeout(ms(1)).unwrap_err();
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    COUNTING.store(true, Ordering::SeqCst);
+    for _ in 0..10 {
+        r.recv_timeout(ms(1)).unwrap_err();
+    }
+    COUNTING.store(false, Ordering::SeqCst);
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), 0);
+    drop(s);
+}
@@ -0,0 +1,246 @@
+//! Tests for buffered senders.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, unbounded, SendError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s, r) = bounded(10);
+    let mut b = s.buffered(3);
+    assert_eq!(b.capacity(), 3);
+
+    b.send(1).unwrap();
+    b.send(2).unwrap();
+    assert_eq!(b.len(), 2);
+    assert!(r.is_empty());
+
+    b.send(3).unwrap();
+    assert!(b.is_empty());
+    assert_eq!(r.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn flush() {
+    let (s, r) = unbounded();
+    let mut b = s.buffered(10);
+
+    b.flush().unwrap();
+    assert!(r.is_empty());
+
+    b.send(1).unwrap();
+    b.send(2).unwrap();
+    b.flush().unwrap();
+    assert!(b.is_empty());
+    assert_eq!(r.try_iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn drop_flushes() {
+    let (s, r) = bounded(10);
+    let mut b = s.buffered(10);
+    drop(s);
+
+    b.send(1).unwrap();
+    b.send(2).unwrap();
+    assert!(r.is_empty());
+
+    drop(b);
+    assert_eq!(r.iter().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn disconnected() {
+    let (s, r) = bounded(10);
+    let mut b = s.buffered(3);
+
+    b.send(1).unwrap();
+    b.send(2).unwrap();
+    drop(r);
+
+    assert_eq!(b.send(3), Err(SendError(vec![1, 2, 3])));
+    assert!(b.is_empty());
+
+    b.send(4).unwrap();
+    assert_eq!(b.flush(), Err(SendError(vec![4])));
+}
+
+#[test]
+fn disconnected_while_blocked() {
+    let (s, r) = bounded(2);
+    let mut b = s.buffered(5);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            assert_eq!(r.recv(), Ok(1));
+            thread::sleep(ms(100));
+            drop(r);
+        });
+
+        for i in 1..5 {
+            b.send(i).unwrap();
+        }
+
+        // Two messages fit, one of them is received, and the channel gets disconnected while the
+        // rest are waiting for room.
+        assert_eq!(b.send(5), Err(SendError(vec![4, 5])));
+    })
+    .unwrap();
+}
+
+#[test]
+fn max_delay() {
+    let (s, r) = unbounded();
+    let mut b = s.buffered(100);
+
+    b.send(1).unwrap();
+    b.set_max_delay(ms(50));
+    b.send(2).unwrap();
+    assert!(r.is_empty());
+
+    thread::sleep(ms(100));
+    b.send(3).unwrap();
+    assert_eq!(r.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+
+    // The delay starts over with the next message.
+    b.send(4).unwrap();
+    assert!(r.is_empty());
+    thread::sleep(ms(100));
+    b.send(5).unwrap();
+    assert_eq!(r.try_iter().collect::<Vec<_>>(), [4, 5]);
+}
+
+#[test]
+fn blocks_when_full() {
+    let (s, r) = bounded(4);
+    let mut b = s.buffered(10);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..10 {
+                thread::sleep(ms(10));
+                assert_eq!(r.recv(), Ok(i));
+            }
+        });
+
+        for i in 0..10 {
+            b.send(i).unwrap();
+        }
+        assert!(b.is_empty());
+    })
+    .unwrap();
+}
+
+#[test]
+fn wrap_around() {
+    let (s, r) = bounded(5);
+    let mut b = s.buffered(3);
+
+    for i in 0..30 {
+        b.send(i).unwrap();
+        if i % 3 == 2 {
+            for j in i - 2..=i {
+                assert_eq!(r.try_recv(), Ok(j));
+            }
+        }
+    }
+    assert!(r.is_empty());
+}
+
+#[test]
+fn partially_free() {
+    let (s, r) = bounded(4);
+    s.send(0).unwrap();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(r.recv(), Ok(0));
+
+    // Only two slots are free, at the end and the start of the buffer.
+    let mut b = s.buffered(2);
+    b.send(3).unwrap();
+    b.send(4).unwrap();
+    assert_eq!(r.try_iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn zero_capacity() {
+    let (s, r) = bounded(0);
+    let mut b = s.buffered(3);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..3 {
+                assert_eq!(r.recv(), Ok(i));
+            }
+        });
+
+        for i in 0..3 {
+            b.send(i).unwrap();
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "buffer capacity must be non-zero")]
+fn zero_buffer_capacity() {
+    let (s, _r) = unbounded::<i32>();
+    let _ = s.buffered(0);
+}
+
+#[test]
+fn stress() {
+    #[cfg(miri)]
+    const COUNT: usize = 100;
+    #[cfg(not(miri))]
+    const COUNT: usize = 100_000;
+    const THREADS: usize = 4;
+
+    for &cap in &[0, 1, 7, 64] {
+        let (s, r) = bounded(cap);
+        let mut seen = [0usize; THREADS];
+
+        scope(|scope| {
+            for t in 0..THREADS {
+                let s = &s;
+                scope.spawn(move |_| {
+                    let mut b = s.buffered(t * 5 + 1);
+                    for i in 0..COUNT / THREADS {
+                        b.send((t, i)).unwrap();
+                    }
+                });
+            }
+
+            for _ in 0..COUNT {
+                let (t, i) = r.recv().unwrap();
+                assert_eq!(seen[t], i);
+                seen[t] += 1;
+            }
+        })
+        .unwrap();
+
+        assert!(r.is_empty());
+    }
+
+    let (s, r) = unbounded();
+    scope(|scope| {
+        scope.spawn(|_| {
+            let mut b = s.buffered(10);
+            for i in 0..COUNT {
+                b.send(i).unwrap();
+            }
+        });
+
+        for i in 0..COUNT {
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+}
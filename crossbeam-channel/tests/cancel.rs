@@ -0,0 +1,176 @@
+//! Tests for cancellation tokens.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, cancel_token, unbounded, Select};
+use crossbeam_channel::{RecvCancelError, SendCancelError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (token, signal) = cancel_token();
+    let (s, r) = unbounded();
+
+    s.send(1).unwrap();
+    assert_eq!(r.recv_cancellable(&signal), Ok(1));
+    assert_eq!(s.send_cancellable(2, &signal), Ok(()));
+
+    assert!(!token.is_cancelled());
+    token.cancel();
+    assert!(token.is_cancelled());
+    assert!(signal.is_cancelled());
+
+    // Cancellation takes priority over ready operations.
+    assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+    assert_eq!(
+        s.send_cancellable(3, &signal),
+        Err(SendCancelError::Cancelled(3))
+    );
+    assert_eq!(r.try_recv(), Ok(2));
+}
+
+#[test]
+fn cancel_blocked_recv() {
+    let (token, signal) = cancel_token();
+    let (_s, r) = bounded::<i32>(0);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            token.cancel();
+        });
+
+        let start = Instant::now();
+        assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+        assert!(start.elapsed() >= ms(500));
+    })
+    .unwrap();
+}
+
+#[test]
+fn cancel_blocked_send() {
+    let (token, signal) = cancel_token();
+    let (s, _r) = bounded(1);
+    s.send(1).unwrap();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            token.cancel();
+        });
+
+        let start = Instant::now();
+        assert_eq!(
+            s.send_cancellable(2, &signal),
+            Err(SendCancelError::Cancelled(2))
+        );
+        assert!(start.elapsed() >= ms(500));
+    })
+    .unwrap();
+}
+
+#[test]
+fn disconnected() {
+    let (_token, signal) = cancel_token();
+    let (s, r) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            drop(s);
+        });
+
+        assert_eq!(
+            r.recv_cancellable(&signal),
+            Err(RecvCancelError::Disconnected)
+        );
+    })
+    .unwrap();
+
+    let (s, r) = bounded(0);
+    drop(r);
+    assert_eq!(
+        s.send_cancellable(1, &signal),
+        Err(SendCancelError::Disconnected(1))
+    );
+}
+
+#[test]
+fn many_threads() {
+    const THREADS: usize = 10;
+
+    let (token, signal) = cancel_token();
+    let (_s, r) = bounded::<i32>(0);
+
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+            });
+        }
+
+        thread::sleep(ms(300));
+        token.clone().cancel();
+    })
+    .unwrap();
+}
+
+#[test]
+fn select() {
+    let (token, signal) = cancel_token();
+    let (_s, r) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(300));
+            token.cancel();
+        });
+
+        let mut sel = Select::new();
+        sel.recv(&r);
+        let cancel = sel.custom(&signal);
+
+        let oper = sel.select();
+        assert_eq!(oper.index(), cancel);
+        oper.custom(&signal);
+    })
+    .unwrap();
+
+    // A cancelled token stays cancelled.
+    let mut sel = Select::new();
+    let cancel = sel.custom(&signal);
+    let oper = sel.try_select().unwrap();
+    assert_eq!(oper.index(), cancel);
+    oper.custom(&signal);
+}
+
+#[test]
+fn stress() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (token, signal) = cancel_token();
+    let (s, r) = bounded::<usize>(0);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+            token.cancel();
+        });
+
+        for i in 0..COUNT {
+            assert_eq!(r.recv_cancellable(&signal), Ok(i));
+        }
+        assert_eq!(r.recv_cancellable(&signal), Err(RecvCancelError::Cancelled));
+    })
+    .unwrap();
+}
@@ -0,0 +1,163 @@
+//! Tests for timer channels driven by custom clocks.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{
+    after_with_clock, at_with_clock, select, tick_with_clock, unbounded, Clock, MissedTicks,
+    MockClock, RecvTimeoutError, Select, SystemClock, TryRecvError,
+};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn mock_advance() {
+    let clock = MockClock::new();
+    let start = clock.now();
+
+    thread::sleep(ms(10));
+    assert_eq!(clock.now(), start);
+
+    clock.advance(ms(500));
+    assert_eq!(clock.now(), start + ms(500));
+}
+
+#[test]
+fn system_clock() {
+    let before = Instant::now();
+    let now = SystemClock.now();
+    assert!(before <= now);
+    assert!(now <= Instant::now());
+}
+
+#[test]
+fn after() {
+    let clock = Arc::new(MockClock::new());
+    let start = clock.now();
+    let r = after_with_clock(ms(100), clock.clone());
+
+    thread::sleep(ms(150));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    assert!(r.is_empty());
+
+    clock.advance(ms(99));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    clock.advance(ms(1));
+    assert_eq!(r.len(), 1);
+    assert_eq!(r.try_recv(), Ok(start + ms(100)));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn at() {
+    let clock = Arc::new(MockClock::new());
+    let when = clock.now() + ms(100);
+    let r = at_with_clock(when, clock.clone());
+
+    assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+    clock.advance(ms(100));
+    assert_eq!(r.recv_timeout(ms(50)), Ok(when));
+}
+
+#[test]
+fn recv_wakes_on_advance() {
+    let clock = Arc::new(MockClock::new());
+    let r = after_with_clock(Duration::from_secs(3600), clock.clone());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            let start = Instant::now();
+            r.recv().unwrap();
+            assert!(start.elapsed() < Duration::from_secs(10));
+        });
+
+        // Advancing by less than the duration does not deliver the message.
+        thread::sleep(ms(50));
+        clock.advance(Duration::from_secs(1800));
+        thread::sleep(ms(50));
+        clock.advance(Duration::from_secs(1800));
+    })
+    .unwrap();
+}
+
+#[test]
+fn tick() {
+    let clock = Arc::new(MockClock::new());
+    let start = clock.now();
+    let r = tick_with_clock(ms(100), MissedTicks::Delay, clock.clone());
+
+    for i in 1..=3 {
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+        clock.advance(ms(100));
+        assert_eq!(r.try_recv(), Ok(start + ms(100) * i));
+    }
+
+    // Late ticks are handled according to the policy.
+    clock.advance(ms(250));
+    assert_eq!(r.try_recv(), Ok(start + ms(400)));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    clock.advance(ms(100));
+    assert_eq!(r.try_recv(), Ok(start + ms(650)));
+}
+
+#[test]
+fn tick_recv() {
+    let clock = Arc::new(MockClock::new());
+    let start = clock.now();
+    let r = tick_with_clock(Duration::from_secs(60), MissedTicks::Burst, clock.clone());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 1..=3 {
+                assert_eq!(r.recv(), Ok(start + Duration::from_secs(60) * i));
+            }
+        });
+
+        for _ in 0..6 {
+            thread::sleep(ms(20));
+            clock.advance(Duration::from_secs(30));
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn select_timeout() {
+    let clock = Arc::new(MockClock::new());
+    let (s, r) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            let timeout = after_with_clock(Duration::from_secs(5), clock.clone());
+            select! {
+                recv(r) -> _ => panic!(),
+                recv(timeout) -> _ => {}
+            }
+        });
+
+        thread::sleep(ms(50));
+        clock.advance(Duration::from_secs(5));
+    })
+    .unwrap();
+
+    drop(s);
+}
+
+#[test]
+fn select_no_deadline() {
+    let clock = Arc::new(MockClock::new());
+    let r = after_with_clock(ms(10), clock.clone());
+
+    // Real time passing does not make a timer on a mock clock ready.
+    let mut sel = Select::new();
+    sel.recv(&r);
+    assert!(sel.ready_timeout(ms(100)).is_err());
+
+    clock.advance(ms(10));
+    assert_eq!(sel.ready_timeout(ms(1000)), Ok(0));
+}
@@ -0,0 +1,317 @@
+//! Tests for closing channels.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{
+    after, bounded, unbounded, Receiver, RecvError, RecvTimeoutError, SendError, Sender,
+    TryRecvError,
+};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Returns a channel of each flavor that can hold at least `cap` messages.
+fn channels(cap: usize) -> Vec<(Sender<usize>, Receiver<usize>)> {
+    vec![bounded(cap), unbounded()]
+}
+
+#[test]
+fn close_sender() {
+    for (s, r) in channels(10) {
+        let s2 = s.clone();
+        s.send(1).unwrap();
+        s.send(2).unwrap();
+
+        assert!(s.close());
+        assert!(!s2.close());
+        assert!(!r.close());
+        assert_eq!(s.send(3), Err(SendError(3)));
+        assert_eq!(s2.send(4), Err(SendError(4)));
+
+        assert_eq!(r.recv(), Ok(1));
+        assert_eq!(r.recv(), Ok(2));
+        assert_eq!(r.recv(), Err(RecvError));
+    }
+}
+
+#[test]
+fn close_receiver() {
+    for (s, r) in channels(10) {
+        s.send(1).unwrap();
+        assert!(r.close());
+        assert!(!s.close());
+        assert_eq!(s.send(2), Err(SendError(2)));
+
+        let r2 = r.clone();
+        assert_eq!(r2.try_recv(), Ok(1));
+        assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    }
+}
+
+#[test]
+fn drain_before_disconnect() {
+    for (s, r) in channels(100) {
+        for i in 0..100 {
+            s.send(i).unwrap();
+        }
+        s.close();
+
+        for i in 0..100 {
+            assert_eq!(r.recv_timeout(ms(0)), Ok(i));
+        }
+        assert_eq!(r.recv_timeout(ms(0)), Err(RecvTimeoutError::Disconnected));
+        assert_eq!(r.iter().count(), 0);
+    }
+}
+
+#[test]
+fn wakes_blocked() {
+    let (s, r) = bounded::<i32>(0);
+    scope(|scope| {
+        scope.spawn(|_| assert_eq!(r.recv(), Err(RecvError)));
+        thread::sleep(ms(100));
+        s.close();
+    })
+    .unwrap();
+
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    scope(|scope| {
+        scope.spawn(|_| assert_eq!(s.send(2), Err(SendError(2))));
+        thread::sleep(ms(100));
+        r.close();
+    })
+    .unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Err(RecvError));
+}
+
+#[test]
+fn remaining() {
+    for (s, r) in channels(3) {
+        assert_eq!(r.remaining(), None);
+        s.send(1).unwrap();
+        s.send(2).unwrap();
+        s.send(3).unwrap();
+        assert_eq!(r.remaining(), None);
+
+        s.close();
+        assert_eq!(r.remaining(), Some(3));
+        r.recv().unwrap();
+        assert_eq!(r.remaining(), Some(2));
+        assert_eq!(r.recv_remaining(), Some(vec![2, 3]));
+        assert_eq!(r.remaining(), Some(0));
+        assert_eq!(r.recv_remaining(), Some(vec![]));
+    }
+
+    let (s, r) = bounded::<i32>(0);
+    assert_eq!(r.remaining(), None);
+    drop(s);
+    assert_eq!(r.remaining(), Some(0));
+}
+
+#[test]
+fn recv_remaining() {
+    for (s, r) in channels(1000) {
+        assert_eq!(r.recv_remaining(), None);
+        for i in 0..1000 {
+            s.send(i).unwrap();
+        }
+        drop(s);
+
+        let mut all = scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|_| r.recv_remaining().unwrap()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+        all.sort();
+        assert_eq!(all, (0..1000).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn timer() {
+    let r = after(ms(10));
+    assert!(!r.close());
+    assert_eq!(r.remaining(), None);
+    assert_eq!(r.recv_remaining(), None);
+    assert!(r.recv().is_ok());
+}
+
+#[test]
+fn close_with() {
+    for (s, r) in channels(10) {
+        assert!(s.close_reason().is_none());
+        s.send(1).unwrap();
+
+        assert!(s.close_with("shutting down"));
+        assert!(!r.close_with("too late"));
+        assert!(!s.close());
+        assert_eq!(r.recv(), Ok(1));
+        assert_eq!(r.recv(), Err(RecvError));
+
+        assert_eq!(r.close_reason().unwrap().to_string(), "shutting down");
+        assert_eq!(s.close_reason().unwrap().to_string(), "shutting down");
+    }
+}
+
+#[test]
+fn close_reason_error() {
+    let (s, r) = bounded::<i32>(0);
+    let err = io::Error::new(io::ErrorKind::Other, "disk full");
+    assert!(r.close_with(err));
+
+    let reason = s.close_reason().unwrap();
+    let err = reason.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn close_reason_without_reason() {
+    let (s, r) = unbounded::<i32>();
+    s.close();
+    assert!(r.close_reason().is_none());
+
+    let (s, r) = unbounded::<i32>();
+    drop(s);
+    assert!(!r.close_with("after disconnection"));
+    assert!(r.close_reason().is_none());
+
+    let r = after(ms(10));
+    assert!(!r.close_with("timer"));
+    assert!(r.close_reason().is_none());
+}
+
+#[test]
+fn close_reason_visible_on_disconnect() {
+    for _ in 0..100 {
+        let (s, r) = bounded::<i32>(0);
+        scope(|scope| {
+            scope.spawn(|_| {
+                assert_eq!(r.recv(), Err(RecvError));
+                assert_eq!(r.close_reason().unwrap().to_string(), "done");
+            });
+            s.close_with("done");
+        })
+        .unwrap();
+    }
+}
+
+#[test]
+fn reopen() {
+    for (s, r) in channels(10) {
+        assert!(!s.reopen());
+        s.send(1).unwrap();
+        assert!(s.close_with("pause"));
+
+        // Messages left from before closing keep the channel closed.
+        assert!(!r.reopen());
+        assert_eq!(r.recv(), Ok(1));
+        assert!(r.reopen());
+        assert!(!s.reopen());
+        assert!(r.close_reason().is_none());
+
+        s.send(2).unwrap();
+        assert_eq!(r.recv(), Ok(2));
+        assert!(s.close());
+        assert_eq!(r.recv(), Err(RecvError));
+    }
+
+    let (s, r) = bounded(0);
+    assert!(r.close());
+    assert!(s.reopen());
+    scope(|scope| {
+        scope.spawn(|_| s.send(1).unwrap());
+        assert_eq!(r.recv(), Ok(1));
+    })
+    .unwrap();
+}
+
+#[test]
+fn reopen_after_disconnect() {
+    for (s, r) in channels(10) {
+        drop(s);
+        assert!(!r.reopen());
+        assert_eq!(r.recv(), Err(RecvError));
+    }
+    for (s, r) in channels(10) {
+        drop(r);
+        assert!(!s.reopen());
+        assert_eq!(s.send(1), Err(SendError(1)));
+    }
+    assert!(!after(ms(10)).reopen());
+}
+
+#[test]
+fn reopen_many_times() {
+    for (s, r) in channels(1) {
+        scope(|scope| {
+            scope.spawn(|_| {
+                for i in 0..100 {
+                    // Wait for the receiver to reopen the channel before sending the next message.
+                    let mut msg = i;
+                    while let Err(SendError(m)) = s.send(msg) {
+                        msg = m;
+                        thread::yield_now();
+                    }
+                    s.close();
+                }
+            });
+            for i in 0..100 {
+                assert_eq!(r.recv(), Ok(i));
+                // Wait for the sender to close the channel, then reopen it for the next message.
+                while !r.reopen() {
+                    thread::yield_now();
+                }
+            }
+        })
+        .unwrap();
+    }
+}
+
+#[test]
+fn close_while_sending_zero_sized() {
+    #[cfg(miri)]
+    const RUNS: usize = 5;
+    #[cfg(not(miri))]
+    const RUNS: usize = 1000;
+    const THREADS: usize = 4;
+
+    for _ in 0..RUNS {
+        let (s, r) = bounded::<()>(2);
+        let sent = AtomicUsize::new(0);
+        let mut received = 0;
+
+        scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|_| {
+                    while s.send(()).is_ok() {
+                        sent.fetch_add(1, Ordering::SeqCst);
+                    }
+                });
+            }
+            scope.spawn(|_| {
+                thread::yield_now();
+                s.close();
+            });
+
+            // Every send that succeeded before the channel was closed must be received.
+            while r.recv().is_ok() {
+                received += 1;
+            }
+        })
+        .unwrap();
+
+        assert_eq!(received, sent.load(Ordering::SeqCst));
+    }
+}
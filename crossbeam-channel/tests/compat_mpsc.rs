@@ -0,0 +1,123 @@
+//! Tests for the `compat::mpsc` module.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::compat::mpsc::{channel, sync_channel, Receiver, Sender};
+use crossbeam_channel::compat::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use crossbeam_channel::compat::mpsc::{SyncSender, TrySendError};
+use crossbeam_channel::{select, unbounded};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (tx, rx) = channel();
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(rx.recv_timeout(ms(10)), Err(RecvTimeoutError::Timeout));
+
+    drop(tx);
+    assert_eq!(rx.recv(), Err(RecvError));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(rx.recv_timeout(ms(10)), Err(RecvTimeoutError::Disconnected));
+}
+
+#[test]
+fn std_types() {
+    // The same function works with channels from the standard library and from this module.
+    fn pipe<T>(tx: std::sync::mpsc::Sender<T>, rx: std::sync::mpsc::Receiver<T>) {
+        let _ = (tx, rx);
+    }
+    fn errors<T>(e: SendError<T>) -> std::sync::mpsc::SendError<T> {
+        e
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel::<i32>();
+    pipe(tx, rx);
+
+    let (tx, rx) = channel();
+    drop(rx);
+    assert_eq!(errors(tx.send(7).unwrap_err()).0, 7);
+}
+
+#[test]
+fn sync_channel_full() {
+    let (tx, rx) = sync_channel(1);
+    assert_eq!(tx.try_send(1), Ok(()));
+    assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+    assert_eq!(rx.recv(), Ok(1));
+
+    drop(rx);
+    assert_eq!(tx.try_send(3), Err(TrySendError::Disconnected(3)));
+    assert_eq!(tx.send(4), Err(SendError(4)));
+}
+
+#[test]
+fn rendezvous() {
+    let (tx, rx) = sync_channel(0);
+    assert_eq!(tx.try_send(1), Err(TrySendError::Full(1)));
+
+    scope(|scope| {
+        scope.spawn(|_| tx.send(2).unwrap());
+        assert_eq!(rx.recv(), Ok(2));
+    })
+    .unwrap();
+}
+
+#[test]
+fn iter() {
+    let (tx, rx) = channel();
+    let t = thread::spawn(move || {
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+    });
+    t.join().unwrap();
+
+    assert_eq!(rx.try_iter().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+    assert_eq!((&rx).into_iter().next(), Some(3));
+    assert_eq!(rx.iter().next(), Some(4));
+    assert_eq!(rx.into_iter().sum::<i32>(), 35);
+}
+
+#[test]
+fn conversions() {
+    let (tx, rx) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(1);
+
+    let s: crossbeam_channel::Sender<i32> = tx.into();
+    let s2: crossbeam_channel::Sender<i32> = tx2.into();
+    let r: crossbeam_channel::Receiver<i32> = rx.into();
+    let r2: crossbeam_channel::Receiver<i32> = rx2.into();
+    assert_eq!(s.capacity(), None);
+    assert_eq!(s2.capacity(), Some(1));
+
+    s2.send(2).unwrap();
+    select! {
+        recv(r) -> _ => panic!(),
+        recv(r2) -> msg => assert_eq!(msg, Ok(2)),
+    }
+
+    let (s, r) = unbounded();
+    let tx = Sender::from(s.clone());
+    let tx2 = SyncSender::from(s);
+    let rx = Receiver::from(r);
+    tx.send(1).unwrap();
+    tx2.send(2).unwrap();
+    assert_eq!(rx.iter().take(2).collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn debug() {
+    let (tx, rx) = channel::<i32>();
+    let (tx2, _rx2) = sync_channel::<i32>(1);
+    assert_eq!(format!("{:?}", tx), "Sender { .. }");
+    assert_eq!(format!("{:?}", tx2), "SyncSender { .. }");
+    assert_eq!(format!("{:?}", rx), "Receiver { .. }");
+    assert_eq!(format!("{:?}", rx.try_iter()), "TryIter { .. }");
+}
@@ -0,0 +1,129 @@
+//! Tests for deadlock detection.
+
+#![cfg(feature = "deadlock-detection")]
+
+use std::any::Any;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, select, unbounded};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn is_deadlock(payload: &(dyn Any + Send)) -> bool {
+    match payload.downcast_ref::<String>() {
+        Some(msg) => msg.starts_with("deadlock detected"),
+        None => false,
+    }
+}
+
+#[test]
+fn recv() {
+    let (s1, r1) = bounded(0);
+    let (s2, r2) = bounded(0);
+
+    let a = thread::Builder::new()
+        .name("a".to_string())
+        .spawn(move || {
+            s1.send(()).unwrap();
+            r2.recv().unwrap();
+            r2.recv().map(|_| drop(s1))
+        })
+        .unwrap();
+    let b = thread::Builder::new()
+        .name("b".to_string())
+        .spawn(move || {
+            r1.recv().unwrap();
+            s2.send(()).unwrap();
+            r1.recv().map(|_| drop(s2))
+        })
+        .unwrap();
+
+    // One of the threads panics, which disconnects the channel the other one is blocked on.
+    let a = a.join();
+    let b = b.join();
+    match (a, b) {
+        (Err(p), Ok(r)) | (Ok(r), Err(p)) => {
+            assert!(is_deadlock(&*p));
+            assert!(r.is_err());
+        }
+        _ => panic!("expected exactly one thread to panic"),
+    }
+}
+
+#[test]
+fn send() {
+    let (s1, r1) = bounded(1);
+    let (s2, r2) = bounded(1);
+
+    let a = thread::spawn(move || {
+        s1.send(1).unwrap();
+        r2.recv().unwrap();
+        s1.send(2).unwrap();
+        s1.send(3).map(|_| drop(r2))
+    });
+    let b = thread::spawn(move || {
+        s2.send(1).unwrap();
+        r1.recv().unwrap();
+        s2.send(2).unwrap();
+        s2.send(3).map(|_| drop(r1))
+    });
+
+    let a = a.join();
+    let b = b.join();
+    match (a, b) {
+        (Err(p), Ok(r)) | (Ok(r), Err(p)) => {
+            assert!(is_deadlock(&*p));
+            assert!(r.is_err());
+        }
+        _ => panic!("expected exactly one thread to panic"),
+    }
+}
+
+#[test]
+fn select() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    let a = thread::spawn(move || {
+        s1.send(1).unwrap();
+        r2.recv().unwrap();
+        select! {
+            recv(r2) -> msg => msg.map(|_| drop(s1)).is_ok(),
+        }
+    });
+    let b = thread::spawn(move || {
+        s2.send(1).unwrap();
+        r1.recv().unwrap();
+        select! {
+            recv(r1) -> msg => msg.map(|_| drop(s2)).is_ok(),
+        }
+    });
+
+    let a = a.join();
+    let b = b.join();
+    match (a, b) {
+        (Err(p), Ok(r)) | (Ok(r), Err(p)) => {
+            assert!(is_deadlock(&*p));
+            assert!(!r);
+        }
+        _ => panic!("expected exactly one thread to panic"),
+    }
+}
+
+#[test]
+fn sleeping_sender() {
+    let (s, r) = bounded(0);
+
+    let t = thread::spawn(move || {
+        s.send(1).unwrap();
+        thread::sleep(ms(4000));
+        s.send(2).unwrap();
+    });
+
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Ok(2));
+    t.join().unwrap();
+}
@@ -0,0 +1,105 @@
+//! Tests for debounced channels.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{debounce, select, unbounded, RecvTimeoutError, TryRecvError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(50));
+
+    s.send(7).unwrap();
+    assert_eq!(r.recv(), Ok(7));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn delivers_latest() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(100));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..10 {
+                s.send(i).unwrap();
+                thread::sleep(ms(10));
+            }
+        });
+
+        assert_eq!(r.recv(), Ok(9));
+    })
+    .unwrap();
+
+    assert_eq!(r.recv_timeout(ms(200)), Err(RecvTimeoutError::Timeout));
+}
+
+#[test]
+fn waits_for_quiet_period() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(200));
+
+    let start = Instant::now();
+    s.send(1).unwrap();
+    thread::sleep(ms(100));
+    s.send(2).unwrap();
+
+    assert_eq!(r.recv(), Ok(2));
+    assert!(start.elapsed() >= ms(300));
+}
+
+#[test]
+fn separate_bursts() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(50));
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(r.recv(), Ok(2));
+
+    s.send(3).unwrap();
+    s.send(4).unwrap();
+    assert_eq!(r.recv(), Ok(4));
+}
+
+#[test]
+fn disconnect_flushes() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(10_000));
+
+    let start = Instant::now();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    drop(s);
+
+    assert_eq!(r.recv(), Ok(2));
+    assert!(r.recv().is_err());
+    assert!(start.elapsed() < ms(1000));
+}
+
+#[test]
+fn disconnect_empty() {
+    let (s, r) = unbounded::<i32>();
+    let r = debounce(r, ms(50));
+
+    drop(s);
+    assert!(r.recv().is_err());
+}
+
+#[test]
+fn select() {
+    let (s, r) = unbounded();
+    let r = debounce(r, ms(50));
+
+    s.send(1).unwrap();
+    select! {
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+        default(ms(1000)) => panic!(),
+    }
+}
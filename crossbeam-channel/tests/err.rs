@@ -0,0 +1,97 @@
+//! Tests for error types.
+
+use std::error::Error;
+use std::time::Duration;
+
+use crossbeam_channel::{
+    bounded, unbounded, ReadyTimeoutError, RecvTimeoutError, Select, SelectTimeoutError,
+    SendTimeoutError, Sender, TryReadyError, TryRecvError, TrySelectError, TrySendError,
+};
+
+#[test]
+fn send_conversions() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+
+    let err = SendTimeoutError::from(s.try_send(2).unwrap_err());
+    assert_eq!(err, SendTimeoutError::Timeout(2));
+    let err = TrySendError::from(s.send_timeout(3, Duration::from_millis(10)).unwrap_err());
+    assert_eq!(err, TrySendError::Full(3));
+
+    drop(r);
+    let err = SendTimeoutError::from(s.try_send(4).unwrap_err());
+    assert_eq!(err, SendTimeoutError::Disconnected(4));
+    let err = TrySendError::from(err);
+    assert_eq!(err, TrySendError::Disconnected(4));
+    assert_eq!(err.into_inner(), 4);
+}
+
+#[test]
+fn recv_conversions() {
+    let (s, r) = unbounded::<i32>();
+    assert_eq!(
+        RecvTimeoutError::from(r.try_recv().unwrap_err()),
+        RecvTimeoutError::Timeout
+    );
+    assert_eq!(
+        TryRecvError::from(r.recv_timeout(Duration::from_millis(10)).unwrap_err()),
+        TryRecvError::Empty
+    );
+
+    drop(s);
+    assert_eq!(
+        RecvTimeoutError::from(r.try_recv().unwrap_err()),
+        RecvTimeoutError::Disconnected
+    );
+    assert_eq!(
+        TryRecvError::from(RecvTimeoutError::Disconnected),
+        TryRecvError::Disconnected
+    );
+}
+
+#[test]
+fn question_mark() {
+    fn forward(s: &Sender<i32>) -> Result<(), SendTimeoutError<i32>> {
+        s.try_send(1)?;
+        Ok(())
+    }
+
+    let (s, _r) = bounded(0);
+    assert_eq!(forward(&s), Err(SendTimeoutError::Timeout(1)));
+}
+
+#[test]
+fn select_errors() {
+    let (_s, r) = unbounded::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&r);
+
+    assert_eq!(
+        SelectTimeoutError::from(sel.try_select().unwrap_err()),
+        SelectTimeoutError
+    );
+    assert_eq!(TrySelectError::from(SelectTimeoutError), TrySelectError);
+    assert_eq!(
+        ReadyTimeoutError::from(sel.try_ready().unwrap_err()),
+        ReadyTimeoutError
+    );
+    assert_eq!(TryReadyError::from(ReadyTimeoutError), TryReadyError);
+}
+
+#[test]
+fn display() {
+    let errors: Vec<Box<dyn Error>> = vec![
+        Box::new(TryReadyError),
+        Box::new(ReadyTimeoutError),
+        Box::new(TrySelectError),
+        Box::new(SelectTimeoutError),
+    ];
+    for err in errors {
+        assert!(!err.to_string().is_empty());
+    }
+    assert_eq!(
+        TryReadyError.to_string(),
+        "all operations in select would block"
+    );
+    assert_eq!(ReadyTimeoutError.to_string(), "timed out waiting on select");
+}
@@ -0,0 +1,165 @@
+//! Tests for channel event logs.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, select, tick, unbounded, EventKind};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn kinds(events: &[crossbeam_channel::ChannelEvent]) -> Vec<EventKind> {
+    events.iter().map(|e| e.kind()).collect()
+}
+
+#[test]
+fn disabled() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+    r.recv().unwrap();
+    assert!(s.events().is_empty());
+    assert!(r.events().is_empty());
+}
+
+#[test]
+fn send_recv() {
+    let (s, r) = bounded(2);
+    r.record_events(10);
+    s.send(1).unwrap();
+    s.try_send(2).unwrap();
+    r.recv().unwrap();
+    r.try_recv().unwrap();
+
+    let events = s.events();
+    assert_eq!(
+        kinds(&events),
+        [
+            EventKind::Send,
+            EventKind::Send,
+            EventKind::Recv,
+            EventKind::Recv,
+        ]
+    );
+    assert!(events
+        .iter()
+        .all(|e| e.thread_id() == thread::current().id()));
+    assert!(events.windows(2).all(|w| w[0].time() <= w[1].time()));
+}
+
+#[test]
+fn ring() {
+    let (s, r) = unbounded();
+    s.record_events(3);
+    for i in 0..5 {
+        s.send(i).unwrap();
+    }
+    r.recv().unwrap();
+    assert_eq!(
+        kinds(&r.events()),
+        [EventKind::Send, EventKind::Send, EventKind::Recv]
+    );
+}
+
+#[test]
+fn restart_and_stop() {
+    let (s, r) = unbounded();
+    s.record_events(10);
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    s.record_events(10);
+    r.recv().unwrap();
+    assert_eq!(kinds(&s.events()), [EventKind::Recv]);
+
+    s.record_events(0);
+    r.recv().unwrap();
+    assert!(s.events().is_empty());
+}
+
+#[test]
+fn disconnect() {
+    let (s, r) = unbounded::<i32>();
+    let r2 = r.clone();
+    r.record_events(10);
+    drop(s);
+    drop(r);
+    assert_eq!(kinds(&r2.events()), [EventKind::SendersDisconnected]);
+
+    let (s, r) = bounded::<i32>(1);
+    s.record_events(10);
+    let s2 = s.clone();
+    drop(r);
+    assert_eq!(kinds(&s2.events()), [EventKind::ReceiversDisconnected]);
+    drop(s);
+}
+
+#[test]
+fn park() {
+    let (s, r) = bounded(0);
+    s.record_events(10);
+
+    let t = thread::Builder::new()
+        .name("receiver".to_string())
+        .spawn(move || {
+            assert_eq!(r.recv(), Ok(1));
+            r
+        })
+        .unwrap();
+    thread::sleep(ms(100));
+    s.send(1).unwrap();
+    let r = t.join().unwrap();
+
+    // The sender and the woken receiver record their events concurrently.
+    let events = r.events();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].kind(), EventKind::ParkRecv);
+    assert_eq!(events[0].thread_name(), Some("receiver"));
+    for e in &events[1..] {
+        match e.kind() {
+            EventKind::Send => assert_eq!(e.thread_id(), thread::current().id()),
+            EventKind::Recv => assert_eq!(e.thread_name(), Some("receiver")),
+            kind => panic!("unexpected event {:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn park_send() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    s.record_events(10);
+
+    let t = thread::spawn(move || s.send(2).unwrap());
+    thread::sleep(ms(100));
+    r.recv().unwrap();
+    t.join().unwrap();
+
+    let kinds = kinds(&r.events());
+    assert_eq!(kinds.len(), 4);
+    assert_eq!(kinds[0], EventKind::ParkSend);
+    assert!(kinds.contains(&EventKind::Recv));
+    assert!(kinds.contains(&EventKind::Send));
+    assert!(kinds.contains(&EventKind::SendersDisconnected));
+}
+
+#[test]
+fn select() {
+    let (s, r) = unbounded();
+    r.record_events(10);
+    select! {
+        send(s, 1) -> res => res.unwrap(),
+    }
+    select! {
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+    }
+    assert_eq!(kinds(&r.events()), [EventKind::Send, EventKind::Recv]);
+}
+
+#[test]
+fn timer() {
+    let r = tick(ms(10));
+    r.record_events(10);
+    r.recv().unwrap();
+    assert!(r.events().is_empty());
+}
@@ -0,0 +1,201 @@
+//! Tests for channels that watch file descriptors.
+
+#![cfg(all(unix, feature = "fd", not(miri)))]
+
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_utils::thread::scope;
+
+use crossbeam_channel::{
+    bounded, readable, select, unbounded, writable, ReadyFd, RecvTimeoutError, TryRecvError,
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    let r = readable(b.as_raw_fd());
+
+    assert_eq!(r.recv_timeout(ms(100)), Err(RecvTimeoutError::Timeout));
+
+    a.write_all(b"x").unwrap();
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(()));
+
+    let mut buf = [0; 1];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"x");
+}
+
+#[test]
+fn writable_socket() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    let r = writable(a.as_raw_fd());
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(()));
+}
+
+#[test]
+fn select_with_channel() {
+    let (mut a, b) = UnixStream::pair().unwrap();
+    let (s, r) = unbounded::<i32>();
+    let ready = readable(b.as_raw_fd());
+
+    s.send(7).unwrap();
+    select! {
+        recv(ready) -> _ => panic!(),
+        recv(r) -> msg => assert_eq!(msg, Ok(7)),
+    }
+
+    let t = thread::spawn(move || {
+        thread::sleep(ms(100));
+        a.write_all(b"x").unwrap();
+        a
+    });
+
+    select! {
+        recv(ready) -> msg => assert_eq!(msg, Ok(())),
+        recv(r) -> _ => panic!(),
+        default(ms(1000)) => panic!(),
+    }
+
+    t.join().unwrap();
+}
+
+#[test]
+fn drop_receiver() {
+    let (_a, b) = UnixStream::pair().unwrap();
+
+    for _ in 0..10 {
+        drop(readable(b.as_raw_fd()));
+    }
+
+    // Give the polling threads a chance to wake up and exit.
+    thread::sleep(ms(300));
+}
+
+#[test]
+fn invalid_fd() {
+    let r = readable(0x3fff_ffff);
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+/// Returns `true` if the descriptor of `ready` becomes readable within `timeout`.
+fn is_readable<T>(ready: &ReadyFd<T>, timeout: Duration) -> bool {
+    readable(ready.as_raw_fd()).recv_timeout(timeout).is_ok()
+}
+
+#[test]
+fn ready_fd_smoke() {
+    let (s, r) = unbounded();
+    let ready = r.ready_fd().unwrap();
+    assert!(!is_readable(&ready, ms(100)));
+
+    s.send(1).unwrap();
+    assert!(is_readable(&ready, ms(1000)));
+
+    ready.clear();
+    assert!(!is_readable(&ready, ms(100)));
+    assert_eq!(r.try_recv(), Ok(1));
+}
+
+#[test]
+fn ready_fd_stays_readable() {
+    let (s, r) = unbounded();
+    let ready = r.ready_fd().unwrap();
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(r.try_recv(), Ok(1));
+    assert_eq!(r.try_recv(), Ok(2));
+
+    // Readiness is only reset by `clear`.
+    assert!(is_readable(&ready, ms(1000)));
+    ready.clear();
+    assert!(!is_readable(&ready, ms(100)));
+}
+
+#[test]
+fn ready_fd_non_empty() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+
+    let ready = r.ready_fd().unwrap();
+    assert!(is_readable(&ready, ms(1000)));
+}
+
+#[test]
+fn ready_fd_disconnect() {
+    let (s, r) = unbounded::<i32>();
+    let ready = r.ready_fd().unwrap();
+
+    drop(s);
+    assert!(is_readable(&ready, ms(1000)));
+
+    ready.clear();
+    assert_eq!(ready.receiver().try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn ready_fd_zero() {
+    let (s, r) = bounded(0);
+    let ready = r.ready_fd().unwrap();
+
+    scope(|scope| {
+        scope.spawn(|_| s.send(7).unwrap());
+
+        assert!(is_readable(&ready, ms(1000)));
+        ready.clear();
+        assert_eq!(r.recv(), Ok(7));
+    })
+    .unwrap();
+}
+
+#[test]
+fn ready_fd_keeps_channel() {
+    let (s, r) = unbounded();
+    let ready = r.ready_fd().unwrap();
+    drop(r);
+
+    s.send(1).unwrap();
+    assert!(is_readable(&ready, ms(1000)));
+    assert_eq!(ready.receiver().try_recv(), Ok(1));
+
+    drop(ready);
+    assert!(s.send(2).is_err());
+}
+
+#[test]
+fn ready_fd_event_loop() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(10);
+    let ready = r.ready_fd().unwrap();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+        });
+
+        let mut next = 0;
+        while next < COUNT {
+            assert!(is_readable(&ready, ms(5000)));
+            ready.clear();
+            while let Ok(i) = r.try_recv() {
+                assert_eq!(i, next);
+                next += 1;
+            }
+        }
+    })
+    .unwrap();
+}
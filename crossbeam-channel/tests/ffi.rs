@@ -0,0 +1,149 @@
+//! Tests for the C interface.
+
+#![cfg(feature = "ffi")]
+
+use std::ptr;
+use std::slice;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::ffi::*;
+use crossbeam_channel::unbounded;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Receives a message through the C interface and copies it into a vector.
+fn recv_timeout(chan: *const Channel, timeout_ms: u64) -> Result<Vec<u8>, i32> {
+    let mut data = ptr::null_mut();
+    let mut len = 0;
+    unsafe {
+        match crossbeam_channel_recv_timeout(chan, timeout_ms, &mut data, &mut len) {
+            CROSSBEAM_CHANNEL_OK => {
+                let msg = slice::from_raw_parts(data, len).to_vec();
+                crossbeam_channel_free_message(data, len);
+                Ok(msg)
+            }
+            status => Err(status),
+        }
+    }
+}
+
+fn send(chan: *const Channel, msg: &[u8]) -> i32 {
+    unsafe { crossbeam_channel_send(chan, msg.as_ptr(), msg.len()) }
+}
+
+#[test]
+fn smoke() {
+    let chan = crossbeam_channel_unbounded();
+    assert_eq!(send(chan, b"abc"), CROSSBEAM_CHANNEL_OK);
+    assert_eq!(send(chan, b""), CROSSBEAM_CHANNEL_OK);
+
+    let mut data = ptr::null_mut();
+    let mut len = 0;
+    unsafe {
+        assert_eq!(
+            crossbeam_channel_recv(chan, &mut data, &mut len),
+            CROSSBEAM_CHANNEL_OK
+        );
+        assert_eq!(slice::from_raw_parts(data, len), b"abc");
+        crossbeam_channel_free_message(data, len);
+    }
+    assert_eq!(recv_timeout(chan, 0), Ok(Vec::new()));
+    assert_eq!(recv_timeout(chan, 10), Err(CROSSBEAM_CHANNEL_TIMEOUT));
+
+    unsafe { crossbeam_channel_destroy(chan) };
+}
+
+#[test]
+fn close() {
+    let chan = crossbeam_channel_bounded(2);
+    assert_eq!(send(chan, b"1"), CROSSBEAM_CHANNEL_OK);
+
+    unsafe {
+        assert_eq!(crossbeam_channel_close(chan), CROSSBEAM_CHANNEL_OK);
+        assert_eq!(
+            crossbeam_channel_close(chan),
+            CROSSBEAM_CHANNEL_DISCONNECTED
+        );
+    }
+    assert_eq!(send(chan, b"2"), CROSSBEAM_CHANNEL_DISCONNECTED);
+    assert_eq!(recv_timeout(chan, 0), Ok(b"1".to_vec()));
+    assert_eq!(recv_timeout(chan, 0), Err(CROSSBEAM_CHANNEL_DISCONNECTED));
+
+    unsafe { crossbeam_channel_destroy(chan) };
+}
+
+#[test]
+fn null() {
+    let mut data = ptr::null_mut();
+    let mut len = 0;
+    unsafe {
+        assert_eq!(
+            crossbeam_channel_send(ptr::null(), ptr::null(), 0),
+            CROSSBEAM_CHANNEL_INVALID
+        );
+        assert_eq!(
+            crossbeam_channel_recv(ptr::null(), &mut data, &mut len),
+            CROSSBEAM_CHANNEL_INVALID
+        );
+        assert_eq!(
+            crossbeam_channel_close(ptr::null()),
+            CROSSBEAM_CHANNEL_INVALID
+        );
+        crossbeam_channel_free_message(ptr::null_mut(), 0);
+        crossbeam_channel_destroy(ptr::null_mut());
+
+        let chan = crossbeam_channel_unbounded();
+        assert_eq!(
+            crossbeam_channel_send(chan, ptr::null(), 1),
+            CROSSBEAM_CHANNEL_INVALID
+        );
+        assert_eq!(
+            crossbeam_channel_recv_timeout(chan, 0, ptr::null_mut(), &mut len),
+            CROSSBEAM_CHANNEL_INVALID
+        );
+        crossbeam_channel_destroy(chan);
+    }
+}
+
+#[test]
+fn rust_threads() {
+    let (s1, r1) = unbounded();
+    let (s2, r2) = unbounded();
+    let requests = Channel::new(s1.clone(), r1).into_raw() as usize;
+    let replies = Channel::new(s2, r2.clone()).into_raw() as usize;
+
+    // A "C" thread replies to every request until the requests are closed.
+    let t = thread::spawn(move || {
+        let requests = requests as *mut Channel;
+        let replies = replies as *mut Channel;
+        while let Ok(msg) = recv_timeout(requests, 1_000) {
+            let mut reply = b"re:".to_vec();
+            reply.extend(msg);
+            assert_eq!(send(replies, &reply), CROSSBEAM_CHANNEL_OK);
+        }
+        unsafe {
+            crossbeam_channel_destroy(requests);
+            crossbeam_channel_destroy(replies);
+        }
+    });
+
+    s1.send(b"ping".to_vec()).unwrap();
+    assert_eq!(r2.recv_timeout(ms(1_000)), Ok(b"re:ping".to_vec()));
+
+    s1.close();
+    t.join().unwrap();
+    assert!(r2.recv().is_err());
+}
+
+#[test]
+fn from_raw() {
+    let chan = crossbeam_channel_bounded(1);
+    assert_eq!(send(chan, b"x"), CROSSBEAM_CHANNEL_OK);
+
+    let chan = unsafe { Channel::from_raw(chan) };
+    assert!(chan.sender().is_full());
+    assert_eq!(chan.receiver().recv(), Ok(b"x".to_vec()));
+}
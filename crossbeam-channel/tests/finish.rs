@@ -0,0 +1,80 @@
+//! Tests for finishing senders and counting handles.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, never, tick, unbounded, Receiver, RecvError, Sender};
+
+/// Returns a channel of each flavor.
+fn channels() -> Vec<(Sender<usize>, Receiver<usize>)> {
+    vec![bounded(0), bounded(10), unbounded()]
+}
+
+#[test]
+fn counts() {
+    for (s, r) in channels() {
+        assert_eq!(r.sender_count(), 1);
+        assert_eq!(s.receiver_count(), 1);
+
+        let s2 = s.clone();
+        let r2 = r.clone();
+        assert_eq!(r.sender_count(), 2);
+        assert_eq!(r2.sender_count(), 2);
+        assert_eq!(s2.receiver_count(), 2);
+
+        s.finish();
+        assert_eq!(r.sender_count(), 1);
+        drop(r2);
+        assert_eq!(s2.receiver_count(), 1);
+
+        s2.finish();
+        assert_eq!(r.sender_count(), 0);
+        assert_eq!(r.recv(), Err(RecvError));
+    }
+}
+
+#[test]
+fn other_senders_keep_working() {
+    let (s1, r) = unbounded();
+    let s2 = s1.clone();
+    s1.send(1).unwrap();
+    s1.finish();
+
+    s2.send(2).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Ok(2));
+    assert!(r.try_recv().unwrap_err().is_empty());
+}
+
+#[test]
+fn fan_in() {
+    for (s, r) in channels() {
+        let producers = (0..4)
+            .map(|i| {
+                let s = s.clone();
+                thread::spawn(move || {
+                    for j in 0..100 {
+                        s.send(i * 100 + j).unwrap();
+                    }
+                    s.finish();
+                })
+            })
+            .collect::<Vec<_>>();
+        s.finish();
+
+        let mut received = r.iter().collect::<Vec<_>>();
+        assert_eq!(r.sender_count(), 0);
+        received.sort();
+        assert_eq!(received, (0..400).collect::<Vec<_>>());
+
+        for p in producers {
+            p.join().unwrap();
+        }
+    }
+}
+
+#[test]
+fn timers() {
+    assert_eq!(tick(Duration::from_secs(1)).sender_count(), 0);
+    assert_eq!(never::<i32>().sender_count(), 0);
+}
@@ -0,0 +1,324 @@
+//! Tests for `send_async` and `recv_async`.
+
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{after, bounded, unbounded, RecvError, SendError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Wakes up a thread and counts how many times that happened.
+struct Unparker {
+    thread: Thread,
+    wakes: AtomicUsize,
+}
+
+unsafe fn clone(p: *const ()) -> RawWaker {
+    let arc = Arc::from_raw(p as *const Unparker);
+    let raw = RawWaker::new(Arc::into_raw(arc.clone()) as *const (), &VTABLE);
+    let _ = Arc::into_raw(arc);
+    raw
+}
+
+unsafe fn wake(p: *const ()) {
+    wake_by_ref(p);
+    drop_waker(p);
+}
+
+unsafe fn wake_by_ref(p: *const ()) {
+    let unparker = &*(p as *const Unparker);
+    unparker.wakes.fetch_add(1, Ordering::SeqCst);
+    unparker.thread.unpark();
+}
+
+unsafe fn drop_waker(p: *const ()) {
+    drop(Arc::from_raw(p as *const Unparker));
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_waker);
+
+fn waker(unparker: &Arc<Unparker>) -> Waker {
+    let raw = RawWaker::new(Arc::into_raw(unparker.clone()) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+fn unparker() -> Arc<Unparker> {
+    Arc::new(Unparker {
+        thread: thread::current(),
+        wakes: AtomicUsize::new(0),
+    })
+}
+
+/// Runs a future to completion on the current thread.
+fn block_on<F: Future>(mut f: F) -> F::Output {
+    let unparker = unparker();
+    let waker = waker(&unparker);
+    let mut cx = Context::from_waker(&waker);
+    let mut f = unsafe { Pin::new_unchecked(&mut f) };
+
+    loop {
+        if let Poll::Ready(out) = f.as_mut().poll(&mut cx) {
+            return out;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future + Unpin>(f: &mut F, waker: &Waker) -> Poll<F::Output> {
+    Pin::new(f).poll(&mut Context::from_waker(waker))
+}
+
+#[test]
+fn recv_ready() {
+    let (s, r) = unbounded();
+    s.send(7).unwrap();
+    assert_eq!(block_on(r.recv_async()), Ok(7));
+}
+
+#[test]
+fn recv_disconnected() {
+    let (s, r) = unbounded::<i32>();
+    s.send(1).unwrap();
+    drop(s);
+
+    assert_eq!(block_on(r.recv_async()), Ok(1));
+    assert_eq!(block_on(r.recv_async()), Err(RecvError));
+}
+
+#[test]
+fn recv_wakes_on_send() {
+    let (s, r) = bounded(1);
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = r.recv_async();
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 0);
+
+    s.send(3).unwrap();
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Ok(3)));
+}
+
+#[test]
+fn recv_wakes_on_disconnect() {
+    let (s, r) = unbounded::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = r.recv_async();
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+
+    drop(s);
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Err(RecvError)));
+}
+
+#[test]
+fn recv_timer() {
+    let start = Instant::now();
+    let r = after(ms(50));
+
+    assert!(block_on(r.recv_async()).is_ok());
+    assert!(start.elapsed() >= ms(50));
+}
+
+#[test]
+fn send_ready() {
+    let (s, r) = bounded(1);
+    assert_eq!(block_on(s.send_async(1)), Ok(()));
+    assert_eq!(r.try_recv(), Ok(1));
+}
+
+#[test]
+fn send_disconnected() {
+    let (s, r) = bounded(1);
+    drop(r);
+    assert_eq!(block_on(s.send_async(5)), Err(SendError(5)));
+}
+
+#[test]
+fn send_wakes_on_recv() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = s.send_async(2);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 0);
+
+    assert_eq!(r.recv(), Ok(1));
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Ok(())));
+    assert_eq!(r.try_recv(), Ok(2));
+}
+
+#[test]
+fn send_wakes_on_disconnect() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = s.send_async(2);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+
+    drop(r);
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Err(SendError(2))));
+}
+
+#[test]
+fn drop_send_future() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = s.send_async(2);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+    drop(fut);
+
+    assert_eq!(r.try_recv(), Ok(1));
+    assert!(r.try_recv().is_err());
+}
+
+#[test]
+fn send_future_into_inner() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = s.send_async(2);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+    assert_eq!(fut.into_inner(), Some(2));
+
+    let mut fut = s.send_async(3);
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Ok(())));
+    assert_eq!(fut.into_inner(), None);
+    assert_eq!(r.try_recv(), Ok(3));
+}
+
+#[test]
+fn drop_recv_future() {
+    let (s, r) = unbounded();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = r.recv_async();
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Pending);
+    s.send(1).unwrap();
+    drop(fut);
+
+    // The message wasn't taken by the dropped future.
+    assert_eq!(r.try_recv(), Ok(1));
+}
+
+#[test]
+#[should_panic(expected = "polled after completion")]
+fn send_polled_after_completion() {
+    let (s, _r) = unbounded();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut fut = s.send_async(1);
+    assert_eq!(poll_once(&mut fut, &waker), Poll::Ready(Ok(())));
+    let _ = poll_once(&mut fut, &waker);
+}
+
+#[test]
+fn backpressure() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(2);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            block_on(async {
+                for i in 0..COUNT {
+                    s.send_async(i).await.unwrap();
+                }
+            })
+        });
+
+        for i in 0..COUNT {
+            if i % 100 == 0 {
+                thread::sleep(ms(1));
+            }
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn async_to_async() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(4);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            block_on(async {
+                for i in 0..COUNT {
+                    s.send_async(i).await.unwrap();
+                }
+            })
+        });
+
+        let sum = block_on(async {
+            let mut sum = 0;
+            for _ in 0..COUNT {
+                sum += r.recv_async().await.unwrap();
+            }
+            sum
+        });
+        assert_eq!(sum, COUNT * (COUNT - 1) / 2);
+    })
+    .unwrap();
+}
+
+#[test]
+fn mpmc() {
+    const COUNT: usize = 5000;
+    const THREADS: usize = 4;
+
+    let (s, r) = bounded::<usize>(3);
+    let received = AtomicUsize::new(0);
+
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                block_on(async {
+                    for i in 0..COUNT / THREADS {
+                        s.send_async(i).await.unwrap();
+                    }
+                })
+            });
+            scope.spawn(|_| {
+                block_on(async {
+                    for _ in 0..COUNT / THREADS {
+                        r.recv_async().await.unwrap();
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+            });
+        }
+    })
+    .unwrap();
+
+    assert_eq!(received.load(Ordering::SeqCst), COUNT);
+}
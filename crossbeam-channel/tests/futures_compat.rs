@@ -0,0 +1,244 @@
+//! Tests for the adapters between crossbeam and futures channels.
+
+#![cfg(feature = "futures-compat")]
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, futures_compat, select, unbounded, RecvTimeoutError};
+use crossbeam_utils::thread::scope;
+use futures_channel::mpsc;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Waits until a message or the end of the channel can be received from `rx`.
+fn next<T>(rx: &mut mpsc::Receiver<T>) -> Option<T> {
+    loop {
+        if let Ok(msg) = rx.try_next() {
+            return msg;
+        }
+        thread::sleep(ms(1));
+    }
+}
+
+#[test]
+fn from_receiver() {
+    let (mut tx, rx) = mpsc::channel(4);
+    let r = futures_compat::from_receiver(rx);
+
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Ok(2));
+    assert_eq!(r.recv_timeout(ms(100)), Err(RecvTimeoutError::Timeout));
+
+    drop(tx);
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn from_receiver_backpressure() {
+    let (mut tx, rx) = mpsc::channel(0);
+    let r = futures_compat::from_receiver(rx);
+
+    tx.try_send(0).unwrap();
+    thread::sleep(ms(100));
+    tx.try_send(1).unwrap();
+    thread::sleep(ms(100));
+
+    // One message is held by the forwarding thread and one is in the futures channel.
+    assert!(tx.try_send(2).unwrap_err().is_full());
+    assert_eq!(r.recv(), Ok(0));
+    assert_eq!(r.recv(), Ok(1));
+}
+
+#[test]
+fn from_receiver_dropped() {
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let r = futures_compat::from_receiver(rx);
+    drop(r);
+
+    thread::sleep(ms(300));
+    assert!(tx.is_closed());
+}
+
+#[test]
+fn from_receiver_select() {
+    let (mut tx, rx) = mpsc::channel(1);
+    let r1 = futures_compat::from_receiver(rx);
+    let (s2, r2) = unbounded();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(50));
+            s2.send(2).unwrap();
+            thread::sleep(ms(50));
+            tx.try_send(1).unwrap();
+        });
+
+        select! {
+            recv(r1) -> _ => panic!(),
+            recv(r2) -> msg => assert_eq!(msg, Ok(2)),
+        }
+        select! {
+            recv(r1) -> msg => assert_eq!(msg, Ok(1)),
+            recv(r2) -> _ => panic!(),
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn from_unbounded_receiver() {
+    let (tx, rx) = mpsc::unbounded();
+    let r = futures_compat::from_unbounded_receiver(rx);
+
+    for i in 0..10 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+
+    assert_eq!(r.iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn from_sender() {
+    let (tx, mut rx) = mpsc::channel(4);
+    let s = futures_compat::from_sender(tx);
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(next(&mut rx), Some(1));
+    assert_eq!(next(&mut rx), Some(2));
+
+    drop(s);
+    assert_eq!(next(&mut rx), None);
+}
+
+#[test]
+fn from_sender_closed() {
+    let (tx, rx) = mpsc::channel::<i32>(4);
+    let s = futures_compat::from_sender(tx);
+    drop(rx);
+
+    thread::sleep(ms(300));
+    assert!(s.send(1).is_err());
+}
+
+#[test]
+fn from_unbounded_sender() {
+    let (tx, mut rx) = mpsc::unbounded();
+    let s = futures_compat::from_unbounded_sender(tx);
+
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    drop(s);
+
+    for i in 0..10 {
+        let msg = loop {
+            if let Ok(msg) = rx.try_next() {
+                break msg;
+            }
+            thread::sleep(ms(1));
+        };
+        assert_eq!(msg, Some(i));
+    }
+}
+
+#[test]
+fn into_receiver() {
+    let (s, r) = unbounded();
+    let mut rx = futures_compat::into_receiver(r);
+
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    drop(s);
+
+    for i in 0..10 {
+        assert_eq!(next(&mut rx), Some(i));
+    }
+    assert_eq!(next(&mut rx), None);
+}
+
+#[test]
+fn into_receiver_backpressure() {
+    let (s, r) = unbounded();
+    let mut rx = futures_compat::into_receiver(r);
+
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    thread::sleep(ms(100));
+
+    // One message is in the futures channel and one is held by the forwarding thread.
+    assert_eq!(s.len(), 8);
+    assert_eq!(next(&mut rx), Some(0));
+}
+
+#[test]
+fn into_receiver_dropped() {
+    let (s, r) = unbounded::<i32>();
+    let rx = futures_compat::into_receiver(r);
+    drop(rx);
+
+    thread::sleep(ms(300));
+    assert!(s.send(1).is_err());
+}
+
+#[test]
+fn into_sender() {
+    let (s, r) = bounded(1);
+    let mut tx = futures_compat::into_sender(s);
+
+    for i in 0..100 {
+        while tx.try_send(i).is_err() {
+            thread::sleep(ms(1));
+        }
+        assert_eq!(r.recv(), Ok(i));
+    }
+
+    drop(tx);
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn into_sender_dropped() {
+    let (s, r) = unbounded::<i32>();
+    let tx = futures_compat::into_sender(s);
+    drop(r);
+
+    thread::sleep(ms(300));
+    assert!(tx.is_closed());
+}
+
+#[test]
+fn round_trip() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(2);
+    let r = futures_compat::from_receiver(futures_compat::into_receiver(r));
+    let s = futures_compat::from_sender(futures_compat::into_sender(s));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+        });
+
+        for i in 0..COUNT {
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+}
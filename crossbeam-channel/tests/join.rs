@@ -0,0 +1,71 @@
+//! Tests for channels that report completion of threads.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{joined, select, unbounded, RecvTimeoutError, TryRecvError};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let r = joined(thread::spawn(|| 7));
+    assert_eq!(r.recv().unwrap().unwrap(), 7);
+    assert!(r.recv().is_err());
+}
+
+#[test]
+fn waits_for_thread() {
+    let r = joined(thread::spawn(|| thread::sleep(ms(500))));
+
+    assert_eq!(r.try_recv().err(), Some(TryRecvError::Empty));
+    assert_eq!(
+        r.recv_timeout(ms(100)).err(),
+        Some(RecvTimeoutError::Timeout)
+    );
+    assert!(r.recv_timeout(ms(1000)).unwrap().is_ok());
+}
+
+#[test]
+fn panic() {
+    let r = joined(thread::spawn(|| panic!("oops")));
+
+    let payload = r.recv().unwrap().unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"oops"));
+}
+
+#[test]
+fn select_with_channel() {
+    let (s, r) = unbounded::<i32>();
+    let done = joined(thread::spawn(|| {
+        thread::sleep(ms(300));
+        "done"
+    }));
+
+    s.send(1).unwrap();
+    select! {
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+        recv(done) -> _ => panic!(),
+    }
+
+    select! {
+        recv(r) -> _ => panic!(),
+        recv(done) -> res => assert_eq!(res.unwrap().unwrap(), "done"),
+        default(ms(1000)) => panic!(),
+    }
+}
+
+#[test]
+fn drop_receiver() {
+    let (s, r) = unbounded();
+
+    drop(joined(thread::spawn(move || {
+        thread::sleep(ms(100));
+        s.send(()).unwrap();
+    })));
+
+    // The thread keeps running and finishes on its own.
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(()));
+}
@@ -0,0 +1,144 @@
+//! Tests for channels that stay open without senders.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{
+    bounded, bounded_keep_open, never, unbounded, unbounded_keep_open, Receiver, RecvError,
+    RecvTimeoutError, SendError, Sender, TryRecvError,
+};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Returns a keep-open channel of each flavor.
+fn channels() -> Vec<(Sender<usize>, Receiver<usize>)> {
+    vec![
+        bounded_keep_open(0),
+        bounded_keep_open(10),
+        unbounded_keep_open(),
+    ]
+}
+
+#[test]
+fn survives_senders() {
+    for (s, r) in channels() {
+        assert_eq!(r.sender_count(), 1);
+        drop(s);
+        assert_eq!(r.sender_count(), 0);
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+        assert_eq!(r.remaining(), None);
+
+        let s = r.new_sender().unwrap();
+        assert_eq!(r.sender_count(), 1);
+        if r.capacity() != Some(0) {
+            s.send(1).unwrap();
+            assert_eq!(r.recv(), Ok(1));
+        }
+    }
+}
+
+#[test]
+fn blocked_receiver_stays_blocked() {
+    for (s, r) in channels() {
+        scope(|scope| {
+            scope.spawn(|_| assert_eq!(r.recv(), Ok(7)));
+            thread::sleep(ms(100));
+            drop(s);
+            thread::sleep(ms(100));
+            r.new_sender().unwrap().send(7).unwrap();
+        })
+        .unwrap();
+    }
+}
+
+#[test]
+fn receivers_still_disconnect() {
+    for (s, r) in channels() {
+        drop(r);
+        assert_eq!(s.send(1), Err(SendError(1)));
+        assert_eq!(s.receiver_count(), 0);
+    }
+}
+
+#[test]
+fn close() {
+    for (s, r) in channels() {
+        drop(s);
+        assert!(r.close());
+        assert_eq!(r.recv(), Err(RecvError));
+
+        let s = r.new_sender().unwrap();
+        assert_eq!(s.send(1), Err(SendError(1)));
+    }
+}
+
+#[test]
+fn new_sender_without_keep_open() {
+    let (s, r) = bounded::<i32>(1);
+    let s2 = r.new_sender().unwrap();
+    assert_eq!(r.sender_count(), 2);
+    drop(s);
+    s2.send(1).unwrap();
+    drop(s2);
+    assert!(r.new_sender().is_none());
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Err(RecvError));
+
+    let (s, r) = unbounded::<i32>();
+    drop(s);
+    assert!(r.new_sender().is_none());
+
+    assert!(never::<i32>().new_sender().is_none());
+}
+
+#[test]
+fn drops_messages() {
+    // Both sides being dropped in either order frees the channel and its messages.
+    for &senders_first in &[true, false] {
+        let (s, r) = unbounded_keep_open();
+        let msg = Arc::new(());
+        s.send(msg.clone()).unwrap();
+        let s2 = r.new_sender().unwrap();
+        if senders_first {
+            drop(s);
+            drop(s2);
+            drop(r);
+        } else {
+            drop(r);
+            drop(s);
+            drop(s2);
+        }
+        assert_eq!(Arc::strong_count(&msg), 1);
+    }
+}
+
+#[test]
+fn mailbox() {
+    let (s, r) = unbounded_keep_open();
+    drop(s);
+
+    let mut received = scope(|scope| {
+        let consumer = scope.spawn(|_| r.iter().take(40).collect::<Vec<_>>());
+        for round in 0..4 {
+            let producers = (0..10)
+                .map(|i| {
+                    let s = r.new_sender().unwrap();
+                    scope.spawn(move |_| s.send(round * 10 + i).unwrap())
+                })
+                .collect::<Vec<_>>();
+            for p in producers {
+                p.join().unwrap();
+            }
+            assert_eq!(r.sender_count(), 0);
+        }
+        consumer.join().unwrap()
+    })
+    .unwrap();
+    received.sort();
+    assert_eq!(received, (0..40).collect::<Vec<_>>());
+}
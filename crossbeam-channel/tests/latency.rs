@@ -0,0 +1,125 @@
+//! Tests for message latency histograms.
+
+#![cfg(feature = "timestamps")]
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{after, bounded, unbounded};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn empty() {
+    let (s, r) = unbounded::<i32>();
+    let latency = s.latency().unwrap();
+    assert!(latency.is_empty());
+    assert_eq!(latency.count(), 0);
+    assert_eq!(latency.min(), None);
+    assert_eq!(latency.max(), None);
+    assert_eq!(latency.mean(), None);
+    assert_eq!(latency.percentile(50.0), None);
+    assert_eq!(r.latency(), Some(latency));
+}
+
+#[test]
+fn unbounded_delay() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+    thread::sleep(ms(100));
+    r.recv().unwrap();
+
+    let latency = r.latency().unwrap();
+    assert_eq!(latency.count(), 1);
+    assert!(latency.min().unwrap() >= ms(95));
+    assert!(latency.max().unwrap() < ms(1000));
+    assert_eq!(latency.max(), latency.percentile(100.0));
+}
+
+#[test]
+fn bounded_delay() {
+    let (s, r) = bounded(10);
+    s.send(1).unwrap();
+    thread::sleep(ms(100));
+    s.send(2).unwrap();
+    r.recv().unwrap();
+    r.recv().unwrap();
+
+    let latency = r.latency().unwrap();
+    assert_eq!(latency.count(), 2);
+    assert!(latency.percentile(50.0).unwrap() < ms(95));
+    assert!(latency.percentile(100.0).unwrap() >= ms(95));
+    assert!(latency.mean().unwrap() >= ms(45));
+    assert!(latency.mean().unwrap() < latency.max().unwrap());
+}
+
+#[test]
+fn batch() {
+    let (s, r) = bounded(10);
+    let mut buffered = s.buffered(3);
+    for i in 0..3 {
+        buffered.send(i).unwrap();
+    }
+    buffered.flush().unwrap();
+    assert_eq!(r.try_iter().count(), 3);
+    assert_eq!(s.latency().unwrap().count(), 3);
+}
+
+#[test]
+fn unreceived() {
+    let (s, _r) = unbounded();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert!(s.latency().unwrap().is_empty());
+}
+
+#[test]
+fn reset() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    r.recv().unwrap();
+    assert_eq!(r.latency().unwrap().count(), 1);
+
+    s.reset_latency();
+    assert!(r.latency().unwrap().is_empty());
+}
+
+#[test]
+fn percentiles() {
+    let (s, r) = unbounded();
+    for i in 0..100 {
+        s.send(i).unwrap();
+    }
+    thread::sleep(ms(50));
+    for _ in 0..100 {
+        r.recv().unwrap();
+    }
+
+    let latency = r.latency().unwrap();
+    assert_eq!(latency.count(), 100);
+    let mut prev = Duration::from_millis(0);
+    for p in &[0.0, 10.0, 50.0, 90.0, 99.0, 100.0] {
+        let d = latency.percentile(*p).unwrap();
+        assert!(d >= prev);
+        assert!(d >= ms(45));
+        prev = d;
+    }
+    assert_eq!(latency.max(), latency.percentile(100.0));
+}
+
+#[test]
+#[should_panic(expected = "percentile must be between 0 and 100")]
+fn percentile_out_of_range() {
+    let (_s, r) = unbounded::<i32>();
+    r.latency().unwrap().percentile(101.0);
+}
+
+#[test]
+fn untracked() {
+    let (s, r) = bounded::<i32>(0);
+    assert_eq!(s.latency(), None);
+    assert_eq!(r.latency(), None);
+    assert_eq!(after(ms(0)).latency(), None);
+}
@@ -1,6 +1,7 @@
 //! Tests for the list channel flavor.
 
 use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::thread;
@@ -237,6 +238,102 @@ fn len() {
     assert_eq!(r.len(), 0);
 }
 
+#[test]
+fn debug() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+    assert_eq!(
+        format!("{:?}", r),
+        "Receiver { flavor: \"list\", capacity: None, len: 1, disconnected: false, \
+         blocked_receivers: 0 }"
+    );
+
+    drop(r);
+    assert!(format!("{:?}", s).contains("disconnected: true"));
+}
+
+#[test]
+fn max_len_seen() {
+    let (s, r) = unbounded();
+    assert_eq!(s.max_len_seen(), 0);
+    r.track_max_len();
+
+    for i in 0..100 {
+        s.send(i).unwrap();
+    }
+    for _ in 0..100 {
+        r.recv().unwrap();
+    }
+    s.send(0).unwrap();
+    assert_eq!(r.max_len_seen(), 100);
+
+    s.reset_max_len_seen();
+    assert_eq!(r.max_len_seen(), 1);
+}
+
+#[test]
+fn max_len_untracked() {
+    let (s, r) = unbounded();
+    for i in 0..3 {
+        s.send(i).unwrap();
+    }
+    r.recv().unwrap();
+    assert_eq!(s.max_len_seen(), 2);
+}
+
+#[test]
+fn blocked() {
+    let (s, r) = unbounded();
+    assert_eq!(s.blocked_senders(), 0);
+    assert!(s.blocked_sender_names().is_empty());
+
+    scope(|scope| {
+        scope.spawn(|_| r.recv());
+        thread::sleep(ms(100));
+        assert_eq!(s.blocked_receivers(), 1);
+        assert_eq!(s.blocked_receiver_names().len(), 1);
+        s.send(1).unwrap();
+    })
+    .unwrap();
+    assert_eq!(r.blocked_receivers(), 0);
+}
+
+#[test]
+fn len_under_churn() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 25_000;
+
+    let (s, r) = unbounded();
+
+    scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    r.recv().unwrap();
+                }
+            });
+        }
+
+        // Keep asking for the length while the channel is busy.
+        scope.spawn(|_| {
+            for _ in 0..COUNT {
+                let len = r.len();
+                assert!(len <= COUNT * 2);
+            }
+        });
+    })
+    .unwrap();
+
+    assert_eq!(s.len(), 0);
+}
+
 #[test]
 fn disconnect_wakes_receiver() {
     let (s, r) = unbounded::<()>();
@@ -456,6 +553,67 @@ fn drops() {
     }
 }
 
+#[test]
+fn panicking_drop() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Bomb(bool);
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+            if self.0 {
+                panic!("bomb");
+            }
+        }
+    }
+
+    // The channel is destroyed by the last receiver, or its messages are discarded when the
+    // receivers are dropped first. Either way, every message is dropped despite the panic.
+    for &receivers_first in &[false, true] {
+        DROPS.store(0, Ordering::SeqCst);
+        let (s, r) = unbounded();
+        for i in 0..100 {
+            s.send(Bomb(i == 10)).unwrap();
+        }
+
+        if receivers_first {
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(r))).is_err());
+            drop(s);
+        } else {
+            drop(s);
+            assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(r))).is_err());
+        }
+        assert_eq!(DROPS.load(Ordering::SeqCst), 100);
+    }
+}
+
+#[test]
+fn panicking_clear() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Bomb(bool);
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+            if self.0 {
+                panic!("bomb");
+            }
+        }
+    }
+
+    let (s, r) = unbounded();
+    for i in 0..10 {
+        s.send(Bomb(i == 3)).unwrap();
+    }
+
+    // The messages after the one that panicked are still dropped.
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| r.clear())).is_err());
+    assert_eq!(DROPS.load(Ordering::SeqCst), 10);
+    assert!(r.is_empty());
+}
+
 #[test]
 fn linearizable() {
     #[cfg(miri)]
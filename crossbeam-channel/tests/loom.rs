@@ -0,0 +1,137 @@
+//! Model checks of the array flavor and the waker under loom.
+
+#![cfg(crossbeam_loom)]
+
+use crossbeam_channel::{bounded, select, RecvError, TryRecvError, TrySendError};
+use loom_crate as loom;
+
+use loom::thread;
+
+#[test]
+fn array_try_send_try_recv() {
+    loom::model(|| {
+        let (s, r) = bounded(1);
+
+        let t = thread::spawn(move || loop {
+            match s.try_send(1) {
+                Ok(()) => break,
+                Err(TrySendError::Full(_)) => thread::yield_now(),
+                Err(TrySendError::Disconnected(_)) => unreachable!(),
+            }
+        });
+
+        loop {
+            match r.try_recv() {
+                Ok(v) => {
+                    assert_eq!(v, 1);
+                    break;
+                }
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                Err(TryRecvError::Disconnected) => unreachable!(),
+            }
+        }
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn array_wraps_around() {
+    loom::model(|| {
+        let (s, r) = bounded(1);
+
+        let t = thread::spawn(move || {
+            for i in 0..3 {
+                s.send(i).unwrap();
+            }
+        });
+
+        for i in 0..3 {
+            assert_eq!(r.recv(), Ok(i));
+        }
+        assert_eq!(r.recv(), Err(RecvError));
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn array_two_senders() {
+    loom::model(|| {
+        let (s, r) = bounded(1);
+        let s2 = s.clone();
+
+        let t1 = thread::spawn(move || s.send(1).unwrap());
+        let t2 = thread::spawn(move || s2.send(2).unwrap());
+
+        let a = r.recv().unwrap();
+        let b = r.recv().unwrap();
+        assert_eq!(a + b, 3);
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+}
+
+#[test]
+fn array_disconnect_wakes_receiver() {
+    loom::model(|| {
+        let (s, r) = bounded::<i32>(1);
+
+        let t = thread::spawn(move || drop(s));
+
+        assert_eq!(r.recv(), Err(RecvError));
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn array_disconnect_wakes_sender() {
+    loom::model(|| {
+        let (s, r) = bounded(1);
+        s.send(1).unwrap();
+
+        let t = thread::spawn(move || drop(r));
+
+        assert!(s.send(2).is_err());
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn zero_rendezvous() {
+    loom::model(|| {
+        let (s, r) = bounded(0);
+
+        let t = thread::spawn(move || s.send(1).unwrap());
+
+        assert_eq!(r.recv(), Ok(1));
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn select_wakes_once() {
+    loom::model(|| {
+        let (s1, r1) = bounded(1);
+        let (s2, r2) = bounded(1);
+
+        // The senders are handed back so that neither channel gets disconnected.
+        let t1 = thread::spawn(move || {
+            s1.send(1).unwrap();
+            s1
+        });
+        let t2 = thread::spawn(move || {
+            s2.send(2).unwrap();
+            s2
+        });
+
+        let mut sum = 0;
+        for _ in 0..2 {
+            select! {
+                recv(r1) -> v => sum += v.unwrap(),
+                recv(r2) -> v => sum += v.unwrap(),
+            }
+        }
+        assert_eq!(sum, 3);
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+}
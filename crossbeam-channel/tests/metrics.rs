@@ -0,0 +1,246 @@
+//! Tests for metrics hooks.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{
+    bounded_with_metrics, select, unbounded_with_metrics, ChannelMetrics, TryRecvError,
+};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Send(usize),
+    SendBatch(usize, usize),
+    Recv(usize),
+    Block(usize),
+    Drop(usize),
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<(String, Event)>>,
+}
+
+impl Recorder {
+    fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().iter().map(|e| e.1).collect()
+    }
+
+    fn push(&self, name: &str, event: Event) {
+        self.events.lock().unwrap().push((name.to_string(), event));
+    }
+}
+
+impl ChannelMetrics for Recorder {
+    fn on_send(&self, name: &str, len: usize) {
+        self.push(name, Event::Send(len));
+    }
+
+    fn on_send_batch(&self, name: &str, count: usize, len: usize) {
+        self.push(name, Event::SendBatch(count, len));
+    }
+
+    fn on_recv(&self, name: &str, len: usize) {
+        self.push(name, Event::Recv(len));
+    }
+
+    fn on_block(&self, name: &str, len: usize) {
+        self.push(name, Event::Block(len));
+    }
+
+    fn on_drop(&self, name: &str, len: usize) {
+        self.push(name, Event::Drop(len));
+    }
+}
+
+#[test]
+fn unbounded() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = unbounded_with_metrics("a", m.clone());
+
+    s.send(1).unwrap();
+    s.try_send(2).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.try_recv(), Ok(2));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    assert_eq!(
+        m.events(),
+        [
+            Event::Send(1),
+            Event::Send(2),
+            Event::Recv(1),
+            Event::Recv(0),
+        ]
+    );
+}
+
+#[test]
+fn bounded() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = bounded_with_metrics(2, "a", m.clone());
+
+    s.send(1).unwrap();
+    s.send_timeout(2, ms(10)).unwrap();
+    assert!(s.try_send(3).is_err());
+    assert_eq!(r.recv_timeout(ms(10)), Ok(1));
+
+    assert_eq!(m.events(), [Event::Send(1), Event::Send(2), Event::Recv(1)]);
+}
+
+#[test]
+fn zero_capacity() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = bounded_with_metrics(0, "a", m.clone());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            s.send(7).unwrap();
+        });
+        assert_eq!(r.recv(), Ok(7));
+    })
+    .unwrap();
+
+    let events = m.events();
+    assert_eq!(events[0], Event::Block(0));
+    assert!(events.contains(&Event::Send(0)));
+    assert!(events.contains(&Event::Recv(0)));
+    assert_eq!(events.len(), 3);
+}
+
+#[test]
+fn block() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = bounded_with_metrics(1, "a", m.clone());
+
+    s.send(1).unwrap();
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            assert_eq!(r.recv(), Ok(1));
+        });
+        s.send(2).unwrap();
+    })
+    .unwrap();
+
+    let events = m.events();
+    assert_eq!(events[..2], [Event::Send(1), Event::Block(1)]);
+    assert!(events.contains(&Event::Recv(0)));
+    assert_eq!(events.len(), 4);
+}
+
+#[test]
+fn drop_reports_remaining() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = unbounded_with_metrics("a", m.clone());
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    s.send(3).unwrap();
+    r.recv().unwrap();
+    drop(s);
+    assert_eq!(m.events().last(), Some(&Event::Recv(2)));
+    drop(r);
+
+    assert_eq!(m.events().last(), Some(&Event::Drop(2)));
+}
+
+#[test]
+fn drop_reports_discarded() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = unbounded_with_metrics("a", m.clone());
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    s.send(3).unwrap();
+    r.recv().unwrap();
+    drop(r);
+    assert_eq!(m.events().last(), Some(&Event::Recv(2)));
+    drop(s);
+
+    assert_eq!(m.events().last(), Some(&Event::Drop(2)));
+
+    let m = Arc::new(Recorder::default());
+    let (s, r) = bounded_with_metrics(5, "b", m.clone());
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    drop(r);
+    drop(s);
+
+    assert_eq!(m.events().last(), Some(&Event::Drop(2)));
+}
+
+#[test]
+fn select_records_once() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = bounded_with_metrics(1, "a", m.clone());
+
+    select! {
+        send(s, 1) -> res => res.unwrap(),
+        recv(r) -> _ => panic!(),
+    }
+    select! {
+        send(s, 2) -> _ => panic!(),
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+    }
+
+    assert_eq!(m.events(), [Event::Send(1), Event::Recv(0)]);
+}
+
+#[test]
+fn buffered() {
+    let m = Arc::new(Recorder::default());
+    let (s, r) = unbounded_with_metrics("a", m.clone());
+    let mut buffered = s.buffered(3);
+
+    for i in 0..5 {
+        buffered.send(i).unwrap();
+    }
+    buffered.flush().unwrap();
+    for _ in 0..5 {
+        r.recv().unwrap();
+    }
+
+    // Every batch is reported once, with the length after the whole batch was sent.
+    assert_eq!(
+        m.events(),
+        [
+            Event::SendBatch(3, 3),
+            Event::SendBatch(2, 5),
+            Event::Recv(4),
+            Event::Recv(3),
+            Event::Recv(2),
+            Event::Recv(1),
+            Event::Recv(0),
+        ]
+    );
+}
+
+#[test]
+fn shared_hooks() {
+    let m = Arc::new(Recorder::default());
+    let (s1, r1) = unbounded_with_metrics("first", m.clone());
+    let (s2, r2) = bounded_with_metrics(1, "second", m.clone());
+
+    s1.send(()).unwrap();
+    s2.send(()).unwrap();
+    r2.recv().unwrap();
+    r1.recv().unwrap();
+
+    let names = m
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.0.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["first", "second", "second", "first"]);
+}
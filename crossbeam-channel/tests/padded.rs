@@ -0,0 +1,180 @@
+//! Tests for bounded channels with padded slots.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded_padded, select, TryRecvError, TrySendError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s, r) = bounded_padded(1);
+    s.send(7).unwrap();
+    assert_eq!(r.try_recv(), Ok(7));
+
+    s.send(8).unwrap();
+    assert_eq!(r.recv(), Ok(8));
+
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn capacity() {
+    for i in 1..10 {
+        let (s, r) = bounded_padded::<()>(i);
+        assert_eq!(s.capacity(), Some(i));
+        assert_eq!(r.capacity(), Some(i));
+    }
+
+    let (s, r) = bounded_padded::<()>(0);
+    assert_eq!(s.capacity(), Some(0));
+    assert_eq!(r.capacity(), Some(0));
+}
+
+#[test]
+fn len_and_full() {
+    const CAP: usize = 5;
+
+    let (s, r) = bounded_padded(CAP);
+
+    for lap in 0..3 {
+        for i in 0..CAP {
+            assert_eq!(s.len(), i);
+            s.send(lap * CAP + i).unwrap();
+        }
+        assert!(s.is_full());
+        assert_eq!(s.try_send(0), Err(TrySendError::Full(0)));
+
+        for i in 0..CAP {
+            assert_eq!(r.len(), CAP - i);
+            assert_eq!(r.recv(), Ok(lap * CAP + i));
+        }
+        assert!(r.is_empty());
+    }
+}
+
+#[test]
+fn large_messages() {
+    let (s, r) = bounded_padded(2);
+    s.send([1u8; 300]).unwrap();
+    s.send([2u8; 300]).unwrap();
+    assert_eq!(r.recv().unwrap()[..], [1u8; 300][..]);
+    assert_eq!(r.recv().unwrap()[..], [2u8; 300][..]);
+}
+
+#[test]
+fn zero_capacity() {
+    let (s, r) = bounded_padded(0);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            s.send(7).unwrap();
+        });
+        assert_eq!(r.recv(), Ok(7));
+    })
+    .unwrap();
+}
+
+#[test]
+fn drops() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct DropCounter;
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let (s, r) = bounded_padded(10);
+
+    // Move the head and tail to the middle of the buffer, then wrap around.
+    for _ in 0..5 {
+        s.send(DropCounter).unwrap();
+        r.recv().unwrap();
+    }
+    for _ in 0..8 {
+        s.send(DropCounter).unwrap();
+    }
+    assert_eq!(DROPS.load(Ordering::SeqCst), 5);
+
+    drop(s);
+    drop(r);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 13);
+}
+
+#[test]
+fn select() {
+    let (s, r) = bounded_padded(1);
+
+    select! {
+        send(s, 1) -> res => res.unwrap(),
+        recv(r) -> _ => panic!(),
+    }
+    select! {
+        send(s, 2) -> _ => panic!(),
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+    }
+}
+
+#[test]
+fn batches() {
+    let (s, r) = bounded_padded(4);
+    let mut buffered = s.buffered(3);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..100 {
+                buffered.send(i).unwrap();
+            }
+            buffered.flush().unwrap();
+        });
+
+        for i in 0..100 {
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn mpmc() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 25_000;
+    const THREADS: usize = 4;
+
+    let (s, r) = bounded_padded::<usize>(3);
+    let v = (0..COUNT).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>();
+
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    let n = r.recv().unwrap();
+                    v[n].fetch_add(1, Ordering::SeqCst);
+                }
+            });
+        }
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+        }
+    })
+    .unwrap();
+
+    for c in v {
+        assert_eq!(c.load(Ordering::SeqCst), THREADS);
+    }
+}
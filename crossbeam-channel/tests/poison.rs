@@ -0,0 +1,105 @@
+//! Tests for channel poisoning.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, tick, unbounded, Receiver, RecvPoisonError, Sender};
+
+/// Returns a channel of each flavor.
+fn channels() -> Vec<(Sender<i32>, Receiver<i32>)> {
+    vec![bounded(0), bounded(10), unbounded()]
+}
+
+/// Drops `s` on a thread that panics.
+fn panic_with(s: Sender<i32>) {
+    let t = thread::spawn(move || {
+        let _s = s;
+        panic!("producer failed");
+    });
+    assert!(t.join().is_err());
+}
+
+#[test]
+fn disabled() {
+    for (s, r) in channels() {
+        panic_with(s);
+        assert!(!r.is_poisoned());
+        assert_eq!(r.recv_checked(), Err(RecvPoisonError::Disconnected));
+    }
+}
+
+#[test]
+fn normal_drop() {
+    for (s, r) in channels() {
+        s.poison_on_panic();
+        drop(s);
+        assert!(!r.is_poisoned());
+        assert_eq!(r.recv_checked(), Err(RecvPoisonError::Disconnected));
+    }
+}
+
+#[test]
+fn poisoned() {
+    for (s, r) in channels() {
+        r.poison_on_panic();
+        let s2 = s.clone();
+        panic_with(s);
+
+        // The channel is poisoned but still connected.
+        assert!(r.is_poisoned());
+        assert!(s2.is_poisoned());
+        if r.capacity() != Some(0) {
+            s2.send(1).unwrap();
+            assert_eq!(r.recv_checked(), Ok(1));
+        }
+
+        drop(s2);
+        assert_eq!(r.recv_checked(), Err(RecvPoisonError::Poisoned));
+        assert!(r.recv().is_err());
+    }
+}
+
+#[test]
+fn drain_first() {
+    let (s, r) = unbounded();
+    s.poison_on_panic();
+    let t = thread::spawn(move || {
+        for i in 0..10 {
+            s.send(i).unwrap();
+        }
+        panic!("producer failed");
+    });
+    assert!(t.join().is_err());
+
+    for i in 0..10 {
+        assert_eq!(r.recv_checked(), Ok(i));
+    }
+    assert_eq!(r.recv_checked(), Err(RecvPoisonError::Poisoned));
+}
+
+#[test]
+fn wakes_blocked() {
+    let (s, r) = bounded::<i32>(0);
+    s.poison_on_panic();
+    let t = thread::spawn(move || r.recv_checked());
+    thread::sleep(Duration::from_millis(100));
+    panic_with(s);
+    assert_eq!(t.join().unwrap(), Err(RecvPoisonError::Poisoned));
+}
+
+#[test]
+fn timer() {
+    let r = tick(Duration::from_millis(10));
+    r.poison_on_panic();
+    assert!(!r.is_poisoned());
+    assert!(r.recv_checked().is_ok());
+}
+
+#[test]
+fn error() {
+    let err = RecvPoisonError::Poisoned;
+    assert!(err.is_poisoned());
+    assert!(!err.is_disconnected());
+    assert_eq!(err.to_string(), "channel is empty and a sender panicked");
+    assert!(RecvPoisonError::Disconnected.is_disconnected());
+}
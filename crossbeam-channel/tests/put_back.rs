@@ -0,0 +1,81 @@
+//! Tests for receivers that can put a message back.
+
+use std::time::Duration;
+
+use crossbeam_channel::{
+    bounded, unbounded, PutBackError, RecvError, RecvTimeoutError, TryRecvError,
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn put_back_first() {
+    let (s, r) = unbounded();
+    let mut r = r.with_put_back();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    let msg = r.recv().unwrap();
+    assert!(!r.has_put_back());
+    r.put_back(msg).unwrap();
+    assert!(r.has_put_back());
+    assert_eq!(r.try_recv(), Ok(1));
+    assert!(!r.has_put_back());
+    assert_eq!(r.try_recv(), Ok(2));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn one_at_a_time() {
+    let (s, r) = unbounded();
+    let mut r = r.with_put_back();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    let a = r.recv().unwrap();
+    let b = r.recv().unwrap();
+    r.put_back(b).unwrap();
+    let err = r.put_back(a).unwrap_err();
+    assert_eq!(err, PutBackError(1));
+    assert_eq!(err.into_inner(), 1);
+    assert_eq!(err.to_string(), "a message was already put back");
+
+    assert_eq!(r.recv(), Ok(2));
+    r.put_back(1).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+}
+
+#[test]
+fn after_disconnect() {
+    let (s, r) = bounded(1);
+    let mut r = r.with_put_back();
+    s.send(1).unwrap();
+    drop(s);
+
+    let msg = r.recv().unwrap();
+    r.put_back(msg).unwrap();
+    assert!(!r.is_empty());
+    assert_eq!(r.recv_timeout(ms(0)), Ok(1));
+    assert!(r.is_empty());
+    assert_eq!(r.recv_timeout(ms(0)), Err(RecvTimeoutError::Disconnected));
+    assert_eq!(r.recv(), Err(RecvError));
+}
+
+#[test]
+fn not_shared_with_clones() {
+    let (s, r) = unbounded();
+    let r2 = r.clone();
+    let mut r = r.with_put_back();
+    s.send(1).unwrap();
+
+    let msg = r.recv().unwrap();
+    r.put_back(msg).unwrap();
+    assert_eq!(r2.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(r.receiver().try_recv(), Err(TryRecvError::Empty));
+
+    let (r, msg) = r.into_inner();
+    assert_eq!(msg, Some(1));
+    assert!(r.is_empty());
+}
@@ -734,6 +734,141 @@ fn channel_through_channel() {
     }
 }
 
+#[test]
+fn ready_all() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = bounded::<i32>(1);
+    let (s3, r3) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.send(&s2);
+    let oper3 = sel.recv(&r3);
+
+    // Only the send operation is ready initially.
+    assert_eq!(sel.try_ready_all(), vec![oper2]);
+
+    s2.send(2).unwrap();
+    assert_eq!(sel.try_ready_all(), Vec::<usize>::new());
+    assert!(sel.ready_all_timeout(ms(100)).is_err());
+
+    s1.send(1).unwrap();
+    s3.send(3).unwrap();
+    assert_eq!(sel.ready_all(), vec![oper1, oper3]);
+
+    drop(r2);
+    assert_eq!(sel.ready_all(), vec![oper1, oper2, oper3]);
+}
+
+#[test]
+fn ready_all_blocks() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            s1.send(1).unwrap();
+        });
+
+        let mut sel = Select::new();
+        let oper1 = sel.recv(&r1);
+        sel.recv(&r2);
+
+        assert_eq!(sel.ready_all_timeout(ms(1000)), Ok(vec![oper1]));
+        assert_eq!(r1.try_recv(), Ok(1));
+    })
+    .unwrap();
+
+    drop(s2);
+}
+
+#[test]
+fn wait_all() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = bounded::<i32>(1);
+
+    let mut sel = Select::new();
+    sel.recv(&r1);
+    sel.send(&s2);
+
+    // Only the send operation is ready.
+    assert!(sel.try_wait_all().is_err());
+    assert!(sel.wait_all_timeout(ms(100)).is_err());
+
+    s1.send(1).unwrap();
+    assert_eq!(sel.try_wait_all(), Ok(()));
+
+    s2.send(2).unwrap();
+    assert!(sel.try_wait_all().is_err());
+
+    drop(r2);
+    assert_eq!(sel.try_wait_all(), Ok(()));
+}
+
+#[test]
+fn wait_all_blocks() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(300));
+            s1.send(1).unwrap();
+            thread::sleep(ms(300));
+            s2.send(2).unwrap();
+        });
+
+        let mut sel = Select::new();
+        sel.recv(&r1);
+        sel.recv(&r2);
+
+        let start = Instant::now();
+        sel.wait_all();
+        assert!(start.elapsed() >= ms(600));
+        assert_eq!(r1.try_recv(), Ok(1));
+        assert_eq!(r2.try_recv(), Ok(2));
+    })
+    .unwrap();
+}
+
+#[test]
+fn wait_all_rechecks() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            s1.send(1).unwrap();
+            thread::sleep(ms(300));
+            // The first channel stops being ready before the second one becomes ready.
+            assert_eq!(r1.recv(), Ok(1));
+            s2.send(2).unwrap();
+            thread::sleep(ms(300));
+            s1.send(3).unwrap();
+        });
+
+        thread::sleep(ms(100));
+        let mut sel = Select::new();
+        sel.recv(&r1);
+        sel.recv(&r2);
+
+        let start = Instant::now();
+        sel.wait_all();
+        assert!(start.elapsed() >= ms(400));
+        assert_eq!(r1.try_recv(), Ok(3));
+        assert_eq!(r2.try_recv(), Ok(2));
+    })
+    .unwrap();
+}
+
+#[test]
+fn wait_all_empty() {
+    let mut sel = Select::new();
+    assert_eq!(sel.try_wait_all(), Ok(()));
+    sel.wait_all();
+}
+
 #[test]
 fn fairness1() {
     #[cfg(miri)]
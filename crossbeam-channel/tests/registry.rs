@@ -0,0 +1,290 @@
+//! Tests for the channel registry.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::registry::{self, ChannelInfo};
+use crossbeam_channel::{bounded, bounded_with_metrics, unbounded_with_metrics, ChannelMetrics};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Returns the name the registry lists the current thread under.
+fn current() -> String {
+    let thread = thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+struct NoHooks;
+
+impl ChannelMetrics for NoHooks {}
+
+fn find(name: &str) -> Option<ChannelInfo> {
+    registry::snapshot()
+        .into_iter()
+        .find(|c| c.name() == Some(name))
+}
+
+#[test]
+fn unbounded_channel() {
+    registry::enable();
+    let (s, r) = unbounded_with_metrics("unbounded", Arc::new(NoHooks));
+
+    let info = find("unbounded").unwrap();
+    assert_eq!(info.capacity(), None);
+    assert!(info.is_empty());
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    s.send(3).unwrap();
+    r.recv().unwrap();
+    assert_eq!(find("unbounded").unwrap().len(), 2);
+}
+
+#[test]
+fn bounded_channel() {
+    registry::enable();
+    let (s, _r) = bounded_with_metrics(5, "bounded", Arc::new(NoHooks));
+    s.send(1).unwrap();
+
+    let info = find("bounded").unwrap();
+    assert_eq!(info.capacity(), Some(5));
+    assert_eq!(info.len(), 1);
+}
+
+#[test]
+fn zero_capacity() {
+    registry::enable();
+    let (_s, _r) = bounded_with_metrics::<i32>(0, "zero", Arc::new(NoHooks));
+
+    let info = find("zero").unwrap();
+    assert_eq!(info.capacity(), Some(0));
+    assert_eq!(info.len(), 0);
+}
+
+#[test]
+fn unnamed() {
+    registry::enable();
+    assert!(registry::is_enabled());
+
+    let (_s, _r) = bounded::<i32>(987_654);
+    assert!(registry::snapshot()
+        .iter()
+        .any(|c| c.name().is_none() && c.capacity() == Some(987_654)));
+}
+
+#[test]
+fn counts() {
+    registry::enable();
+    let (s, r) = unbounded_with_metrics::<i32>("counts", Arc::new(NoHooks));
+
+    let s2 = s.clone();
+    let s3 = s.clone();
+    let r2 = r.clone();
+    let info = find("counts").unwrap();
+    assert_eq!(info.senders(), 3);
+    assert_eq!(info.receivers(), 2);
+
+    drop(s2);
+    drop(r2);
+    let info = find("counts").unwrap();
+    assert_eq!(info.senders(), 2);
+    assert_eq!(info.receivers(), 1);
+
+    // The channel stays registered while either side is alive.
+    drop(r);
+    let info = find("counts").unwrap();
+    assert_eq!(info.senders(), 2);
+    assert_eq!(info.receivers(), 0);
+
+    drop(s);
+    drop(s3);
+    assert!(find("counts").is_none());
+}
+
+#[test]
+fn order() {
+    registry::enable();
+    let (_s1, _r1) = unbounded_with_metrics::<i32>("order-1", Arc::new(NoHooks));
+    let (_s2, _r2) = bounded_with_metrics::<i32>(1, "order-2", Arc::new(NoHooks));
+
+    let names = registry::snapshot()
+        .into_iter()
+        .filter_map(|c| c.name().map(String::from))
+        .filter(|n| n.starts_with("order-"))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["order-1", "order-2"]);
+}
+
+#[test]
+fn threads() {
+    registry::enable();
+    let (s, r) = bounded_with_metrics(1, "threads", Arc::new(NoHooks));
+
+    let s2 = s.clone();
+    thread::Builder::new()
+        .name("producer".to_string())
+        .spawn(move || s2.send(1).unwrap())
+        .unwrap()
+        .join()
+        .unwrap();
+    let t = thread::Builder::new()
+        .name("blocked".to_string())
+        .spawn(move || s.send(3).unwrap())
+        .unwrap();
+    thread::sleep(ms(100));
+
+    let info = find("threads").unwrap();
+    assert_eq!(info.sender_threads(), ["producer"]);
+    assert!(info.receiver_threads().is_empty());
+    assert_eq!(info.blocked_senders(), ["blocked"]);
+    assert!(info.blocked_receivers().is_empty());
+
+    assert_eq!(r.recv(), Ok(1));
+    t.join().unwrap();
+    let info = find("threads").unwrap();
+    assert_eq!(info.sender_threads(), ["blocked", "producer"]);
+    assert_eq!(info.receiver_threads(), [current()]);
+    assert!(info.blocked_senders().is_empty());
+}
+
+#[test]
+fn shared_sender() {
+    registry::enable();
+    let (s, r) = bounded_with_metrics(10, "shared", Arc::new(NoHooks));
+
+    // Threads taking turns with the same sender are all listed.
+    scope(|scope| {
+        for name in &["first", "second", "first"] {
+            scope
+                .builder()
+                .name(name.to_string())
+                .spawn(|_| s.send(1).unwrap())
+                .unwrap()
+                .join()
+                .unwrap();
+        }
+    })
+    .unwrap();
+
+    assert_eq!(r.try_iter().count(), 3);
+    let info = find("shared").unwrap();
+    assert_eq!(info.sender_threads(), ["first", "second"]);
+}
+
+#[test]
+fn many_short_lived_threads() {
+    registry::enable();
+    let (s, r) = unbounded_with_metrics("short-lived", Arc::new(NoHooks));
+
+    // Only the most recent senders are remembered.
+    for i in 0..100 {
+        let s = s.clone();
+        thread::Builder::new()
+            .name(format!("short-lived-{:03}", i))
+            .spawn(move || s.send(i).unwrap())
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    assert_eq!(r.try_iter().count(), 100);
+    let info = find("short-lived").unwrap();
+    let expected: Vec<String> = (68..100).map(|i| format!("short-lived-{:03}", i)).collect();
+    assert_eq!(info.sender_threads(), &expected[..]);
+}
+
+#[test]
+fn dot_escapes_line_breaks() {
+    registry::enable();
+    let (s, _r) = unbounded_with_metrics("line\nbreak\r", Arc::new(NoHooks));
+    s.send(1).unwrap();
+
+    let dot = registry::dot();
+    assert!(dot.contains(r#"[shape=box, label="line\nbreak\r\n1"];"#));
+}
+
+#[test]
+fn dot() {
+    registry::enable();
+    let (s1, r1) = unbounded_with_metrics("dot \"in\"", Arc::new(NoHooks));
+    let (s2, r2) = bounded_with_metrics(4, "dot out", Arc::new(NoHooks));
+
+    let worker = thread::Builder::new()
+        .name("dot-worker".to_string())
+        .spawn(move || {
+            for msg in r1.iter() {
+                s2.send(msg).unwrap();
+            }
+            (r1, s2)
+        })
+        .unwrap();
+    s1.send(1).unwrap();
+    drop(s1);
+
+    // The threads hand their channels back so that they stay registered.
+    let (_r1, s2) = worker.join().unwrap();
+    drop(s2);
+    let receiver = thread::Builder::new()
+        .name("dot-receiver".to_string())
+        .spawn(move || {
+            r2.recv().unwrap();
+            r2
+        })
+        .unwrap();
+    let _r2 = receiver.join().unwrap();
+
+    let dot = registry::dot();
+    assert!(dot.starts_with("digraph channels {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains(r#"    "thread:dot-worker" [label="dot-worker"];"#));
+    assert!(dot.contains(r#"[shape=box, label="dot \"in\"\n0"];"#));
+    assert!(dot.contains(r#"[shape=box, label="dot out\n0/4"];"#));
+
+    let edge = |from: &str, to: &str| {
+        dot.lines()
+            .any(|l| l.trim() == format!("\"{}\" -> \"{}\";", from, to))
+    };
+    let id = |name: &str| {
+        let line = dot.lines().find(|l| l.contains(name)).unwrap();
+        line.trim()
+            .split(' ')
+            .next()
+            .unwrap()
+            .trim_matches('"')
+            .to_string()
+    };
+    let input = id(r#"label="dot \"in\""#);
+    let output = id(r#"label="dot out"#);
+    let main = format!("thread:{}", current());
+    assert!(edge(&main, &input));
+    assert!(edge(&input, "thread:dot-worker"));
+    assert!(edge("thread:dot-worker", &output));
+    assert!(edge(&output, "thread:dot-receiver"));
+}
+
+#[test]
+fn dot_blocked() {
+    registry::enable();
+    let (s, r) = bounded_with_metrics::<i32>(0, "dot-blocked", Arc::new(NoHooks));
+    let t = thread::Builder::new()
+        .name("dot-waiting".to_string())
+        .spawn(move || r.recv())
+        .unwrap();
+    thread::sleep(ms(100));
+
+    let dot = registry::dot();
+    let line = dot
+        .lines()
+        .find(|l| l.ends_with(r#" -> "thread:dot-waiting" [style=dashed];"#));
+    assert!(line.is_some());
+
+    drop(s);
+    t.join().unwrap().unwrap_err();
+}
@@ -0,0 +1,158 @@
+//! Tests for the schedule channel flavor.
+
+#![cfg(not(miri))] // TODO: many assertions failed due to Miri is slow
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{after, schedule, select, RecvTimeoutError, Select, TryRecvError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn fire() {
+    let start = Instant::now();
+    let times = vec![start + ms(50), start + ms(100)];
+    let r = schedule(times.clone());
+
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    assert!(r.is_empty());
+
+    assert_eq!(r.recv(), Ok(times[0]));
+    assert!(Instant::now() >= times[0]);
+    assert_eq!(r.recv(), Ok(times[1]));
+    assert!(Instant::now() >= times[1]);
+
+    assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    assert!(r.recv().is_err());
+}
+
+#[test]
+fn empty() {
+    let r = schedule(Vec::new());
+    assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    assert!(r.is_empty());
+
+    select! {
+        recv(r) -> msg => assert!(msg.is_err()),
+        default(ms(1000)) => panic!(),
+    }
+}
+
+#[test]
+fn past() {
+    let start = Instant::now();
+    thread::sleep(ms(50));
+
+    let r = schedule(vec![start, start + ms(10)]);
+    assert_eq!(r.len(), 1);
+    assert_eq!(r.try_recv(), Ok(start));
+    assert_eq!(r.try_recv(), Ok(start + ms(10)));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn capacity() {
+    let r = schedule(vec![Instant::now()]);
+    assert_eq!(r.capacity(), Some(1));
+    assert!(r.is_full());
+}
+
+#[test]
+fn recv_timeout() {
+    let start = Instant::now();
+    let r = schedule(vec![start + ms(200)]);
+
+    assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() >= ms(50));
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(start + ms(200)));
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn infinite() {
+    let start = Instant::now();
+    let r = schedule((1..).map(move |i| start + ms(20) * i));
+
+    for i in 1..5 {
+        assert_eq!(r.recv(), Ok(start + ms(20) * i));
+    }
+}
+
+#[test]
+fn lazy() {
+    let start = Instant::now();
+    let r = schedule((1..).map(move |i| {
+        // Each instant is computed only after the previous one was received.
+        let now = Instant::now();
+        assert!(now >= start + ms(30) * (i - 1));
+        now + ms(30)
+    }));
+
+    for _ in 0..3 {
+        r.recv().unwrap();
+    }
+}
+
+#[test]
+fn recv_race() {
+    let start = Instant::now();
+    let r = schedule((1..=10).map(move |i| start + ms(10) * i));
+
+    scope(|scope| {
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            handles.push(scope.spawn(|_| r.iter().collect::<Vec<_>>()));
+        }
+
+        let mut all: Vec<Instant> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        all.sort();
+        assert_eq!(
+            all,
+            (1..=10).map(|i| start + ms(10) * i).collect::<Vec<_>>()
+        );
+    })
+    .unwrap();
+}
+
+#[test]
+fn select() {
+    let start = Instant::now();
+    let r = schedule(vec![start + ms(100), start + ms(200)]);
+    let timeout = after(ms(1000));
+
+    let mut hits = Vec::new();
+    loop {
+        select! {
+            recv(r) -> msg => match msg {
+                Ok(when) => hits.push(when),
+                Err(_) => break,
+            },
+            recv(timeout) -> _ => panic!(),
+        }
+    }
+    assert_eq!(hits, vec![start + ms(100), start + ms(200)]);
+}
+
+#[test]
+fn ready() {
+    let start = Instant::now();
+    let r = schedule(vec![start + ms(50)]);
+
+    let mut sel = Select::new();
+    sel.recv(&r);
+    assert_eq!(sel.ready_timeout(ms(1000)), Ok(0));
+    assert_eq!(r.try_recv(), Ok(start + ms(50)));
+
+    // A finished schedule is disconnected, so it's always ready.
+    assert_eq!(sel.ready(), 0);
+}
@@ -4,10 +4,13 @@
 
 use std::any::Any;
 use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::{after, bounded, tick, unbounded, Receiver, Select, TryRecvError};
+use crossbeam_channel::{Fairness, Notifier, Selectable};
+use crossbeam_channel::{RecvTimeoutError, SendTimeoutError};
 use crossbeam_utils::thread::scope;
 
 fn ms(ms: u64) -> Duration {
@@ -1169,6 +1172,433 @@ fn fairness1() {
     assert!(hits.iter().all(|x| x.get() >= COUNT / hits.len() / 2));
 }
 
+#[test]
+fn biased() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = unbounded::<()>();
+    let (s2, r2) = unbounded::<()>();
+
+    for _ in 0..COUNT {
+        s1.send(()).unwrap();
+        s2.send(()).unwrap();
+    }
+
+    let mut sel = Select::new_biased();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+
+    // The first operation is always preferred while it is ready.
+    for _ in 0..COUNT {
+        let oper = sel.select();
+        assert_eq!(oper.index(), oper1);
+        oper.recv(&r1).unwrap();
+    }
+
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper2);
+    oper.recv(&r2).unwrap();
+
+    assert_eq!(sel.ready(), oper2);
+    assert_eq!(sel.try_ready(), Ok(oper2));
+}
+
+#[test]
+fn biased_remove() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+    let (s3, r3) = unbounded::<i32>();
+
+    s1.send(1).unwrap();
+    s2.send(2).unwrap();
+    s3.send(3).unwrap();
+
+    let mut sel = Select::new_biased();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    let oper3 = sel.recv(&r3);
+
+    // Removing an operation keeps the order of the remaining ones.
+    sel.remove(oper1);
+    for _ in 0..10 {
+        assert_eq!(sel.try_ready(), Ok(oper2));
+    }
+
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper2);
+    assert_eq!(oper.recv(&r2), Ok(2));
+
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper3);
+    assert_eq!(oper.recv(&r3), Ok(3));
+}
+
+#[test]
+fn round_robin() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = unbounded::<()>();
+    let (s2, r2) = unbounded::<()>();
+    let (s3, r3) = unbounded::<()>();
+
+    for _ in 0..COUNT {
+        s1.send(()).unwrap();
+        s2.send(()).unwrap();
+        s3.send(()).unwrap();
+    }
+
+    let mut sel = Select::with_fairness(Fairness::RoundRobin);
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    let oper3 = sel.recv(&r3);
+
+    // Ready operations take turns in the order they were added.
+    for _ in 0..COUNT {
+        for &(i, r) in &[(oper1, &r1), (oper2, &r2), (oper3, &r3)] {
+            let oper = sel.select();
+            assert_eq!(oper.index(), i);
+            oper.recv(r).unwrap();
+        }
+    }
+}
+
+#[test]
+fn round_robin_skips_unready() {
+    let (s1, r1) = unbounded::<i32>();
+    let (_s2, r2) = unbounded::<i32>();
+    let (s3, r3) = unbounded::<i32>();
+
+    let mut sel = Select::with_fairness(Fairness::RoundRobin);
+    let oper1 = sel.recv(&r1);
+    let _oper2 = sel.recv(&r2);
+    let oper3 = sel.recv(&r3);
+
+    s1.send(1).unwrap();
+    s1.send(2).unwrap();
+    s3.send(3).unwrap();
+
+    assert_eq!(sel.try_ready(), Ok(oper1));
+    assert_eq!(r1.try_recv(), Ok(1));
+    assert_eq!(sel.try_ready(), Ok(oper3));
+    assert_eq!(r3.try_recv(), Ok(3));
+    assert_eq!(sel.try_ready(), Ok(oper1));
+    assert_eq!(r1.try_recv(), Ok(2));
+
+    // Removing the most recently selected operation keeps the rotation going.
+    s1.send(4).unwrap();
+    s3.send(5).unwrap();
+    sel.remove(oper1);
+    assert_eq!(sel.try_ready(), Ok(oper3));
+    assert_eq!(r3.try_recv(), Ok(5));
+}
+
+#[test]
+fn weighted() {
+    #[cfg(miri)]
+    const COUNT: usize = 100;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = unbounded::<()>();
+    let (s2, r2) = unbounded::<()>();
+    let (s3, r3) = unbounded::<()>();
+
+    for _ in 0..COUNT {
+        s1.send(()).unwrap();
+        s2.send(()).unwrap();
+        s3.send(()).unwrap();
+    }
+
+    let mut sel = Select::with_fairness(Fairness::Weighted);
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    let oper3 = sel.recv(&r3);
+    sel.set_weight(oper1, 3);
+    sel.set_weight(oper3, 0);
+
+    let mut hits = [0usize; 2];
+    for _ in 0..COUNT {
+        let oper = sel.select();
+        match oper.index() {
+            i if i == oper1 => {
+                oper.recv(&r1).unwrap();
+                hits[0] += 1;
+            }
+            i if i == oper2 => {
+                oper.recv(&r2).unwrap();
+                hits[1] += 1;
+            }
+            _ => panic!("operation with zero weight was selected"),
+        }
+    }
+
+    // The first operation should be chosen roughly three times as often as the second one.
+    assert!(hits[0] > hits[1] * 2);
+    assert!(hits[1] > 0);
+}
+
+#[test]
+fn weighted_zero() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    let mut sel = Select::with_fairness(Fairness::Weighted);
+    let _oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    sel.set_weight(oper2, 0);
+
+    // An operation with zero weight is still selected when it's the only one ready.
+    s2.send(7).unwrap();
+    let oper = sel.select_timeout(ms(1000)).unwrap();
+    assert_eq!(oper.index(), oper2);
+    assert_eq!(oper.recv(&r2), Ok(7));
+}
+
+#[test]
+fn weighted_partially_ready() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+    let (s3, r3) = unbounded::<i32>();
+
+    let mut sel = Select::with_fairness(Fairness::Weighted);
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    let oper3 = sel.recv(&r3);
+    sel.set_weight(oper1, 100);
+    sel.set_weight(oper3, 0);
+
+    // The heavy operation is never ready, and the one with zero weight loses to the other one.
+    for i in 0..100 {
+        s2.send(i).unwrap();
+        s3.send(i).unwrap();
+        let oper = sel.select();
+        assert_eq!(oper.index(), oper2);
+        assert_eq!(oper.recv(&r2), Ok(i));
+    }
+}
+
+#[test]
+#[should_panic(expected = "no operation with this index")]
+fn weight_of_removed_operation() {
+    let (_s, r) = unbounded::<i32>();
+
+    let mut sel = Select::with_fairness(Fairness::Weighted);
+    let oper = sel.recv(&r);
+    sel.remove(oper);
+    sel.set_weight(oper, 2);
+}
+
+#[test]
+fn operation_deadlines() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (s2, _r2) = bounded::<i32>(0);
+    let (s3, r3) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.send(&s2);
+    let oper3 = sel.recv(&r3);
+    sel.set_timeout(oper1, ms(1000));
+    sel.set_timeout(oper2, ms(300));
+
+    let start = Instant::now();
+
+    // The send operation has the earliest deadline.
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper2);
+    assert_eq!(oper.send_timeout(&s2, 7), Err(SendTimeoutError::Timeout(7)));
+    assert!(start.elapsed() >= ms(300));
+    sel.remove(oper2);
+
+    s3.send(3).unwrap();
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper3);
+    assert_eq!(oper.recv_timeout(&r3), Ok(3));
+
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper1);
+    assert_eq!(oper.recv_timeout(&r1), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() >= ms(1000));
+    sel.remove(oper1);
+
+    assert!(sel.select_timeout(ms(100)).is_err());
+}
+
+#[test]
+fn operation_deadline_ready_first() {
+    let (s, r) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r);
+    sel.set_timeout(oper1, ms(0));
+
+    // A ready operation completes normally even after its deadline.
+    s.send(1).unwrap();
+    let oper = sel.select();
+    assert_eq!(oper.recv_timeout(&r), Ok(1));
+
+    // The deadline doesn't cut the whole select short.
+    assert_eq!(sel.ready_timeout(ms(100)), Ok(oper1));
+    assert_eq!(sel.try_ready(), Ok(oper1));
+}
+
+#[test]
+fn operation_deadlines_ready() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (_s2, r2) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    sel.set_timeout(oper1, ms(1000));
+    sel.set_timeout(oper2, ms(300));
+
+    let start = Instant::now();
+    assert_eq!(sel.ready(), oper2);
+    assert!(start.elapsed() >= ms(300));
+    assert!(start.elapsed() < ms(1000));
+
+    sel.remove(oper2);
+    assert_eq!(sel.ready(), oper1);
+    assert!(start.elapsed() >= ms(1000));
+}
+
+#[test]
+#[should_panic(expected = "timed out")]
+fn operation_deadline_recv_panics() {
+    let (_s, r) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r);
+    sel.set_timeout(oper1, ms(0));
+
+    let oper = sel.select();
+    let _ = oper.recv(&r);
+}
+
+/// A counting semaphore used to test custom selectable primitives.
+struct Semaphore {
+    permits: AtomicUsize,
+    notifier: Notifier,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Semaphore {
+        Semaphore {
+            permits: AtomicUsize::new(permits),
+            notifier: Notifier::new(),
+        }
+    }
+
+    fn try_acquire(&self) -> bool {
+        let mut n = self.permits.load(Ordering::SeqCst);
+        while n > 0 {
+            match self
+                .permits
+                .compare_exchange(n, n - 1, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return true,
+                Err(m) => n = m,
+            }
+        }
+        false
+    }
+
+    fn release(&self) {
+        self.permits.fetch_add(1, Ordering::SeqCst);
+        self.notifier.notify_one();
+    }
+}
+
+impl Selectable for Semaphore {
+    fn is_ready(&self) -> bool {
+        self.permits.load(Ordering::SeqCst) > 0
+    }
+
+    fn notifier(&self) -> &Notifier {
+        &self.notifier
+    }
+}
+
+#[test]
+fn custom() {
+    let sem = Semaphore::new(0);
+    let (s, r) = unbounded::<i32>();
+
+    let mut sel = Select::new();
+    let oper1 = sel.custom(&sem);
+    let oper2 = sel.recv(&r);
+
+    assert!(sel.try_select().is_err());
+
+    s.send(1).unwrap();
+    let oper = sel.select();
+    assert_eq!(oper.index(), oper2);
+    assert_eq!(oper.recv(&r), Ok(1));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            sem.release();
+        });
+
+        let oper = sel.select_timeout(ms(1000)).unwrap();
+        assert_eq!(oper.index(), oper1);
+        oper.custom(&sem);
+        assert!(sem.try_acquire());
+    })
+    .unwrap();
+
+    assert!(sel.ready_timeout(ms(100)).is_err());
+}
+
+#[test]
+fn custom_many_threads() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+    const THREADS: usize = 4;
+
+    let sem = Semaphore::new(0);
+    let (s, r) = bounded::<()>(0);
+
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| loop {
+                let mut sel = Select::new();
+                let oper1 = sel.custom(&sem);
+                let oper2 = sel.recv(&r);
+                match sel.ready() {
+                    i if i == oper1 => {
+                        sem.try_acquire();
+                    }
+                    i if i == oper2 => {
+                        if r.try_recv().is_ok() {
+                            break;
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            });
+        }
+
+        for _ in 0..COUNT {
+            sem.release();
+        }
+        for _ in 0..THREADS {
+            s.send(()).unwrap();
+        }
+    })
+    .unwrap();
+}
+
 #[test]
 fn fairness2() {
     #[cfg(miri)]
@@ -1326,3 +1756,169 @@ fn reuse() {
     })
     .unwrap();
 }
+
+#[test]
+fn stats_disabled() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+
+    let mut sel = Select::new();
+    sel.recv(&r);
+    assert!(sel.stats().is_none());
+
+    let oper = sel.select();
+    assert_eq!(oper.recv(&r), Ok(1));
+    assert!(sel.stats().is_none());
+}
+
+#[test]
+fn stats_wins() {
+    let (s1, r1) = unbounded();
+    let (s2, r2) = unbounded();
+    for i in 0..10 {
+        s1.send(i).unwrap();
+    }
+    s2.send(0).unwrap();
+
+    let mut sel = Select::new_biased();
+    let oper1 = sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+    sel.enable_stats();
+
+    for _ in 0..11 {
+        let oper = sel.select();
+        match oper.index() {
+            i if i == oper1 => assert!(oper.recv(&r1).is_ok()),
+            i if i == oper2 => assert!(oper.recv(&r2).is_ok()),
+            _ => unreachable!(),
+        }
+    }
+    assert!(sel.try_select().is_err());
+
+    let stats = sel.stats().unwrap();
+    assert_eq!(stats.wins(oper1), 10);
+    assert_eq!(stats.wins(oper2), 1);
+    assert_eq!(stats.wins(oper2 + 1), 0);
+    assert_eq!(stats.total_wins(), 11);
+    assert_eq!(stats.timeouts(), 1);
+}
+
+#[test]
+fn stats_ready() {
+    let (s, r) = unbounded();
+    let (_s2, r2) = unbounded::<i32>();
+    s.send(1).unwrap();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r);
+    let oper2 = sel.recv(&r2);
+    sel.enable_stats();
+
+    assert_eq!(sel.ready(), oper1);
+    assert_eq!(sel.try_ready(), Ok(oper1));
+    assert_eq!(r.try_recv(), Ok(1));
+    assert!(sel.ready_timeout(ms(10)).is_err());
+
+    let stats = sel.stats().unwrap();
+    assert_eq!(stats.wins(oper1), 2);
+    assert_eq!(stats.wins(oper2), 0);
+    assert_eq!(stats.timeouts(), 1);
+}
+
+#[test]
+fn stats_blocked() {
+    let (s1, r1) = unbounded();
+    let (_s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        let mut sel = Select::new();
+        let oper1 = sel.recv(&r1);
+        let oper2 = sel.recv(&r2);
+        sel.enable_stats();
+
+        scope.spawn(|_| {
+            thread::sleep(ms(200));
+            s1.send(1).unwrap();
+        });
+
+        assert!(sel.select_timeout(ms(100)).is_err());
+        let oper = sel.select();
+        assert_eq!(oper.index(), oper1);
+        assert_eq!(oper.recv(&r1), Ok(1));
+
+        let stats = sel.stats().unwrap();
+        assert!(stats.blocked(oper1) >= ms(50));
+        assert!(stats.blocked(oper1) < ms(1000));
+        assert_eq!(stats.blocked(oper2), ms(0));
+        assert!(stats.total_blocked() >= ms(200));
+        assert!(stats.total_blocked() >= stats.blocked(oper1));
+    })
+    .unwrap();
+}
+
+#[test]
+fn stats_reset() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    let mut sel = Select::new();
+    let oper = sel.recv(&r);
+    sel.enable_stats();
+
+    assert_eq!(sel.ready(), oper);
+    assert_eq!(sel.stats().unwrap().wins(oper), 1);
+
+    let cloned = sel.clone();
+    sel.enable_stats();
+    assert_eq!(sel.stats().unwrap().wins(oper), 0);
+    assert_eq!(cloned.stats().unwrap().wins(oper), 1);
+}
+
+#[test]
+fn timeouts_keep_messages() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 2_000;
+
+    let chans = vec![unbounded(), bounded(3), bounded(0)];
+
+    for (s, r) in chans {
+        let received = AtomicUsize::new(0);
+
+        scope(|scope| {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+
+            // Receivers keep timing out while messages arrive, both on their own and in a
+            // selection. Every message must still be received exactly once.
+            for _ in 0..2 {
+                scope.spawn(|_| {
+                    while received.load(Ordering::SeqCst) < COUNT {
+                        if r.recv_timeout(Duration::from_micros(1)).is_ok() {
+                            received.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                });
+                scope.spawn(|_| {
+                    while received.load(Ordering::SeqCst) < COUNT {
+                        let mut sel = Select::new();
+                        sel.recv(&r);
+                        if let Ok(oper) = sel.select_timeout(Duration::from_micros(1)) {
+                            oper.recv(&r).unwrap();
+                            received.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                });
+            }
+        })
+        .unwrap();
+
+        assert_eq!(received.load(Ordering::SeqCst), COUNT);
+        assert!(r.try_recv().is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Tests that selection over a few operations doesn't allocate.
+
+#![cfg(not(miri))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, select, unbounded, Fairness, Select};
+
+/// An allocator that counts allocations while `COUNTING` is set.
+struct Counter;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::SeqCst) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+/// Returns the number of allocations made by `f`.
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    COUNTING.store(true, Ordering::SeqCst);
+    f();
+    COUNTING.store(false, Ordering::SeqCst);
+    ALLOCATIONS.load(Ordering::SeqCst)
+}
+
+// All checks are in a single test so that no other test allocates at the same time.
+#[test]
+fn small_select_does_not_allocate() {
+    let (s1, r1) = bounded(100);
+    let (s2, r2) = unbounded();
+    let (s3, r3) = bounded::<i32>(0);
+    let others: Vec<_> = (0..5).map(|_| unbounded::<i32>()).collect();
+
+    for i in 0..100 {
+        s1.send(i).unwrap();
+        s2.send(i).unwrap();
+    }
+
+    // A list of 8 operations created on every iteration.
+    let n = allocations(|| {
+        for _ in 0..10 {
+            let mut sel = Select::new();
+            sel.recv(&r1);
+            sel.recv(&r2);
+            sel.send(&s3);
+            for (_, r) in &others {
+                sel.recv(r);
+            }
+
+            let oper = sel.try_select().unwrap();
+            match oper.index() {
+                0 => assert!(oper.recv(&r1).is_ok()),
+                1 => assert!(oper.recv(&r2).is_ok()),
+                _ => unreachable!(),
+            }
+            assert!(sel.ready() < 2);
+        }
+    });
+    assert_eq!(n, 0);
+
+    // Other fairness policies, weights and deadlines.
+    for &fairness in &[Fairness::Biased, Fairness::RoundRobin, Fairness::Weighted] {
+        let mut sel = Select::with_fairness(fairness);
+        let oper1 = sel.recv(&r1);
+        let oper2 = sel.recv(&r3);
+        sel.set_weight(oper2, 5);
+        sel.set_deadline(oper2, Instant::now() + Duration::from_secs(10));
+
+        let n = allocations(|| {
+            for _ in 0..10 {
+                let oper = sel.select();
+                assert_eq!(oper.index(), oper1);
+                assert!(oper.recv(&r1).is_ok());
+            }
+        });
+        assert_eq!(n, 0);
+    }
+
+    // The macro keeps its operations on the stack too.
+    let n = allocations(|| {
+        for _ in 0..10 {
+            select! {
+                recv(r1) -> msg => assert!(msg.is_ok()),
+                recv(r2) -> msg => assert!(msg.is_ok()),
+                recv(r3) -> _ => unreachable!(),
+            }
+        }
+    });
+    assert_eq!(n, 0);
+
+    // More operations than fit inline.
+    let n = allocations(|| {
+        let mut sel = Select::new();
+        for _ in 0..9 {
+            sel.recv(&r1);
+        }
+        sel.try_select().unwrap().recv(&r1).unwrap();
+    });
+    assert_eq!(n, 1);
+}
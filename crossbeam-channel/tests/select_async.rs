@@ -0,0 +1,349 @@
+//! Tests for async selection.
+
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{after, bounded, never, tick, unbounded, RecvTimeoutError, Select};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Wakes up a thread and counts how many times that happened.
+struct Unparker {
+    thread: Thread,
+    wakes: AtomicUsize,
+}
+
+unsafe fn clone(p: *const ()) -> RawWaker {
+    let arc = Arc::from_raw(p as *const Unparker);
+    let raw = RawWaker::new(Arc::into_raw(arc.clone()) as *const (), &VTABLE);
+    let _ = Arc::into_raw(arc);
+    raw
+}
+
+unsafe fn wake(p: *const ()) {
+    wake_by_ref(p);
+    drop_waker(p);
+}
+
+unsafe fn wake_by_ref(p: *const ()) {
+    let unparker = &*(p as *const Unparker);
+    unparker.wakes.fetch_add(1, Ordering::SeqCst);
+    unparker.thread.unpark();
+}
+
+unsafe fn drop_waker(p: *const ()) {
+    drop(Arc::from_raw(p as *const Unparker));
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_waker);
+
+fn waker(unparker: &Arc<Unparker>) -> Waker {
+    let raw = RawWaker::new(Arc::into_raw(unparker.clone()) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+fn unparker() -> Arc<Unparker> {
+    Arc::new(Unparker {
+        thread: thread::current(),
+        wakes: AtomicUsize::new(0),
+    })
+}
+
+/// Runs a future to completion on the current thread.
+fn block_on<F: Future>(mut f: F) -> F::Output {
+    let unparker = unparker();
+    let waker = waker(&unparker);
+    let mut cx = Context::from_waker(&waker);
+    let mut f = unsafe { Pin::new_unchecked(&mut f) };
+
+    loop {
+        if let Poll::Ready(out) = f.as_mut().poll(&mut cx) {
+            return out;
+        }
+        thread::park();
+    }
+}
+
+fn poll_once<F: Future + Unpin>(f: &mut F, waker: &Waker) -> Poll<F::Output> {
+    Pin::new(f).poll(&mut Context::from_waker(waker))
+}
+
+#[test]
+fn select_ready() {
+    let (s1, r1) = unbounded::<i32>();
+    let (_s2, r2) = unbounded::<i32>();
+    s1.send(1).unwrap();
+
+    let mut sel = Select::new();
+    let oper1 = sel.recv(&r1);
+    sel.recv(&r2);
+
+    let oper = block_on(sel.select_async());
+    assert_eq!(oper.index(), oper1);
+    assert_eq!(oper.recv(&r1), Ok(1));
+}
+
+#[test]
+fn select_wakes_on_send() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut sel = Select::new();
+    sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+
+    let mut fut = sel.select_async();
+    assert!(poll_once(&mut fut, &waker).is_pending());
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 0);
+
+    s2.send(2).unwrap();
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    match poll_once(&mut fut, &waker) {
+        Poll::Ready(oper) => {
+            assert_eq!(oper.index(), oper2);
+            assert_eq!(oper.recv(&r2), Ok(2));
+        }
+        Poll::Pending => panic!("operation should be ready"),
+    };
+}
+
+#[test]
+fn drop_select_future() {
+    let (s, r) = unbounded::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut sel = Select::new();
+    sel.recv(&r);
+    let mut fut = sel.select_async();
+    assert!(poll_once(&mut fut, &waker).is_pending());
+    s.send(1).unwrap();
+    drop(fut);
+
+    // The message wasn't claimed by the dropped future.
+    assert_eq!(r.try_recv(), Ok(1));
+}
+
+#[test]
+fn select_send() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut sel = Select::new();
+    let oper1 = sel.send(&s);
+
+    let mut fut = sel.select_async();
+    assert!(poll_once(&mut fut, &waker).is_pending());
+
+    assert_eq!(r.recv(), Ok(1));
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    match poll_once(&mut fut, &waker) {
+        Poll::Ready(oper) => {
+            assert_eq!(oper.index(), oper1);
+            assert_eq!(oper.send(&s, 2), Ok(()));
+        }
+        Poll::Pending => panic!("operation should be ready"),
+    }
+    assert_eq!(r.try_recv(), Ok(2));
+}
+
+#[test]
+fn select_disconnected() {
+    let (s, r) = unbounded::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    let mut sel = Select::new();
+    sel.recv(&r);
+
+    let mut fut = sel.select_async();
+    assert!(poll_once(&mut fut, &waker).is_pending());
+
+    drop(s);
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    match poll_once(&mut fut, &waker) {
+        Poll::Ready(oper) => assert!(oper.recv(&r).is_err()),
+        Poll::Pending => panic!("operation should be ready"),
+    };
+}
+
+#[test]
+fn select_timer() {
+    let start = Instant::now();
+    let r1 = never::<i32>();
+    let r2 = after(ms(50));
+
+    let mut sel = Select::new();
+    sel.recv(&r1);
+    let oper2 = sel.recv(&r2);
+
+    let oper = block_on(sel.select_async());
+    assert_eq!(oper.index(), oper2);
+    assert!(oper.recv(&r2).is_ok());
+    assert!(start.elapsed() >= ms(50));
+}
+
+#[test]
+fn select_deadline() {
+    let start = Instant::now();
+    let r = never::<i32>();
+
+    let mut sel = Select::new();
+    let oper = sel.recv(&r);
+    sel.set_timeout(oper, ms(50));
+
+    let selected = block_on(sel.select_async());
+    assert_eq!(selected.index(), oper);
+    assert_eq!(selected.recv_timeout(&r), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() >= ms(50));
+}
+
+#[test]
+fn ready() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(50));
+            s2.send(2).unwrap();
+            thread::sleep(ms(50));
+            s1.send(1).unwrap();
+        });
+
+        let mut sel = Select::new();
+        let oper1 = sel.recv(&r1);
+        let oper2 = sel.recv(&r2);
+
+        assert_eq!(block_on(sel.ready_async()), oper2);
+        assert_eq!(r2.try_recv(), Ok(2));
+        assert_eq!(block_on(sel.ready_async()), oper1);
+        assert_eq!(r1.try_recv(), Ok(1));
+    })
+    .unwrap();
+}
+
+#[test]
+fn ticker_and_channel() {
+    let (s, r) = unbounded();
+    let t = tick(ms(20));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..5 {
+                thread::sleep(ms(30));
+                s.send(i).unwrap();
+            }
+        });
+
+        block_on(async {
+            let mut msgs = 0;
+            let mut ticks = 0;
+            while msgs < 5 {
+                let mut sel = Select::new();
+                let oper1 = sel.recv(&r);
+                let oper2 = sel.recv(&t);
+
+                let oper = sel.select_async().await;
+                match oper.index() {
+                    i if i == oper1 => {
+                        assert_eq!(oper.recv(&r), Ok(msgs));
+                        msgs += 1;
+                    }
+                    i if i == oper2 => {
+                        assert!(oper.recv(&t).is_ok());
+                        ticks += 1;
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert!(ticks >= 3);
+        });
+    })
+    .unwrap();
+}
+
+#[test]
+fn stats() {
+    let (s, r) = unbounded::<i32>();
+    s.send(1).unwrap();
+
+    let mut sel = Select::new();
+    let oper = sel.recv(&r);
+    sel.enable_stats();
+
+    let selected = block_on(sel.select_async());
+    assert_eq!(selected.recv(&r), Ok(1));
+
+    let stats = sel.stats().unwrap();
+    assert_eq!(stats.wins(oper), 1);
+    assert_eq!(stats.timeouts(), 0);
+}
+
+#[test]
+#[should_panic(expected = "no operations have been added to `Select`")]
+fn empty() {
+    let mut sel = Select::new();
+    drop(sel.select_async());
+}
+
+#[test]
+fn many_tasks() {
+    const COUNT: usize = 2000;
+    const THREADS: usize = 4;
+
+    let (s1, r1) = bounded::<usize>(2);
+    let (s2, r2) = bounded::<usize>(2);
+    let received = AtomicUsize::new(0);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                if i % 2 == 0 {
+                    s1.send(i).unwrap();
+                } else {
+                    s2.send(i).unwrap();
+                }
+            }
+        });
+
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                block_on(async {
+                    while received.load(Ordering::SeqCst) < COUNT {
+                        let mut sel = Select::new();
+                        sel.recv(&r1);
+                        sel.recv(&r2);
+                        sel.set_timeout(0, ms(10));
+
+                        let oper = sel.select_async().await;
+                        let res = match oper.index() {
+                            0 => oper.recv_timeout(&r1).map_err(|_| ()),
+                            _ => oper.recv(&r2).map_err(|_| ()),
+                        };
+                        if res.is_ok() {
+                            received.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                })
+            });
+        }
+    })
+    .unwrap();
+
+    assert_eq!(received.load(Ordering::SeqCst), COUNT);
+}
@@ -0,0 +1,123 @@
+//! Tests for the `select_enum!` macro.
+
+#![forbid(unsafe_code)] // select_enum! is safe.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, select_enum, unbounded, RecvError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Number(i32),
+    Text(String),
+    Flag(bool),
+    Timeout,
+}
+
+#[test]
+fn smoke() {
+    let (s1, r1) = unbounded();
+    let (s2, r2) = unbounded();
+
+    s1.send(1).unwrap();
+    assert_eq!(
+        select_enum! {
+            recv(r1) => Event::Number,
+            recv(r2) => Event::Text,
+        },
+        Ok(Event::Number(1))
+    );
+
+    s2.send("foo".to_string()).unwrap();
+    assert_eq!(
+        select_enum! {
+            recv(r1) => Event::Number,
+            recv(r2) => Event::Text,
+        },
+        Ok(Event::Text("foo".to_string()))
+    );
+}
+
+#[test]
+fn single() {
+    let (s, r) = unbounded();
+    s.send(true).unwrap();
+
+    assert_eq!(
+        select_enum! { recv(r) => Event::Flag },
+        Ok(Event::Flag(true))
+    );
+}
+
+#[test]
+fn closure() {
+    let (s, r) = unbounded();
+    s.send(20).unwrap();
+
+    let event = select_enum! {
+        recv(r) => |n| Event::Number(n * 2),
+    };
+    assert_eq!(event, Ok(Event::Number(40)));
+}
+
+#[test]
+fn disconnected() {
+    let (s, r1) = unbounded::<i32>();
+    let (_s, r2) = unbounded::<bool>();
+    drop(s);
+
+    let event = select_enum! {
+        recv(r1) => Event::Number,
+        recv(r2) => Event::Flag,
+    };
+    assert_eq!(event, Err(RecvError));
+}
+
+#[test]
+fn default() {
+    let (_s1, r1) = unbounded::<i32>();
+    let (_s2, r2) = unbounded::<bool>();
+
+    let event = select_enum! {
+        recv(r1) => Event::Number,
+        recv(r2) => Event::Flag,
+        default => Event::Timeout,
+    };
+    assert_eq!(event, Ok(Event::Timeout));
+
+    let start = Instant::now();
+    let event = select_enum! {
+        recv(r1) => Event::Number,
+        recv(r2) => Event::Flag,
+        default(ms(100)) => Event::Timeout
+    };
+    assert_eq!(event, Ok(Event::Timeout));
+    assert!(start.elapsed() >= ms(100));
+}
+
+#[test]
+fn blocks() {
+    let (s1, r1) = bounded(0);
+    let (_s2, r2) = unbounded::<bool>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            s1.send(7).unwrap();
+        });
+
+        let event = select_enum! {
+            recv(r1) => Event::Number,
+            recv(r2) => Event::Flag,
+            default(ms(1000)) => Event::Timeout,
+        };
+        assert_eq!(event, Ok(Event::Number(7)));
+    })
+    .unwrap();
+}
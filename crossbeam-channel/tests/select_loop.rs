@@ -0,0 +1,210 @@
+//! Tests for the `select_loop!` macro.
+
+#![forbid(unsafe_code)] // select_loop! is safe.
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{after, bounded, never, select_loop, unbounded};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s1, r1) = unbounded();
+    let (s2, r2) = unbounded();
+
+    s1.send(1).unwrap();
+    s2.send("a").unwrap();
+    s1.send(2).unwrap();
+    drop(s1);
+    drop(s2);
+
+    let mut numbers = Vec::new();
+    let mut strings = Vec::new();
+
+    select_loop! {
+        recv(r1) -> n => numbers.push(n),
+        recv(r2) -> s => strings.push(s),
+    }
+
+    assert_eq!(numbers, [1, 2]);
+    assert_eq!(strings, ["a"]);
+}
+
+#[test]
+fn single() {
+    let (s, r) = unbounded();
+    s.send(7).unwrap();
+    drop(s);
+
+    let mut received = None;
+    select_loop! {
+        recv(r) -> n => received = Some(n)
+    }
+    assert_eq!(received, Some(7));
+}
+
+#[test]
+fn blocks() {
+    let (s1, r1) = unbounded();
+    let (s2, r2) = unbounded();
+
+    scope(|scope| {
+        scope.spawn(move |_| {
+            thread::sleep(ms(100));
+            s1.send(1).unwrap();
+            drop(s1);
+            thread::sleep(ms(100));
+            s2.send(2).unwrap();
+        });
+
+        let mut sum = 0;
+        select_loop! {
+            recv(r1) -> n => sum += n,
+            recv(r2) -> n => sum += n,
+        }
+        assert_eq!(sum, 3);
+    })
+    .unwrap();
+}
+
+#[test]
+fn drains_before_disconnecting() {
+    let (s, r) = bounded(10);
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    drop(s);
+
+    let mut count = 0;
+    select_loop! {
+        recv(r) -> _ => count += 1,
+        recv(r) -> _ => count += 1,
+    }
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn never_disconnects() {
+    let (s, r) = unbounded();
+    s.send(1).unwrap();
+    drop(s);
+
+    // A `never` channel keeps the loop running, so it has to be stopped with `break`.
+    let mut received = Vec::new();
+    select_loop! {
+        recv(r) -> n => received.push(n),
+        recv(never::<()>()) -> _ => panic!(),
+        recv(after(ms(100))) -> _ => break,
+    }
+    assert_eq!(received, [1]);
+}
+
+#[test]
+fn break_and_continue() {
+    let (s, r) = unbounded();
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+
+    let mut received = Vec::new();
+    select_loop! {
+        recv(r) -> n => {
+            if n % 2 == 0 {
+                continue;
+            }
+            if n > 6 {
+                break;
+            }
+            received.push(n);
+        }
+    }
+
+    assert_eq!(received, [1, 3, 5]);
+    assert_eq!(r.try_recv(), Ok(8));
+}
+
+#[test]
+fn patterns() {
+    let (s, r) = unbounded();
+    s.send((1, "a")).unwrap();
+    s.send((2, "b")).unwrap();
+    drop(s);
+
+    let mut received = Vec::new();
+    select_loop! {
+        recv(r) -> (n, _) => received.push(n),
+    }
+    assert_eq!(received, [1, 2]);
+}
+
+#[test]
+fn send() {
+    let (s1, r1) = bounded(0);
+    let (s2, r2) = bounded(0);
+
+    scope(|scope| {
+        let w1 = scope.spawn(move |_| r1.iter().take(3).collect::<Vec<i32>>());
+        let w2 = scope.spawn(move |_| r2.iter().take(5).collect::<Vec<i32>>());
+
+        let mut next = 0;
+        select_loop! {
+            send(s1, next) => next += 1,
+            send(s2, next) => { next += 1 }
+        }
+        assert_eq!(next, 8);
+
+        let mut sent = w1.join().unwrap();
+        sent.extend(w2.join().unwrap());
+        sent.sort();
+        assert_eq!(sent, (0..8).collect::<Vec<_>>());
+    })
+    .unwrap();
+}
+
+#[test]
+fn send_disconnected() {
+    let (s, r) = unbounded::<i32>();
+    drop(r);
+
+    // The message is only evaluated when the case is selected, and then dropped.
+    let mut evaluated = 0;
+    select_loop! {
+        send(s, {
+            evaluated += 1;
+            evaluated
+        }) => panic!()
+    }
+    assert_eq!(evaluated, 1);
+}
+
+#[test]
+fn mixed() {
+    let (in_s, in_r) = unbounded();
+    let (out_s, out_r) = bounded(0);
+    for i in 0..5 {
+        in_s.send(i).unwrap();
+    }
+    drop(in_s);
+
+    scope(|scope| {
+        let consumer = scope.spawn(move |_| out_r.iter().take(3).count());
+
+        // Both cases are dropped as their channels disconnect, which ends the loop.
+        let mut received = Vec::new();
+        let mut sent = 0;
+        select_loop! {
+            recv(in_r) -> n => received.push(n),
+            send(out_s, "tick") => sent += 1,
+        }
+
+        assert_eq!(received, [0, 1, 2, 3, 4]);
+        assert_eq!(sent, 3);
+        assert_eq!(consumer.join().unwrap(), 3);
+    })
+    .unwrap();
+}
@@ -9,7 +9,7 @@ use std::ops::Deref;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crossbeam_channel::{after, bounded, never, select, tick, unbounded};
+use crossbeam_channel::{after, bounded, never, select, select_biased, tick, unbounded};
 use crossbeam_channel::{Receiver, RecvError, SendError, Sender, TryRecvError};
 use crossbeam_utils::thread::scope;
 
@@ -858,6 +858,67 @@ fn fairness1() {
     assert!(hits.iter().all(|x| *x >= COUNT / hits.len() / 2));
 }
 
+#[test]
+fn biased() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = unbounded::<()>();
+    let (s2, r2) = unbounded::<()>();
+    let (s3, r3) = unbounded::<()>();
+
+    for _ in 0..COUNT {
+        s1.send(()).unwrap();
+        s2.send(()).unwrap();
+        s3.send(()).unwrap();
+    }
+
+    let hits = vec![Cell::new(0usize); 3];
+    for _ in 0..COUNT * 3 {
+        select_biased! {
+            recv(r1) -> _ => hits[0].set(hits[0].get() + 1),
+            recv(r2) -> _ => hits[1].set(hits[1].get() + 1),
+            recv(r3) -> _ => hits[2].set(hits[2].get() + 1),
+        }
+
+        // Operations listed later are only selected once the earlier ones are not ready.
+        if hits[1].get() > 0 {
+            assert_eq!(hits[0].get(), COUNT);
+        }
+        if hits[2].get() > 0 {
+            assert_eq!(hits[1].get(), COUNT);
+        }
+    }
+    assert!(hits.iter().all(|x| x.get() == COUNT));
+}
+
+#[test]
+fn biased_default() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    s1.send(1).unwrap();
+    s2.send(2).unwrap();
+
+    select_biased! {
+        recv(r1) -> msg => assert_eq!(msg, Ok(1)),
+        recv(r2) -> _ => panic!(),
+        default => panic!(),
+    }
+    select_biased! {
+        recv(r1) -> _ => panic!(),
+        recv(r2) -> msg => assert_eq!(msg, Ok(2)),
+        default(ms(1000)) => panic!(),
+    }
+    select_biased! {
+        recv(r1) -> _ => panic!(),
+        recv(r2) -> _ => panic!(),
+        default => {}
+    }
+}
+
 #[test]
 fn fairness2() {
     #[cfg(miri)]
@@ -1478,3 +1539,122 @@ fn trailing_comma() {
         default(ms(1000),) => {},
     }
 }
+
+#[test]
+fn guard() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    s1.send(1).unwrap();
+    s2.send(2).unwrap();
+
+    for _ in 0..10 {
+        select! {
+            recv(r1) -> _, if false => panic!(),
+            recv(r2) -> v, if true => assert_eq!(v, Ok(2)),
+        }
+        s2.send(2).unwrap();
+    }
+
+    let enabled = Cell::new(false);
+    select! {
+        recv(r1) -> _, if enabled.get() => panic!(),
+        default => {}
+    }
+    select! {
+        recv(r1) -> _, if enabled.get() => panic!(),
+        default(ms(100)) => {}
+    }
+
+    enabled.set(true);
+    select! {
+        recv(r1) -> v, if enabled.get() => assert_eq!(v, Ok(1)),
+        default => panic!(),
+    }
+}
+
+#[test]
+fn guard_send() {
+    let (s, r) = bounded::<i32>(1);
+    let evaluated = Cell::new(false);
+
+    select! {
+        send(s, { evaluated.set(true); 1 }) -> _, if false => panic!(),
+        default => {}
+    }
+    assert!(!evaluated.get());
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    select! {
+        send(s, { evaluated.set(true); 1 }) -> res, if true => assert_eq!(res, Ok(())),
+        default => panic!(),
+    }
+    assert!(evaluated.get());
+    assert_eq!(r.try_recv(), Ok(1));
+}
+
+#[test]
+fn guard_disconnected() {
+    let (s, r) = unbounded::<i32>();
+    drop(s);
+
+    // A disconnected channel is ready, but not if its guard is false.
+    select! {
+        recv(r) -> _, if false => panic!(),
+        default(ms(100)) => {}
+    }
+}
+
+#[test]
+fn guard_blocks() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            s1.send(1).unwrap();
+            thread::sleep(ms(100));
+            s2.send(2).unwrap();
+        });
+
+        let start = Instant::now();
+        select! {
+            recv(r1) -> _, if false => panic!(),
+            recv(r2) -> v => assert_eq!(v, Ok(2)),
+        }
+        assert!(start.elapsed() >= ms(200));
+    })
+    .unwrap();
+}
+
+#[test]
+fn guard_biased() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    s1.send(1).unwrap();
+    s2.send(2).unwrap();
+
+    select_biased! {
+        recv(r1) -> _, if false => panic!(),
+        recv(r2) -> v => assert_eq!(v, Ok(2)),
+    }
+    select_biased! {
+        recv(r1) -> v, if true => assert_eq!(v, Ok(1)),
+        recv(r2) -> _ => panic!(),
+    }
+}
+
+#[test]
+fn guard_block_body() {
+    let (s, r) = unbounded::<i32>();
+    s.send(1).unwrap();
+
+    select! {
+        recv(r) -> v, if 1 + 1 == 2 => {
+            assert_eq!(v, Ok(1));
+        }
+        default => panic!()
+    }
+}
@@ -0,0 +1,328 @@
+//! Tests for persistent readiness selection using the `SelectSet` struct.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{after, bounded, tick, unbounded, Receiver, SelectSet};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s1, r1) = unbounded::<usize>();
+    let (s2, r2) = unbounded::<usize>();
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    let oper2 = set.recv(&r2);
+
+    assert!(set.try_ready().is_err());
+
+    s1.send(1).unwrap();
+    assert_eq!(set.ready(), oper1);
+    assert_eq!(r1.try_recv(), Ok(1));
+
+    s2.send(2).unwrap();
+    assert_eq!(set.ready(), oper2);
+    assert_eq!(r2.try_recv(), Ok(2));
+
+    assert!(set.try_ready().is_err());
+}
+
+#[test]
+fn disconnected() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    let mut set = SelectSet::new();
+    set.recv(&r1);
+    let oper2 = set.recv(&r2);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(500));
+            drop(s2);
+        });
+
+        assert_eq!(set.ready_timeout(ms(1000)), Ok(oper2));
+        assert!(r2.try_recv().is_err());
+    })
+    .unwrap();
+
+    drop(s1);
+}
+
+#[test]
+fn timeout() {
+    let (_s, r) = unbounded::<i32>();
+
+    let mut set = SelectSet::new();
+    set.recv(&r);
+
+    let start = Instant::now();
+    assert!(set.ready_timeout(ms(300)).is_err());
+    assert!(start.elapsed() >= ms(300));
+}
+
+#[test]
+fn send_operations() {
+    let (s1, r1) = bounded::<i32>(1);
+    let (s2, r2) = bounded::<i32>(0);
+
+    let mut set = SelectSet::new();
+    let oper1 = set.send(&s1);
+    let oper2 = set.send(&s2);
+
+    assert_eq!(set.try_ready(), Ok(oper1));
+    s1.send(1).unwrap();
+    assert!(set.try_ready().is_err());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(300));
+            assert_eq!(r2.recv(), Ok(2));
+        });
+
+        // A receiver blocking on the zero-capacity channel makes the send operation ready.
+        loop {
+            assert_eq!(set.ready_timeout(ms(1000)), Ok(oper2));
+            if s2.try_send(2).is_ok() {
+                break;
+            }
+        }
+    })
+    .unwrap();
+
+    assert_eq!(r1.try_recv(), Ok(1));
+    assert_eq!(set.try_ready(), Ok(oper1));
+}
+
+#[test]
+fn timers() {
+    let r1 = after(ms(300));
+    let r2 = tick(ms(1000));
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    set.recv(&r2);
+
+    let start = Instant::now();
+    assert_eq!(set.ready(), oper1);
+    assert!(start.elapsed() >= ms(300));
+    assert!(r1.try_recv().is_ok());
+}
+
+#[test]
+fn many_timers() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 1000;
+
+    // Deadlines in reverse order of addition, spread over 200 ms.
+    let rs: Vec<_> = (0..COUNT)
+        .map(|i| after(ms(200) * (COUNT - i) as u32 / COUNT as u32))
+        .collect();
+
+    let mut set = SelectSet::new();
+    for r in &rs {
+        set.recv(r);
+    }
+
+    let mut fired = vec![false; COUNT];
+    for _ in 0..COUNT {
+        let index = set.ready();
+        let when = rs[index].try_recv().unwrap();
+        assert!(when <= Instant::now());
+        assert!(!fired[index]);
+        fired[index] = true;
+    }
+    assert!(set.ready_timeout(ms(100)).is_err());
+}
+
+#[test]
+fn tick_keeps_firing() {
+    let r1 = tick(ms(50));
+    let (_s2, r2) = unbounded::<i32>();
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    set.recv(&r2);
+
+    let start = Instant::now();
+    for i in 1..=4 {
+        assert_eq!(set.ready(), oper1);
+        assert!(r1.try_recv().is_ok());
+        assert!(start.elapsed() >= ms(50) * i);
+    }
+
+    // An expired tick stays ready until it is received.
+    thread::sleep(ms(100));
+    assert_eq!(set.try_ready(), Ok(oper1));
+    assert_eq!(set.try_ready(), Ok(oper1));
+    assert!(r1.try_recv().is_ok());
+    assert!(set.try_ready().is_err());
+}
+
+#[test]
+fn remove() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    let oper2 = set.recv(&r2);
+
+    s1.send(1).unwrap();
+    set.remove(oper1);
+    assert!(set.try_ready().is_err());
+
+    s2.send(2).unwrap();
+    assert_eq!(set.ready(), oper2);
+
+    // Removed operations stop notifying the set, so their channels can be used freely.
+    s1.send(3).unwrap();
+    assert_eq!(r1.try_recv(), Ok(1));
+    assert_eq!(r1.try_recv(), Ok(3));
+}
+
+#[test]
+#[should_panic(expected = "no operations have been added to `SelectSet`")]
+fn empty_ready_panics() {
+    let mut set = SelectSet::new();
+    set.ready();
+}
+
+#[test]
+fn takes_turns() {
+    let (s1, r1) = unbounded::<()>();
+    let (s2, r2) = unbounded::<()>();
+
+    for _ in 0..10 {
+        s1.send(()).unwrap();
+        s2.send(()).unwrap();
+    }
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    let oper2 = set.recv(&r2);
+
+    // Both channels stay ready, so neither one starves the other.
+    let mut prev = None;
+    for _ in 0..10 {
+        let index = set.ready();
+        assert_ne!(Some(index), prev);
+        prev = Some(index);
+        assert!(index == oper1 || index == oper2);
+    }
+}
+
+#[test]
+fn disconnected_does_not_starve() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+    drop(s1);
+
+    let mut set = SelectSet::new();
+    let oper1 = set.recv(&r1);
+    let oper2 = set.recv(&r2);
+
+    // The first operation stays ready forever, and the second one isn't ready yet.
+    assert_eq!(set.ready(), oper1);
+    assert_eq!(set.ready(), oper1);
+
+    s2.send(1).unwrap();
+    let mut found = false;
+    for _ in 0..10 {
+        if set.ready() == oper2 {
+            found = true;
+            break;
+        }
+    }
+    assert!(found);
+    assert_eq!(r2.try_recv(), Ok(1));
+}
+
+#[test]
+fn many_channels() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+    const CHANNELS: usize = 200;
+
+    let (ss, rs): (Vec<_>, Vec<Receiver<usize>>) = (0..CHANNELS).map(|_| bounded(1)).unzip();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                ss[i % CHANNELS].send(i).unwrap();
+            }
+        });
+
+        let mut set = SelectSet::new();
+        for r in &rs {
+            set.recv(r);
+        }
+
+        let mut sum = 0;
+        let mut received = 0;
+        while received < COUNT {
+            let index = set.ready();
+            if let Ok(i) = rs[index].try_recv() {
+                sum += i;
+                received += 1;
+            }
+        }
+        assert_eq!(sum, COUNT * (COUNT - 1) / 2);
+    })
+    .unwrap();
+}
+
+#[test]
+fn stress_threads() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = bounded::<usize>(0);
+    let (s2, r2) = unbounded::<usize>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                if i % 2 == 0 {
+                    s1.send(i).unwrap();
+                } else {
+                    s2.send(i).unwrap();
+                }
+            }
+        });
+
+        let mut set = SelectSet::new();
+        let oper1 = set.recv(&r1);
+        let oper2 = set.recv(&r2);
+
+        let mut received = 0;
+        while received < COUNT {
+            match set.ready() {
+                i if i == oper1 => {
+                    if r1.try_recv().is_ok() {
+                        received += 1;
+                    }
+                }
+                i if i == oper2 => {
+                    if r2.try_recv().is_ok() {
+                        received += 1;
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    })
+    .unwrap();
+}
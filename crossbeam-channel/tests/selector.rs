@@ -0,0 +1,200 @@
+//! Tests for selection using the `Selector` struct.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, never, unbounded, Selector};
+use crossbeam_channel::{RecvError, SendError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s1, r1) = unbounded::<i32>();
+    let (s2, r2) = unbounded::<i32>();
+
+    s1.send(1).unwrap();
+    let res = Selector::new()
+        .recv(&r1, |msg| (1, msg))
+        .recv(&r2, |msg| (2, msg))
+        .wait();
+    assert_eq!(res, (1, Ok(1)));
+
+    s2.send(2).unwrap();
+    let res = Selector::new()
+        .recv(&r1, |msg| (1, msg))
+        .recv(&r2, |msg| (2, msg))
+        .wait();
+    assert_eq!(res, (2, Ok(2)));
+}
+
+#[test]
+fn send() {
+    let (s1, r1) = bounded::<i32>(0);
+    let (s2, r2) = bounded::<i32>(1);
+
+    let res = Selector::new()
+        .send(&s1, || panic!(), |_| 1)
+        .send(
+            &s2,
+            || 7,
+            |res| {
+                assert_eq!(res, Ok(()));
+                2
+            },
+        )
+        .wait();
+    assert_eq!(res, 2);
+    assert_eq!(r2.try_recv(), Ok(7));
+
+    drop(r1);
+    let res = Selector::new().send(&s1, || 8, |res| res).wait();
+    assert_eq!(res, Err(SendError(8)));
+}
+
+#[test]
+fn disconnected() {
+    let (s, r) = unbounded::<i32>();
+    drop(s);
+
+    let res = Selector::new().recv(&r, |msg| msg).wait();
+    assert_eq!(res, Err(RecvError));
+}
+
+#[test]
+fn timeout() {
+    let (_s, r) = unbounded::<i32>();
+
+    let start = Instant::now();
+    let res = Selector::new()
+        .recv(&r, |_| "received")
+        .timeout(ms(300), || "timed out")
+        .wait();
+    assert_eq!(res, "timed out");
+    assert!(start.elapsed() >= ms(300));
+
+    scope(|scope| {
+        let (s, r) = unbounded::<i32>();
+        scope.spawn(move |_| {
+            thread::sleep(ms(100));
+            s.send(1).unwrap();
+        });
+
+        let res = Selector::new()
+            .recv(&r, |_| "received")
+            .deadline(Instant::now() + ms(1000), || "timed out")
+            .wait();
+        assert_eq!(res, "received");
+    })
+    .unwrap();
+}
+
+#[test]
+fn default() {
+    let (s, r) = unbounded::<i32>();
+
+    let res = Selector::new().recv(&r, |_| 1).default(|| 2).wait();
+    assert_eq!(res, 2);
+
+    s.send(1).unwrap();
+    let res = Selector::new().recv(&r, |_| 1).default(|| 2).wait();
+    assert_eq!(res, 1);
+
+    // Only the last fallback counts.
+    let res = Selector::new()
+        .recv(&r, |_| 1)
+        .default(|| 2)
+        .timeout(ms(100), || 3)
+        .wait();
+    assert_eq!(res, 3);
+}
+
+#[test]
+fn no_operations() {
+    let res = Selector::<i32>::new().default(|| 1).wait();
+    assert_eq!(res, 1);
+
+    let res = Selector::<i32>::new().timeout(ms(100), || 2).wait();
+    assert_eq!(res, 2);
+}
+
+#[test]
+#[should_panic(expected = "no operations have been added to `Select`")]
+fn no_operations_panics() {
+    Selector::<()>::new().wait();
+}
+
+#[test]
+fn loop_arms() {
+    let (ss, rs): (Vec<_>, Vec<_>) = (0..10).map(|_| unbounded::<usize>()).unzip();
+    let r = never();
+
+    for (i, s) in ss.iter().enumerate() {
+        s.send(i * 10).unwrap();
+    }
+
+    let mut sum = 0;
+    for _ in 0..10 {
+        let mut selector = Selector::new().recv(&r, |_: Result<(), _>| unreachable!());
+        for (i, r) in rs.iter().enumerate() {
+            selector = selector.recv(r, move |msg| {
+                assert_eq!(msg, Ok(i * 10));
+                i
+            });
+        }
+        sum += selector.wait();
+    }
+    assert_eq!(sum, 45);
+}
+
+#[test]
+fn borrows_state() {
+    let (s, r) = unbounded::<i32>();
+    let mut received = Vec::new();
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+
+    for _ in 0..2 {
+        Selector::new()
+            .recv(&r, |msg| received.push(msg.unwrap()))
+            .wait();
+    }
+    assert_eq!(received, [1, 2]);
+}
+
+#[test]
+fn stress() {
+    #[cfg(miri)]
+    const COUNT: usize = 50;
+    #[cfg(not(miri))]
+    const COUNT: usize = 10_000;
+
+    let (s1, r1) = bounded::<usize>(0);
+    let (s2, r2) = unbounded::<usize>();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                if i % 2 == 0 {
+                    s1.send(i).unwrap();
+                } else {
+                    s2.send(i).unwrap();
+                }
+            }
+        });
+
+        let mut sum = 0;
+        for _ in 0..COUNT {
+            sum += Selector::new()
+                .recv(&r1, |msg| msg.unwrap())
+                .recv(&r2, |msg| msg.unwrap())
+                .wait();
+        }
+        assert_eq!(sum, COUNT * (COUNT - 1) / 2);
+    })
+    .unwrap();
+}
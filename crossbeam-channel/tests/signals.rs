@@ -0,0 +1,72 @@
+//! Tests for channels that deliver OS signals.
+
+#![cfg(all(unix, feature = "signals", not(miri)))]
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{select, signals, unbounded, RecvTimeoutError};
+use signal_hook::consts::{SIGINT, SIGUSR1, SIGUSR2, SIGWINCH};
+use signal_hook::low_level::raise;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn ctrl_c() {
+    let r = signals::ctrl_c();
+    assert_eq!(r.recv_timeout(ms(100)), Err(RecvTimeoutError::Timeout));
+
+    raise(SIGINT).unwrap();
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(()));
+}
+
+#[test]
+fn notify() {
+    let r = signals::notify(&[SIGUSR1, SIGUSR2]).unwrap();
+
+    raise(SIGUSR1).unwrap();
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(SIGUSR1));
+
+    raise(SIGUSR2).unwrap();
+    assert_eq!(r.recv_timeout(ms(1000)), Ok(SIGUSR2));
+}
+
+#[test]
+fn forbidden() {
+    assert!(signals::notify(&[signal_hook::consts::SIGKILL]).is_err());
+}
+
+#[test]
+fn select_with_channel() {
+    let sig = signals::notify(&[SIGWINCH]).unwrap();
+    let (s, r) = unbounded::<i32>();
+
+    s.send(7).unwrap();
+    select! {
+        recv(sig) -> _ => panic!(),
+        recv(r) -> msg => assert_eq!(msg, Ok(7)),
+    }
+
+    thread::spawn(|| {
+        thread::sleep(ms(100));
+        raise(SIGWINCH).unwrap();
+    });
+
+    select! {
+        recv(sig) -> msg => assert_eq!(msg, Ok(SIGWINCH)),
+        recv(r) -> _ => panic!(),
+        default(ms(1000)) => panic!(),
+    }
+}
+
+#[test]
+fn drop_receiver() {
+    for _ in 0..10 {
+        drop(signals::ctrl_c());
+    }
+
+    // Give the polling threads a chance to notice the receivers are gone.
+    thread::sleep(ms(300));
+}
@@ -0,0 +1,161 @@
+//! Tests for the deterministic scheduler.
+
+#![cfg(feature = "sim")]
+
+use std::any::Any;
+use std::panic;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{
+    after, after_with_clock, bounded, select, sim, tick, unbounded, Clock, RecvTimeoutError,
+};
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(msg) => msg.to_string(),
+        None => payload.downcast_ref::<String>().unwrap().clone(),
+    }
+}
+
+/// Three threads race to send five messages each; returns the order they arrived in.
+fn race(seed: u64) -> Vec<(usize, usize)> {
+    sim::run(seed, || {
+        let (s, r) = unbounded();
+        for t in 0..3 {
+            let s = s.clone();
+            sim::spawn(move || {
+                for i in 0..5 {
+                    s.send((t, i)).unwrap();
+                }
+            });
+        }
+        drop(s);
+        r.iter().collect()
+    })
+}
+
+#[test]
+fn deterministic() {
+    for seed in 0..20 {
+        assert_eq!(race(seed), race(seed));
+    }
+
+    let orders = (0..20).map(race).collect::<Vec<_>>();
+    assert!(orders.iter().any(|o| *o != orders[0]));
+}
+
+#[test]
+fn recv_timeout() {
+    let start = Instant::now();
+    sim::run(0, || {
+        let (_s, r) = bounded::<i32>(0);
+        let before = sim::now();
+        assert_eq!(r.recv_timeout(secs(3600)), Err(RecvTimeoutError::Timeout));
+        assert_eq!(sim::now() - before, secs(3600));
+    });
+    assert!(start.elapsed() < secs(10));
+}
+
+#[test]
+fn sleep_order() {
+    let order = sim::run(1, || {
+        let (s, r) = unbounded();
+        for &d in &[30, 10, 20] {
+            let s = s.clone();
+            sim::spawn(move || {
+                sim::sleep(secs(d));
+                s.send(d).unwrap();
+            });
+        }
+        drop(s);
+        r.iter().collect::<Vec<_>>()
+    });
+    assert_eq!(order, [10, 20, 30]);
+}
+
+#[test]
+fn timers() {
+    sim::run(2, || {
+        let start = sim::now();
+        let r = tick(secs(10));
+        for i in 1..=3 {
+            assert_eq!(r.recv().unwrap(), start + secs(10 * i));
+        }
+        assert_eq!(sim::now() - start, secs(30));
+
+        let timeout = after(secs(5));
+        let (_s, r) = unbounded::<i32>();
+        select! {
+            recv(r) -> _ => panic!(),
+            recv(timeout) -> _ => {}
+        }
+        assert_eq!(sim::now() - start, secs(35));
+    });
+}
+
+#[test]
+fn clock() {
+    sim::run(3, || {
+        let clock = sim::clock();
+        let start = clock.now();
+        let r = after_with_clock(secs(60), clock.clone());
+
+        let (s, done) = bounded(0);
+        sim::spawn(move || {
+            sim::sleep(secs(60));
+            s.send(()).unwrap();
+        });
+        assert_eq!(r.recv(), Ok(start + secs(60)));
+        done.recv().unwrap();
+    });
+}
+
+#[test]
+fn join() {
+    sim::run(4, || {
+        let ok = sim::spawn(|| 7);
+        let err = sim::spawn(|| panic!("boom"));
+        assert_eq!(ok.join().unwrap(), 7);
+        assert_eq!(message(&*err.join().unwrap_err()), "boom");
+    });
+}
+
+#[test]
+fn panic() {
+    let res = panic::catch_unwind(|| sim::run(5, || panic!("boom")));
+    assert_eq!(message(&*res.unwrap_err()), "boom");
+}
+
+#[test]
+fn deadlock() {
+    let res = panic::catch_unwind(|| {
+        sim::run(6, || {
+            let (s1, r1) = bounded::<()>(0);
+            let (s2, r2) = bounded::<()>(0);
+            sim::spawn(move || {
+                r1.recv().unwrap();
+                s2.send(()).unwrap();
+            });
+            r2.recv().unwrap();
+            s1.send(()).unwrap();
+        })
+    });
+    let msg = message(&*res.unwrap_err());
+    assert!(msg.starts_with("deadlock in simulation"), "{}", msg);
+    assert!(
+        msg.contains("`sim-0`") && msg.contains("`sim-1`"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn outside() {
+    let before = Instant::now();
+    assert!(sim::now() >= before);
+    assert!(panic::catch_unwind(|| sim::spawn(|| ())).is_err());
+}
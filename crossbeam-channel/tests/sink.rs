@@ -0,0 +1,138 @@
+//! Tests for senders used as sinks.
+
+#![cfg(feature = "async")]
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use crossbeam_channel::{bounded, unbounded, SendError, SendSink};
+use futures_sink::Sink;
+
+/// Counts how many times a task was woken up.
+type Counter = Arc<AtomicUsize>;
+
+unsafe fn clone(p: *const ()) -> RawWaker {
+    let arc = Arc::from_raw(p as *const AtomicUsize);
+    let raw = RawWaker::new(Arc::into_raw(arc.clone()) as *const (), &VTABLE);
+    let _ = Arc::into_raw(arc);
+    raw
+}
+
+unsafe fn wake(p: *const ()) {
+    wake_by_ref(p);
+    drop_waker(p);
+}
+
+unsafe fn wake_by_ref(p: *const ()) {
+    (*(p as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe fn drop_waker(p: *const ()) {
+    drop(Arc::from_raw(p as *const AtomicUsize));
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_waker);
+
+fn waker() -> (Waker, Counter) {
+    let counter = Counter::default();
+    let raw = RawWaker::new(Arc::into_raw(counter.clone()) as *const (), &VTABLE);
+    (unsafe { Waker::from_raw(raw) }, counter)
+}
+
+fn ready<T>(sink: &mut SendSink<T>, waker: &Waker) -> Poll<Result<(), SendError<T>>> {
+    Pin::new(sink).poll_ready(&mut Context::from_waker(waker))
+}
+
+fn flush<T>(sink: &mut SendSink<T>, waker: &Waker) -> Poll<Result<(), SendError<T>>> {
+    Pin::new(sink).poll_flush(&mut Context::from_waker(waker))
+}
+
+fn send<T>(sink: &mut SendSink<T>, msg: T) -> Result<(), SendError<T>> {
+    Pin::new(sink).start_send(msg)
+}
+
+#[test]
+fn smoke() {
+    let (s, r) = unbounded();
+    let mut sink = s.sink();
+    let (waker, _) = waker();
+
+    assert_eq!(ready(&mut sink, &waker), Poll::Ready(Ok(())));
+    assert_eq!(send(&mut sink, 7), Ok(()));
+    assert_eq!(flush(&mut sink, &waker), Poll::Ready(Ok(())));
+    assert_eq!(r.try_recv(), Ok(7));
+    assert!(sink.sender().same_channel(&s));
+}
+
+#[test]
+fn backpressure() {
+    let (s, r) = bounded(1);
+    let mut sink = s.sink();
+    let (waker, wakes) = waker();
+
+    assert_eq!(send(&mut sink, 1), Ok(()));
+
+    // The second message doesn't fit and is kept by the sink.
+    assert_eq!(ready(&mut sink, &waker), Poll::Ready(Ok(())));
+    assert_eq!(send(&mut sink, 2), Ok(()));
+    assert_eq!(ready(&mut sink, &waker), Poll::Pending);
+    assert_eq!(flush(&mut sink, &waker), Poll::Pending);
+    assert_eq!(wakes.load(Ordering::SeqCst), 0);
+
+    // Receiving makes room and wakes the task up.
+    assert_eq!(r.try_recv(), Ok(1));
+    assert!(wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(ready(&mut sink, &waker), Poll::Ready(Ok(())));
+    assert_eq!(r.try_recv(), Ok(2));
+}
+
+#[test]
+fn disconnected() {
+    let (s, r) = bounded(1);
+    let mut sink = s.sink();
+    let (waker, wakes) = waker();
+
+    assert_eq!(send(&mut sink, 1), Ok(()));
+    assert_eq!(send(&mut sink, 2), Ok(()));
+    assert_eq!(flush(&mut sink, &waker), Poll::Pending);
+
+    // Dropping the receiver wakes the task, and the pending message is returned.
+    drop(r);
+    assert!(wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(flush(&mut sink, &waker), Poll::Ready(Err(SendError(2))));
+    assert_eq!(send(&mut sink, 3), Err(SendError(3)));
+}
+
+#[test]
+fn close_flushes() {
+    let (s, r) = bounded(1);
+    let mut sink = s.sink();
+    let (waker, _) = waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(send(&mut sink, 1), Ok(()));
+    assert_eq!(send(&mut sink, 2), Ok(()));
+    assert_eq!(Pin::new(&mut sink).poll_close(&mut cx), Poll::Pending);
+
+    assert_eq!(r.try_recv(), Ok(1));
+    assert_eq!(Pin::new(&mut sink).poll_close(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(r.try_recv(), Ok(2));
+
+    // The channel gets disconnected once every sender is gone.
+    drop(s);
+    drop(sink);
+    assert!(r.recv().is_err());
+}
+
+#[test]
+#[should_panic(expected = "without `poll_ready`")]
+fn start_send_twice() {
+    let (s, _r) = bounded(1);
+    let mut sink = s.sink();
+
+    send(&mut sink, 1).unwrap();
+    send(&mut sink, 2).unwrap();
+    send(&mut sink, 3).unwrap();
+}
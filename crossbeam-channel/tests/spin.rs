@@ -0,0 +1,197 @@
+//! Tests for channels with a custom spin policy.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{
+    bounded_with_spin, select, unbounded_with_spin, RecvTimeoutError, SendTimeoutError, SpinPolicy,
+};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn policies() -> Vec<SpinPolicy> {
+    vec![
+        SpinPolicy::default(),
+        SpinPolicy::park(),
+        SpinPolicy::new(0, 5),
+        SpinPolicy::new(20, 0),
+    ]
+}
+
+#[test]
+fn policy() {
+    assert_eq!(SpinPolicy::default(), SpinPolicy::new(7, 4));
+    assert_eq!(SpinPolicy::park().spins(), 0);
+    assert_eq!(SpinPolicy::park().yields(), 0);
+
+    let p = SpinPolicy::new(3, 8);
+    assert_eq!(p.spins(), 3);
+    assert_eq!(p.yields(), 8);
+
+    let p = SpinPolicy::new(u32::MAX, u32::MAX);
+    assert_eq!(p.spins(), 1024);
+    assert_eq!(p.yields(), 1024);
+}
+
+#[test]
+fn smoke() {
+    for spin in policies() {
+        for cap in 1..3 {
+            let (s, r) = bounded_with_spin(cap, spin);
+            s.send(7).unwrap();
+            assert_eq!(r.recv(), Ok(7));
+        }
+
+        let (s, r) = unbounded_with_spin(spin);
+        s.send(7).unwrap();
+        assert_eq!(r.recv(), Ok(7));
+    }
+}
+
+#[test]
+fn zero_capacity() {
+    for spin in policies() {
+        let (s, r) = bounded_with_spin(0, spin);
+
+        scope(|scope| {
+            scope.spawn(|_| {
+                thread::sleep(ms(100));
+                s.send(7).unwrap();
+            });
+            assert_eq!(r.recv(), Ok(7));
+        })
+        .unwrap();
+    }
+}
+
+#[test]
+fn recv_blocks() {
+    let (s, r) = unbounded_with_spin(SpinPolicy::park());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            s.send(7).unwrap();
+        });
+
+        let start = Instant::now();
+        assert_eq!(r.recv(), Ok(7));
+        assert!(start.elapsed() >= ms(80));
+    })
+    .unwrap();
+}
+
+#[test]
+fn send_blocks() {
+    let (s, r) = bounded_with_spin(1, SpinPolicy::park());
+    s.send(1).unwrap();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(100));
+            assert_eq!(r.recv(), Ok(1));
+        });
+
+        let start = Instant::now();
+        s.send(2).unwrap();
+        assert!(start.elapsed() >= ms(80));
+    })
+    .unwrap();
+
+    assert_eq!(r.try_recv(), Ok(2));
+}
+
+#[test]
+fn timeouts() {
+    for spin in policies() {
+        let (s, r) = bounded_with_spin(1, spin);
+        assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+        s.send(1).unwrap();
+        assert_eq!(s.send_timeout(2, ms(50)), Err(SendTimeoutError::Timeout(2)));
+
+        let (s, r) = bounded_with_spin(0, spin);
+        assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+        assert_eq!(s.send_timeout(2, ms(50)), Err(SendTimeoutError::Timeout(2)));
+
+        let (_s, r) = unbounded_with_spin::<i32>(spin);
+        assert_eq!(r.recv_timeout(ms(50)), Err(RecvTimeoutError::Timeout));
+    }
+}
+
+#[test]
+fn disconnect_wakes_up() {
+    for spin in policies() {
+        let (s, r) = bounded_with_spin::<i32>(1, spin);
+
+        scope(|scope| {
+            scope.spawn(|_| {
+                thread::sleep(ms(50));
+                drop(s);
+            });
+            assert!(r.recv().is_err());
+        })
+        .unwrap();
+    }
+}
+
+#[test]
+fn select() {
+    let (s1, r1) = bounded_with_spin(0, SpinPolicy::park());
+    let (_s2, r2) = unbounded_with_spin::<i32>(SpinPolicy::park());
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(50));
+            s1.send(1).unwrap();
+        });
+
+        select! {
+            recv(r1) -> msg => assert_eq!(msg, Ok(1)),
+            recv(r2) -> _ => panic!(),
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn stress() {
+    const COUNT: usize = 10_000;
+
+    for spin in policies() {
+        for &cap in &[0, 1, 100] {
+            let (s, r) = bounded_with_spin(cap, spin);
+
+            scope(|scope| {
+                for _ in 0..2 {
+                    scope.spawn(|_| {
+                        for i in 0..COUNT {
+                            s.send(i).unwrap();
+                        }
+                    });
+                    scope.spawn(|_| {
+                        for _ in 0..COUNT {
+                            r.recv().unwrap();
+                        }
+                    });
+                }
+            })
+            .unwrap();
+        }
+
+        let (s, r) = unbounded_with_spin(spin);
+        scope(|scope| {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+            for i in 0..COUNT {
+                assert_eq!(r.recv(), Ok(i));
+            }
+        })
+        .unwrap();
+    }
+}
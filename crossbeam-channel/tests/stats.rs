@@ -0,0 +1,143 @@
+//! Tests for lifetime counters.
+
+#![cfg(feature = "stats")]
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{after, bounded, select, unbounded, ChannelStats};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn new() {
+    let (s, r) = unbounded::<i32>();
+    assert_eq!(s.stats(), ChannelStats::default());
+    assert_eq!(r.stats(), ChannelStats::default());
+}
+
+#[test]
+fn bounded_try_ops() {
+    let (s, r) = bounded(2);
+    s.try_send(1).unwrap();
+    s.try_send(2).unwrap();
+    assert!(s.try_send(3).is_err());
+    assert!(s.try_send(4).is_err());
+    assert_eq!(r.try_recv(), Ok(1));
+    assert_eq!(r.try_recv(), Ok(2));
+    assert!(r.try_recv().is_err());
+
+    let stats = r.stats();
+    assert_eq!(stats.sent(), 2);
+    assert_eq!(stats.received(), 2);
+    assert_eq!(stats.rejected_full(), 2);
+    assert_eq!(stats.rejected_empty(), 1);
+    assert_eq!(s.stats(), stats);
+}
+
+#[test]
+fn disconnected_is_not_rejected() {
+    let (s, r) = bounded::<i32>(1);
+    drop(r);
+    assert!(s.try_send(1).is_err());
+    assert_eq!(s.stats().rejected_full(), 0);
+
+    let (s, r) = unbounded::<i32>();
+    drop(s);
+    assert!(r.try_recv().is_err());
+    assert_eq!(r.stats().rejected_empty(), 0);
+}
+
+#[test]
+fn zero() {
+    let (s, r) = bounded(0);
+    assert!(s.try_send(1).is_err());
+    assert!(r.try_recv().is_err());
+
+    scope(|scope| {
+        scope.spawn(|_| s.send(2).unwrap());
+        assert_eq!(r.recv(), Ok(2));
+    })
+    .unwrap();
+
+    let stats = s.stats();
+    assert_eq!(stats.sent(), 1);
+    assert_eq!(stats.received(), 1);
+    assert_eq!(stats.rejected_full(), 1);
+    assert_eq!(stats.rejected_empty(), 1);
+}
+
+#[test]
+fn blocking_and_select() {
+    let (s, r) = bounded(1);
+    s.send(1).unwrap();
+    s.send_timeout(2, ms(10)).unwrap_err();
+    select! {
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+    }
+    select! {
+        send(s, 3) -> res => res.unwrap(),
+    }
+    assert_eq!(r.recv_timeout(ms(10)), Ok(3));
+
+    // Only the non-blocking operations count as rejected.
+    let stats = s.stats();
+    assert_eq!(stats.sent(), 2);
+    assert_eq!(stats.received(), 2);
+    assert_eq!(stats.rejected_full(), 0);
+    assert_eq!(stats.rejected_empty(), 0);
+}
+
+#[test]
+fn batch() {
+    let (s, r) = unbounded();
+    let mut buffered = s.buffered(4);
+    for i in 0..4 {
+        buffered.send(i).unwrap();
+    }
+    buffered.flush().unwrap();
+    assert_eq!(r.try_iter().count(), 4);
+
+    let stats = s.stats();
+    assert_eq!(stats.sent(), 4);
+    assert_eq!(stats.received(), 4);
+    assert_eq!(stats.rejected_empty(), 1);
+}
+
+#[test]
+fn threads() {
+    const COUNT: u64 = 1000;
+    const THREADS: u64 = 4;
+
+    let (s, r) = bounded(3);
+    scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|_| {
+                for i in 0..COUNT {
+                    s.send(i).unwrap();
+                }
+            });
+            scope.spawn(|_| {
+                for _ in 0..COUNT {
+                    r.recv().unwrap();
+                }
+            });
+        }
+    })
+    .unwrap();
+
+    let stats = r.stats();
+    assert_eq!(stats.sent(), COUNT * THREADS);
+    assert_eq!(stats.received(), COUNT * THREADS);
+}
+
+#[test]
+fn timer() {
+    let r = after(ms(0));
+    thread::sleep(ms(10));
+    r.try_recv().unwrap();
+    assert_eq!(r.stats(), ChannelStats::default());
+}
@@ -0,0 +1,298 @@
+//! Tests for receivers used as streams.
+
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{after, bounded, never, tick, unbounded, Receiver};
+use crossbeam_utils::thread::scope;
+use futures_core::Stream;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// Wakes up a thread and counts how many times that happened.
+struct Unparker {
+    thread: Thread,
+    wakes: AtomicUsize,
+}
+
+unsafe fn clone(p: *const ()) -> RawWaker {
+    let arc = Arc::from_raw(p as *const Unparker);
+    let raw = RawWaker::new(Arc::into_raw(arc.clone()) as *const (), &VTABLE);
+    let _ = Arc::into_raw(arc);
+    raw
+}
+
+unsafe fn wake(p: *const ()) {
+    wake_by_ref(p);
+    drop_waker(p);
+}
+
+unsafe fn wake_by_ref(p: *const ()) {
+    let unparker = &*(p as *const Unparker);
+    unparker.wakes.fetch_add(1, Ordering::SeqCst);
+    unparker.thread.unpark();
+}
+
+unsafe fn drop_waker(p: *const ()) {
+    drop(Arc::from_raw(p as *const Unparker));
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_waker);
+
+fn waker(unparker: &Arc<Unparker>) -> Waker {
+    let raw = RawWaker::new(Arc::into_raw(unparker.clone()) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+fn unparker() -> Arc<Unparker> {
+    Arc::new(Unparker {
+        thread: thread::current(),
+        wakes: AtomicUsize::new(0),
+    })
+}
+
+/// Runs a future to completion on the current thread.
+fn block_on<F: Future>(mut f: F) -> F::Output {
+    let unparker = unparker();
+    let waker = waker(&unparker);
+    let mut cx = Context::from_waker(&waker);
+    let mut f = unsafe { Pin::new_unchecked(&mut f) };
+
+    loop {
+        if let Poll::Ready(out) = f.as_mut().poll(&mut cx) {
+            return out;
+        }
+        thread::park();
+    }
+}
+
+/// A future receiving the next item from a stream.
+struct Next<'a, S>(&'a mut S);
+
+impl<S: Stream + Unpin> Future for Next<'_, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        Pin::new(&mut *self.0).poll_next(cx)
+    }
+}
+
+fn poll_once<T>(r: &mut Receiver<T>, waker: &Waker) -> Poll<Option<T>> {
+    Pin::new(r).poll_next(&mut Context::from_waker(waker))
+}
+
+#[test]
+fn smoke() {
+    let (s, mut r) = unbounded();
+    s.send(7).unwrap();
+    assert_eq!(block_on(Next(&mut r)), Some(7));
+
+    drop(s);
+    assert_eq!(block_on(Next(&mut r)), None);
+}
+
+#[test]
+fn pending_then_ready() {
+    let (s, mut r) = bounded(1);
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    assert_eq!(poll_once(&mut r, &waker), Poll::Pending);
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 0);
+
+    s.send(1).unwrap();
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 1);
+    assert_eq!(poll_once(&mut r, &waker), Poll::Ready(Some(1)));
+}
+
+#[test]
+fn wake_on_disconnect() {
+    let (s, mut r) = unbounded::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    assert_eq!(poll_once(&mut r, &waker), Poll::Pending);
+    drop(s);
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 1);
+    assert_eq!(poll_once(&mut r, &waker), Poll::Ready(None));
+}
+
+#[test]
+fn repeated_polls() {
+    let (s, mut r) = unbounded();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    // Polling again with the same waker doesn't register it twice.
+    for _ in 0..10 {
+        assert_eq!(poll_once(&mut r, &waker), Poll::Pending);
+    }
+    s.send(1).unwrap();
+    assert_eq!(unparker.wakes.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn dropped_while_pending() {
+    let (s, mut r) = unbounded();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    assert_eq!(poll_once(&mut r, &waker), Poll::Pending);
+    drop(r);
+    drop(waker);
+    assert!(s.send(1).is_err());
+}
+
+#[test]
+fn zero_capacity() {
+    let (s, mut r) = bounded(0);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            thread::sleep(ms(50));
+            s.send(5).unwrap();
+        });
+
+        assert_eq!(block_on(Next(&mut r)), Some(5));
+    })
+    .unwrap();
+}
+
+#[test]
+fn from_threads() {
+    const COUNT: usize = 1000;
+    let (s, mut r) = bounded(3);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+            drop(s);
+        });
+
+        let mut received = Vec::new();
+        while let Some(i) = block_on(Next(&mut r)) {
+            received.push(i);
+        }
+        assert_eq!(received, (0..COUNT).collect::<Vec<_>>());
+    })
+    .unwrap();
+}
+
+#[test]
+fn timers() {
+    let start = Instant::now();
+    let mut r = after(ms(100));
+    assert!(block_on(Next(&mut r)).is_some());
+    assert!(start.elapsed() >= ms(100));
+
+    let start = Instant::now();
+    let mut r = tick(ms(50));
+    for _ in 0..3 {
+        assert!(block_on(Next(&mut r)).is_some());
+    }
+    assert!(start.elapsed() >= ms(150));
+}
+
+#[test]
+fn never_pending() {
+    let mut r = never::<i32>();
+    let unparker = unparker();
+    let waker = waker(&unparker);
+
+    assert_eq!(poll_once(&mut r, &waker), Poll::Pending);
+}
+
+#[test]
+fn ready_chunks() {
+    let (s, r) = unbounded();
+    for i in 0..5 {
+        s.send(i).unwrap();
+    }
+
+    let mut chunks = r.ready_chunks(3);
+    assert_eq!(block_on(Next(&mut chunks)), Some(vec![0, 1, 2]));
+    assert_eq!(block_on(Next(&mut chunks)), Some(vec![3, 4]));
+
+    s.send(5).unwrap();
+    assert_eq!(block_on(Next(&mut chunks)), Some(vec![5]));
+
+    drop(s);
+    assert_eq!(block_on(Next(&mut chunks)), None);
+}
+
+#[test]
+fn ready_chunks_pending() {
+    let (s, r) = unbounded();
+    let mut chunks = r.ready_chunks(10);
+    let unparker = unparker();
+    let waker = waker(&unparker);
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(Pin::new(&mut chunks).poll_next(&mut cx), Poll::Pending);
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert!(unparker.wakes.load(Ordering::SeqCst) >= 1);
+    assert_eq!(
+        Pin::new(&mut chunks).poll_next(&mut cx),
+        Poll::Ready(Some(vec![1, 2]))
+    );
+}
+
+#[test]
+fn ready_chunks_backpressure() {
+    let (s, r) = bounded(4);
+    for i in 0..4 {
+        s.send(i).unwrap();
+    }
+
+    // Messages beyond the chunk size stay in the channel.
+    let mut chunks = r.ready_chunks(3);
+    assert_eq!(block_on(Next(&mut chunks)), Some(vec![0, 1, 2]));
+    assert_eq!(chunks.receiver().len(), 1);
+    for i in 4..7 {
+        assert!(s.try_send(i).is_ok());
+    }
+    assert!(s.try_send(7).is_err());
+}
+
+#[test]
+fn ready_chunks_from_threads() {
+    const COUNT: usize = 1000;
+    let (s, r) = bounded(16);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+            drop(s);
+        });
+
+        let mut chunks = r.ready_chunks(8);
+        let mut received = Vec::new();
+        while let Some(chunk) = block_on(Next(&mut chunks)) {
+            assert!(!chunk.is_empty() && chunk.len() <= 8);
+            received.extend(chunk);
+        }
+        assert_eq!(received, (0..COUNT).collect::<Vec<_>>());
+    })
+    .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn ready_chunks_zero() {
+    let (_s, r) = unbounded::<i32>();
+    let _ = r.ready_chunks(0);
+}
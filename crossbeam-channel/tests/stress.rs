@@ -0,0 +1,197 @@
+//! Randomized stress tests.
+//!
+//! Every round picks a flavor and a capacity, the numbers of senders and receivers, and which of
+//! them give up early. Each thread mixes blocking, non-blocking, timed, and selecting operations
+//! at random. In the end, every message that was sent must have been received exactly once or
+//! still be in the channel when it got dropped.
+//!
+//! Set `STRESS_SEED` to replay a single round.
+
+use std::collections::HashSet;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{
+    bounded_with_metrics, select, unbounded_with_metrics, ChannelMetrics, Receiver,
+    RecvTimeoutError, SendTimeoutError, Sender, TryRecvError, TrySendError,
+};
+use crossbeam_utils::thread::scope;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+#[cfg(miri)]
+const ROUNDS: usize = 2;
+#[cfg(not(miri))]
+const ROUNDS: usize = 40;
+#[cfg(miri)]
+const MESSAGES: usize = 20;
+#[cfg(not(miri))]
+const MESSAGES: usize = 2_000;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// A message: the index of its sender and its sequence number.
+type Msg = (usize, usize);
+
+/// Remembers how many messages were left in the channel when it got dropped.
+#[derive(Default)]
+struct Leftover(Mutex<Option<usize>>);
+
+impl ChannelMetrics for Leftover {
+    fn on_drop(&self, _name: &str, len: usize) {
+        *self.0.lock().unwrap() = Some(len);
+    }
+}
+
+/// Sends `msg` with a random operation, returning `false` if the channel is disconnected.
+fn send(s: &Sender<Msg>, msg: Msg, rng: &mut StdRng) -> bool {
+    match rng.gen_range(0..4) {
+        0 => s.send(msg).is_ok(),
+        1 => loop {
+            match s.try_send(msg) {
+                Ok(()) => return true,
+                Err(TrySendError::Full(_)) => thread::yield_now(),
+                Err(TrySendError::Disconnected(_)) => return false,
+            }
+        },
+        2 => loop {
+            match s.send_timeout(msg, ms(1)) {
+                Ok(()) => return true,
+                Err(SendTimeoutError::Timeout(_)) => {}
+                Err(SendTimeoutError::Disconnected(_)) => return false,
+            }
+        },
+        _ => select! {
+            send(s, msg) -> res => res.is_ok(),
+        },
+    }
+}
+
+/// Receives a message with a random operation, returning `None` if the channel is disconnected.
+fn recv(r: &Receiver<Msg>, rng: &mut StdRng) -> Option<Msg> {
+    match rng.gen_range(0..4) {
+        0 => r.recv().ok(),
+        1 => loop {
+            match r.try_recv() {
+                Ok(msg) => return Some(msg),
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        },
+        2 => loop {
+            match r.recv_timeout(ms(1)) {
+                Ok(msg) => return Some(msg),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        },
+        _ => select! {
+            recv(r) -> msg => msg.ok(),
+        },
+    }
+}
+
+/// Runs the round chosen by `seed` and checks that no message was lost or duplicated.
+fn round(seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let leftover = Arc::new(Leftover::default());
+    let (s, r) = if rng.gen_bool(0.5) {
+        unbounded_with_metrics("stress", leftover.clone())
+    } else if rng.gen_bool(0.5) {
+        bounded_with_metrics(rng.gen_range(1..4), "stress", leftover.clone())
+    } else {
+        bounded_with_metrics(rng.gen_range(1..100), "stress", leftover.clone())
+    };
+    let senders = rng.gen_range(1..5);
+    let receivers = rng.gen_range(1..5);
+
+    let sent = Mutex::new(vec![0; senders]);
+    let received = Mutex::new(Vec::new());
+
+    scope(|scope| {
+        for id in 0..senders {
+            // A sender that gives up early disconnects mid-stream if it's the last one.
+            let limit = if rng.gen_bool(0.25) {
+                rng.gen_range(0..MESSAGES)
+            } else {
+                MESSAGES
+            };
+            let mut rng = StdRng::seed_from_u64(rng.gen());
+            let s = s.clone();
+            let sent = &sent;
+            scope.spawn(move |_| {
+                let mut count = 0;
+                while count < limit && send(&s, (id, count), &mut rng) {
+                    count += 1;
+                }
+                sent.lock().unwrap()[id] = count;
+            });
+        }
+
+        for _ in 0..receivers {
+            // A receiver that gives up early disconnects mid-stream if it's the last one.
+            let limit = if rng.gen_bool(0.25) {
+                rng.gen_range(0..MESSAGES)
+            } else {
+                usize::max_value()
+            };
+            let mut rng = StdRng::seed_from_u64(rng.gen());
+            let r = r.clone();
+            let received = &received;
+            scope.spawn(move |_| {
+                let mut msgs = Vec::new();
+                while msgs.len() < limit {
+                    match recv(&r, &mut rng) {
+                        Some(msg) => msgs.push(msg),
+                        None => break,
+                    }
+                }
+                received.lock().unwrap().push(msgs);
+            });
+        }
+
+        drop(s);
+        drop(r);
+    })
+    .unwrap();
+
+    let sent = sent.into_inner().unwrap();
+    let received = received.into_inner().unwrap();
+    let leftover = leftover.0.lock().unwrap().expect("channel wasn't dropped");
+
+    let mut seen = HashSet::new();
+    for msgs in &received {
+        let mut next = vec![0; senders];
+        for &msg in msgs {
+            let (id, seq) = msg;
+            assert!(seq < sent[id], "seed {}: {:?} never sent", seed, msg);
+            assert!(seen.insert(msg), "seed {}: {:?} received twice", seed, msg);
+            // Messages from the same sender arrive in order.
+            assert!(seq >= next[id], "seed {}: {:?} out of order", seed, msg);
+            next[id] = seq + 1;
+        }
+    }
+    assert_eq!(
+        seen.len() + leftover,
+        sent.iter().sum::<usize>(),
+        "seed {}: messages were lost",
+        seed
+    );
+}
+
+#[test]
+fn stress() {
+    match env::var("STRESS_SEED") {
+        Ok(seed) => round(seed.parse().expect("STRESS_SEED must be a number")),
+        Err(_) => {
+            let mut rng = thread_rng();
+            for _ in 0..ROUNDS {
+                round(rng.gen());
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Tests for throttled channels.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{bounded, select, throttle, unbounded, ThrottlePolicy, TryRecvError};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn smoke() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(50), ThrottlePolicy::Buffer);
+
+    let start = Instant::now();
+    s.send(7).unwrap();
+    assert_eq!(r.recv(), Ok(7));
+    assert!(start.elapsed() < ms(50));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn buffer() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(50), ThrottlePolicy::Buffer);
+
+    for i in 0..4 {
+        s.send(i).unwrap();
+    }
+
+    // Compare against the start rather than the previous message, since this thread may wake up
+    // late for one message and then early for the next.
+    let start = Instant::now();
+    for i in 0..4 {
+        assert_eq!(r.recv(), Ok(i));
+        assert!(start.elapsed() >= ms(50) * i);
+    }
+}
+
+#[test]
+fn conflate() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(100), ThrottlePolicy::Conflate);
+
+    s.send(0).unwrap();
+    assert_eq!(r.recv(), Ok(0));
+
+    let start = Instant::now();
+    for i in 1..10 {
+        s.send(i).unwrap();
+        thread::sleep(ms(5));
+    }
+    assert_eq!(r.recv(), Ok(9));
+    assert!(start.elapsed() >= ms(100));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn idle_then_immediate() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(50), ThrottlePolicy::Buffer);
+
+    s.send(1).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+
+    // After a full interval of silence, a message is delivered right away.
+    thread::sleep(ms(100));
+    let start = Instant::now();
+    s.send(2).unwrap();
+    assert_eq!(r.recv(), Ok(2));
+    assert!(start.elapsed() < ms(50));
+}
+
+#[test]
+fn disconnect() {
+    let (s, r) = bounded(4);
+    let r = throttle(r, ms(10), ThrottlePolicy::Buffer);
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    drop(s);
+
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Ok(2));
+    assert!(r.recv().is_err());
+}
+
+#[test]
+fn disconnect_conflate() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(10), ThrottlePolicy::Conflate);
+
+    s.send(1).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    s.send(2).unwrap();
+    s.send(3).unwrap();
+    drop(s);
+
+    assert_eq!(r.recv(), Ok(3));
+    assert!(r.recv().is_err());
+}
+
+#[test]
+fn select() {
+    let (s, r) = unbounded();
+    let r = throttle(r, ms(50), ThrottlePolicy::Buffer);
+
+    s.send(1).unwrap();
+    select! {
+        recv(r) -> msg => assert_eq!(msg, Ok(1)),
+        default(ms(1000)) => panic!(),
+    }
+}
@@ -7,7 +7,9 @@ use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crossbeam_channel::{after, select, tick, Select, TryRecvError};
+use crossbeam_channel::{
+    after, select, tick, tick_with, ticker, MissedTicks, RecvTimeoutError, Select, TryRecvError,
+};
 use crossbeam_utils::thread::scope;
 
 fn ms(ms: u64) -> Duration {
@@ -350,3 +352,175 @@ fn fairness_duplicates() {
         assert!(hits.iter().all(|x| *x >= COUNT / hits.len() / 2));
     }
 }
+
+#[test]
+fn missed_burst() {
+    let start = Instant::now();
+    let r = tick_with(ms(50), MissedTicks::Burst);
+
+    thread::sleep(ms(180));
+    let t1 = r.try_recv().unwrap();
+    assert!(start + ms(50) <= t1);
+    assert_eq!(r.try_recv(), Ok(t1 + ms(50)));
+    assert_eq!(r.try_recv(), Ok(t1 + ms(100)));
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    assert_eq!(r.recv(), Ok(t1 + ms(150)));
+    assert!(Instant::now() >= t1 + ms(150));
+}
+
+#[test]
+fn missed_delay() {
+    let r = tick_with(ms(50), MissedTicks::Delay);
+
+    thread::sleep(ms(180));
+    let received = Instant::now();
+    r.try_recv().unwrap();
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    // The next tick is due 50 ms after the late one was received.
+    let t2 = r.recv().unwrap();
+    assert!(received + ms(50) <= t2);
+    assert!(received + ms(100) > t2);
+}
+
+#[test]
+fn missed_skip() {
+    let r = tick_with(ms(50), MissedTicks::Skip);
+
+    thread::sleep(ms(180));
+    let t1 = r.try_recv().unwrap();
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    // Ticks due 50 and 100 ms after the first one were skipped.
+    assert_eq!(r.recv(), Ok(t1 + ms(150)));
+    assert_eq!(r.recv(), Ok(t1 + ms(200)));
+}
+
+#[test]
+fn missed_default() {
+    assert_eq!(MissedTicks::default(), MissedTicks::Delay);
+}
+
+#[test]
+fn ticker_pause_resume() {
+    let (ticker, r) = ticker(ms(50));
+    assert!(!ticker.is_paused());
+    assert_eq!(ticker.period(), ms(50));
+
+    ticker.pause();
+    assert!(ticker.is_paused());
+    assert!(r.is_empty());
+    assert_eq!(r.recv_timeout(ms(100)), Err(RecvTimeoutError::Timeout));
+
+    let resumed = Instant::now();
+    ticker.resume();
+    assert!(!ticker.is_paused());
+    let t1 = r.recv().unwrap();
+    assert!(resumed < t1);
+    assert!(t1 - resumed <= ms(50));
+
+    let t2 = r.recv().unwrap();
+    assert!(t1 + ms(50) <= t2);
+    assert!(t1 + ms(100) > t2);
+}
+
+#[test]
+fn ticker_pause_keeps_due_message() {
+    let (ticker, r) = ticker(ms(50));
+
+    thread::sleep(ms(100));
+    ticker.pause();
+    ticker.pause();
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+
+    // The message that was already due is delivered right after resuming.
+    ticker.resume();
+    ticker.resume();
+    assert!(r.try_recv().is_ok());
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn ticker_reset() {
+    let (ticker, r) = ticker(ms(50));
+
+    thread::sleep(ms(100));
+    let reset = Instant::now();
+    ticker.reset(ms(100));
+    assert_eq!(ticker.period(), ms(100));
+
+    // The due message was discarded.
+    assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+    let t1 = r.recv().unwrap();
+    assert!(reset + ms(100) <= t1);
+
+    let t2 = r.recv().unwrap();
+    assert!(t1 + ms(100) <= t2);
+    assert!(t1 + ms(200) > t2);
+}
+
+#[test]
+fn ticker_reset_paused() {
+    let (ticker, r) = ticker(ms(50));
+
+    ticker.pause();
+    ticker.reset(ms(100));
+    assert!(ticker.is_paused());
+    assert_eq!(r.recv_timeout(ms(150)), Err(RecvTimeoutError::Timeout));
+
+    let resumed = Instant::now();
+    ticker.resume();
+    assert!(r.recv().unwrap() >= resumed + ms(100));
+}
+
+#[test]
+fn ticker_wakes_recv() {
+    let (ticker, r) = ticker(ms(10_000));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            let start = Instant::now();
+            r.recv().unwrap();
+            assert!(start.elapsed() < ms(1000));
+        });
+
+        thread::sleep(ms(100));
+        ticker.reset(ms(50));
+    })
+    .unwrap();
+}
+
+#[test]
+fn ticker_wakes_select() {
+    let (t, r) = ticker(ms(50));
+    t.pause();
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            let mut sel = Select::new();
+            sel.recv(&r);
+            let oper = sel.select_timeout(ms(1000)).unwrap();
+            oper.recv(&r).unwrap();
+        });
+
+        thread::sleep(ms(100));
+        t.resume();
+    })
+    .unwrap();
+
+    // A paused ticker no longer wakes up a blocked select.
+    let (t, r) = ticker(ms(50));
+    scope(|scope| {
+        scope.spawn(|_| {
+            select! {
+                recv(r) -> _ => panic!(),
+                recv(after(ms(300))) -> _ => {}
+            }
+        });
+
+        thread::sleep(ms(10));
+        t.pause();
+    })
+    .unwrap();
+}
@@ -0,0 +1,182 @@
+//! Tests for the adapters between crossbeam and tokio channels.
+
+#![cfg(feature = "tokio-compat")]
+
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, tokio_compat, unbounded, RecvTimeoutError};
+use crossbeam_utils::thread::scope;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn from_receiver() {
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let r = tokio_compat::from_receiver(rx);
+
+    tx.blocking_send(1).unwrap();
+    tx.blocking_send(2).unwrap();
+    assert_eq!(r.recv(), Ok(1));
+    assert_eq!(r.recv(), Ok(2));
+    assert_eq!(r.recv_timeout(ms(100)), Err(RecvTimeoutError::Timeout));
+
+    drop(tx);
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn from_receiver_backpressure() {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let r = tokio_compat::from_receiver(rx);
+
+    tx.blocking_send(0).unwrap();
+    tx.blocking_send(1).unwrap();
+    thread::sleep(ms(100));
+
+    // One message is held by the forwarding thread and one is in the tokio channel.
+    assert!(tx.try_send(2).is_err());
+    assert_eq!(r.recv(), Ok(0));
+    assert_eq!(r.recv(), Ok(1));
+}
+
+#[test]
+fn from_receiver_dropped() {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let r = tokio_compat::from_receiver(rx);
+    drop(r);
+
+    // The first message wakes up the forwarding thread, which then closes the tokio channel.
+    let _ = tx.blocking_send(1);
+    thread::sleep(ms(100));
+    assert!(tx.is_closed());
+}
+
+#[test]
+fn from_sender() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    let s = tokio_compat::from_sender(tx);
+
+    s.send(1).unwrap();
+    s.send(2).unwrap();
+    assert_eq!(rx.blocking_recv(), Some(1));
+    assert_eq!(rx.blocking_recv(), Some(2));
+
+    drop(s);
+    assert_eq!(rx.blocking_recv(), None);
+}
+
+#[test]
+fn from_sender_closed() {
+    let (tx, rx) = tokio::sync::mpsc::channel::<i32>(4);
+    let s = tokio_compat::from_sender(tx);
+    drop(rx);
+
+    thread::sleep(ms(300));
+    assert!(s.send(1).is_err());
+}
+
+#[test]
+fn into_receiver() {
+    let (s, r) = unbounded();
+    let mut rx = tokio_compat::into_receiver(r);
+
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    drop(s);
+
+    for i in 0..10 {
+        assert_eq!(rx.blocking_recv(), Some(i));
+    }
+    assert_eq!(rx.blocking_recv(), None);
+}
+
+#[test]
+fn into_receiver_backpressure() {
+    let (s, r) = unbounded();
+    let mut rx = tokio_compat::into_receiver(r);
+
+    for i in 0..10 {
+        s.send(i).unwrap();
+    }
+    thread::sleep(ms(100));
+
+    // One message is in the tokio channel and one is held by the forwarding thread.
+    assert_eq!(s.len(), 8);
+    assert_eq!(rx.blocking_recv(), Some(0));
+}
+
+#[test]
+fn into_receiver_dropped() {
+    let (s, r) = unbounded::<i32>();
+    let rx = tokio_compat::into_receiver(r);
+    drop(rx);
+
+    thread::sleep(ms(300));
+    assert!(s.send(1).is_err());
+}
+
+#[test]
+fn into_sender() {
+    let (s, r) = bounded(1);
+    let tx = tokio_compat::into_sender(s);
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..100 {
+                tx.blocking_send(i).unwrap();
+            }
+        });
+
+        for i in 0..100 {
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+
+    drop(tx);
+    assert_eq!(
+        r.recv_timeout(ms(1000)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn into_sender_dropped() {
+    let (s, r) = unbounded::<i32>();
+    let tx = tokio_compat::into_sender(s);
+    drop(r);
+
+    // The first message wakes up the forwarding thread, which then closes the tokio channel.
+    let _ = tx.blocking_send(1);
+    thread::sleep(ms(100));
+    assert!(tx.is_closed());
+}
+
+#[test]
+fn round_trip() {
+    const COUNT: usize = 1000;
+
+    let (s, r) = bounded(2);
+    let r = tokio_compat::from_receiver(tokio_compat::into_receiver(r));
+    let s = tokio_compat::from_sender(tokio_compat::into_sender(s));
+
+    scope(|scope| {
+        scope.spawn(|_| {
+            for i in 0..COUNT {
+                s.send(i).unwrap();
+            }
+        });
+
+        for i in 0..COUNT {
+            assert_eq!(r.recv(), Ok(i));
+        }
+    })
+    .unwrap();
+}
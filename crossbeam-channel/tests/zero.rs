@@ -218,6 +218,73 @@ fn len() {
     assert_eq!(r.len(), 0);
 }
 
+#[test]
+fn debug() {
+    let (s, r) = bounded(0);
+    assert_eq!(
+        format!("{:?}", s),
+        "Sender { flavor: \"zero\", capacity: Some(0), len: 0, disconnected: false, \
+         blocked_senders: 0, blocked_receivers: 0 }"
+    );
+
+    scope(|scope| {
+        scope.spawn(|_| s.send(7));
+        thread::sleep(ms(100));
+        assert!(format!("{:?}", r).contains("blocked_senders: 1"));
+        assert_eq!(r.recv(), Ok(7));
+    })
+    .unwrap();
+}
+
+#[test]
+fn max_len_seen() {
+    let (s, r) = bounded(0);
+
+    scope(|scope| {
+        scope.spawn(|_| s.send(7));
+        assert_eq!(r.recv(), Ok(7));
+    })
+    .unwrap();
+
+    assert_eq!(s.max_len_seen(), 0);
+    assert_eq!(r.max_len_seen(), 0);
+}
+
+#[test]
+fn blocked() {
+    let (s, r) = bounded(0);
+
+    scope(|scope| {
+        for i in 0..2 {
+            let s = &s;
+            scope
+                .builder()
+                .name(format!("sender-{}", i))
+                .spawn(move |_| s.send(i))
+                .unwrap();
+        }
+        thread::sleep(ms(100));
+        assert_eq!(r.blocked_senders(), 2);
+        let mut names = r.blocked_sender_names();
+        names.sort();
+        assert_eq!(names, ["sender-0", "sender-1"]);
+
+        r.recv().unwrap();
+        r.recv().unwrap();
+    })
+    .unwrap();
+    assert_eq!(r.blocked_senders(), 0);
+
+    scope(|scope| {
+        scope.spawn(|_| r.recv());
+        thread::sleep(ms(100));
+        assert_eq!(s.blocked_receivers(), 1);
+        s.send(0).unwrap();
+    })
+    .unwrap();
+    assert_eq!(s.blocked_receivers(), 0);
+}
+
 #[test]
 fn disconnect_wakes_sender() {
     let (s, r) = bounded(0);